@@ -1,5 +1,4 @@
 #![feature(test)]
-#![cfg_attr(feature = "i128_support", feature(i128_type, i128))]
 
 extern crate test;
 extern crate rand;
@@ -35,7 +34,6 @@ distr!(distr_range_i8, i8, Range::new(20i8, 100));
 distr!(distr_range_i16, i16, Range::new(-500i16, 2000));
 distr!(distr_range_i32, i32, Range::new(-200_000_000i32, 800_000_000));
 distr!(distr_range_i64, i64, Range::new(3i64, 12345678901234));
-#[cfg(feature = "i128_support")]
 distr!(distr_range_i128, i128, Range::new(-12345678901234i128, 12345678901234567890));
 
 distr!(distr_range_f32, f32, Range::new(2.26f32, 2.319));
@@ -46,7 +44,6 @@ distr!(distr_uniform_i8, i8, Uniform);
 distr!(distr_uniform_i16, i16, Uniform);
 distr!(distr_uniform_i32, i32, Uniform);
 distr!(distr_uniform_i64, i64, Uniform);
-#[cfg(feature = "i128_support")]
 distr!(distr_uniform_i128, i128, Uniform);
 
 distr!(distr_uniform_bool, bool, Uniform);
@@ -58,11 +55,26 @@ distr!(distr_uniform_f64, f64, Uniform);
 
 // distributions
 distr!(distr_exp, f64, Exp::new(2.71828 * 3.14159));
+distr!(distr_exp_f32, f32, ExpF32::new(2.71828 * 3.14159));
 distr!(distr_normal, f64, Normal::new(-2.71828, 3.14159));
+distr!(distr_standard_normal, f64, StandardNormal);
+distr!(distr_standard_normal_f32, f32, StandardNormalF32);
 distr!(distr_log_normal, f64, LogNormal::new(-2.71828, 3.14159));
 distr!(distr_gamma_large_shape, f64, Gamma::new(10., 1.0));
 distr!(distr_gamma_small_shape, f64, Gamma::new(0.1, 1.0));
 
+#[bench]
+fn distr_standard_normal_fill(b: &mut Bencher) {
+    let mut rng = XorShiftRng::new();
+    let mut buf = [0.0f64; RAND_BENCH_N as usize];
+
+    b.iter(|| {
+        StandardNormal.sample_fill(&mut rng, &mut buf);
+        black_box(&buf);
+    });
+    b.bytes = size_of::<f64>() as u64 * ::RAND_BENCH_N;
+}
+
 
 // construct and sample from a range
 macro_rules! gen_range_int {
@@ -74,7 +86,7 @@ macro_rules! gen_range_int {
 
             b.iter(|| {
                 for _ in 0..::RAND_BENCH_N {
-                    let x: $ty = rng.gen_range($low, high);
+                    let x: $ty = rng.gen_range($low..high);
                     black_box(x);
                     black_box(high);
                 }
@@ -88,5 +100,4 @@ gen_range_int!(gen_range_i8, i8, 20i8, 100);
 gen_range_int!(gen_range_i16, i16, -500i16, 2000);
 gen_range_int!(gen_range_i32, i32, -200_000_000i32, 800_000_000);
 gen_range_int!(gen_range_i64, i64, 3i64, 12345678901234);
-#[cfg(feature = "i128_support")]
 gen_range_int!(gen_range_i128, i128, -12345678901234i128, 12345678901234567890);
@@ -18,6 +18,16 @@ fn misc_shuffle_100(b: &mut Bencher) {
     })
 }
 
+#[bench]
+fn misc_shuffle_1_000_000(b: &mut Bencher) {
+    let mut rng = SmallRng::from_rng(&mut thread_rng()).unwrap();
+    let x : &mut [usize] = &mut vec![1; 1_000_000];
+    b.iter(|| {
+        rng.shuffle(x);
+        black_box(&x);
+    })
+}
+
 #[bench]
 fn misc_sample_iter_10_of_100(b: &mut Bencher) {
     let mut rng = SmallRng::from_rng(&mut thread_rng()).unwrap();
@@ -0,0 +1,257 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `#[derive(RngCore, SeedableRng, CryptoRng)]`.
+//!
+//! These derives implement the `rand-core` traits for a struct by forwarding
+//! to one of its fields: the only field, or otherwise the field marked with
+//! `#[rng]`. This removes the boilerplate of newtypes around a generator,
+//! which are useful to keep generators used for different purposes apart.
+//!
+//! - `RngCore` forwards all methods to the field.
+//! - `SeedableRng` uses the seed type of the field; the other fields are
+//!   initialised with `Default::default()`.
+//! - `CryptoRng` is implemented if the field implements it.
+//!
+//! The generated code refers to `::rand_core`. Usually this crate is used via
+//! the `derive` feature of `rand-core`, which re-exports the macros.
+//!
+//! # Example
+//!
+//! ```
+//! extern crate rand_core;
+//! #[macro_use]
+//! extern crate rand_core_derive;
+//!
+//! use rand_core::{RngCore, SeedableRng, Error, impls, le};
+//!
+//! # #[derive(Clone, Debug)]
+//! # pub struct CounterRng(u64);
+//! # impl RngCore for CounterRng {
+//! #     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+//! #     fn next_u64(&mut self) -> u64 { self.0 += 1; self.0 }
+//! #     fn fill_bytes(&mut self, dest: &mut [u8]) {
+//! #         impls::fill_bytes_via_u64(self, dest)
+//! #     }
+//! #     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+//! #         Ok(self.fill_bytes(dest))
+//! #     }
+//! # }
+//! # impl SeedableRng for CounterRng {
+//! #     type Seed = [u8; 8];
+//! #     fn from_seed(seed: [u8; 8]) -> CounterRng {
+//! #         let mut x = [0u64; 1];
+//! #         le::read_u64_into(&seed, &mut x);
+//! #         CounterRng(x[0])
+//! #     }
+//! # }
+//! // A generator only used to shuffle decks
+//! #[derive(Clone, Debug, RngCore, SeedableRng)]
+//! pub struct DeckRng(CounterRng);
+//!
+//! #[derive(Clone, Debug, RngCore, SeedableRng)]
+//! pub struct LoggedRng {
+//!     #[rng]
+//!     rng: CounterRng,
+//!     name: String,
+//! }
+//!
+//! fn main() {
+//!     let mut deck = DeckRng::from_seed([1, 0, 0, 0, 0, 0, 0, 0]);
+//!     assert_eq!(deck.next_u64(), 2);
+//!
+//!     let mut logged = LoggedRng::from_seed([0; 8]);
+//!     assert_eq!(logged.next_u64(), 1);
+//!     assert_eq!(logged.name, "");
+//! }
+//! ```
+
+#![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk.png",
+       html_favicon_url = "https://www.rust-lang.org/favicon.ico",
+       html_root_url = "https://docs.rs/rand_core_derive/0.1")]
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as Tokens;
+use syn::{Data, DeriveInput, Error, Fields, Member, Type};
+
+#[proc_macro_derive(RngCore, attributes(rng))]
+pub fn derive_rng_core(input: TokenStream) -> TokenStream {
+    expand(input, impl_rng_core)
+}
+
+#[proc_macro_derive(SeedableRng, attributes(rng))]
+pub fn derive_seedable_rng(input: TokenStream) -> TokenStream {
+    expand(input, impl_seedable_rng)
+}
+
+#[proc_macro_derive(CryptoRng, attributes(rng))]
+pub fn derive_crypto_rng(input: TokenStream) -> TokenStream {
+    expand(input, impl_crypto_rng)
+}
+
+fn expand(input: TokenStream,
+          f: fn(&DeriveInput, &Inner) -> Tokens) -> TokenStream
+{
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    match find_inner(&ast) {
+        Ok(inner) => {
+            let tokens = f(&ast, &inner);
+            // The block makes `core` available independently of the edition
+            // and of `no_std`.
+            let tokens = quote! {
+                const _: () = {
+                    extern crate core as _core;
+                    #tokens
+                };
+            };
+            tokens.into()
+        }
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// The field the implementations forward to.
+struct Inner {
+    member: Member,
+    ty: Type,
+    /// The other fields, to be initialised with `Default::default()`.
+    others: Vec<Member>,
+}
+
+fn find_inner(ast: &DeriveInput) -> Result<Inner, Error> {
+    let fields = match ast.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => return Err(Error::new_spanned(&ast.ident,
+                "this derive is only supported for structs")),
+    };
+    if let Fields::Unit = *fields {
+        return Err(Error::new_spanned(&ast.ident,
+                "this derive needs a field holding the generator"));
+    }
+
+    let members = fields.members().collect::<Vec<_>>();
+    let fields = fields.iter().collect::<Vec<_>>();
+    let marked = fields.iter()
+        .enumerate()
+        .filter(|&(_, field)| {
+            field.attrs.iter().any(|attr| attr.path().is_ident("rng"))
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let index = match (marked.len(), fields.len()) {
+        (1, _) => marked[0],
+        (0, 1) => 0,
+        (0, _) => return Err(Error::new_spanned(&ast.ident,
+                "mark the field holding the generator with `#[rng]`")),
+        _ => return Err(Error::new_spanned(fields[marked[1]],
+                "only one field may be marked with `#[rng]`")),
+    };
+
+    let others = members.iter()
+        .enumerate()
+        .filter(|&(i, _)| i != index)
+        .map(|(_, member)| member.clone())
+        .collect();
+    Ok(Inner {
+        member: members[index].clone(),
+        ty: fields[index].ty.clone(),
+        others: others,
+    })
+}
+
+/// The impl header, adding `bound` for the field type to the where clause.
+fn header(ast: &DeriveInput, inner: &Inner, bound: Tokens) -> Tokens {
+    let name = &ast.ident;
+    let ty = &inner.ty;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let predicates = where_clause.map(|w| &w.predicates);
+    quote! {
+        impl #impl_generics #bound for #name #ty_generics
+            where #ty: #bound, #predicates
+    }
+}
+
+fn impl_rng_core(ast: &DeriveInput, inner: &Inner) -> Tokens {
+    let header = header(ast, inner, quote!(::rand_core::RngCore));
+    let member = &inner.member;
+    quote! {
+        #header {
+            #[inline]
+            fn next_u32(&mut self) -> u32 {
+                ::rand_core::RngCore::next_u32(&mut self.#member)
+            }
+
+            #[inline]
+            fn next_u64(&mut self) -> u64 {
+                ::rand_core::RngCore::next_u64(&mut self.#member)
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                ::rand_core::RngCore::fill_bytes(&mut self.#member, dest)
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8])
+                -> _core::result::Result<(), ::rand_core::Error>
+            {
+                ::rand_core::RngCore::try_fill_bytes(&mut self.#member, dest)
+            }
+        }
+    }
+}
+
+fn impl_seedable_rng(ast: &DeriveInput, inner: &Inner) -> Tokens {
+    let header = header(ast, inner, quote!(::rand_core::SeedableRng));
+    let ty = &inner.ty;
+    let construct = |value: Tokens| {
+        let member = &inner.member;
+        let others = &inner.others;
+        // This also works for tuple structs, with numeric field names.
+        quote! {
+            Self {
+                #member: #value,
+                #(#others: _core::default::Default::default(),)*
+            }
+        }
+    };
+    let from_seed = construct(quote! {
+        <#ty as ::rand_core::SeedableRng>::from_seed(seed)
+    });
+    let from_rng = construct(quote! {
+        <#ty as ::rand_core::SeedableRng>::from_rng(rng)?
+    });
+    quote! {
+        #header {
+            type Seed = <#ty as ::rand_core::SeedableRng>::Seed;
+
+            fn from_seed(seed: Self::Seed) -> Self {
+                #from_seed
+            }
+
+            fn from_rng<__R: ::rand_core::RngCore + ?Sized>(rng: &mut __R)
+                -> _core::result::Result<Self, ::rand_core::Error>
+            {
+                _core::result::Result::Ok(#from_rng)
+            }
+        }
+    }
+}
+
+fn impl_crypto_rng(ast: &DeriveInput, inner: &Inner) -> Tokens {
+    let header = header(ast, inner, quote!(::rand_core::CryptoRng));
+    quote! {
+        #header {}
+    }
+}
@@ -0,0 +1,96 @@
+#![allow(dead_code)]
+
+extern crate rand_core;
+#[macro_use]
+extern crate rand_core_derive;
+
+use rand_core::{RngCore, SeedableRng, CryptoRng, Error, impls, le};
+
+#[derive(Clone, Debug, PartialEq)]
+struct StepRng(u64);
+
+impl RngCore for StepRng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 += 1;
+        self.0
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_u64(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+impl SeedableRng for StepRng {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: [u8; 8]) -> StepRng {
+        let mut x = [0u64; 1];
+        le::read_u64_into(&seed, &mut x);
+        StepRng(x[0])
+    }
+}
+
+impl CryptoRng for StepRng {}
+
+#[derive(Clone, Debug, RngCore, SeedableRng, CryptoRng)]
+struct Newtype(StepRng);
+
+#[derive(Debug, RngCore, SeedableRng)]
+struct Named {
+    label: String,
+    #[rng]
+    rng: StepRng,
+    count: u32,
+}
+
+#[derive(Debug, RngCore, SeedableRng, CryptoRng)]
+struct Generic<R> {
+    #[rng]
+    rng: R,
+    count: u32,
+}
+
+fn require_crypto<R: RngCore + CryptoRng>(rng: &mut R) -> u64 {
+    rng.next_u64()
+}
+
+#[test]
+fn newtype() {
+    let mut rng = Newtype::from_seed([5, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(rng.next_u32(), 6);
+    assert_eq!(rng.next_u64(), 7);
+    let mut buf = [0u8; 3];
+    rng.fill_bytes(&mut buf);
+    assert_eq!(buf, [8, 0, 0]);
+    rng.try_fill_bytes(&mut buf).unwrap();
+    assert_eq!(buf, [9, 0, 0]);
+    assert_eq!(require_crypto(&mut rng), 10);
+
+    // Seeded with the next value of `rng`, 11
+    let mut other = Newtype::from_rng(&mut rng).unwrap();
+    assert_eq!(other.next_u64(), 12);
+    assert_eq!(Newtype::seed_from_u64(1).0, StepRng::seed_from_u64(1));
+}
+
+#[test]
+fn named() {
+    let mut rng = Named::from_seed([0; 8]);
+    assert_eq!(rng.next_u64(), 1);
+    assert_eq!(rng.label, "");
+    assert_eq!(rng.count, 0);
+}
+
+#[test]
+fn generic() {
+    let mut rng = Generic::<StepRng>::from_seed([1, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(require_crypto(&mut rng), 2);
+    assert_eq!(rng.count, 0);
+}
@@ -0,0 +1,200 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Helpers for implementing block-based RNGs.
+//!
+//! Many generators, such as ChaCha, HC-128 and ISAAC, produce a block of
+//! values at a time. An implementation only has to provide the generation of
+//! one block via [`BlockRngCore`]; the [`BlockRng`] wrapper buffers the
+//! results and implements `RngCore` on top of it, including the handling of
+//! requests crossing block boundaries.
+//!
+//! [`BlockRngCore`]: trait.BlockRngCore.html
+//! [`BlockRng`]: struct.BlockRng.html
+
+use core::fmt;
+use {RngCore, SeedableRng, Error};
+use impls::fill_via_u32_chunks;
+
+/// A trait for RNGs which do not generate random numbers individually, but in
+/// blocks (typically `[u32; N]`).
+///
+/// Wrap an implementation in [`BlockRng`] to get an `RngCore`.
+///
+/// [`BlockRng`]: struct.BlockRng.html
+pub trait BlockRngCore {
+    /// Results element type, e.g. `u32`.
+    type Item;
+
+    /// Results type. This is the 'block' an RNG implementing `BlockRngCore`
+    /// generates, which will usually be an array like `[u32; 16]`.
+    type Results: AsRef<[Self::Item]> + AsMut<[Self::Item]> + Default;
+
+    /// Generate a new block of results.
+    fn generate(&mut self, results: &mut Self::Results);
+}
+
+/// A wrapper implementing `RngCore` for a [`BlockRngCore`] generating blocks
+/// of `u32`.
+///
+/// `next_u32` returns the values of a block in order; `next_u64` combines two
+/// of them, the first being the low half. `fill_bytes` copies the values in
+/// little-endian byte order, discarding the unused bytes of the last value it
+/// uses. A new block is only generated when the current one is used up, so
+/// (unlike generating a block per request) no values are wasted.
+///
+/// # Example
+///
+/// ```rust
+/// use rand_core::RngCore;
+/// use rand_core::block::{BlockRngCore, BlockRng};
+///
+/// // A (bad) generator producing four consecutive numbers per block
+/// struct CounterCore(u32);
+///
+/// impl BlockRngCore for CounterCore {
+///     type Item = u32;
+///     type Results = [u32; 4];
+///
+///     fn generate(&mut self, results: &mut [u32; 4]) {
+///         for x in results.iter_mut() {
+///             *x = self.0;
+///             self.0 += 1;
+///         }
+///     }
+/// }
+///
+/// let mut rng = BlockRng::new(CounterCore(0));
+/// assert_eq!(rng.next_u32(), 0);
+/// assert_eq!(rng.next_u64(), (2 << 32) | 1);
+/// let mut bytes = [0u8; 6];
+/// rng.fill_bytes(&mut bytes);
+/// assert_eq!(bytes, [3, 0, 0, 0, 4, 0]);
+/// assert_eq!(rng.next_u32(), 5);
+/// ```
+///
+/// [`BlockRngCore`]: trait.BlockRngCore.html
+#[derive(Clone)]
+pub struct BlockRng<R: BlockRngCore> {
+    core: R,
+    results: R::Results,
+    index: usize,
+}
+
+// Custom Debug implementation that does not expose the contents of `results`.
+impl<R: BlockRngCore + fmt::Debug> fmt::Debug for BlockRng<R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("BlockRng")
+           .field("core", &self.core)
+           .field("result_len", &self.results.as_ref().len())
+           .field("index", &self.index)
+           .finish()
+    }
+}
+
+impl<R: BlockRngCore> BlockRng<R> {
+    /// Create a new `BlockRng` from an existing RNG implementing
+    /// `BlockRngCore`. The first block is generated on first use.
+    pub fn new(core: R) -> BlockRng<R> {
+        let results = R::Results::default();
+        let index = results.as_ref().len();
+        BlockRng { core: core, results: results, index: index }
+    }
+
+    /// A reference to the wrapped core.
+    pub fn inner(&self) -> &R {
+        &self.core
+    }
+
+    /// A mutable reference to the wrapped core.
+    ///
+    /// Results already generated are still returned first; use [`reset`]
+    /// after changing the core to discard them.
+    ///
+    /// [`reset`]: #method.reset
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.core
+    }
+
+    /// The index into the current block of the next value to be returned.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Discard the remaining results of the current block, so the next
+    /// request generates a new one.
+    pub fn reset(&mut self) {
+        self.index = self.results.as_ref().len();
+    }
+
+    /// Generate a new block, and continue at `index` in it.
+    pub fn generate_and_set(&mut self, index: usize) {
+        assert!(index < self.results.as_ref().len());
+        self.core.generate(&mut self.results);
+        self.index = index;
+    }
+}
+
+impl<R: BlockRngCore<Item=u32>> RngCore for BlockRng<R> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= self.results.as_ref().len() {
+            self.generate_and_set(0);
+        }
+        let value = self.results.as_ref()[self.index];
+        self.index += 1;
+        value
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let len = self.results.as_ref().len();
+        let index = self.index;
+        if index + 1 < len {
+            self.index += 2;
+            let results = self.results.as_ref();
+            (results[index + 1] as u64) << 32 | results[index] as u64
+        } else {
+            let lo = self.next_u32() as u64;
+            (self.next_u32() as u64) << 32 | lo
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut read_len = 0;
+        while read_len < dest.len() {
+            if self.index >= self.results.as_ref().len() {
+                self.generate_and_set(0);
+            }
+            let (consumed_u32, filled_u8) =
+                fill_via_u32_chunks(&self.results.as_ref()[self.index..],
+                                    &mut dest[read_len..]);
+            self.index += consumed_u32;
+            read_len += filled_u8;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl<R: BlockRngCore + SeedableRng> SeedableRng for BlockRng<R> {
+    type Seed = R::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        BlockRng::new(R::from_seed(seed))
+    }
+
+    fn from_rng<S: RngCore + ?Sized>(rng: &mut S) -> Result<Self, Error> {
+        Ok(BlockRng::new(R::from_rng(rng)?))
+    }
+}
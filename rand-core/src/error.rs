@@ -81,16 +81,25 @@ impl ErrorKind {
 /// 
 /// This is a relatively simple error type, designed for compatibility with and
 /// without the Rust `std` library. It embeds a "kind" code, a message (static
-/// string only), and an optional chained cause (`std` only). The `kind` and
-/// `msg` fields can be accessed directly; cause can be accessed via
-/// `std::error::Error::cause` or `Error::take_cause`. Construction can only be
-/// done via `Error::new` or `Error::with_cause`.
+/// string only), an optional error code, and an optional chained cause (`std`
+/// only). The `kind` and `msg` fields can be accessed directly; the code via
+/// `Error::code` and `Error::domain`, and the cause via
+/// `std::error::Error::source`, `Error::downcast_ref` or `Error::take_cause`.
+/// Construction can only be done via `Error::new`, `Error::with_code`,
+/// `Error::with_cause` or `Error::custom`.
+///
+/// The `kind` describes how to handle the error, e.g. whether to retry.
+/// Sources with their own error codes, such as hardware or remote entropy
+/// services, should use `Error::custom` to report what went wrong in terms of
+/// their own *domain*, with a code and optionally a cause of their own type.
 #[derive(Debug)]
 pub struct Error {
     /// The error kind
     pub kind: ErrorKind,
     /// The error message
     pub msg: &'static str,
+    code: Option<i32>,
+    domain: Option<&'static str>,
     #[cfg(feature="std")]
     cause: Option<Box<stdError + Send + Sync>>,
 }
@@ -99,18 +108,31 @@ impl Error {
     /// Create a new instance, with specified kind and a message.
     pub fn new(kind: ErrorKind, msg: &'static str) -> Self {
         #[cfg(feature="std")] {
-            Error { kind: kind, msg: msg, cause: None }
+            Error { kind: kind, msg: msg, code: None, domain: None, cause: None }
         }
         #[cfg(not(feature="std"))] {
-            Error { kind: kind, msg: msg }
+            Error { kind: kind, msg: msg, code: None, domain: None }
         }
     }
+
+    /// Create a new instance, with specified kind, message, and an error
+    /// code, e.g. the `errno` value of a failed system call.
+    ///
+    /// Unlike a cause, the code is also kept in `no_std` mode.
+    pub fn with_code(kind: ErrorKind, msg: &'static str, code: i32) -> Self {
+        let mut err = Error::new(kind, msg);
+        err.code = Some(code);
+        err
+    }
     
     /// Create a new instance, with specified kind, message, and a
     /// chained cause.
     /// 
     /// Note: `stdError` is an alias for `std::error::Error`.
     /// 
+    /// If the cause is a `std::io::Error` with an OS error code, that code is
+    /// also available via `Error::code`, with domain `"os"`.
+    /// 
     /// If not targetting `std` (i.e. `no_std`), this function is replaced by
     /// another with the same prototype, except that there are no bounds on the
     /// type `E` (because both `Box` and `stdError` are unavailable), and the
@@ -119,7 +141,16 @@ impl Error {
     pub fn with_cause<E>(kind: ErrorKind, msg: &'static str, cause: E) -> Self
         where E: Into<Box<stdError + Send + Sync>>
     {
-        Error { kind: kind, msg: msg, cause: Some(cause.into()) }
+        let cause = cause.into();
+        let code = cause.downcast_ref::<::std::io::Error>()
+            .and_then(|e| e.raw_os_error());
+        Error {
+            kind: kind,
+            msg: msg,
+            code: code,
+            domain: code.map(|_| "os"),
+            cause: Some(cause),
+        }
     }
     
     /// Create a new instance, with specified kind, message, and a
@@ -128,7 +159,76 @@ impl Error {
     /// In `no_std` mode the *cause* is ignored.
     #[cfg(not(feature="std"))]
     pub fn with_cause<E>(kind: ErrorKind, msg: &'static str, _cause: E) -> Self {
-        Error { kind: kind, msg: msg }
+        Error::new(kind, msg)
+    }
+
+    /// Create a new instance for a custom error source, with specified kind,
+    /// message, the domain of the source, an error code within that domain,
+    /// and a chained cause.
+    ///
+    /// The domain is a short name identifying the error source, which gives
+    /// meaning to the code, e.g. `"pkcs11"` for the return values of a
+    /// PKCS #11 module. The cause may be a payload of the source's own error
+    /// type, which can be recovered with `Error::downcast_ref`. As for
+    /// `Error::with_cause`, there are no bounds on `E` in `no_std` mode, and
+    /// the cause is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand_core::{Error, ErrorKind};
+    ///
+    /// const CKR_DEVICE_REMOVED: i32 = 0x32;
+    ///
+    /// let err = Error::custom(ErrorKind::Unavailable, "HSM removed",
+    ///                         "pkcs11", CKR_DEVICE_REMOVED, "slot 0");
+    /// assert_eq!(err.domain(), Some("pkcs11"));
+    /// assert_eq!(err.code(), Some(CKR_DEVICE_REMOVED));
+    /// ```
+    #[cfg(feature="std")]
+    pub fn custom<E>(kind: ErrorKind, msg: &'static str, domain: &'static str,
+                     code: i32, cause: E) -> Self
+        where E: Into<Box<stdError + Send + Sync>>
+    {
+        Error {
+            kind: kind,
+            msg: msg,
+            code: Some(code),
+            domain: Some(domain),
+            cause: Some(cause.into()),
+        }
+    }
+
+    /// Create a new instance for a custom error source, with specified kind,
+    /// message, the domain of the source, an error code within that domain,
+    /// and a chained cause.
+    ///
+    /// In `no_std` mode the *cause* is ignored.
+    #[cfg(not(feature="std"))]
+    pub fn custom<E>(kind: ErrorKind, msg: &'static str, domain: &'static str,
+                     code: i32, _cause: E) -> Self
+    {
+        Error { kind: kind, msg: msg, code: Some(code), domain: Some(domain) }
+    }
+
+    /// The error code, if any.
+    ///
+    /// This is the code given to `Error::with_code` or `Error::custom`, or
+    /// the OS error code of a `std::io::Error` cause.
+    pub fn code(&self) -> Option<i32> {
+        self.code
+    }
+
+    /// The domain of the error code, if any: the domain given to
+    /// `Error::custom`, or `"os"` for the code of a `std::io::Error` cause.
+    pub fn domain(&self) -> Option<&'static str> {
+        self.domain
+    }
+
+    /// A reference to the cause, if it is of type `T`.
+    #[cfg(feature="std")]
+    pub fn downcast_ref<T: stdError + 'static>(&self) -> Option<&T> {
+        self.cause.as_ref().and_then(|e| e.downcast_ref::<T>())
     }
     
     /// Take the cause, if any. This allows the embedded cause to be extracted.
@@ -141,13 +241,18 @@ impl Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.msg, self.kind.description())?;
+        match (self.domain, self.code) {
+            (Some(domain), Some(code)) => write!(f, "; {} code: {}", domain, code)?,
+            (None, Some(code)) => write!(f, "; code: {}", code)?,
+            _ => {}
+        }
         #[cfg(feature="std")] {
             if let Some(ref cause) = self.cause {
-                return write!(f, "{} ({}); cause: {}",
-                        self.msg, self.kind.description(), cause);
+                write!(f, "; cause: {}", cause)?;
             }
         }
-        write!(f, "{} ({})", self.msg, self.kind.description())
+        Ok(())
     }
 }
 
@@ -160,4 +265,8 @@ impl stdError for Error {
     fn cause(&self) -> Option<&stdError> {
         self.cause.as_ref().map(|e| e.as_ref() as &stdError)
     }
+
+    fn source(&self) -> Option<&(stdError + 'static)> {
+        self.cause.as_ref().map(|e| e.as_ref() as &(stdError + 'static))
+    }
 }
@@ -0,0 +1,146 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A stable hash function for deriving seeds.
+
+use core::hash::Hasher;
+
+/// A `Hasher` with stable output, used by `SeedableRng::from_hashable`.
+///
+/// This is SipHash-2-4 with both keys 0. Unlike the hashers of the standard
+/// library, its output is fixed: it does not use random keys, does not depend
+/// on the platform, and will not change in future versions. Integers are
+/// hashed as their little-endian bytes, with `usize` and `isize` extended to
+/// 64 bits.
+///
+/// The values of a type implementing `Hash` are only as stable as the `Hash`
+/// implementation, and the standard library makes no promise that its
+/// implementations won't change. Where the output must never change, write
+/// the input to the hasher directly with the methods of `Hasher`.
+///
+/// With fixed keys, SipHash is not a secure PRF: seeds derived with it must be
+/// expected to be known to anyone knowing the input.
+///
+/// # Example
+///
+/// ```rust
+/// use std::hash::Hasher;
+/// use rand_core::hash::SeedHasher;
+///
+/// let mut hasher = SeedHasher::new();
+/// hasher.write(b"level-42/chunk-7");
+/// println!("{:x}", hasher.finish());
+/// ```
+#[derive(Clone, Debug)]
+pub struct SeedHasher {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    tail: u64,
+    ntail: usize,
+    length: usize,
+}
+
+macro_rules! sip_round {
+    ($v0:expr, $v1:expr, $v2:expr, $v3:expr) => {
+        $v0 = $v0.wrapping_add($v1); $v1 = $v1.rotate_left(13); $v1 ^= $v0;
+        $v0 = $v0.rotate_left(32);
+        $v2 = $v2.wrapping_add($v3); $v3 = $v3.rotate_left(16); $v3 ^= $v2;
+        $v0 = $v0.wrapping_add($v3); $v3 = $v3.rotate_left(21); $v3 ^= $v0;
+        $v2 = $v2.wrapping_add($v1); $v1 = $v1.rotate_left(17); $v1 ^= $v2;
+        $v2 = $v2.rotate_left(32);
+    }
+}
+
+impl SeedHasher {
+    /// Create a new hasher.
+    pub fn new() -> SeedHasher {
+        SeedHasher {
+            v0: 0x736f_6d65_7073_6575,
+            v1: 0x646f_7261_6e64_6f6d,
+            v2: 0x6c79_6765_6e65_7261,
+            v3: 0x7465_6462_7974_6573,
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    #[inline]
+    fn compress(&mut self, m: u64) {
+        self.v3 ^= m;
+        sip_round!(self.v0, self.v1, self.v2, self.v3);
+        sip_round!(self.v0, self.v1, self.v2, self.v3);
+        self.v0 ^= m;
+    }
+
+    #[inline]
+    fn write_u64_bytes(&mut self, x: u64, len: usize) {
+        let mut bytes = [0u8; 8];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (x >> (8 * i)) as u8;
+        }
+        self.write(&bytes[..len])
+    }
+}
+
+impl Default for SeedHasher {
+    fn default() -> SeedHasher {
+        SeedHasher::new()
+    }
+}
+
+impl Hasher for SeedHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.length = self.length.wrapping_add(bytes.len());
+        for &byte in bytes {
+            self.tail |= (byte as u64) << (8 * self.ntail);
+            self.ntail += 1;
+            if self.ntail == 8 {
+                let m = self.tail;
+                self.compress(m);
+                self.tail = 0;
+                self.ntail = 0;
+            }
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) { self.write(&[i]) }
+    fn write_u16(&mut self, i: u16) { self.write_u64_bytes(i as u64, 2) }
+    fn write_u32(&mut self, i: u32) { self.write_u64_bytes(i as u64, 4) }
+    fn write_u64(&mut self, i: u64) { self.write_u64_bytes(i, 8) }
+    fn write_u128(&mut self, i: u128) {
+        self.write_u64(i as u64);
+        self.write_u64((i >> 64) as u64)
+    }
+    fn write_usize(&mut self, i: usize) { self.write_u64_bytes(i as u64, 8) }
+    fn write_i8(&mut self, i: i8) { self.write_u8(i as u8) }
+    fn write_i16(&mut self, i: i16) { self.write_u16(i as u16) }
+    fn write_i32(&mut self, i: i32) { self.write_u32(i as u32) }
+    fn write_i64(&mut self, i: i64) { self.write_u64(i as u64) }
+    fn write_i128(&mut self, i: i128) { self.write_u128(i as u128) }
+    fn write_isize(&mut self, i: isize) { self.write_u64(i as i64 as u64) }
+
+    fn finish(&self) -> u64 {
+        let (mut v0, mut v1, mut v2, mut v3) = (self.v0, self.v1, self.v2, self.v3);
+        let b = ((self.length as u64 & 0xff) << 56) | self.tail;
+        v3 ^= b;
+        sip_round!(v0, v1, v2, v3);
+        sip_round!(v0, v1, v2, v3);
+        v0 ^= b;
+        v2 ^= 0xff;
+        sip_round!(v0, v1, v2, v3);
+        sip_round!(v0, v1, v2, v3);
+        sip_round!(v0, v1, v2, v3);
+        sip_round!(v0, v1, v2, v3);
+        v0 ^ v1 ^ v2 ^ v3
+    }
+}
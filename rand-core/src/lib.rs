@@ -14,6 +14,11 @@
 //! `RngCore`. Other users are encouraged to use the [rand] crate instead
 //! which re-exports the main traits and error types.
 //!
+//! It contains only what is needed to implement a generator, and has no
+//! dependencies: implementations depending on `rand-core` rather than [rand]
+//! do not pull in distributions, sequence functions or OS interfaces, and
+//! need not follow the more frequent releases of [rand].
+//!
 //! `RngCore` is the core trait implemented by algorithmic pseudo-random number
 //! generators and external random-number sources.
 //! 
@@ -24,9 +29,17 @@
 //! environments.
 //! 
 //! The `impls` and `le` sub-modules include a few small functions to assist
-//! implementation of `RngCore`.
+//! implementation of `RngCore`, and the `block` sub-module implements
+//! `RngCore` for generators producing a block of values at a time via
+//! `BlockRngCore`. The `hash` sub-module provides the stable hash function
+//! used by `SeedableRng::from_hashable`.
+//! 
+//! With the `derive` feature, `#[derive(RngCore, SeedableRng, CryptoRng)]`
+//! implements these traits for a wrapper by forwarding to one of its fields;
+//! see [rand_core_derive] for details.
 //! 
 //! [rand]: https://crates.io/crates/rand
+//! [rand_core_derive]: https://docs.rs/rand_core_derive
 
 #![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk.png",
        html_favicon_url = "https://www.rust-lang.org/favicon.ico",
@@ -35,21 +48,26 @@
 #![deny(missing_debug_implementations)]
 
 #![cfg_attr(not(feature="std"), no_std)]
-#![cfg_attr(all(feature="alloc", not(feature="std")), feature(alloc))]
 
 #[cfg(feature="std")] extern crate core;
 #[cfg(all(feature = "alloc", not(feature="std")))] extern crate alloc;
+#[cfg(feature="derive")] extern crate rand_core_derive;
 
 
 use core::default::Default;
 use core::convert::AsMut;
+use core::hash::{Hash, Hasher};
 
 #[cfg(all(feature="alloc", not(feature="std")))] use alloc::boxed::Box;
 
 pub use error::{ErrorKind, Error};
+#[cfg(feature="derive")] pub use rand_core_derive::{RngCore, SeedableRng, CryptoRng};
+use hash::SeedHasher;
 
 
 mod error;
+pub mod block;
+pub mod hash;
 pub mod impls;
 pub mod le;
 
@@ -160,6 +178,29 @@ pub trait RngCore {
     /// 
     /// [`fill_bytes`]: trait.RngCore.html#method.fill_bytes
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error>;
+
+    /// Fill `dest` with random `u32` values.
+    ///
+    /// The result is the same as calling `next_u32` for each element in turn,
+    /// which is what the default implementation does. Block generators
+    /// override this to copy whole blocks of output at once, avoiding the
+    /// byte conversions of going through `fill_bytes`.
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        for x in dest.iter_mut() {
+            *x = self.next_u32();
+        }
+    }
+
+    /// Fill `dest` with random `u64` values.
+    ///
+    /// The result is the same as calling `next_u64` for each element in turn,
+    /// which is what the default implementation does. Block generators
+    /// override this to copy whole blocks of output at once.
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        for x in dest.iter_mut() {
+            *x = self.next_u64();
+        }
+    }
 }
 
 /// A marker trait for an `Rng` which may be considered for use in
@@ -182,6 +223,11 @@ pub trait RngCore {
 /// 
 /// Note also that use of a `CryptoRng` does not protect against other
 /// weaknesses such as seeding from a weak entropy source or leaking state.
+/// 
+/// Code requiring a secure generator can express this with a bound, e.g.
+/// `fn generate_key<R: RngCore + CryptoRng>(rng: &mut R)`. `&mut R` and
+/// `Box<R>` implement `CryptoRng` if `R` does, as do wrappers in [rand] such
+/// as `ReseedingRng`.
 pub trait CryptoRng: RngCore {}
 
 /// A random number generator that can be explicitly seeded.
@@ -203,6 +249,40 @@ pub trait SeedableRng: Sized {
     /// partially overlapping periods.
     ///
     /// For cryptographic RNG's a seed of 256 bits is recommended, `[u8; 32]`.
+    ///
+    /// The standard library only implements `Default` for arrays of up to 32
+    /// elements. A generator with a larger natural seed can use a newtype:
+    ///
+    /// ```rust
+    /// use rand_core::SeedableRng;
+    ///
+    /// pub struct MyRngSeed(pub [u8; 64]);
+    ///
+    /// impl Default for MyRngSeed {
+    ///     fn default() -> MyRngSeed {
+    ///         MyRngSeed([0; 64])
+    ///     }
+    /// }
+    ///
+    /// impl AsMut<[u8]> for MyRngSeed {
+    ///     fn as_mut(&mut self) -> &mut [u8] {
+    ///         &mut self.0
+    ///     }
+    /// }
+    ///
+    /// pub struct MyRng([u8; 64]);
+    ///
+    /// impl SeedableRng for MyRng {
+    ///     type Seed = MyRngSeed;
+    ///
+    ///     fn from_seed(seed: MyRngSeed) -> MyRng {
+    ///         MyRng(seed.0)
+    ///     }
+    /// }
+    ///
+    /// let rng = MyRng::seed_from_u64(5);
+    /// assert!(rng.0[56..].iter().any(|&b| b != 0));
+    /// ```
     type Seed: Sized + Default + AsMut<[u8]>;
 
     /// Create a new PRNG using the given seed.
@@ -222,6 +302,64 @@ pub trait SeedableRng: Sized {
     /// implementation can be added.
     fn from_seed(seed: Self::Seed) -> Self;
 
+    /// Create a new PRNG using a `u64` seed.
+    ///
+    /// This is a convenience wrapper around `from_seed` for tests, examples
+    /// and command-line options, where a reproducible generator is wanted from
+    /// a small integer. The `u64` is expanded into a full seed with the
+    /// SplitMix64 generator, so similar inputs give unrelated seeds with well
+    /// distributed bits. The expansion is fixed: the same `state` always gives
+    /// the same seed.
+    ///
+    /// This is not suitable for cryptography: there are only `2^64` possible
+    /// seeds, which is easily searched.
+    fn seed_from_u64(state: u64) -> Self {
+        let mut seed = Self::Seed::default();
+        let mut state = state;
+        for chunk in seed.as_mut().chunks_mut(8) {
+            // SplitMix64, by Sebastiano Vigna
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^= z >> 31;
+            for byte in chunk.iter_mut() {
+                *byte = z as u8;
+                z >>= 8;
+            }
+        }
+        Self::from_seed(seed)
+    }
+
+    /// Create a new PRNG with a seed derived from any hashable value, such
+    /// as a string or a tuple of integers.
+    ///
+    /// This is meant for reproducible generators keyed by a name, e.g. in
+    /// procedural generation (`R::from_hashable("level-42/chunk-7")`). The
+    /// input is hashed with [`SeedHasher`], which gives the same result on all
+    /// platforms and in all versions; each 8-byte chunk `i` of the seed is the
+    /// little-endian hash of the input followed by `i` as a `u64`.
+    ///
+    /// This is not suitable for cryptography: anyone who knows or guesses the
+    /// input knows the seed.
+    ///
+    /// [`SeedHasher`]: hash/struct.SeedHasher.html
+    fn from_hashable<T: Hash + ?Sized>(input: &T) -> Self {
+        let mut hasher = SeedHasher::new();
+        input.hash(&mut hasher);
+        let mut seed = Self::Seed::default();
+        for (i, chunk) in seed.as_mut().chunks_mut(8).enumerate() {
+            let mut h = hasher.clone();
+            h.write_u64(i as u64);
+            let mut z = h.finish();
+            for byte in chunk.iter_mut() {
+                *byte = z as u8;
+                z >>= 8;
+            }
+        }
+        Self::from_seed(seed)
+    }
+
     /// Create a new PRNG seeded from another `Rng`.
     ///
     /// This is the recommended way to initialize PRNGs with fresh entropy. The
@@ -254,7 +392,7 @@ pub trait SeedableRng: Sized {
     /// [`NewRng`]: https://docs.rs/rand/0.5/rand/trait.NewRng.html
     /// [`OsRng`]: https://docs.rs/rand/0.5/rand/os/struct.OsRng.html
     /// [`XorShiftRng`]: https://docs.rs/rand/0.5/rand/prng/xorshift/struct.XorShiftRng.html
-    fn from_rng<R: RngCore>(rng: &mut R) -> Result<Self, Error> {
+    fn from_rng<R: RngCore + ?Sized>(rng: &mut R) -> Result<Self, Error> {
         let mut seed = Self::Seed::default();
         rng.try_fill_bytes(seed.as_mut())?;
         Ok(Self::from_seed(seed))
@@ -282,8 +420,20 @@ impl<'a, R: RngCore + ?Sized> RngCore for &'a mut R {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         (**self).try_fill_bytes(dest)
     }
+
+    #[inline]
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        (**self).fill_u32_slice(dest)
+    }
+
+    #[inline]
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        (**self).fill_u64_slice(dest)
+    }
 }
 
+impl<'a, R: CryptoRng + ?Sized> CryptoRng for &'a mut R {}
+
 #[cfg(any(feature="std", feature="alloc"))]
 impl<R: RngCore + ?Sized> RngCore for Box<R> {
     #[inline]
@@ -305,4 +455,83 @@ impl<R: RngCore + ?Sized> RngCore for Box<R> {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         (**self).try_fill_bytes(dest)
     }
+
+    #[inline]
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        (**self).fill_u32_slice(dest)
+    }
+
+    #[inline]
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        (**self).fill_u64_slice(dest)
+    }
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl<R: CryptoRng + ?Sized> CryptoRng for Box<R> {}
+
+#[cfg(test)]
+mod test {
+    use core::hash::Hasher;
+    use hash::SeedHasher;
+    use le;
+    use SeedableRng;
+
+    struct SeedRecorder([u8; 20]);
+
+    impl SeedableRng for SeedRecorder {
+        type Seed = [u8; 20];
+        fn from_seed(seed: [u8; 20]) -> SeedRecorder {
+            SeedRecorder(seed)
+        }
+    }
+
+    #[test]
+    fn test_seed_from_u64() {
+        let seed = SeedRecorder::seed_from_u64(1234567).0;
+        let mut words = [0u64; 2];
+        le::read_u64_into(&seed[..16], &mut words);
+        assert_eq!(words, [6457827717110365317, 3203168211198807973]);
+        // The last chunk takes the low bytes of the third value
+        let mut last = [0u32; 1];
+        le::read_u32_into(&seed[16..], &mut last);
+        assert_eq!(last[0], 9817491932198370423u64 as u32);
+
+        assert!(SeedRecorder::seed_from_u64(0).0 != SeedRecorder::seed_from_u64(1).0);
+        assert_eq!(SeedRecorder::seed_from_u64(7).0, SeedRecorder::seed_from_u64(7).0);
+    }
+
+    #[test]
+    fn test_from_hashable() {
+        let seed = SeedRecorder::from_hashable("level-42/chunk-7").0;
+        let mut words = [0u64; 2];
+        le::read_u64_into(&seed[..16], &mut words);
+        assert_eq!(words, [687424141130118637, 9360948154361144004]);
+        let mut last = [0u32; 1];
+        le::read_u32_into(&seed[16..], &mut last);
+        assert_eq!(last[0], 12437127659316408348u64 as u32);
+
+        let seed = SeedRecorder::from_hashable(&42u64).0;
+        le::read_u64_into(&seed[..16], &mut words);
+        assert_eq!(words, [11209826275224755505, 12982946451702789700]);
+        // `usize` is hashed as a `u64` on all platforms
+        assert_eq!(SeedRecorder::from_hashable(&42usize).0, seed);
+
+        assert!(SeedRecorder::from_hashable(&("level", 42)).0
+                != SeedRecorder::from_hashable(&("level", 43)).0);
+    }
+
+    #[test]
+    fn test_seed_hasher_128() {
+        // `u128` and `i128` are hashed as their little-endian bytes
+        let x = 0x0f0e_0d0c_0b0a_0908_0706_0504_0302_0100u128;
+        let mut h1 = SeedHasher::new();
+        h1.write_u128(x);
+        let mut h2 = SeedHasher::new();
+        h2.write(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(h1.finish(), h2.finish());
+        let mut h3 = SeedHasher::new();
+        h3.write_i128(x as i128);
+        assert_eq!(h1.finish(), h3.finish());
+    }
 }
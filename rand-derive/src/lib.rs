@@ -1,5 +1,17 @@
 //! Support for `#[derive(Rand)]`
 //!
+//! The derive implements `Distribution<T> for Uniform`, so that values of the
+//! type can be generated with `Rng::gen` and `rand::random`. Every field is
+//! sampled from `Uniform`.
+//!
+//! For enums, a variant is picked uniformly at random by default. Variants
+//! can be given relative weights with `#[rand(weight = N)]`; variants without
+//! the attribute have weight 1, and variants with weight 0 are never picked.
+//! An enum with weights also gets a generated distribution type, named after
+//! the enum with a `Distribution` suffix and with the same visibility, which
+//! samples the variants with these weights; `Uniform` samples the enum with
+//! it.
+//!
 //! # Examples
 //!
 //! ```
@@ -13,104 +25,206 @@
 //!     b: u32,
 //! }
 //!
+//! // Mostly `Ok`, occasionally `Retry`, rarely `Fail`
+//! #[derive(Rand, Debug)]
+//! enum Response {
+//!     #[rand(weight = 90)]
+//!     Ok,
+//!     #[rand(weight = 9)]
+//!     Retry { after: u8 },
+//!     Fail(u16),
+//! }
+//!
 //! fn main() {
 //!     println!("{:?}", rand::random::<MyStruct>());
+//!     println!("{:?}", rand::random::<Response>());
+//!
+//!     use rand::distributions::Distribution;
+//!     let mut rng = rand::thread_rng();
+//!     println!("{:?}", ResponseDistribution.sample(&mut rng));
 //! }
 //! ```
 
 extern crate proc_macro;
+extern crate proc_macro2;
 #[macro_use]
 extern crate quote;
 extern crate syn;
 
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as Tokens;
+use syn::{Data, DeriveInput, Error, Fields, Ident, LitInt, Type, Variant};
 
-#[proc_macro_derive(Rand)]
+#[proc_macro_derive(Rand, attributes(rand))]
 pub fn rand_derive(input: TokenStream) -> TokenStream {
-    let s = input.to_string();
-    let ast = syn::parse_derive_input(&s).unwrap();
-    let gen = impl_rand_derive(&ast);
-    gen.parse().unwrap()
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    match impl_rand_derive(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
 
-fn impl_rand_derive(ast: &syn::MacroInput) -> quote::Tokens {
+fn impl_rand_derive(ast: &DeriveInput) -> Result<Tokens, Error> {
     let name = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    let rand = match ast.body {
-        syn::Body::Struct(syn::VariantData::Struct(ref body)) => {
-            let fields = body
-                .iter()
-                .filter_map(|field| field.ident.as_ref())
-                .map(|ident| quote! { #ident: __rng.gen() })
-                .collect::<Vec<_>>();
-
-            quote! { #name { #(#fields,)* } }
-        },
-        syn::Body::Struct(syn::VariantData::Tuple(ref body)) => {
-            let fields = (0..body.len())
-                .map(|_| quote! { __rng.gen() })
-                .collect::<Vec<_>>();
+    let mut field_types = Vec::new();
+    let mut weighted = false;
 
-            quote! { #name (#(#fields),*) }
+    let rand = match ast.data {
+        Data::Struct(ref data) => {
+            field_types.extend(data.fields.iter().map(|f| &f.ty));
+            construct(quote!(#name), &data.fields)
         },
-        syn::Body::Struct(syn::VariantData::Unit) => {
-            quote! { #name }
-        },
-        syn::Body::Enum(ref body) => {
-            if body.is_empty() {
-                panic!("`Rand` cannot be derived for enums with no variants");
+        Data::Enum(ref data) => {
+            if data.variants.is_empty() {
+                return Err(Error::new_spanned(name,
+                        "`Rand` cannot be derived for enums with no variants"));
+            }
+            let mut arms = Vec::new();
+            for variant in data.variants.iter() {
+                field_types.extend(variant.fields.iter().map(|f| &f.ty));
+                let ident = &variant.ident;
+                let weight = weight(variant)?;
+                weighted |= weight.is_some();
+                arms.push((weight.unwrap_or(1),
+                           construct(quote!(#name::#ident), &variant.fields)));
             }
+            choose(name, arms)?
+        },
+        Data::Union(_) => return Err(Error::new_spanned(name,
+                "`Rand` cannot be derived for unions")),
+    };
 
-            let len = body.len();
-            let mut arms = body
-                .iter()
-                .map(|variant| {
-                    let ident = &variant.ident;
-                    match variant.data {
-                        syn::VariantData::Struct(ref body) => {
-                            let fields = body
-                                .iter()
-                                .filter_map(|field| field.ident.as_ref())
-                                .map(|ident| quote! { #ident: __rng.gen() })
-                                .collect::<Vec<_>>();
-                            quote! { #name::#ident { #(#fields,)* } }
-                        },
-                        syn::VariantData::Tuple(ref body) => {
-                            let fields = (0..body.len())
-                                .map(|_| quote! { __rng.gen() })
-                                .collect::<Vec<_>>();
+    if !weighted {
+        return Ok(impl_distribution(ast, &field_types,
+                                    quote!(::rand::distributions::Uniform), rand));
+    }
 
-                            quote! { #name::#ident (#(#fields),*) }
-                        },
-                        syn::VariantData::Unit => quote! { #name::#ident }
-                    }
-                });
+    // Sample weighted enums with a dedicated distribution, which `Uniform`
+    // delegates to
+    let vis = &ast.vis;
+    let distr = Ident::new(&format!("{}Distribution", name), name.span());
+    let doc = format!("Samples the variants of [`{}`] with the relative weights given by \
+                       their `#[rand(weight = N)]` attributes.", name);
+    let distr_impl = impl_distribution(ast, &field_types, quote!(#distr), rand);
+    let uniform_impl = impl_distribution(ast, &field_types,
+        quote!(::rand::distributions::Uniform),
+        quote! { ::rand::distributions::Distribution::sample(&#distr, __rng) });
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug)]
+        #vis struct #distr;
 
-            match len {
-                1 => quote! { #(#arms)* },
-                2 => {
-                    let (a, b) = (arms.next(), arms.next());
-                    quote! { if __rng.gen() { #a } else { #b } }
-                },
-                _ => {
-                    let mut variants = arms
-                        .enumerate()
-                        .map(|(index, arm)| quote! { #index => #arm })
-                        .collect::<Vec<_>>();
-                    variants.push(quote! { _ => unreachable!() });
-                    quote! { match __rng.gen_range(0, #len) { #(#variants,)* } }
-                },
-            }
-        }
-    };
+        #distr_impl
+        #uniform_impl
+    })
+}
 
+// An implementation of `Distribution` for `distr`, sampling values of the
+// derived type with the expression `sample`.
+fn impl_distribution(ast: &DeriveInput, field_types: &[&Type], distr: Tokens, sample: Tokens)
+    -> Tokens
+{
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let predicates = where_clause.map(|w| &w.predicates);
     quote! {
-        impl #impl_generics ::rand::Rand for #name #ty_generics #where_clause {
+        impl #impl_generics ::rand::distributions::Distribution<#name #ty_generics>
+            for #distr
+            where #(::rand::distributions::Uniform:
+                        ::rand::distributions::Distribution<#field_types>,)*
+                  #predicates
+        {
             #[inline]
-            fn rand<__R: ::rand::Rng>(__rng: &mut __R) -> Self {
-                #rand
+            fn sample<__R: ::rand::Rng + ?Sized>(&self, __rng: &mut __R)
+                -> #name #ty_generics
+            {
+                #sample
+            }
+        }
+    }
+}
+
+// An expression constructing `path` with random fields.
+fn construct(path: Tokens, fields: &Fields) -> Tokens {
+    match *fields {
+        Fields::Named(ref fields) => {
+            let fields = fields.named.iter()
+                .map(|field| {
+                    let ident = &field.ident;
+                    quote! { #ident: __rng.gen() }
+                });
+            quote! { #path { #(#fields,)* } }
+        },
+        Fields::Unnamed(ref fields) => {
+            let fields = fields.unnamed.iter().map(|_| quote! { __rng.gen() });
+            quote! { #path (#(#fields),*) }
+        },
+        Fields::Unit => path,
+    }
+}
+
+// The weight of a variant, if given by `#[rand(weight = N)]`.
+fn weight(variant: &Variant) -> Result<Option<u64>, Error> {
+    let mut weight = None;
+    for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("rand")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("weight") {
+                let lit: LitInt = meta.value()?.parse()?;
+                weight = Some(lit.base10_parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported `rand` attribute, expected `weight`"))
             }
+        })?;
+    }
+    Ok(weight)
+}
+
+// An expression picking one of `arms` with probability proportional to its
+// weight.
+fn choose(name: &Ident, arms: Vec<(u64, Tokens)>) -> Result<Tokens, Error> {
+    let total = arms.iter()
+        .try_fold(0u64, |sum, &(weight, _)| sum.checked_add(weight))
+        .ok_or_else(|| Error::new_spanned(name, "the sum of the weights overflows `u64`"))?;
+    if total == 0 {
+        return Err(Error::new_spanned(name, "all variants have weight 0"));
+    }
+
+    if arms.iter().all(|&(weight, _)| weight == 1) {
+        // Keep the output of the unweighted version
+        let len = arms.len();
+        let mut arms = arms.into_iter().map(|(_, arm)| arm);
+        return Ok(match len {
+            1 => quote! { #(#arms)* },
+            2 => {
+                let (a, b) = (arms.next(), arms.next());
+                quote! { if __rng.gen() { #a } else { #b } }
+            },
+            _ => {
+                let mut variants = arms
+                    .enumerate()
+                    .map(|(index, arm)| quote! { #index => #arm })
+                    .collect::<Vec<_>>();
+                variants.push(quote! { _ => unreachable!() });
+                quote! { match __rng.gen_range(0..#len) { #(#variants,)* } }
+            },
+        });
+    }
+
+    let arms = arms.into_iter().filter(|&(weight, _)| weight > 0).collect::<Vec<_>>();
+    let last = arms.len() - 1;
+    let mut cumulative = 0;
+    let mut branches = Vec::new();
+    for (i, (weight, arm)) in arms.into_iter().enumerate() {
+        cumulative += weight;
+        if i == last {
+            branches.push(quote! { { #arm } });
+        } else {
+            branches.push(quote! { if __x < #cumulative { #arm } else });
         }
     }
+    Ok(quote! {
+        let __x: u64 = __rng.gen_range(0..#total);
+        #(#branches)*
+    })
 }
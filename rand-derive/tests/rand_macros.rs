@@ -5,6 +5,7 @@ extern crate rand;
 extern crate rand_derive;
 
 use rand::Rng;
+use rand::distributions::Distribution;
 
 #[derive(Rand)]
 struct Struct {
@@ -56,3 +57,57 @@ fn smoke() {
         let _ = rng.gen::<Enum3>();
     }
 }
+
+#[derive(Rand, Debug, PartialEq)]
+enum Weighted {
+    #[rand(weight = 6)]
+    A,
+    B(u8),
+    #[rand(weight = 0)]
+    C,
+    #[rand(weight = 3)]
+    D { x: bool },
+}
+
+#[test]
+fn weighted() {
+    let mut rng = rand::XorShiftRng::new_unseeded();
+
+    let mut counts = [0; 4];
+    for i in 0..10000 {
+        let x = if i % 2 == 0 {
+            rng.gen::<Weighted>()
+        } else {
+            WeightedDistribution.sample(&mut rng)
+        };
+        match x {
+            Weighted::A => counts[0] += 1,
+            Weighted::B(_) => counts[1] += 1,
+            Weighted::C => counts[2] += 1,
+            Weighted::D { .. } => counts[3] += 1,
+        }
+    }
+    // Expected: 6000, 1000, 0, 3000
+    assert!(counts[0] > 5700 && counts[0] < 6300);
+    assert!(counts[1] > 850 && counts[1] < 1150);
+    assert_eq!(counts[2], 0);
+    assert!(counts[3] > 2700 && counts[3] < 3300);
+}
+
+#[derive(Rand)]
+enum Generic<T> {
+    #[rand(weight = 0)]
+    None,
+    Some(T),
+}
+
+#[test]
+fn weighted_generic() {
+    let mut rng = rand::XorShiftRng::new_unseeded();
+    for _ in 0..100 {
+        match GenericDistribution.sample(&mut rng) {
+            Generic::Some(x) => { let _: u8 = x; },
+            Generic::None => panic!("`None` has weight 0"),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Random arrays of the [ndarray] crate.
+//!
+//! The [`RandomExt`] trait adds methods to construct and fill arrays of any
+//! shape with values sampled from a [`Distribution`]. Contiguous arrays are
+//! filled with [`Distribution::sample_fill`], so they benefit from its fast
+//! paths.
+//!
+//! [ndarray]: https://docs.rs/ndarray
+//! [`RandomExt`]: trait.RandomExt.html
+//! [`Distribution`]: ../distributions/trait.Distribution.html
+//! [`Distribution::sample_fill`]: ../distributions/trait.Distribution.html#method.sample_fill
+
+use ndarray::{ArrayBase, DataMut, DataOwned, Dimension, RawData, ShapeBuilder};
+
+use Rng;
+use distributions::Distribution;
+
+/// Extension trait constructing and filling arrays with random values.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate ndarray;
+/// extern crate rand;
+///
+/// use ndarray::Array2;
+/// use rand::thread_rng;
+/// use rand::array::RandomExt;
+/// use rand::distributions::{Range, StandardNormal};
+///
+/// # fn main() {
+/// let mut rng = thread_rng();
+///
+/// // A 3 x 4 matrix of normally distributed values
+/// let mut matrix = Array2::<f64>::random_using((3, 4), StandardNormal, &mut rng);
+/// assert_eq!(matrix.shape(), &[3, 4]);
+///
+/// // Overwrite the first row with values from `[0, 10)`
+/// matrix.row_mut(0).fill_using(Range::new(0.0, 10.0), &mut rng);
+/// # }
+/// ```
+pub trait RandomExt<S, A, D>
+    where S: RawData<Elem = A>, D: Dimension
+{
+    /// Create an array of the given shape, with elements sampled from
+    /// `distr` using `rng` as the source of randomness.
+    ///
+    /// Elements are generated in the memory order of the array, which is
+    /// row-major unless `shape` asks for column-major order with `.f()`.
+    fn random_using<Sh, T, R>(shape: Sh, distr: T, rng: &mut R) -> ArrayBase<S, D>
+        where S: DataOwned<Elem = A> + DataMut,
+              A: Clone + Default,
+              Sh: ShapeBuilder<Dim = D>,
+              T: Distribution<A>,
+              R: Rng + ?Sized;
+
+    /// Overwrite all elements of the array with values sampled from `distr`,
+    /// using `rng` as the source of randomness.
+    ///
+    /// Contiguous arrays are filled in memory order, other arrays in
+    /// logical (row-major) order.
+    fn fill_using<T, R>(&mut self, distr: T, rng: &mut R)
+        where S: DataMut<Elem = A>,
+              T: Distribution<A>,
+              R: Rng + ?Sized;
+}
+
+impl<S, A, D> RandomExt<S, A, D> for ArrayBase<S, D>
+    where S: RawData<Elem = A>, D: Dimension
+{
+    fn random_using<Sh, T, R>(shape: Sh, distr: T, rng: &mut R) -> ArrayBase<S, D>
+        where S: DataOwned<Elem = A> + DataMut,
+              A: Clone + Default,
+              Sh: ShapeBuilder<Dim = D>,
+              T: Distribution<A>,
+              R: Rng + ?Sized
+    {
+        let mut array = ArrayBase::from_elem(shape, A::default());
+        array.fill_using(distr, rng);
+        array
+    }
+
+    fn fill_using<T, R>(&mut self, distr: T, rng: &mut R)
+        where S: DataMut<Elem = A>,
+              T: Distribution<A>,
+              R: Rng + ?Sized
+    {
+        if let Some(slice) = self.as_slice_memory_order_mut() {
+            distr.sample_fill(rng, slice);
+            return;
+        }
+        for x in self.iter_mut() {
+            *x = distr.sample(rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ndarray::{Array, Array1, Array2, Array3, Axis, ShapeBuilder};
+    use {SeedableRng, ChaChaRng};
+    use distributions::{Distribution, Range, Uniform};
+    use super::*;
+
+    #[test]
+    fn test_random_using() {
+        let mut rng = ::test::rng(427);
+        let a = Array3::<u32>::random_using((2, 3, 4), Range::new(10u32, 20), &mut rng);
+        assert_eq!(a.shape(), &[2, 3, 4]);
+        assert!(a.iter().all(|&x| 10 <= x && x < 20));
+
+        let b = Array2::<f64>::random_using((0, 5), Uniform, &mut rng);
+        assert_eq!(b.len(), 0);
+    }
+
+    #[test]
+    fn test_random_using_matches_sample_fill() {
+        // Both row- and column-major arrays are filled in memory order.
+        let mut expected = [0u64; 12];
+        Uniform.sample_fill(&mut ChaChaRng::from_seed([7; 32]), &mut expected[..]);
+
+        let c = Array2::<u64>::random_using((3, 4), Uniform,
+                                            &mut ChaChaRng::from_seed([7; 32]));
+        assert_eq!(c.as_slice().unwrap(), &expected[..]);
+
+        let f = Array2::<u64>::random_using((3, 4).f(), Uniform,
+                                            &mut ChaChaRng::from_seed([7; 32]));
+        assert_eq!(f.as_slice_memory_order().unwrap(), &expected[..]);
+        assert_eq!(f.t().as_slice().unwrap(), &expected[..]);
+    }
+
+    #[test]
+    fn test_fill_using() {
+        let mut rng = ::test::rng(428);
+        let mut a = Array::from_elem((4, 5), 0i8);
+
+        // A non-contiguous view
+        a.column_mut(2).fill_using(Range::new(1i8, 10), &mut rng);
+        for (j, column) in a.axis_iter(Axis(1)).enumerate() {
+            assert!(column.iter().all(|&x| (x != 0) == (j == 2)));
+        }
+
+        let mut v = Array1::<bool>::from_elem(100, false);
+        v.fill_using(Uniform, &mut rng);
+        assert!(v.iter().any(|&x| x) && v.iter().any(|&x| !x));
+    }
+}
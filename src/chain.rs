@@ -0,0 +1,172 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A wrapper falling back to a secondary RNG when the primary one fails.
+
+use core::fmt;
+use {RngCore, CryptoRng, Error};
+use rand_core::impls;
+
+/// An RNG using a primary generator, and falling back to a secondary one for
+/// the requests on which the primary fails.
+///
+/// Every request is first passed to the primary generator's
+/// `try_fill_bytes`. If that returns an error, the request is served by the
+/// secondary generator instead; the next request tries the primary one
+/// again. If both fail, the error of the primary generator is returned (and
+/// `next_u32`, `next_u64` and `fill_bytes` panic).
+///
+/// This is what `EntropyRng` does with `OsRng` and `JitterRng`, for arbitrary
+/// sources. Longer fallback chains can be built by nesting: the secondary
+/// generator can itself be a `ChainRng`.
+///
+/// Failovers are counted by [`failovers`], and can be observed with a hook
+/// set by [`set_hook`], e.g. to log them or raise an alarm. With the `log`
+/// feature they are also logged as warnings.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, OsRng, JitterRng};
+/// use rand::chain::ChainRng;
+///
+/// fn report(err: &rand::Error) {
+///     eprintln!("OsRng failed, using JitterRng: {}", err);
+/// }
+///
+/// let mut rng = ChainRng::new(OsRng::new().unwrap(), JitterRng::new().unwrap());
+/// rng.set_hook(report);
+/// let seed: [u8; 32] = rng.gen();
+/// # let _ = seed;
+/// ```
+///
+/// [`failovers`]: #method.failovers
+/// [`set_hook`]: #method.set_hook
+#[derive(Clone)]
+pub struct ChainRng<A, B> {
+    primary: A,
+    secondary: B,
+    failovers: u64,
+    hook: Option<fn(&Error)>,
+}
+
+impl<A: RngCore, B: RngCore> ChainRng<A, B> {
+    /// Create a `ChainRng` using `primary`, and `secondary` when `primary`
+    /// fails.
+    pub fn new(primary: A, secondary: B) -> ChainRng<A, B> {
+        ChainRng { primary: primary, secondary: secondary, failovers: 0,
+                   hook: None }
+    }
+
+    /// Set a function to be called with the error of the primary generator
+    /// on every failover.
+    pub fn set_hook(&mut self, hook: fn(&Error)) {
+        self.hook = Some(hook);
+    }
+
+    /// The number of requests served by the secondary generator (or on which
+    /// both failed).
+    pub fn failovers(&self) -> u64 {
+        self.failovers
+    }
+
+    /// Unwrap the inner generators.
+    pub fn into_inner(self) -> (A, B) {
+        (self.primary, self.secondary)
+    }
+}
+
+impl<A: fmt::Debug, B: fmt::Debug> fmt::Debug for ChainRng<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ChainRng")
+            .field("primary", &self.primary)
+            .field("secondary", &self.secondary)
+            .field("failovers", &self.failovers)
+            .finish()
+    }
+}
+
+impl<A: RngCore, B: RngCore> RngCore for ChainRng<A, B> {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|err|
+                panic!("ChainRng: all generators failed; first error: {}", err))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        let err = match self.primary.try_fill_bytes(dest) {
+            Ok(()) => return Ok(()),
+            Err(err) => err,
+        };
+        warn!("ChainRng: primary RNG failed [falling back to secondary]: {}",
+              err);
+        self.failovers += 1;
+        if let Some(hook) = self.hook {
+            hook(&err);
+        }
+        match self.secondary.try_fill_bytes(dest) {
+            Ok(()) => Ok(()),
+            Err(_secondary_err) => {
+                warn!("ChainRng: secondary RNG failed: {}", _secondary_err);
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<A: RngCore + CryptoRng, B: RngCore + CryptoRng> CryptoRng for ChainRng<A, B> {}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use {RngCore, Error, ErrorKind};
+    use mock::{StepRng, FaultyRng};
+    use super::ChainRng;
+
+    static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn hook(err: &Error) {
+        assert_eq!(err.kind, ErrorKind::NotReady);
+        HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_chain_rng() {
+        // The primary fails on calls 1 and 2
+        let primary = FaultyRng::new(StepRng::new(1, 0), |call| {
+            if call == 1 || call == 2 { Some(ErrorKind::NotReady) } else { None }
+        });
+        let mut rng = ChainRng::new(primary, StepRng::new(2, 0));
+        rng.set_hook(hook);
+        assert_eq!(rng.next_u64(), 1);
+        assert_eq!(rng.next_u64(), 2);
+        assert_eq!(rng.next_u32(), 2);
+        assert_eq!(rng.next_u64(), 1);
+        assert_eq!(rng.failovers(), 2);
+        assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 2);
+
+        // Both fail: the error of the primary is returned
+        let primary = FaultyRng::new(StepRng::new(0, 0),
+                                     |_| Some(ErrorKind::Unavailable));
+        let secondary = FaultyRng::new(StepRng::new(0, 0),
+                                       |_| Some(ErrorKind::Transient));
+        let mut rng = ChainRng::new(primary, secondary);
+        assert_eq!(rng.try_fill_bytes(&mut [0u8; 4]).unwrap_err().kind,
+                   ErrorKind::Unavailable);
+        assert_eq!(rng.failovers(), 1);
+    }
+}
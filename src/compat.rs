@@ -0,0 +1,212 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Adapters between `RngCore` and generators written against the `Rng`
+//! trait of rand 0.3 and 0.4.
+//!
+//! Before `RngCore` existed, generators implemented `Rng` directly, with only
+//! `next_u32` required and the other methods implemented in terms of it.
+//! [`LegacyRng`] mirrors that interface. To migrate a code base
+//! incrementally, implement `LegacyRng` for the old generators (usually by
+//! forwarding to the methods of their old `Rng` implementation), and wrap
+//! them in [`FromLegacy`] to pass them to code using `RngCore`.
+//! [`ToLegacy`] converts in the other direction.
+//!
+//! [`LegacyRng`]: trait.LegacyRng.html
+//! [`FromLegacy`]: struct.FromLegacy.html
+//! [`ToLegacy`]: struct.ToLegacy.html
+
+use {RngCore, Error};
+
+/// The core methods of the `Rng` trait of rand 0.3 and 0.4.
+///
+/// The default implementations of `next_u64` and `fill_bytes` produce the same
+/// values as those of the old `Rng` trait.
+pub trait LegacyRng {
+    /// Return the next random `u32`.
+    fn next_u32(&mut self) -> u32;
+
+    /// Return the next random `u64`.
+    ///
+    /// By default this is made from two `u32` values, the first being the high
+    /// half.
+    fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | (self.next_u32() as u64)
+    }
+
+    /// Fill `dest` with random data.
+    ///
+    /// By default this uses `next_u64`, filling the bytes of each value in
+    /// little-endian order.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let mut x = self.next_u64();
+            for byte in chunk.iter_mut() {
+                *byte = x as u8;
+                x >>= 8;
+            }
+        }
+    }
+}
+
+impl<'a, R: LegacyRng + ?Sized> LegacyRng for &'a mut R {
+    fn next_u32(&mut self) -> u32 {
+        (**self).next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (**self).next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        (**self).fill_bytes(dest)
+    }
+}
+
+/// A wrapper implementing `RngCore` for a generator with the old interface.
+///
+/// All values are passed through unchanged, and `try_fill_bytes` never fails.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::Rng;
+/// use rand::compat::{LegacyRng, FromLegacy};
+///
+/// // A generator written for rand 0.4
+/// struct Counter(u32);
+///
+/// impl LegacyRng for Counter {
+///     fn next_u32(&mut self) -> u32 {
+///         self.0 = self.0.wrapping_add(0x9E37_79B9);
+///         self.0
+///     }
+/// }
+///
+/// let mut rng = FromLegacy::new(Counter(0));
+/// let x = rng.gen_range(0..10);
+/// assert!(x < 10);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FromLegacy<R> {
+    rng: R,
+}
+
+impl<R: LegacyRng> FromLegacy<R> {
+    /// Create a new `FromLegacy` wrapping `rng`.
+    pub fn new(rng: R) -> FromLegacy<R> {
+        FromLegacy { rng: rng }
+    }
+
+    /// Unwrap the inner generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: LegacyRng> RngCore for FromLegacy<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.rng.fill_bytes(dest))
+    }
+}
+
+/// A wrapper implementing the old interface for an `RngCore`.
+///
+/// All values are passed through unchanged. The old interface cannot report
+/// errors, so this panics where the wrapped generator's `fill_bytes` would.
+#[derive(Clone, Debug)]
+pub struct ToLegacy<R> {
+    rng: R,
+}
+
+impl<R: RngCore> ToLegacy<R> {
+    /// Create a new `ToLegacy` wrapping `rng`.
+    pub fn new(rng: R) -> ToLegacy<R> {
+        ToLegacy { rng: rng }
+    }
+
+    /// Unwrap the inner generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: RngCore> LegacyRng for ToLegacy<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {Rng, RngCore};
+    use mock::StepRng;
+    use super::{LegacyRng, FromLegacy, ToLegacy};
+
+    struct OldStepRng(u32);
+
+    impl LegacyRng for OldStepRng {
+        fn next_u32(&mut self) -> u32 {
+            self.0 += 1;
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_from_legacy() {
+        let mut rng = FromLegacy::new(OldStepRng(0));
+        assert_eq!(rng.next_u32(), 1);
+        // High half first, as in rand 0.4
+        assert_eq!(rng.next_u64(), (2 << 32) | 3);
+        let mut buf = [0u8; 10];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf, [5, 0, 0, 0, 4, 0, 0, 0, 7, 0]);
+        assert!(rng.gen_range(0..10) < 10);
+        assert_eq!(rng.into_inner().0, 8);
+    }
+
+    #[test]
+    fn test_to_legacy() {
+        let mut rng = ToLegacy::new(StepRng::new(1, 1));
+        {
+            // Usable by code taking `&mut LegacyRng`
+            let legacy: &mut LegacyRng = &mut rng;
+            assert_eq!(legacy.next_u64(), 1);
+            assert_eq!(legacy.next_u32(), 2);
+        }
+        let mut buf = [0u8; 3];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf, [3, 0, 0]);
+
+        // A round trip leaves the values unchanged
+        let mut back = FromLegacy::new(rng);
+        assert_eq!(back.next_u64(), 4);
+    }
+}
@@ -0,0 +1,483 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Bernoulli distribution.
+
+use Rng;
+use distributions::{Distribution, ParamError};
+#[cfg(feature="std")]
+use distributions::OpenClosed01;
+use distributions::range::{Range, RangeInt};
+
+// 2^64, as a float; `u64::MAX as f64` would round to the same value.
+const SCALE: f64 = 2.0 * (1u64 << 63) as f64;
+
+/// The Bernoulli distribution.
+///
+/// This is a special case of the Binomial distribution where `n = 1`: each
+/// sample is `true` with probability `p` and `false` otherwise.
+///
+/// # Representation
+///
+/// The way a `Bernoulli` decides is part of its documented behaviour, so that
+/// a given parameter always produces the same sequence of decisions from the
+/// same RNG stream, on every platform:
+///
+/// - `Bernoulli::new(p)` stores `p` as the integer threshold
+///   `p_int = (p * 2^64) as u64` and returns `rng.next_u64() < p_int`. Since
+///   `p` is a `f64`, the result is only as precise as that threshold, i.e.
+///   to within 2<sup>-64</sup>. `p == 1.0` is special-cased and always
+///   returns `true` without consuming any randomness.
+/// - `Bernoulli::from_ratio(numerator, denominator)` stores the ratio exactly.
+///   It draws `x` uniformly from `[0, denominator)` using `Range<u32>` and
+///   returns `x < numerator`, so the probability is exactly
+///   `numerator / denominator`. Ratios equal to 0 or 1 are special-cased and
+///   never consume randomness.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::distributions::{Bernoulli, Distribution};
+///
+/// let d = Bernoulli::from_ratio(2, 3);
+/// let v = d.sample(&mut rand::thread_rng());
+/// println!("{} is from a Bernoulli distribution", v);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Bernoulli {
+    repr: Repr,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Repr {
+    /// True if `rng.next_u64()` is below the threshold.
+    Threshold(u64),
+    /// Probability 1, which cannot be represented by a `u64` threshold.
+    AlwaysTrue,
+    /// True if a value sampled from `[0, denominator)` is below `numerator`.
+    Ratio {
+        numerator: u32,
+        #[cfg_attr(not(any(feature="std", feature="serde-1")), allow(dead_code))]
+        denominator: u32,
+        range: Range<RangeInt<u32>>,
+    },
+}
+
+impl Bernoulli {
+    /// Construct a new `Bernoulli` with the given probability of success `p`.
+    ///
+    /// Panics if `p < 0` or `p > 1`.
+    #[inline]
+    pub fn new(p: f64) -> Bernoulli {
+        Bernoulli::try_new(p)
+            .unwrap_or_else(|e| panic!("Bernoulli::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct a new `Bernoulli` with the given probability of success `p`,
+    /// or return an error if `p` is not in `[0, 1]`.
+    #[inline]
+    pub fn try_new(p: f64) -> Result<Bernoulli, ParamError> {
+        if !(p >= 0.0 && p <= 1.0) {
+            return Err(ParamError::OutOfRange("p"));
+        }
+        if p == 1.0 {
+            return Ok(Bernoulli { repr: Repr::AlwaysTrue });
+        }
+        Ok(Bernoulli { repr: Repr::Threshold((p * SCALE) as u64) })
+    }
+
+    /// Construct a new `Bernoulli` with a probability of success of exactly
+    /// `numerator / denominator`.
+    ///
+    /// No floating-point arithmetic is involved, so this is exact for every
+    /// rational probability with a `u32` numerator and denominator.
+    ///
+    /// Panics if `denominator == 0` or `numerator > denominator`.
+    #[inline]
+    pub fn from_ratio(numerator: u32, denominator: u32) -> Bernoulli {
+        Bernoulli::try_from_ratio(numerator, denominator).unwrap_or_else(|e| {
+            panic!("Bernoulli::from_ratio called with invalid parameters: {}", e)
+        })
+    }
+
+    /// Construct a new `Bernoulli` with a probability of success of exactly
+    /// `numerator / denominator`, or return an error if `denominator == 0`
+    /// or `numerator > denominator`.
+    #[inline]
+    pub fn try_from_ratio(numerator: u32, denominator: u32)
+        -> Result<Bernoulli, ParamError>
+    {
+        if denominator == 0 {
+            return Err(ParamError::NotPositive("denominator"));
+        }
+        if numerator > denominator {
+            return Err(ParamError::OutOfRange("numerator"));
+        }
+        if numerator == denominator {
+            return Ok(Bernoulli { repr: Repr::AlwaysTrue });
+        }
+        if numerator == 0 {
+            return Ok(Bernoulli { repr: Repr::Threshold(0) });
+        }
+        Ok(Bernoulli { repr: Repr::Ratio {
+            numerator: numerator,
+            denominator: denominator,
+            range: Range::new(0, denominator),
+        } })
+    }
+
+    /// Return an iterator over the indices of the successes in an infinite
+    /// stream of trials with this distribution.
+    ///
+    /// Rather than sampling each trial, the gaps between successes are
+    /// sampled from the geometric distribution, using one random `f64` per
+    /// success. For small probabilities this is much faster than sampling
+    /// every trial, e.g. to simulate rare events over billions of trials.
+    ///
+    /// The indices are distributed exactly as those of the `true` samples of
+    /// repeated calls to `sample`, up to the precision of `f64`, but they are
+    /// not the same indices for the same RNG stream.
+    ///
+    /// The iterator ends if the next index would not fit in a `u64`, and
+    /// immediately if the probability is 0. With probability 1 it yields
+    /// every index without consuming randomness.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::distributions::Bernoulli;
+    ///
+    /// let d = Bernoulli::new(1e-6);
+    /// let events: Vec<u64> = d.sample_indices(rand::thread_rng())
+    ///     .take_while(|&i| i < 1_000_000_000)
+    ///     .collect();
+    /// println!("{} events in 10^9 trials", events.len());
+    /// ```
+    #[cfg(feature="std")]
+    pub fn sample_indices<R: Rng>(&self, rng: R) -> SampleIndices<R> {
+        let p = match self.repr {
+            Repr::Threshold(p_int) => p_int as f64 / SCALE,
+            Repr::AlwaysTrue => 1.0,
+            Repr::Ratio { numerator, denominator, .. } => {
+                numerator as f64 / denominator as f64
+            }
+        };
+        SampleIndices {
+            rng: rng,
+            ln_q: (-p).ln_1p(),
+            next: Some(0),
+        }
+    }
+}
+
+/// An iterator over the indices of the successes in an infinite stream of
+/// Bernoulli trials.
+///
+/// This `struct` is created by [`Bernoulli::sample_indices`]. See its
+/// documentation for more.
+///
+/// [`Bernoulli::sample_indices`]: struct.Bernoulli.html#method.sample_indices
+#[cfg(feature="std")]
+#[derive(Debug)]
+pub struct SampleIndices<R> {
+    rng: R,
+    /// `ln(1 - p)`: 0 if `p == 0`, -∞ if `p == 1`.
+    ln_q: f64,
+    /// The index of the first trial not yet sampled.
+    next: Option<u64>,
+}
+
+#[cfg(feature="std")]
+impl<R: Rng> Iterator for SampleIndices<R> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let next = self.next?;
+        if self.ln_q == 0.0 {
+            self.next = None;
+            return None;
+        }
+        // The number of failures before the next success, which is
+        // `floor(ln(u) / ln(1 - p))` for `u` uniform in `(0, 1]`.
+        let gap = if self.ln_q == ::std::f64::NEG_INFINITY {
+            0.0
+        } else {
+            let u: f64 = self.rng.sample(OpenClosed01);
+            (u.ln() / self.ln_q).floor()
+        };
+        // A gap too large for a `u64` takes the next success beyond the last
+        // index, so the iterator ends. This must be checked before casting,
+        // which is undefined for out-of-range values before Rust 1.45.
+        if !(gap < SCALE) {
+            self.next = None;
+            return None;
+        }
+        let index = next.checked_add(gap as u64);
+        self.next = index.and_then(|i| i.checked_add(1));
+        index
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.next {
+            Some(_) if self.ln_q != 0.0 => (0, None),
+            _ => (0, Some(0)),
+        }
+    }
+}
+
+impl Distribution<bool> for Bernoulli {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
+        match self.repr {
+            Repr::Threshold(0) => false,
+            Repr::Threshold(p_int) => rng.next_u64() < p_int,
+            Repr::AlwaysTrue => true,
+            Repr::Ratio { numerator, ref range, .. } => {
+                range.sample(rng) < numerator
+            }
+        }
+    }
+}
+
+// A `Bernoulli` is serialized as the parameters it was constructed with.
+// Thresholds made by `try_new` survive the round trip through `f64` exactly,
+// since `p * 2^64` is either an integer or below 2^53.
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Bernoulli")]
+enum BernoulliParams {
+    Probability(f64),
+    Ratio { numerator: u32, denominator: u32 },
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { Bernoulli, BernoulliParams,
+    |d| match d.repr {
+        Repr::Threshold(p_int) => BernoulliParams::Probability(p_int as f64 / SCALE),
+        Repr::AlwaysTrue => BernoulliParams::Probability(1.0),
+        Repr::Ratio { numerator, denominator, .. } => {
+            BernoulliParams::Ratio { numerator: numerator, denominator: denominator }
+        }
+    },
+    |p| match p {
+        BernoulliParams::Probability(p) => Bernoulli::try_new(p),
+        BernoulliParams::Ratio { numerator, denominator } => {
+            Bernoulli::try_from_ratio(numerator, denominator)
+        }
+    } }
+
+/// Samples SIMD masks, with each lane set independently with the probability
+/// of a `Bernoulli`.
+///
+/// This is a separate type, so that sampling a `Bernoulli` always produces a
+/// `bool` and doesn't need a type annotation.
+///
+/// For a probability `p`, each lane takes a random `u64` and compares it
+/// against the threshold, so the lanes set are the same as those of as many
+/// scalar samples from the `Bernoulli`.
+#[cfg(feature="simd_support")]
+#[derive(Clone, Copy, Debug)]
+pub struct BernoulliMask {
+    bernoulli: Bernoulli,
+}
+
+#[cfg(feature="simd_support")]
+impl BernoulliMask {
+    /// Construct a new `BernoulliMask` with the probability of `bernoulli`
+    /// for each lane.
+    #[inline]
+    pub fn new(bernoulli: Bernoulli) -> BernoulliMask {
+        BernoulliMask { bernoulli: bernoulli }
+    }
+}
+
+#[cfg(feature="simd_support")]
+macro_rules! simd_mask_impl {
+    ($($mask:ident, $lanes:expr;)*) => {
+        $(
+        impl Distribution<::core::simd::$mask> for BernoulliMask {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ::core::simd::$mask {
+                use core::simd::{$mask, Simd};
+                use core::simd::prelude::*;
+                match self.bernoulli.repr {
+                    Repr::Threshold(0) => $mask::splat(false),
+                    Repr::Threshold(p_int) => {
+                        let mut bits = [0u64; $lanes];
+                        rng.fill(&mut bits[..]);
+                        Simd::from_array(bits).simd_lt(Simd::splat(p_int)).cast()
+                    }
+                    Repr::AlwaysTrue => $mask::splat(true),
+                    Repr::Ratio { numerator, ref range, .. } => {
+                        let mut lanes = [false; $lanes];
+                        for lane in lanes.iter_mut() {
+                            *lane = range.sample(rng) < numerator;
+                        }
+                        $mask::from_array(lanes)
+                    }
+                }
+            }
+        }
+        )*
+    }
+}
+
+#[cfg(feature="simd_support")]
+simd_mask_impl! {
+    mask8x16, 16; mask8x32, 32; mask8x64, 64;
+    mask16x8, 8; mask16x16, 16; mask16x32, 32;
+    mask32x4, 4; mask32x8, 8; mask32x16, 16;
+    mask64x2, 2; mask64x4, 4; mask64x8, 8;
+}
+
+#[cfg(test)]
+mod test {
+    use Rng;
+    use distributions::Distribution;
+    use super::Bernoulli;
+
+    #[test]
+    fn test_trivial() {
+        let mut r = ::test::rng(1);
+        let always_false = Bernoulli::new(0.0);
+        let always_true = Bernoulli::new(1.0);
+        let ratio_false = Bernoulli::from_ratio(0, 7);
+        let ratio_true = Bernoulli::from_ratio(7, 7);
+        for _ in 0..5 {
+            assert_eq!(r.sample(&always_false), false);
+            assert_eq!(r.sample(&always_true), true);
+            assert_eq!(r.sample(&ratio_false), false);
+            assert_eq!(r.sample(&ratio_true), true);
+        }
+    }
+
+    #[test]
+    fn test_average() {
+        const N: u32 = 10_000;
+        let mut r = ::test::rng(2);
+        let d1 = Bernoulli::new(0.25);
+        let d2 = Bernoulli::from_ratio(1, 4);
+        let (mut sum1, mut sum2) = (0, 0);
+        for _ in 0..N {
+            if d1.sample(&mut r) { sum1 += 1; }
+            if d2.sample(&mut r) { sum2 += 1; }
+        }
+        assert!(sum1 > 2300 && sum1 < 2700);
+        assert!(sum2 > 2300 && sum2 < 2700);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_goodness_of_fit() {
+        use testing::chi_squared;
+        let mut r = ::test::rng(4);
+        let result = chi_squared(&mut r, &Bernoulli::new(0.3), 10000,
+                                 &[0.7, 0.3], |&b| b as usize);
+        assert!(result.passes(0.001), "{:?}", result);
+    }
+
+    #[test]
+    fn test_ratio_reproducible() {
+        let d = Bernoulli::from_ratio(2, 3);
+        let mut a = ::test::rng(3);
+        let mut b = ::test::rng(3);
+        for _ in 0..100 {
+            assert_eq!(d.sample(&mut a), d.sample(&mut b));
+        }
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_sample_indices() {
+        let mut r = ::test::rng(7);
+        let all: Vec<u64> = Bernoulli::new(1.0).sample_indices(&mut r).take(5).collect();
+        assert_eq!(all, [0, 1, 2, 3, 4]);
+        assert_eq!(Bernoulli::from_ratio(0, 5).sample_indices(&mut r).next(), None);
+
+        // A rare event over many trials
+        let events: Vec<u64> = Bernoulli::new(1e-6).sample_indices(&mut r)
+            .take_while(|&i| i < 1_000_000_000)
+            .collect();
+        assert!(events.len() > 900 && events.len() < 1100, "{}", events.len());
+        assert!(events.windows(2).all(|w| w[0] < w[1]));
+
+        // The same distribution of successes as per-trial sampling
+        let d = Bernoulli::from_ratio(1, 3);
+        let mut hits = [0u32; 6];
+        for i in d.sample_indices(&mut r).take_while(|&i| i < 60_000) {
+            hits[(i % 6) as usize] += 1;
+        }
+        for &h in hits.iter() {
+            assert!(h > 3000 && h < 3700, "{:?}", hits);
+        }
+
+        // Gaps beyond the range of a `u64` end the iterator
+        let mut indices = Bernoulli::new(1.0 / super::SCALE).sample_indices(&mut r);
+        assert!(indices.by_ref().take(100).count() < 100);
+        assert_eq!(indices.next(), None);
+    }
+
+    #[test]
+    #[cfg(feature="simd_support")]
+    fn test_simd_masks() {
+        use core::simd::{mask8x64, mask32x4};
+        use super::BernoulliMask;
+        let mut r = ::test::rng(5);
+        let always = BernoulliMask::new(Bernoulli::new(1.0));
+        let never = BernoulliMask::new(Bernoulli::from_ratio(0, 3));
+        assert!(r.sample::<mask32x4, _>(&always).all());
+        assert!(!r.sample::<mask8x64, _>(&never).any());
+
+        // The same lanes as scalar samples from the same stream
+        let d = Bernoulli::new(0.3);
+        let mut a = ::test::rng(6);
+        let mut b = ::test::rng(6);
+        let mask: mask32x4 = BernoulliMask::new(d).sample(&mut a);
+        for lane in 0..4 {
+            assert_eq!(mask.test(lane), d.sample(&mut b));
+        }
+
+        let p = BernoulliMask::new(Bernoulli::new(0.25));
+        let q = BernoulliMask::new(Bernoulli::from_ratio(1, 4));
+        let (mut sum1, mut sum2) = (0, 0);
+        for _ in 0..1000 {
+            sum1 += r.sample::<mask8x64, _>(&p).to_bitmask().count_ones();
+            sum2 += r.sample::<mask8x64, _>(&q).to_bitmask().count_ones();
+        }
+        assert!(sum1 > 15000 && sum1 < 17000, "{}", sum1);
+        assert!(sum2 > 15000 && sum2 < 17000, "{}", sum2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_p() {
+        Bernoulli::new(1.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_ratio() {
+        Bernoulli::from_ratio(3, 2);
+    }
+
+    #[test]
+    fn test_try_new() {
+        use distributions::ParamError;
+        assert!(Bernoulli::try_new(0.0).is_ok());
+        assert!(Bernoulli::try_new(1.0).is_ok());
+        assert_eq!(Bernoulli::try_new(1.5).unwrap_err(), ParamError::OutOfRange("p"));
+        assert_eq!(Bernoulli::try_new(::core::f64::NAN).unwrap_err(),
+                   ParamError::OutOfRange("p"));
+        assert!(Bernoulli::try_from_ratio(2, 2).is_ok());
+        assert_eq!(Bernoulli::try_from_ratio(0, 0).unwrap_err(),
+                   ParamError::NotPositive("denominator"));
+        assert_eq!(Bernoulli::try_from_ratio(3, 2).unwrap_err(),
+                   ParamError::OutOfRange("numerator"));
+    }
+}
@@ -0,0 +1,310 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A weighted index distribution with changing weights.
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+use Rng;
+use distributions::Distribution;
+use distributions::range::Range;
+use distributions::{lowbit, fenwick_search};
+
+/// A distribution of indices, each chosen with probability proportional to
+/// its weight, where indices can be inserted, removed and reweighted while
+/// sampling.
+///
+/// [`insert`], [`remove`], [`set_weight`] and sampling all take `O(log n)`
+/// time. The weights are kept in a Fenwick tree (a binary indexed tree) of
+/// partial sums, so unlike `WeightedChoice` and the alias method nothing has
+/// to be rebuilt when they change. This suits event schedulers and
+/// agent-based models, where the rates of a few events change after every
+/// step.
+///
+/// Weights are integers, so that updates are exact: with floating-point
+/// weights, rounding errors in the partial sums would accumulate with every
+/// update. Scale fractional rates to integers if needed.
+///
+/// Indices stay valid until they are removed; removed indices are reused by
+/// later insertions. Sampling panics if the total weight is 0, in particular
+/// if there are no indices.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::distributions::{DynamicWeightedIndex, Distribution};
+///
+/// let mut events = DynamicWeightedIndex::new();
+/// let arrival = events.insert(10);
+/// let departure = events.insert(5);
+/// let mut rng = rand::thread_rng();
+/// for _ in 0..10 {
+///     let event = events.sample(&mut rng);
+///     if event == arrival {
+///         // more arrivals, more departures
+///         let weight = events.weight(departure).unwrap();
+///         events.set_weight(departure, weight + 1);
+///     }
+/// }
+/// events.remove(arrival);
+/// assert_eq!(events.sample(&mut rng), departure);
+/// ```
+///
+/// [`insert`]: #method.insert
+/// [`remove`]: #method.remove
+/// [`set_weight`]: #method.set_weight
+#[derive(Clone, Debug, Default)]
+pub struct DynamicWeightedIndex {
+    // With 1-based indices, `tree[i - 1]` is the sum of the weights of
+    // indices `i - lowbit(i) + 1 ..= i`.
+    tree: Vec<u64>,
+    // The weight of each index, `None` if it was removed.
+    weights: Vec<Option<u64>>,
+    // Removed indices, to be reused.
+    free: Vec<usize>,
+    total: u64,
+}
+
+impl DynamicWeightedIndex {
+    /// Create an empty `DynamicWeightedIndex`.
+    pub fn new() -> DynamicWeightedIndex {
+        DynamicWeightedIndex {
+            tree: Vec::new(),
+            weights: Vec::new(),
+            free: Vec::new(),
+            total: 0,
+        }
+    }
+
+    /// Create a `DynamicWeightedIndex` with indices `0..n` for `n` weights,
+    /// in `O(n)` time.
+    ///
+    /// Panics if the total weight is larger than a `u64` can contain.
+    pub fn from_weights<I>(weights: I) -> DynamicWeightedIndex
+        where I: IntoIterator<Item = u64>
+    {
+        let tree = weights.into_iter().collect::<Vec<_>>();
+        let mut total: u64 = 0;
+        for &w in &tree {
+            total = total.checked_add(w).expect(
+                "DynamicWeightedIndex: total weight larger than a u64 can contain");
+        }
+        let weights = tree.iter().map(|&w| Some(w)).collect();
+        let mut index = DynamicWeightedIndex {
+            tree: tree,
+            weights: weights,
+            free: Vec::new(),
+            total: total,
+        };
+        let len = index.tree.len();
+        for i in 1..len + 1 {
+            let parent = i + lowbit(i);
+            if parent <= len {
+                index.tree[parent - 1] += index.tree[i - 1];
+            }
+        }
+        index
+    }
+
+    /// The number of indices which are not removed.
+    pub fn len(&self) -> usize {
+        self.weights.len() - self.free.len()
+    }
+
+    /// Whether all indices are removed (or none were inserted).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The sum of all weights.
+    pub fn total_weight(&self) -> u64 {
+        self.total
+    }
+
+    /// The weight of `index`, or `None` if it is removed or was never
+    /// inserted.
+    pub fn weight(&self, index: usize) -> Option<u64> {
+        self.weights.get(index).and_then(|&w| w)
+    }
+
+    /// Insert a new index with weight `weight`, and return it.
+    ///
+    /// This reuses a removed index if there is one.
+    ///
+    /// Panics if the total weight becomes larger than a `u64` can contain.
+    pub fn insert(&mut self, weight: u64) -> usize {
+        self.total = self.total.checked_add(weight).expect(
+            "DynamicWeightedIndex: total weight larger than a u64 can contain");
+        if let Some(index) = self.free.pop() {
+            self.weights[index] = Some(weight);
+            self.add(index, weight);
+            return index;
+        }
+        // Append a node: it covers the new index and the nodes below it.
+        let i = self.tree.len() + 1;
+        let start = i - lowbit(i);
+        let mut sum = weight;
+        let mut j = i - 1;
+        while j > start {
+            sum += self.tree[j - 1];
+            j -= lowbit(j);
+        }
+        self.tree.push(sum);
+        self.weights.push(Some(weight));
+        i - 1
+    }
+
+    /// Remove `index`, and return its weight. It is never sampled again,
+    /// until it is reused by [`insert`].
+    ///
+    /// Panics if `index` is removed or was never inserted.
+    ///
+    /// [`insert`]: #method.insert
+    pub fn remove(&mut self, index: usize) -> u64 {
+        let weight = self.weight(index)
+            .expect("DynamicWeightedIndex::remove called with an invalid index");
+        self.sub(index, weight);
+        self.total -= weight;
+        self.weights[index] = None;
+        self.free.push(index);
+        weight
+    }
+
+    /// Change the weight of `index` to `weight`.
+    ///
+    /// Panics if `index` is removed or was never inserted, or if the total
+    /// weight becomes larger than a `u64` can contain.
+    pub fn set_weight(&mut self, index: usize, weight: u64) {
+        let old = self.weight(index)
+            .expect("DynamicWeightedIndex::set_weight called with an invalid index");
+        if weight >= old {
+            self.total = self.total.checked_add(weight - old).expect(
+                "DynamicWeightedIndex: total weight larger than a u64 can contain");
+            self.add(index, weight - old);
+        } else {
+            self.total -= old - weight;
+            self.sub(index, old - weight);
+        }
+        self.weights[index] = Some(weight);
+    }
+
+    /// Remove all indices.
+    pub fn clear(&mut self) {
+        self.tree.clear();
+        self.weights.clear();
+        self.free.clear();
+        self.total = 0;
+    }
+
+    fn add(&mut self, index: usize, delta: u64) {
+        let mut i = index + 1;
+        while i <= self.tree.len() {
+            self.tree[i - 1] += delta;
+            i += lowbit(i);
+        }
+    }
+
+    fn sub(&mut self, index: usize, delta: u64) {
+        let mut i = index + 1;
+        while i <= self.tree.len() {
+            self.tree[i - 1] -= delta;
+            i += lowbit(i);
+        }
+    }
+}
+
+impl Distribution<usize> for DynamicWeightedIndex {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        assert!(self.total != 0, "DynamicWeightedIndex: total weight is 0");
+        let target = Range::sample_single(0, self.total, rng);
+
+        // The next index after the last (1-based) position with cumulative
+        // weight <= target is the one whose range contains target.
+        fenwick_search(self.tree.len(), target, |i| self.tree[i - 1])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+    use distributions::Distribution;
+    use super::DynamicWeightedIndex;
+
+    // Check the tree against the weights, and that sampling covers exactly
+    // the indices with non-zero weight.
+    fn check(index: &DynamicWeightedIndex) {
+        let mut counts = vec![0u64; index.weights.len()];
+        let mut rng = ::test::rng(420);
+        for _ in 0..2000 {
+            counts[index.sample(&mut rng)] += 1;
+        }
+        let total = index.weights.iter().map(|w| w.unwrap_or(0)).sum::<u64>();
+        assert_eq!(index.total_weight(), total);
+        for (i, &w) in index.weights.iter().enumerate() {
+            let w = w.unwrap_or(0);
+            assert_eq!(counts[i] == 0, w == 0);
+            let expected = 2000.0 * w as f64 / total as f64;
+            assert!((counts[i] as f64 - expected).abs() < 100.0);
+        }
+        let fresh = DynamicWeightedIndex::from_weights(
+            index.weights.iter().map(|w| w.unwrap_or(0)));
+        assert_eq!(index.tree, fresh.tree);
+    }
+
+    #[test]
+    fn test_dynamic_weighted_index() {
+        let mut index = DynamicWeightedIndex::new();
+        assert!(index.is_empty());
+        for w in 1..12 {
+            assert_eq!(index.insert(w * 10), w as usize - 1);
+        }
+        check(&index);
+
+        index.set_weight(3, 0);
+        index.set_weight(7, 500);
+        index.set_weight(10, 1);
+        check(&index);
+
+        assert_eq!(index.remove(2), 30);
+        assert_eq!(index.remove(7), 500);
+        assert_eq!(index.weight(7), None);
+        assert_eq!(index.len(), 9);
+        check(&index);
+
+        // Removed indices are reused
+        let a = index.insert(100);
+        let b = index.insert(100);
+        let c = index.insert(100);
+        assert_eq!((a, b, c), (7, 2, 11));
+        check(&index);
+
+        index.clear();
+        assert_eq!((index.len(), index.total_weight()), (0, 0));
+        index.insert(3);
+        assert_eq!(index.sample(&mut ::test::rng(421)), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_dynamic_weighted_index_zero() {
+        let mut index = DynamicWeightedIndex::from_weights(vec![1, 2]);
+        index.set_weight(0, 0);
+        index.remove(1);
+        index.sample(&mut ::test::rng(422));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_dynamic_weighted_index_removed() {
+        let mut index = DynamicWeightedIndex::from_weights(vec![1, 2]);
+        index.remove(1);
+        index.set_weight(1, 3);
+    }
+}
@@ -11,7 +11,8 @@
 //! The exponential distribution.
 
 use {Rng};
-use distributions::{ziggurat, ziggurat_tables, Distribution};
+use distributions::{ziggurat, ziggurat_f32, ziggurat_tables, Distribution,
+                    ParamError};
 
 /// Samples floating-point numbers according to the exponential distribution,
 /// with rate parameter `λ = 1`. This is equivalent to `Exp::new(1.0)` or
@@ -37,6 +38,7 @@ use distributions::{ziggurat, ziggurat_tables, Distribution};
 /// println!("{}", val);
 /// ```
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
 pub struct Exp1;
 
 // This could be done via `-rng.gen::<f64>().ln()` but that is slower.
@@ -75,6 +77,9 @@ impl Distribution<f64> for Exp1 {
 /// ```
 #[derive(Clone, Copy, Debug)]
 pub struct Exp {
+    // `lambda` as given, for serialization
+    #[cfg_attr(not(feature="serde-1"), allow(dead_code))]
+    lambda: f64,
     /// `lambda` stored as `1/lambda`, since this is what we scale by.
     lambda_inverse: f64
 }
@@ -84,8 +89,18 @@ impl Exp {
     /// `lambda`. Panics if `lambda <= 0`.
     #[inline]
     pub fn new(lambda: f64) -> Exp {
-        assert!(lambda > 0.0, "Exp::new called with `lambda` <= 0");
-        Exp { lambda_inverse: 1.0 / lambda }
+        Exp::try_new(lambda)
+            .unwrap_or_else(|e| panic!("Exp::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct a new `Exp` with the given shape parameter `lambda`, or
+    /// return an error if `lambda <= 0` or NaN.
+    #[inline]
+    pub fn try_new(lambda: f64) -> Result<Exp, ParamError> {
+        if !(lambda > 0.0) {
+            return Err(ParamError::NotPositive("lambda"));
+        }
+        Ok(Exp { lambda: lambda, lambda_inverse: 1.0 / lambda })
     }
 }
 
@@ -96,10 +111,93 @@ impl Distribution<f64> for Exp {
     }
 }
 
+/// The exponential distribution `Exp(lambda)` over `f32`.
+///
+/// This is the single-precision counterpart of [`Exp`] (and, with
+/// `lambda = 1`, of [`Exp1`]): it uses the same Ziggurat method, but with
+/// `f32` tables and arithmetic, and consumes one `u32` instead of one `u64`
+/// per sample in the common case.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::distributions::{ExpF32, Distribution};
+///
+/// let exp = ExpF32::new(2.0);
+/// let v: f32 = exp.sample(&mut rand::thread_rng());
+/// println!("{} is from a Exp(2) distribution", v);
+/// ```
+///
+/// [`Exp`]: struct.Exp.html
+/// [`Exp1`]: struct.Exp1.html
+#[derive(Clone, Copy, Debug)]
+pub struct ExpF32 {
+    // `lambda` as given, for serialization
+    #[cfg_attr(not(feature="serde-1"), allow(dead_code))]
+    lambda: f32,
+    /// `lambda` stored as `1/lambda`, since this is what we scale by.
+    lambda_inverse: f32
+}
+
+impl ExpF32 {
+    /// Construct a new `ExpF32` with the given shape parameter
+    /// `lambda`. Panics if `lambda <= 0`.
+    #[inline]
+    pub fn new(lambda: f32) -> ExpF32 {
+        ExpF32::try_new(lambda)
+            .unwrap_or_else(|e| panic!("ExpF32::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct a new `ExpF32` with the given shape parameter `lambda`, or
+    /// return an error if `lambda <= 0` or NaN.
+    #[inline]
+    pub fn try_new(lambda: f32) -> Result<ExpF32, ParamError> {
+        if !(lambda > 0.0) {
+            return Err(ParamError::NotPositive("lambda"));
+        }
+        Ok(ExpF32 { lambda: lambda, lambda_inverse: 1.0 / lambda })
+    }
+}
+
+impl Distribution<f32> for ExpF32 {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        #[inline]
+        fn pdf(x: f32) -> f32 {
+            (-x).exp()
+        }
+        #[inline]
+        fn zero_case<R: Rng + ?Sized>(rng: &mut R, _u: f32) -> f32 {
+            ziggurat_tables::ZIG_EXP_R as f32 - rng.gen::<f32>().ln()
+        }
+
+        let n = ziggurat_f32(rng, false,
+                             &ziggurat_tables::ZIG_EXP_X_F32,
+                             &ziggurat_tables::ZIG_EXP_F_F32,
+                             pdf, zero_case);
+        n * self.lambda_inverse
+    }
+}
+
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Exp")]
+struct ExpParams<F> {
+    lambda: F,
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { Exp, ExpParams<f64>,
+    |d| ExpParams { lambda: d.lambda },
+    |p| Exp::try_new(p.lambda) }
+#[cfg(feature="serde-1")]
+serde_via_params! { ExpF32, ExpParams<f32>,
+    |d| ExpParams { lambda: d.lambda },
+    |p| ExpF32::try_new(p.lambda) }
+
 #[cfg(test)]
 mod test {
-    use distributions::Distribution;
-    use super::Exp;
+    use distributions::{Distribution, ParamError};
+    use super::{Exp, ExpF32};
 
     #[test]
     fn test_exp() {
@@ -110,6 +208,26 @@ mod test {
         }
     }
     #[test]
+    fn test_exp_goodness_of_fit() {
+        use testing::kolmogorov_smirnov;
+        let mut rng = ::test::rng(222);
+        let result = kolmogorov_smirnov(&mut rng, &Exp::new(2.5), 10000,
+                                        |x| 1.0 - (-2.5 * x).exp());
+        assert!(result.passes(0.001), "{:?}", result);
+    }
+    #[test]
+    fn test_exp_f32_goodness_of_fit() {
+        use testing::kolmogorov_smirnov;
+        let mut rng = ::test::rng(223);
+        for _ in 0..1000 {
+            assert!(ExpF32::new(1.0).sample(&mut rng) >= 0.0);
+        }
+        let distr = ExpF32::new(2.5).map(|x: f32| x as f64);
+        let result = kolmogorov_smirnov(&mut rng, &distr, 10000,
+                                        |x| 1.0 - (-2.5 * x).exp());
+        assert!(result.passes(0.001), "{:?}", result);
+    }
+    #[test]
     #[should_panic]
     fn test_exp_invalid_lambda_zero() {
         Exp::new(0.0);
@@ -119,4 +237,11 @@ mod test {
     fn test_exp_invalid_lambda_neg() {
         Exp::new(-10.0);
     }
+    #[test]
+    fn test_exp_try_new() {
+        assert!(Exp::try_new(0.5).is_ok());
+        assert_eq!(Exp::try_new(0.0).unwrap_err(), ParamError::NotPositive("lambda"));
+        assert_eq!(ExpF32::try_new(::std::f32::NAN).unwrap_err(),
+                   ParamError::NotPositive("lambda"));
+    }
 }
@@ -10,9 +10,10 @@
 
 //! Basic floating-point number distributions
 
-use core::mem;
-use Rng;
-use distributions::{Distribution, Uniform};
+use core::{mem, slice};
+#[cfg(feature="f16_support")] use half::f16;
+use {Rng, RngCore, AsByteSliceMut};
+use distributions::{Distribution, Uniform, ParamError};
 
 pub(crate) trait IntoFloat {
     type F;
@@ -20,8 +21,8 @@ pub(crate) trait IntoFloat {
     /// Helper method to combine the fraction and a contant exponent into a
     /// float.
     ///
-    /// Only the least significant bits of `self` may be set, 23 for `f32` and
-    /// 52 for `f64`.
+    /// Only the least significant bits of `self` may be set, 23 for `f32`,
+    /// 52 for `f64` and 10 for `f16`.
     /// The resulting value will fall in a range that depends on the exponent.
     /// As an example the range with exponent 0 will be
     /// [2<sup>0</sup>..2<sup>1</sup>), which is [1..2).
@@ -29,9 +30,81 @@ pub(crate) trait IntoFloat {
     fn into_float_with_exponent(self, exponent: i32) -> Self::F;
 }
 
+/// A distribution to sample floating point numbers uniformly in the open
+/// interval `(0, 1)`, i.e. not including either endpoint.
+///
+/// This is currently the same as `Uniform` for floats, but makes the
+/// guarantee explicit: the result can be passed to e.g. `ln(u)` or `1 / u`
+/// without checking for zero. The values are the multiples of
+/// 2<sup>-23</sup> for `f32` or 2<sup>-52</sup> for `f64`, offset by half of
+/// that.
+///
+/// # Example
+/// ```rust
+/// use rand::{thread_rng, Rng};
+/// use rand::distributions::Open01;
+///
+/// let u: f64 = thread_rng().sample(Open01);
+/// println!("{} is finite", u.ln());
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct Open01;
+
+/// A distribution to sample floating point numbers uniformly in the
+/// half-open interval `(0, 1]`, i.e. including 1 but not 0.
+///
+/// The values are the multiples of 2<sup>-23</sup> for `f32` or
+/// 2<sup>-52</sup> for `f64`, starting at that step and including 1.
+///
+/// # Example
+/// ```rust
+/// use rand::{thread_rng, Rng};
+/// use rand::distributions::OpenClosed01;
+///
+/// let u: f32 = thread_rng().sample(OpenClosed01);
+/// assert!(0.0 < u && u <= 1.0);
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct OpenClosed01;
+
+/// Return the 16 most significant bits of `next_u32`; this is what `f16`
+/// values are generated from, since there is no `next_u16`.
+#[cfg(feature="f16_support")]
+#[inline(always)]
+pub(crate) fn next_u16<R: RngCore + ?Sized>(rng: &mut R) -> u16 {
+    (rng.next_u32() >> 16) as u16
+}
+
 macro_rules! float_impls {
     ($ty:ty, $uty:ty, $fraction_bits:expr, $exponent_bias:expr,
-     $next_u:ident) => {
+     $next_u:path) => {
+        /// Filling a float slice via `Rng::fill` yields values uniformly
+        /// distributed in the half-open interval `[0, 1)`.
+        ///
+        /// The slice is first filled with random bytes and then converted in
+        /// place by `to_le`, which keeps the highest fraction bits of each
+        /// little-endian integer and maps those into `[0, 1)`.
+        impl AsByteSliceMut for [$ty] {
+            fn as_byte_slice_mut<'a>(&'a mut self) -> &'a mut [u8] {
+                unsafe {
+                    slice::from_raw_parts_mut(self.as_mut_ptr() as *mut u8,
+                        self.len() * mem::size_of::<$ty>()
+                    )
+                }
+            }
+
+            fn to_le(&mut self) {
+                let float_size = mem::size_of::<$ty>() * 8;
+                for x in self {
+                    let value = <$uty>::from_le(x.to_bits());
+                    let fraction = value >> (float_size - $fraction_bits);
+                    *x = fraction.into_float_with_exponent(0) - 1.0;
+                }
+            }
+        }
+
         impl IntoFloat for $uty {
             type F = $ty;
             #[inline(always)]
@@ -59,21 +132,313 @@ macro_rules! float_impls {
                 const EPSILON: $ty = 1.0 / (1u64 << $fraction_bits) as $ty;
                 let float_size = mem::size_of::<$ty>() * 8;
 
-                let value = rng.$next_u();
+                let value = $next_u(rng);
                 let fraction = value >> (float_size - $fraction_bits);
                 fraction.into_float_with_exponent(0) - (1.0 - EPSILON / 2.0)
             }
+
+            fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R,
+                                            dest: &mut [$ty])
+            {
+                const EPSILON: $ty = 1.0 / (1u64 << $fraction_bits) as $ty;
+                let float_size = mem::size_of::<$ty>() * 8;
+
+                rng.fill_bytes(dest.as_byte_slice_mut());
+                for x in dest.iter_mut() {
+                    let value = <$uty>::from_le(x.to_bits());
+                    let fraction = value >> (float_size - $fraction_bits);
+                    *x = fraction.into_float_with_exponent(0)
+                         - (1.0 - EPSILON / 2.0);
+                }
+            }
+        }
+
+        impl Distribution<$ty> for Open01 {
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                Uniform.sample(rng)
+            }
+
+            fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R,
+                                            dest: &mut [$ty])
+            {
+                Uniform.sample_fill(rng, dest)
+            }
+        }
+
+        impl Distribution<$ty> for OpenClosed01 {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                const EPSILON: $ty = 1.0 / (1u64 << $fraction_bits) as $ty;
+                let float_size = mem::size_of::<$ty>() * 8;
+
+                let value = $next_u(rng);
+                let fraction = value >> (float_size - $fraction_bits);
+                // Maps `[1, 2 - EPSILON]` to `[EPSILON, 1]`
+                fraction.into_float_with_exponent(0) - (1.0 - EPSILON)
+            }
         }
     }
 }
-float_impls! { f32, u32, 23, 127, next_u32 }
-float_impls! { f64, u64, 52, 1023, next_u64 }
+float_impls! { f32, u32, 23, 127, RngCore::next_u32 }
+float_impls! { f64, u64, 52, 1023, RngCore::next_u64 }
+
+// `half::f16` has neither literals nor `as` casts, so it gets its own
+// implementations of the above. The values are computed in `f32` and then
+// converted; both steps are exact, since the values have at most 11
+// significant bits.
+#[cfg(feature="f16_support")]
+const EPSILON16: f32 = 1.0 / (1 << 10) as f32;
+
+#[cfg(feature="f16_support")]
+impl AsByteSliceMut for [f16] {
+    fn as_byte_slice_mut<'a>(&'a mut self) -> &'a mut [u8] {
+        unsafe {
+            slice::from_raw_parts_mut(self.as_mut_ptr() as *mut u8,
+                self.len() * mem::size_of::<f16>()
+            )
+        }
+    }
+
+    fn to_le(&mut self) {
+        for x in self {
+            let fraction = u16::from_le(x.to_bits()) >> (16 - 10);
+            *x = f16::from_f32(fraction as f32 * EPSILON16);
+        }
+    }
+}
+
+#[cfg(feature="f16_support")]
+impl IntoFloat for u16 {
+    type F = f16;
+    #[inline(always)]
+    fn into_float_with_exponent(self, exponent: i32) -> f16 {
+        let exponent_bits = ((15 + exponent) as u16) << 10;
+        f16::from_bits(self | exponent_bits)
+    }
+}
+
+#[cfg(feature="f16_support")]
+impl Distribution<f16> for Uniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f16 {
+        let fraction = next_u16(rng) >> (16 - 10);
+        f16::from_f32((fraction as f32 + 0.5) * EPSILON16)
+    }
+
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [f16]) {
+        rng.fill_bytes(dest.as_byte_slice_mut());
+        for x in dest.iter_mut() {
+            let fraction = u16::from_le(x.to_bits()) >> (16 - 10);
+            *x = f16::from_f32((fraction as f32 + 0.5) * EPSILON16);
+        }
+    }
+}
+
+#[cfg(feature="f16_support")]
+impl Distribution<f16> for Open01 {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f16 {
+        Uniform.sample(rng)
+    }
+
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [f16]) {
+        Uniform.sample_fill(rng, dest)
+    }
+}
+
+#[cfg(feature="f16_support")]
+impl Distribution<f16> for OpenClosed01 {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f16 {
+        let fraction = next_u16(rng) >> (16 - 10);
+        f16::from_f32((fraction as f32 + 1.0) * EPSILON16)
+    }
+}
+
+/// A distribution to sample floating point numbers uniformly in the
+/// half-open interval `[0, 1)`, with full precision.
+///
+/// The `Uniform` distribution only generates multiples of 2<sup>-23</sup>
+/// for `f32` or 2<sup>-52</sup> for `f64` (offset by half of that), so small
+/// values such as 10<sup>-20</sup> can never occur. `HighPrecision01` can
+/// produce every representable float in `[0, 1)`, including subnormals, each
+/// with a probability proportional to the distance to the next float. This
+/// matters when the result is transformed with a function that magnifies
+/// small values, e.g. the inverse CDF of a distribution with long tails.
+///
+/// The exponent is chosen by counting leading zero bits of random numbers,
+/// and the fraction is filled from a separate random number, so this usually
+/// takes two calls to the RNG and is slower than `Uniform`.
+///
+/// # Example
+/// ```rust
+/// use rand::{thread_rng, Rng};
+/// use rand::distributions::HighPrecision01;
+///
+/// let val: f64 = thread_rng().sample(HighPrecision01);
+/// println!("f64 from [0, 1): {}", val);
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct HighPrecision01;
+
+/// A distribution to sample floating point numbers uniformly in the
+/// half-open interval `[low, high)`, using [`HighPrecision01`].
+///
+/// Values are computed as `low + (high - low) * u`, with `u` sampled from
+/// `HighPrecision01`. When `low == 0`, or more generally for values close to
+/// zero, this therefore offers far more precision than `Range`. Results that
+/// are rounded to `high` are rejected.
+///
+/// [`HighPrecision01`]: struct.HighPrecision01.html
+#[derive(Clone, Copy, Debug)]
+pub struct HighPrecision<F> {
+    low: F,
+    // Only kept for serialization; sampling uses `scale`.
+    #[cfg_attr(not(feature="serde-1"), allow(dead_code))]
+    high: F,
+    scale: F,
+}
+
+macro_rules! high_precision_impls {
+    ($ty:ident, $uty:ty, $fraction_bits:expr, $exponent_bias:expr,
+     $next_u:ident) => {
+        impl Distribution<$ty> for HighPrecision01 {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                // The smallest exponent of normal floats; values below
+                // 2^MIN_EXP are subnormals with spacing 2^(MIN_EXP - bits).
+                const MIN_EXP: i32 = 1 - $exponent_bias;
+                let float_size = mem::size_of::<$ty>() as i32 * 8;
+
+                // Every leading zero halves the value: the float falls in
+                // `[2^exponent, 2^(exponent + 1))`.
+                let mut exponent = -1;
+                loop {
+                    let bits = rng.$next_u();
+                    exponent -= bits.leading_zeros() as i32;
+                    if bits != 0 {
+                        break;
+                    }
+                    if exponent < MIN_EXP - $fraction_bits {
+                        // All remaining values round to zero.
+                        return 0.0;
+                    }
+                }
+
+                let fraction = rng.$next_u() >> (float_size - $fraction_bits);
+                if exponent >= MIN_EXP {
+                    let exponent_bits =
+                        ((exponent + $exponent_bias) as $uty) << $fraction_bits;
+                    $ty::from_bits(exponent_bits | fraction)
+                } else {
+                    // Subnormal: the implicit leading bit becomes explicit,
+                    // and the precision drops by one bit per lower exponent.
+                    let shift = MIN_EXP - exponent;
+                    if shift > $fraction_bits {
+                        return 0.0;
+                    }
+                    let mantissa = (1 << $fraction_bits) | fraction;
+                    $ty::from_bits(mantissa >> shift)
+                }
+            }
+        }
+
+        impl HighPrecision<$ty> {
+            /// Create a new `HighPrecision` distribution sampling from
+            /// `[low, high)`.
+            ///
+            /// Panics if `low >= high`, or if either bound is not finite.
+            pub fn new(low: $ty, high: $ty) -> Self {
+                Self::try_new(low, high).unwrap_or_else(|e| {
+                    panic!("HighPrecision::new called with invalid parameters: {}", e)
+                })
+            }
+
+            /// Create a new `HighPrecision` distribution sampling from
+            /// `[low, high)`, or return an error if `low >= high`, or if
+            /// either bound or the size of the range is not finite.
+            pub fn try_new(low: $ty, high: $ty) -> Result<Self, ParamError> {
+                if !low.is_finite() {
+                    return Err(ParamError::OutOfRange("low"));
+                }
+                if !(high.is_finite() && low < high) {
+                    return Err(ParamError::OutOfRange("high"));
+                }
+                let scale = high - low;
+                if !scale.is_finite() {
+                    return Err(ParamError::OutOfRange("high"));
+                }
+                Ok(HighPrecision { low: low, high: high, scale: scale })
+            }
+        }
+
+        impl Distribution<$ty> for HighPrecision<$ty> {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                loop {
+                    let u: $ty = HighPrecision01.sample(rng);
+                    let x = self.low + self.scale * u;
+                    if x < self.low + self.scale {
+                        return x;
+                    }
+                }
+            }
+        }
+    }
+}
+high_precision_impls! { f32, u32, 23, 127, next_u32 }
+high_precision_impls! { f64, u64, 52, 1023, next_u64 }
+
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "HighPrecision")]
+struct HighPrecisionParams<F> {
+    low: F,
+    high: F,
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { HighPrecision<f32>, HighPrecisionParams<f32>,
+    |d| HighPrecisionParams { low: d.low, high: d.high },
+    |p| HighPrecision::<f32>::try_new(p.low, p.high) }
+#[cfg(feature="serde-1")]
+serde_via_params! { HighPrecision<f64>, HighPrecisionParams<f64>,
+    |d| HighPrecisionParams { low: d.low, high: d.high },
+    |p| HighPrecision::<f64>::try_new(p.low, p.high) }
+
+#[cfg(feature="simd_support")]
+macro_rules! simd_float_impls {
+    ($ty:ident, $uty:ident, $scalar:ty, $u_scalar:ty, $fraction_bits:expr,
+     $exponent_bias:expr) => {
+        impl Distribution<::core::simd::$ty> for Uniform {
+            /// Generate a vector with each lane sampled like the scalar
+            /// `Uniform` float, i.e. in the open interval `(0, 1)`.
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ::core::simd::$ty {
+                use core::simd::{$ty, $uty};
+                use core::simd::prelude::*;
+                const EPSILON: $scalar = 1.0 / (1u64 << $fraction_bits) as $scalar;
+                let float_size = mem::size_of::<$scalar>() * 8;
+
+                let value: $uty = Uniform.sample(rng);
+                let fraction = value >> $uty::splat((float_size - $fraction_bits) as $u_scalar);
+                let exponent_bits = $uty::splat(($exponent_bias as $u_scalar) << $fraction_bits);
+                $ty::from_bits(fraction | exponent_bits)
+                    - $ty::splat(1.0 - EPSILON / 2.0)
+            }
+        }
+    }
+}
+
+#[cfg(feature="simd_support")] simd_float_impls! { f32x4, u32x4, f32, u32, 23, 127 }
+#[cfg(feature="simd_support")] simd_float_impls! { f32x8, u32x8, f32, u32, 23, 127 }
+#[cfg(feature="simd_support")] simd_float_impls! { f32x16, u32x16, f32, u32, 23, 127 }
+#[cfg(feature="simd_support")] simd_float_impls! { f64x2, u64x2, f64, u64, 52, 1023 }
+#[cfg(feature="simd_support")] simd_float_impls! { f64x4, u64x4, f64, u64, 52, 1023 }
+#[cfg(feature="simd_support")] simd_float_impls! { f64x8, u64x8, f64, u64, 52, 1023 }
 
 
 #[cfg(test)]
 mod tests {
     use Rng;
     use mock::StepRng;
+    use super::{Open01, OpenClosed01, HighPrecision01, HighPrecision};
 
     const EPSILON32: f32 = ::core::f32::EPSILON;
     const EPSILON64: f64 = ::core::f64::EPSILON;
@@ -96,4 +461,161 @@ mod tests {
         assert_eq!(max.gen::<f32>(), 1.0 - EPSILON32 / 2.0);
         assert_eq!(max.gen::<f64>(), 1.0 - EPSILON64 / 2.0);
     }
+
+    #[test]
+    fn fill_floats() {
+        let mut zeros = StepRng::new(0, 0);
+        let mut a = [1.0f32; 5];
+        zeros.fill(&mut a);
+        assert_eq!(a, [0.0; 5]);
+
+        let mut max = StepRng::new(!0, 0);
+        let mut b = [0.0f64; 5];
+        max.fill(&mut b[..]);
+        assert_eq!(b, [1.0 - EPSILON64; 5]);
+
+        let mut rng = ::test::rng(800);
+        let mut c = [0.0f64; 100];
+        rng.fill(&mut c[..]);
+        for &x in c.iter() {
+            assert!(0.0 <= x && x < 1.0);
+        }
+        let mut d = [0.0f32; 0];
+        rng.fill(&mut d[..]);
+    }
+
+    #[test]
+    fn open_closed_edge_cases() {
+        let mut zeros = StepRng::new(0, 0);
+        assert_eq!(zeros.sample::<f32, _>(Open01), EPSILON32 / 2.0);
+        assert_eq!(zeros.sample::<f32, _>(OpenClosed01), EPSILON32);
+        assert_eq!(zeros.sample::<f64, _>(OpenClosed01), EPSILON64);
+
+        let mut max = StepRng::new(!0, 0);
+        assert_eq!(max.sample::<f64, _>(Open01), 1.0 - EPSILON64 / 2.0);
+        assert_eq!(max.sample::<f32, _>(OpenClosed01), 1.0);
+        assert_eq!(max.sample::<f64, _>(OpenClosed01), 1.0);
+    }
+
+    #[test]
+    #[cfg(feature="simd_support")]
+    fn simd_floats() {
+        use core::simd::{f32x8, f64x4};
+        use distributions::Uniform;
+
+        let mut zeros = StepRng::new(0, 0);
+        assert_eq!(zeros.sample::<f32x8, _>(Uniform), f32x8::splat(EPSILON32 / 2.0));
+        let mut max = StepRng::new(!0, 0);
+        assert_eq!(max.sample::<f64x4, _>(Uniform), f64x4::splat(1.0 - EPSILON64 / 2.0));
+
+        let mut rng = ::test::rng(809);
+        let v: f64x4 = rng.sample(Uniform);
+        for &x in v.as_array().iter() {
+            assert!(0.0 < x && x < 1.0);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="f16_support")]
+    fn f16_edge_cases() {
+        use half::f16;
+        use distributions::{Distribution, Uniform};
+        let epsilon16 = f16::from_f32(1.0 / 1024.0);
+        let half_epsilon16 = f16::from_f32(1.0 / 2048.0);
+
+        let mut zeros = StepRng::new(0, 0);
+        assert_eq!(zeros.gen::<f16>(), half_epsilon16);
+        assert_eq!(zeros.sample::<f16, _>(OpenClosed01), epsilon16);
+        let mut max = StepRng::new(!0, 0);
+        assert_eq!(max.gen::<f16>(), f16::ONE - half_epsilon16);
+        assert_eq!(max.sample::<f16, _>(OpenClosed01), f16::ONE);
+
+        // Only the high bits of `next_u32` are used.
+        let mut low_bits = StepRng::new(0xffff, 0);
+        assert_eq!(low_bits.gen::<f16>(), half_epsilon16);
+
+        let mut rng = ::test::rng(810);
+        let mut a = [f16::ZERO; 32];
+        rng.fill(&mut a);
+        for &x in a.iter() {
+            assert!(f16::ZERO <= x && x < f16::ONE);
+        }
+        let mut b = [f16::ZERO; 32];
+        Uniform.sample_fill(&mut rng, &mut b);
+        for &x in b.iter() {
+            assert!(f16::ZERO < x && x < f16::ONE);
+        }
+    }
+
+    #[test]
+    fn high_precision_01_edge_cases() {
+        // Many leading zeros give a subnormal; all zeros give zero.
+        let mut zeros = StepRng::new(0, 0);
+        assert_eq!(zeros.sample::<f32, _>(HighPrecision01), 0.0);
+        assert_eq!(zeros.sample::<f64, _>(HighPrecision01), 0.0);
+
+        let mut max = StepRng::new(!0, 0);
+        assert_eq!(max.sample::<f32, _>(HighPrecision01), 1.0 - EPSILON32 / 2.0);
+        assert_eq!(max.sample::<f64, _>(HighPrecision01), 1.0 - EPSILON64 / 2.0);
+
+        // A single set bit: leading zeros, then an all-zero fraction.
+        let mut one = StepRng::new(1, 0);
+        let x: f64 = one.sample(HighPrecision01);
+        assert_eq!(x, 1.0 / (1u64 << 63) as f64 / 2.0);
+    }
+
+    #[test]
+    fn high_precision_01_small_values() {
+        let mut rng = ::test::rng(807);
+        let mut small = 0;
+        for _ in 0..1000 {
+            let x: f64 = rng.sample(HighPrecision01);
+            assert!(0.0 <= x && x < 1.0);
+            // With `Uniform`, values below 2^-53 are impossible.
+            if x < 1.0 / (1u64 << 60) as f64 { small += 1; }
+            let y: f32 = rng.sample(HighPrecision01);
+            assert!(0.0 <= y && y < 1.0);
+            if y != 0.0 && y < 1.0 / (1u64 << 30) as f32 { small += 1; }
+        }
+        assert_eq!(small, 0);
+        // the fraction of the first 2^-9 is about 0.2%
+        let count = (0..10_000)
+            .filter(|_| rng.sample::<f64, _>(HighPrecision01) < 1.0 / 512.0)
+            .count();
+        assert!(count > 5 && count < 40);
+    }
+
+    #[test]
+    fn high_precision_range() {
+        let mut rng = ::test::rng(808);
+        let d = HighPrecision::<f64>::new(-2.0, 3.0);
+        let d32 = HighPrecision::<f32>::new(0.0, 1e-30);
+        for _ in 0..100 {
+            let x = rng.sample(d);
+            assert!(-2.0 <= x && x < 3.0);
+            let y = rng.sample(d32);
+            assert!(0.0 <= y && y < 1e-30);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn high_precision_range_invalid() {
+        HighPrecision::<f64>::new(0.0, ::core::f64::INFINITY);
+    }
+
+    #[test]
+    fn high_precision_try_new() {
+        use distributions::ParamError;
+        use core::f64::{MAX, NAN, NEG_INFINITY};
+        assert!(HighPrecision::<f32>::try_new(-1.0, 1.0).is_ok());
+        assert_eq!(HighPrecision::<f64>::try_new(1.0, 1.0).unwrap_err(),
+                   ParamError::OutOfRange("high"));
+        assert_eq!(HighPrecision::<f64>::try_new(NEG_INFINITY, 0.0).unwrap_err(),
+                   ParamError::OutOfRange("low"));
+        assert_eq!(HighPrecision::<f64>::try_new(NAN, 0.0).unwrap_err(),
+                   ParamError::OutOfRange("low"));
+        assert_eq!(HighPrecision::<f64>::try_new(-MAX, MAX).unwrap_err(),
+                   ParamError::OutOfRange("high"));
+    }
 }
@@ -17,7 +17,7 @@ use self::ChiSquaredRepr::*;
 
 use {Rng};
 use distributions::normal::StandardNormal;
-use distributions::{Distribution, Exp, Uniform};
+use distributions::{Distribution, Exp, Uniform, ParamError};
 
 /// The Gamma distribution `Gamma(shape, scale)` distribution.
 ///
@@ -51,6 +51,11 @@ use distributions::{Distribution, Exp, Uniform};
 /// 363-372. DOI:[10.1145/358407.358414](https://doi.acm.org/10.1145/358407.358414)
 #[derive(Clone, Copy, Debug)]
 pub struct Gamma {
+    // The parameters as given, for serialization
+    #[cfg_attr(not(feature="serde-1"), allow(dead_code))]
+    shape: f64,
+    #[cfg_attr(not(feature="serde-1"), allow(dead_code))]
+    scale: f64,
     repr: GammaRepr,
 }
 
@@ -96,11 +101,27 @@ impl Gamma {
     /// Construct an object representing the `Gamma(shape, scale)`
     /// distribution.
     ///
-    /// Panics if `shape <= 0` or `scale <= 0`.
+    /// Panics if `shape <= 0` or `scale <= 0`, or if `scale` is infinite.
     #[inline]
     pub fn new(shape: f64, scale: f64) -> Gamma {
-        assert!(shape > 0.0, "Gamma::new called with shape <= 0");
-        assert!(scale > 0.0, "Gamma::new called with scale <= 0");
+        Gamma::try_new(shape, scale)
+            .unwrap_or_else(|e| panic!("Gamma::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct an object representing the `Gamma(shape, scale)`
+    /// distribution, or return an error if `shape <= 0` or `scale <= 0` (or
+    /// either is NaN), or if `scale` is infinite.
+    #[inline]
+    pub fn try_new(shape: f64, scale: f64) -> Result<Gamma, ParamError> {
+        if !(shape > 0.0) {
+            return Err(ParamError::NotPositive("shape"));
+        }
+        if !(scale > 0.0) {
+            return Err(ParamError::NotPositive("scale"));
+        }
+        if !scale.is_finite() {
+            return Err(ParamError::OutOfRange("scale"));
+        }
 
         let repr = if shape == 1.0 {
             One(Exp::new(1.0 / scale))
@@ -109,7 +130,7 @@ impl Gamma {
         } else {
             Large(GammaLargeShape::new_raw(shape, scale))
         };
-        Gamma { repr: repr }
+        Ok(Gamma { shape: shape, scale: scale, repr: repr })
     }
 }
 
@@ -189,6 +210,9 @@ impl Distribution<f64> for GammaLargeShape {
 /// ```
 #[derive(Clone, Copy, Debug)]
 pub struct ChiSquared {
+    // The degrees of freedom as given, for serialization
+    #[cfg_attr(not(feature="serde-1"), allow(dead_code))]
+    k: f64,
     repr: ChiSquaredRepr,
 }
 
@@ -203,15 +227,23 @@ enum ChiSquaredRepr {
 
 impl ChiSquared {
     /// Create a new chi-squared distribution with degrees-of-freedom
-    /// `k`. Panics if `k < 0`.
+    /// `k`. Panics if `k <= 0`.
     pub fn new(k: f64) -> ChiSquared {
+        ChiSquared::try_new(k)
+            .unwrap_or_else(|e| panic!("ChiSquared::new called with invalid parameters: {}", e))
+    }
+
+    /// Create a new chi-squared distribution with degrees-of-freedom
+    /// `k`, or return an error if `k <= 0` or NaN.
+    pub fn try_new(k: f64) -> Result<ChiSquared, ParamError> {
         let repr = if k == 1.0 {
             DoFExactlyOne
         } else {
-            assert!(k > 0.0, "ChiSquared::new called with `k` < 0");
-            DoFAnythingElse(Gamma::new(0.5 * k, 2.0))
+            let gamma = Gamma::try_new(0.5 * k, 2.0)
+                .map_err(|_| ParamError::NotPositive("k"))?;
+            DoFAnythingElse(gamma)
         };
-        ChiSquared { repr: repr }
+        Ok(ChiSquared { k: k, repr: repr })
     }
 }
 impl Distribution<f64> for ChiSquared {
@@ -255,14 +287,20 @@ impl FisherF {
     /// Create a new `FisherF` distribution, with the given
     /// parameter. Panics if either `m` or `n` are not positive.
     pub fn new(m: f64, n: f64) -> FisherF {
-        assert!(m > 0.0, "FisherF::new called with `m < 0`");
-        assert!(n > 0.0, "FisherF::new called with `n < 0`");
+        FisherF::try_new(m, n)
+            .unwrap_or_else(|e| panic!("FisherF::new called with invalid parameters: {}", e))
+    }
 
-        FisherF {
-            numer: ChiSquared::new(m),
-            denom: ChiSquared::new(n),
+    /// Create a new `FisherF` distribution, with the given parameters, or
+    /// return an error if either `m` or `n` is not positive.
+    pub fn try_new(m: f64, n: f64) -> Result<FisherF, ParamError> {
+        let numer = ChiSquared::try_new(m).map_err(|_| ParamError::NotPositive("m"))?;
+        let denom = ChiSquared::try_new(n).map_err(|_| ParamError::NotPositive("n"))?;
+        Ok(FisherF {
+            numer: numer,
+            denom: denom,
             dof_ratio: n / m
-        }
+        })
     }
 }
 impl Distribution<f64> for FisherF {
@@ -293,11 +331,18 @@ impl StudentT {
     /// Create a new Student t distribution with `n` degrees of
     /// freedom. Panics if `n <= 0`.
     pub fn new(n: f64) -> StudentT {
-        assert!(n > 0.0, "StudentT::new called with `n <= 0`");
-        StudentT {
-            chi: ChiSquared::new(n),
+        StudentT::try_new(n)
+            .unwrap_or_else(|e| panic!("StudentT::new called with invalid parameters: {}", e))
+    }
+
+    /// Create a new Student t distribution with `n` degrees of freedom, or
+    /// return an error if `n <= 0` or NaN.
+    pub fn try_new(n: f64) -> Result<StudentT, ParamError> {
+        let chi = ChiSquared::try_new(n).map_err(|_| ParamError::NotPositive("n"))?;
+        Ok(StudentT {
+            chi: chi,
             dof: n
-        }
+        })
     }
 }
 impl Distribution<f64> for StudentT {
@@ -307,10 +352,61 @@ impl Distribution<f64> for StudentT {
     }
 }
 
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Gamma")]
+struct GammaParams {
+    shape: f64,
+    scale: f64,
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { Gamma, GammaParams,
+    |d| GammaParams { shape: d.shape, scale: d.scale },
+    |p| Gamma::try_new(p.shape, p.scale) }
+
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ChiSquared")]
+struct ChiSquaredParams {
+    k: f64,
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { ChiSquared, ChiSquaredParams,
+    |d| ChiSquaredParams { k: d.k },
+    |p| ChiSquared::try_new(p.k) }
+
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "FisherF")]
+struct FisherFParams {
+    m: f64,
+    n: f64,
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { FisherF, FisherFParams,
+    |d| FisherFParams { m: d.numer.k, n: d.denom.k },
+    |p| FisherF::try_new(p.m, p.n) }
+
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "StudentT")]
+struct StudentTParams {
+    n: f64,
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { StudentT, StudentTParams,
+    |d| StudentTParams { n: d.dof },
+    |p| StudentT::try_new(p.n) }
+
 #[cfg(test)]
 mod test {
     use distributions::Distribution;
-    use super::{ChiSquared, StudentT, FisherF};
+    use distributions::ParamError;
+    use super::{Gamma, ChiSquared, StudentT, FisherF};
 
     #[test]
     fn test_chi_squared_one() {
@@ -321,6 +417,21 @@ mod test {
         }
     }
     #[test]
+    fn test_try_new() {
+        use std::f64::{NAN, INFINITY};
+        assert!(Gamma::try_new(0.5, 2.0).is_ok());
+        assert_eq!(Gamma::try_new(0.0, 1.0).unwrap_err(), ParamError::NotPositive("shape"));
+        assert_eq!(Gamma::try_new(1.0, NAN).unwrap_err(), ParamError::NotPositive("scale"));
+        assert_eq!(Gamma::try_new(1.0, INFINITY).unwrap_err(), ParamError::OutOfRange("scale"));
+        assert_eq!(Gamma::try_new(0.5, INFINITY).unwrap_err(), ParamError::OutOfRange("scale"));
+        assert_eq!(Gamma::try_new(3.0, INFINITY).unwrap_err(), ParamError::OutOfRange("scale"));
+        assert!(Gamma::try_new(1.0, ::std::f64::MAX).is_ok());
+        assert!(ChiSquared::try_new(1.0).is_ok());
+        assert_eq!(ChiSquared::try_new(-1.0).unwrap_err(), ParamError::NotPositive("k"));
+        assert_eq!(FisherF::try_new(2.0, 0.0).unwrap_err(), ParamError::NotPositive("n"));
+        assert_eq!(StudentT::try_new(NAN).unwrap_err(), ParamError::NotPositive("n"));
+    }
+    #[test]
     fn test_chi_squared_small() {
         let chi = ChiSquared::new(0.5);
         let mut rng = ::test::rng(202);
@@ -11,6 +11,8 @@
 //! The implementations of the `Uniform` distribution for integer types.
 
 use core::mem;
+use core::num::{NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroUsize};
+use core::num::NonZeroU128;
 
 use {Rng};
 use distributions::{Distribution, Uniform};
@@ -24,6 +26,11 @@ impl Distribution<isize> for Uniform {
             rng.gen::<i64>() as isize
         }
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [isize]) {
+        rng.fill(dest)
+    }
 }
 
 impl Distribution<i8> for Uniform {
@@ -31,6 +38,11 @@ impl Distribution<i8> for Uniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i8 {
         rng.next_u32() as i8
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [i8]) {
+        rng.fill(dest)
+    }
 }
 
 impl Distribution<i16> for Uniform {
@@ -38,6 +50,11 @@ impl Distribution<i16> for Uniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i16 {
         rng.next_u32() as i16
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [i16]) {
+        rng.fill(dest)
+    }
 }
 
 impl Distribution<i32> for Uniform {
@@ -45,6 +62,11 @@ impl Distribution<i32> for Uniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
         rng.next_u32() as i32
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [i32]) {
+        rng.fill(dest)
+    }
 }
 
 impl Distribution<i64> for Uniform {
@@ -52,14 +74,23 @@ impl Distribution<i64> for Uniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i64 {
         rng.next_u64() as i64
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [i64]) {
+        rng.fill(dest)
+    }
 }
 
-#[cfg(feature = "i128_support")]
 impl Distribution<i128> for Uniform {
     #[inline]
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i128 {
         rng.gen::<u128>() as i128
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [i128]) {
+        rng.fill(dest)
+    }
 }
 
 impl Distribution<usize> for Uniform {
@@ -71,6 +102,11 @@ impl Distribution<usize> for Uniform {
             rng.gen::<u64>() as usize
         }
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [usize]) {
+        rng.fill(dest)
+    }
 }
 
 impl Distribution<u8> for Uniform {
@@ -78,6 +114,11 @@ impl Distribution<u8> for Uniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u8 {
         rng.next_u32() as u8
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [u8]) {
+        rng.fill(dest)
+    }
 }
 
 impl Distribution<u16> for Uniform {
@@ -85,6 +126,11 @@ impl Distribution<u16> for Uniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u16 {
         rng.next_u32() as u16
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [u16]) {
+        rng.fill(dest)
+    }
 }
 
 impl Distribution<u32> for Uniform {
@@ -92,6 +138,11 @@ impl Distribution<u32> for Uniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u32 {
         rng.next_u32()
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [u32]) {
+        rng.fill(dest)
+    }
 }
 
 impl Distribution<u64> for Uniform {
@@ -99,22 +150,100 @@ impl Distribution<u64> for Uniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
         rng.next_u64()
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [u64]) {
+        rng.fill(dest)
+    }
 }
 
-#[cfg(feature = "i128_support")]
 impl Distribution<u128> for Uniform {
     #[inline]
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u128 {
         ((rng.next_u64() as u128) << 64) | (rng.next_u64() as u128)
     }
+
+    #[inline]
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [u128]) {
+        rng.fill(dest)
+    }
+}
+
+macro_rules! impl_nzint {
+    ($ty:ty, $new:path) => {
+        impl Distribution<$ty> for Uniform {
+            /// Generate a non-zero value uniformly, by rejecting zero.
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                loop {
+                    if let Some(nz) = $new(rng.gen()) {
+                        break nz;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl_nzint!(NonZeroU8, NonZeroU8::new);
+impl_nzint!(NonZeroU16, NonZeroU16::new);
+impl_nzint!(NonZeroU32, NonZeroU32::new);
+impl_nzint!(NonZeroU64, NonZeroU64::new);
+impl_nzint!(NonZeroU128, NonZeroU128::new);
+impl_nzint!(NonZeroUsize, NonZeroUsize::new);
+
+
+
+#[cfg(feature="simd_support")]
+macro_rules! simd_impl {
+    ($($ty:ident),*) => {
+        $(
+        impl Distribution<::core::simd::$ty> for Uniform {
+            /// Generate a vector with each lane sampled uniformly.
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ::core::simd::$ty {
+                let mut vec = ::core::simd::$ty::default();
+                rng.fill(vec.as_mut_array());
+                vec
+            }
+        }
+        )*
+    }
 }
 
+#[cfg(feature="simd_support")]
+simd_impl!(u8x16, u8x32, u8x64, u16x8, u16x16, u16x32,
+           u32x4, u32x8, u32x16, u64x2, u64x4, u64x8,
+           i8x16, i8x32, i8x64, i16x8, i16x16, i16x32,
+           i32x4, i32x8, i32x16, i64x2, i64x4, i64x8);
 
 #[cfg(test)]
 mod tests {
     use Rng;
     use distributions::{Uniform};
     
+    #[test]
+    #[cfg(feature="simd_support")]
+    fn test_simd() {
+        use core::simd::{u8x16, i32x8, u64x4};
+        let mut rng = ::test::rng(807);
+        let v: u8x16 = rng.sample(Uniform);
+        assert!(v.as_array().iter().any(|&x| x != v.as_array()[0]));
+        rng.sample::<i32x8, _>(Uniform);
+        rng.sample::<u64x4, _>(Uniform);
+    }
+
+    #[test]
+    #[cfg(feature="simd_support")]
+    fn test_simd_masks() {
+        use core::simd::{mask8x64, mask64x2};
+        let mut rng = ::test::rng(808);
+        let m: mask8x64 = rng.sample(Uniform);
+        let count = m.to_bitmask().count_ones();
+        assert!(count > 10 && count < 54, "{}", count);
+        rng.sample::<mask64x2, _>(Uniform);
+    }
+
     #[test]
     fn test_integers() {
         let mut rng = ::test::rng(806);
@@ -124,7 +253,6 @@ mod tests {
         rng.sample::<i16, _>(Uniform);
         rng.sample::<i32, _>(Uniform);
         rng.sample::<i64, _>(Uniform);
-        #[cfg(feature = "i128_support")]
         rng.sample::<i128, _>(Uniform);
         
         rng.sample::<usize, _>(Uniform);
@@ -132,7 +260,28 @@ mod tests {
         rng.sample::<u16, _>(Uniform);
         rng.sample::<u32, _>(Uniform);
         rng.sample::<u64, _>(Uniform);
-        #[cfg(feature = "i128_support")]
         rng.sample::<u128, _>(Uniform);
     }
+
+    #[test]
+    fn test_u128_uses_both_halves() {
+        use mock::StepRng;
+        let mut rng = StepRng::new(1, 1);
+        assert_eq!(rng.sample::<u128, _>(Uniform), (1u128 << 64) | 2);
+    }
+
+    #[test]
+    fn test_nonzero() {
+        use core::num::{NonZeroU8, NonZeroU32, NonZeroUsize};
+        use mock::StepRng;
+
+        let mut rng = ::test::rng(807);
+        rng.sample::<NonZeroU8, _>(Uniform);
+        rng.sample::<NonZeroU32, _>(Uniform);
+        rng.sample::<NonZeroUsize, _>(Uniform);
+
+        // Zero is rejected; the next value is used instead.
+        let mut rng = StepRng::new(0, 1);
+        assert_eq!(rng.sample::<NonZeroU32, _>(Uniform).get(), 1);
+    }
 }
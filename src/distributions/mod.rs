@@ -15,24 +15,76 @@
 //! bounds. Distributions use the `Distribution` trait to yield values: call
 //! `distr.sample(&mut rng)` to get a random variable.
 
-use Rng;
+use core::{fmt, marker, ops};
+#[cfg(all(not(feature="std"), feature="alloc"))] use alloc::string::String;
+#[cfg(all(not(feature="std"), feature="alloc"))] use alloc::vec::Vec;
+#[cfg(all(not(feature="std"), feature="alloc"))] use alloc::boxed::Box;
 
-pub use self::other::Alphanumeric;
+use {Rng, RngCore, Error};
+
+pub use self::other::{Alphanumeric, AlphanumericUpper, AlphanumericLower,
+                      HexDigit, Charset, Base64Url, UuidV4};
 pub use self::range::Range;
+pub use self::bernoulli::Bernoulli;
+#[cfg(feature="simd_support")]
+pub use self::bernoulli::BernoulliMask;
+pub use self::float::{Open01, OpenClosed01, HighPrecision01, HighPrecision};
 #[cfg(feature="std")]
 pub use self::gamma::{Gamma, ChiSquared, FisherF, StudentT};
 #[cfg(feature="std")]
-pub use self::normal::{Normal, LogNormal, StandardNormal};
+pub use self::normal::{Normal, LogNormal, StandardNormal, StandardNormalF32};
+#[cfg(feature="std")]
+pub use self::exponential::{Exp, Exp1, ExpF32};
+#[cfg(feature="std")]
+pub use self::net::{Ipv4Prefix, Ipv6Prefix};
 #[cfg(feature="std")]
-pub use self::exponential::{Exp, Exp1};
+pub use self::time::Recurring;
+#[cfg(any(feature="std", feature="alloc"))]
+pub use self::dynamic_weighted::DynamicWeightedIndex;
+
+// Implement `Serialize` and `Deserialize` for a distribution by way of a
+// struct of its user-facing parameters. Deserializing always goes through the
+// checked constructor, so the derived internal state can't be tampered with.
+#[cfg(feature="serde-1")]
+macro_rules! serde_via_params {
+    ($ty:ty, $params:ty, |$s:ident| $to:expr, |$p:ident| $from:expr) => {
+        impl ::serde::Serialize for $ty {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where S: ::serde::Serializer
+            {
+                let $s = self;
+                let params: $params = $to;
+                ::serde::Serialize::serialize(&params, serializer)
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $ty {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where D: ::serde::Deserializer<'de>
+            {
+                let $p: $params = ::serde::Deserialize::deserialize(deserializer)?;
+                $from.map_err(::serde::de::Error::custom)
+            }
+        }
+    }
+}
 
 pub mod range;
+pub mod bernoulli;
 #[cfg(feature="std")]
 pub mod gamma;
 #[cfg(feature="std")]
 pub mod normal;
 #[cfg(feature="std")]
 pub mod exponential;
+#[cfg(feature="std")]
+pub mod net;
+#[cfg(feature="std")]
+pub mod time;
+#[cfg(any(feature="std", feature="alloc"))]
+pub mod dynamic_weighted;
+#[cfg(feature="std")]
+pub mod ziggurat;
 
 mod float;
 mod integer;
@@ -123,11 +175,462 @@ pub trait Distribution<T> {
     /// Generate a random value of `T`, using `rng` as the
     /// source of randomness.
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T;
+
+    /// Fill `dest` with random values of `T`, using `rng` as the source of
+    /// randomness.
+    ///
+    /// The default implementation simply calls `sample` for each element.
+    /// Some distributions provide faster implementations, e.g. `Uniform` for
+    /// integer and floating-point types uses a single `fill_bytes` call, and
+    /// `Range` for integers generates its random numbers in batches. The
+    /// values produced may differ from those of repeated `sample` calls with
+    /// the same RNG, but follow the same distribution.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::thread_rng;
+    /// use rand::distributions::{Distribution, Uniform};
+    ///
+    /// let mut buf = [0.0f64; 1000];
+    /// Uniform.sample_fill(&mut thread_rng(), &mut buf[..]);
+    /// ```
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [T]) {
+        for x in dest.iter_mut() {
+            *x = self.sample(rng);
+        }
+    }
+
+    /// Create an iterator that generates random values of `T`, using `rng` as
+    /// the source of randomness.
+    ///
+    /// Both the distribution and the RNG are taken by value, so the iterator
+    /// can be returned or stored freely. Since `&D` implements `Distribution`
+    /// and `&mut R` implements `Rng`, either may also be passed by reference.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::thread_rng;
+    /// use rand::distributions::{Distribution, Alphanumeric, Range};
+    ///
+    /// let mut rng = thread_rng();
+    ///
+    /// // Vec of 16 x f32:
+    /// let v: Vec<f32> = Range::new(0.0f32, 1.0).sample_iter(&mut rng).take(16)
+    ///     .collect();
+    ///
+    /// // String:
+    /// let s: String = Alphanumeric.sample_iter(&mut rng).take(7).collect();
+    /// # let _ = (v, s);
+    /// ```
+    fn sample_iter<R: Rng>(self, rng: R) -> DistIter<Self, R, T>
+        where Self: Sized
+    {
+        DistIter {
+            distr: self,
+            rng: rng,
+            phantom: marker::PhantomData,
+        }
+    }
+
+    /// Create a distribution of values of `S` by mapping the output of this
+    /// distribution through `func`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::thread_rng;
+    /// use rand::distributions::{Distribution, Exp};
+    ///
+    /// let waiting_time = Exp::new(1.0).map(|x: f64| x.ceil() as u64);
+    /// let minutes: u64 = waiting_time.sample(&mut thread_rng());
+    /// println!("Waited {} minutes", minutes);
+    /// ```
+    fn map<F, S>(self, func: F) -> Map<Self, F, T, S>
+        where F: Fn(T) -> S, Self: Sized
+    {
+        Map {
+            distr: self,
+            func: func,
+            phantom: marker::PhantomData,
+        }
+    }
+
+    /// Combine this distribution with `other` into a distribution of pairs.
+    ///
+    /// The result is simply the tuple `(self, other)`: tuples of up to 12
+    /// distributions are themselves distributions, sampling each component
+    /// independently.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::thread_rng;
+    /// use rand::distributions::{Distribution, Range, StandardNormal};
+    ///
+    /// let mut rng = thread_rng();
+    /// let record = Range::new(0, 10).zip(StandardNormal);
+    /// let (id, score): (i32, f64) = record.sample(&mut rng);
+    ///
+    /// // Equivalently, using a tuple directly:
+    /// let (id2, score2, flag): (i32, f64, bool) =
+    ///     (Range::new(0, 10), StandardNormal, rand::distributions::Uniform)
+    ///     .sample(&mut rng);
+    /// # let _ = (id, score, id2, score2, flag);
+    /// ```
+    fn zip<D>(self, other: D) -> (Self, D)
+        where Self: Sized
+    {
+        (self, other)
+    }
+
+    /// Box this distribution as a [`DynDistribution`] trait object, e.g. to
+    /// store distributions of different types in one collection.
+    ///
+    /// See [`DynDistribution`] for an example.
+    ///
+    /// [`DynDistribution`]: trait.DynDistribution.html
+    #[cfg(any(feature="std", feature="alloc"))]
+    fn boxed<'a>(self) -> Box<dyn DynDistribution<T> + 'a>
+        where Self: Sized + 'a
+    {
+        Box::new(self)
+    }
 }
 
-impl<'a, T, D: Distribution<T>> Distribution<T> for &'a D {
+impl<'a, T, D: Distribution<T> + ?Sized> Distribution<T> for &'a D {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
-        (*self).sample(rng)
+        (**self).sample(rng)
+    }
+
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [T]) {
+        (**self).sample_fill(rng, dest)
+    }
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl<T, D: Distribution<T> + ?Sized> Distribution<T> for Box<D> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        (**self).sample(rng)
+    }
+
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [T]) {
+        (**self).sample_fill(rng, dest)
+    }
+}
+
+/// An object-safe counterpart of [`Distribution`].
+///
+/// `Distribution` cannot be used as a trait object, since its methods are
+/// generic over the RNG. `DynDistribution` takes the RNG as a
+/// `&mut dyn RngCore` instead, and is implemented for every `Distribution`.
+/// In turn, `dyn DynDistribution<T>` (and so `&dyn DynDistribution<T>` and
+/// `Box<dyn DynDistribution<T>>`) implements `Distribution<T>`, sampling
+/// through the trait object.
+///
+/// This allows distributions of different types to be stored together,
+/// at the cost of a virtual call per sample and per RNG call.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::distributions::{Distribution, DynDistribution, Range, Normal};
+///
+/// let fields: Vec<Box<dyn DynDistribution<f64>>> = vec![
+///     Range::new(0.0, 10.0).boxed(),
+///     Normal::new(5.0, 1.0).boxed(),
+///     Box::new(Range::new(0, 100).map(|x: i32| x as f64)),
+/// ];
+/// let mut rng = thread_rng();
+/// let row: Vec<f64> = fields.iter().map(|d| d.sample(&mut rng)).collect();
+/// assert_eq!(row.len(), 3);
+/// ```
+///
+/// [`Distribution`]: trait.Distribution.html
+pub trait DynDistribution<T> {
+    /// Generate a random value of `T`, as [`Distribution::sample`].
+    ///
+    /// [`Distribution::sample`]: trait.Distribution.html#tymethod.sample
+    fn sample_dyn(&self, rng: &mut dyn RngCore) -> T;
+
+    /// Fill `dest` with random values of `T`, as
+    /// [`Distribution::sample_fill`].
+    ///
+    /// [`Distribution::sample_fill`]: trait.Distribution.html#method.sample_fill
+    fn sample_fill_dyn(&self, rng: &mut dyn RngCore, dest: &mut [T]);
+}
+
+impl<T, D: Distribution<T>> DynDistribution<T> for D {
+    fn sample_dyn(&self, rng: &mut dyn RngCore) -> T {
+        self.sample(rng)
+    }
+
+    fn sample_fill_dyn(&self, rng: &mut dyn RngCore, dest: &mut [T]) {
+        self.sample_fill(rng, dest)
+    }
+}
+
+impl<'a, T> Distribution<T> for dyn DynDistribution<T> + 'a {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        // `R` may be unsized, so wrap it before erasing the type.
+        self.sample_dyn(&mut RngWrapper(rng))
+    }
+
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [T]) {
+        self.sample_fill_dyn(&mut RngWrapper(rng), dest)
+    }
+}
+
+/// String sampler
+///
+/// Sampling a `String` of random characters is not quite the same as
+/// collecting a sequence of chars. This trait allows distributions to
+/// implement it efficiently; e.g. `Alphanumeric` only produces ASCII and so
+/// can reserve the exact number of bytes needed up front.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::distributions::{Alphanumeric, DistString};
+///
+/// let s = Alphanumeric.sample_string(&mut thread_rng(), 16);
+/// assert_eq!(s.len(), 16);
+/// ```
+#[cfg(any(feature="std", feature="alloc"))]
+pub trait DistString {
+    /// Append `len` random chars to `string`.
+    ///
+    /// Note that `len` counts chars, not bytes: each char is appended as its
+    /// full UTF-8 encoding.
+    fn append_string<R: Rng + ?Sized>(&self, rng: &mut R, string: &mut String,
+                                      len: usize);
+
+    /// Generate a `String` of `len` random chars.
+    #[inline]
+    fn sample_string<R: Rng + ?Sized>(&self, rng: &mut R, len: usize)
+        -> String
+    {
+        let mut s = String::new();
+        self.append_string(rng, &mut s, len);
+        s
+    }
+}
+
+macro_rules! tuple_distribution_impl {
+    ($(($distr:ident, $ty:ident, $idx:tt)),*) => {
+        impl<$($distr, $ty),*> Distribution<($($ty,)*)> for ($($distr,)*)
+            where $($distr: Distribution<$ty>),*
+        {
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ($($ty,)*) {
+                ($(self.$idx.sample(rng),)*)
+            }
+        }
+    }
+}
+
+tuple_distribution_impl!{(DA, A, 0)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2), (DD, D, 3)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2), (DD, D, 3),
+                         (DE, E, 4)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2), (DD, D, 3),
+                         (DE, E, 4), (DF, F, 5)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2), (DD, D, 3),
+                         (DE, E, 4), (DF, F, 5), (DG, G, 6)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2), (DD, D, 3),
+                         (DE, E, 4), (DF, F, 5), (DG, G, 6), (DH, H, 7)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2), (DD, D, 3),
+                         (DE, E, 4), (DF, F, 5), (DG, G, 6), (DH, H, 7),
+                         (DI, I, 8)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2), (DD, D, 3),
+                         (DE, E, 4), (DF, F, 5), (DG, G, 6), (DH, H, 7),
+                         (DI, I, 8), (DJ, J, 9)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2), (DD, D, 3),
+                         (DE, E, 4), (DF, F, 5), (DG, G, 6), (DH, H, 7),
+                         (DI, I, 8), (DJ, J, 9), (DK, K, 10)}
+tuple_distribution_impl!{(DA, A, 0), (DB, B, 1), (DC, C, 2), (DD, D, 3),
+                         (DE, E, 4), (DF, F, 5), (DG, G, 6), (DH, H, 7),
+                         (DI, I, 8), (DJ, J, 9), (DK, K, 10), (DL, L, 11)}
+
+/// A distribution of values of `S` derived from the distribution `D` by
+/// mapping its output of type `T` through the closure `F`.
+///
+/// This `struct` is created by the [`map`] method on [`Distribution`].
+///
+/// [`Distribution`]: trait.Distribution.html
+/// [`map`]: trait.Distribution.html#method.map
+#[derive(Debug)]
+pub struct Map<D, F, T, S> {
+    distr: D,
+    func: F,
+    phantom: marker::PhantomData<fn(T) -> S>,
+}
+
+impl<D, F, T, S> Distribution<S> for Map<D, F, T, S>
+    where D: Distribution<T>, F: Fn(T) -> S
+{
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> S {
+        (self.func)(self.distr.sample(rng))
+    }
+}
+
+/// Create a distribution from a sampling closure.
+///
+/// The closure is given the RNG as a `&mut dyn RngCore` trait object, on which
+/// all `Rng` methods are available.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, Rng};
+/// use rand::distributions::{self, Distribution};
+///
+/// // The sum of two dice
+/// let two_dice = distributions::from_fn(|rng| {
+///     rng.gen_range(1..=6) + rng.gen_range(1..=6)
+/// });
+/// let total: u32 = two_dice.sample(&mut thread_rng());
+/// assert!(total >= 2 && total <= 12);
+/// ```
+pub fn from_fn<T, F>(func: F) -> FromFn<F>
+    where F: Fn(&mut dyn RngCore) -> T
+{
+    FromFn { func: func }
+}
+
+/// A distribution defined by a sampling closure.
+///
+/// This `struct` is created by the [`from_fn`] function.
+///
+/// [`from_fn`]: fn.from_fn.html
+#[derive(Debug)]
+pub struct FromFn<F> {
+    func: F,
+}
+
+impl<T, F> Distribution<T> for FromFn<F> where F: Fn(&mut dyn RngCore) -> T {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        // `R` may be unsized, so wrap it before erasing the type.
+        let mut rng = RngWrapper(rng);
+        (self.func)(&mut rng)
+    }
+}
+
+// Sized wrapper allowing any `RngCore` to be used as a trait object.
+struct RngWrapper<'a, R: RngCore + ?Sized + 'a>(&'a mut R);
+
+impl<'a, R: RngCore + ?Sized> RngCore for RngWrapper<'a, R> {
+    #[inline(always)]
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    #[inline(always)]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    #[inline(always)]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+
+    #[inline(always)]
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        self.0.fill_u32_slice(dest)
+    }
+
+    #[inline(always)]
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        self.0.fill_u64_slice(dest)
+    }
+}
+
+/// An iterator that generates random values of `T` with distribution `D`,
+/// using `R` as the source of randomness.
+///
+/// This `struct` is created by the [`sample_iter`] method on [`Distribution`]
+/// and [`Rng`]. See their documentation for more.
+///
+/// [`Distribution`]: trait.Distribution.html
+/// [`sample_iter`]: trait.Distribution.html#method.sample_iter
+/// [`Rng`]: ../trait.Rng.html
+#[derive(Debug)]
+pub struct DistIter<D, R, T> {
+    distr: D,
+    rng: R,
+    phantom: marker::PhantomData<fn() -> T>,
+}
+
+impl<D, R, T> Iterator for DistIter<D, R, T>
+    where D: Distribution<T>, R: Rng
+{
+    type Item = T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<T> {
+        Some(self.distr.sample(&mut self.rng))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::max_value(), None)
+    }
+}
+
+/// An error returned by the `try_new` constructors of distributions with
+/// invalid parameters.
+///
+/// Each variant names the offending parameter, as in the constructor's
+/// signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamError {
+    /// A parameter which must be positive is zero, negative or NaN.
+    NotPositive(&'static str),
+    /// A parameter which must not be negative is negative or NaN.
+    Negative(&'static str),
+    /// A parameter is NaN.
+    NaN(&'static str),
+    /// A parameter is outside of its valid range otherwise, e.g. a
+    /// probability larger than 1.
+    OutOfRange(&'static str),
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParamError::NotPositive(name) => write!(f, "`{}` must be positive", name),
+            ParamError::Negative(name) => write!(f, "`{}` must not be negative", name),
+            ParamError::NaN(name) => write!(f, "`{}` must not be NaN", name),
+            ParamError::OutOfRange(name) => write!(f, "`{}` is out of range", name),
+            ParamError::__Nonexhaustive => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature="std")]
+impl ::std::error::Error for ParamError {
+    fn description(&self) -> &str {
+        match *self {
+            ParamError::NotPositive(_) => "parameter is not positive",
+            ParamError::Negative(_) => "parameter is negative",
+            ParamError::NaN(_) => "parameter is NaN",
+            ParamError::OutOfRange(_) => "parameter is out of range",
+            ParamError::__Nonexhaustive => unreachable!(),
+        }
     }
 }
 
@@ -145,6 +648,8 @@ impl<'a, T, D: Distribution<T>> Distribution<T> for &'a D {
 ///
 /// * Integers (`i32`, `u32`, `isize`, `usize`, etc.): Uniformly distributed
 ///   over all values of the type.
+/// * Non-zero integers (`NonZeroU32`, `NonZeroU64`, etc.): Uniformly
+///   distributed over all non-zero values of the type.
 /// * `char`: Uniformly distributed over all Unicode scalar values, i.e. all
 ///   code points in the range `0...0x10_FFFF`, except for the range
 ///   `0xD800...0xDFFF` (the surrogate code points). This includes
@@ -157,9 +662,16 @@ impl<'a, T, D: Distribution<T>> Distribution<T> for &'a D {
 /// long as their component types implement it:
 ///
 /// * Tuples and arrays: Each element of the tuple or array is generated
-///   independently, using the `Uniform` distribution recursively.
+///   independently, using the `Uniform` distribution recursively. Arrays are
+///   supported up to 32 elements for any element type, and additionally for
+///   lengths 33 to 64 and 96, 128, 256, ..., 4096 when the element type is
+///   `Copy + Default`.
 /// * `Option<T>`: Returns `None` with probability 0.5; otherwise generates a
 ///   random `T` and returns `Some(T)`.
+/// * `Wrapping<T>`: Wraps a random `T`.
+/// * Network addresses (`Ipv4Addr`, `Ipv6Addr`, `IpAddr`, `SocketAddr`;
+///   requires `std`): Uniformly distributed over the whole address space.
+///   See the [`net`](net/index.html) module.
 ///
 /// # Example
 /// ```rust
@@ -185,6 +697,7 @@ impl<'a, T, D: Distribution<T>> Distribution<T> for &'a D {
 /// [`Exp1`]: struct.Exp1.html
 /// [`StandardNormal`]: struct.StandardNormal.html
 #[derive(Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
 pub struct Uniform;
 
 #[allow(deprecated)]
@@ -197,6 +710,7 @@ impl<T> ::Rand for T where Uniform: Distribution<T> {
 
 /// A value with a particular weight for use with `WeightedChoice`.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
 pub struct Weighted<T> {
     /// The numerical weight of this item
     pub weight: u32,
@@ -243,30 +757,137 @@ impl<'a, T: Clone> WeightedChoice<'a, T> {
     /// - the total weight is 0
     /// - the total weight is larger than a `u32` can contain.
     pub fn new(items: &'a mut [Weighted<T>]) -> WeightedChoice<'a, T> {
-        // strictly speaking, this is subsumed by the total weight == 0 case
-        assert!(!items.is_empty(), "WeightedChoice::new called with no items");
+        WeightedChoice::try_new(items).unwrap_or_else(|e| {
+            panic!("WeightedChoice::new called with invalid parameters: {}", e)
+        })
+    }
 
+    /// Create a new `WeightedChoice`, or return an error if the total weight
+    /// is 0 (including if `items` is empty) or larger than a `u32` can
+    /// contain. `items` is left unchanged on error.
+    pub fn try_new(items: &'a mut [Weighted<T>])
+        -> Result<WeightedChoice<'a, T>, ParamError>
+    {
         let mut running_total: u32 = 0;
-
-        // we convert the list from individual weights to cumulative
-        // weights so we can binary search. This *could* drop elements
-        // with weight == 0 as an optimisation.
-        for item in items.iter_mut() {
+        for item in items.iter() {
             running_total = match running_total.checked_add(item.weight) {
                 Some(n) => n,
-                None => panic!("WeightedChoice::new called with a total weight \
-                               larger than a u32 can contain")
+                None => return Err(ParamError::OutOfRange("items")),
             };
+        }
+        if running_total == 0 {
+            return Err(ParamError::NotPositive("items"));
+        }
 
-            item.weight = running_total;
+        // We convert the list from individual weights to a Fenwick tree (a
+        // binary indexed tree) in place: with 1-based indices, item `i`
+        // holds the sum of the weights of items `i - lowbit(i) + 1 ..= i`.
+        // This allows both finding an item by cumulative weight and
+        // changing a weight in `O(log n)`. No partial sum can exceed the
+        // total, so this cannot overflow.
+        let len = items.len();
+        for i in 1..len + 1 {
+            let parent = i + lowbit(i);
+            if parent <= len {
+                items[parent - 1].weight += items[i - 1].weight;
+            }
         }
-        assert!(running_total != 0, "WeightedChoice::new called with a total weight of 0");
 
-        WeightedChoice {
+        Ok(WeightedChoice {
             items: items,
             // we're likely to be generating numbers in this range
             // relatively often, so might as well cache it
             weight_range: Range::new(0, running_total)
+        })
+    }
+
+    /// Change the weights of some items, given as `(index, new_weight)`
+    /// pairs.
+    ///
+    /// This takes `O(k log n)` time for `k` updates of `n` items, instead of
+    /// the `O(n)` of building a new `WeightedChoice`, which helps when only
+    /// a few weights change between samples. The updates are applied in
+    /// order, so if an index appears more than once the last weight wins.
+    ///
+    /// Panics if:
+    ///
+    /// - an index is out of bounds
+    /// - the total weight becomes larger than a `u32` can contain after any
+    ///   of the updates
+    /// - the total weight is 0 after the updates.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::distributions::{Weighted, WeightedChoice, Distribution};
+    ///
+    /// let mut items = vec!(Weighted { weight: 2, item: 'a' },
+    ///                      Weighted { weight: 4, item: 'b' },
+    ///                      Weighted { weight: 1, item: 'c' });
+    /// let mut wc = WeightedChoice::new(&mut items);
+    /// // never choose 'b' any more, and make 'c' as likely as 'a'
+    /// wc.update_weights(&[(1, 0), (2, 2)]);
+    /// let mut rng = rand::thread_rng();
+    /// assert!(wc.sample(&mut rng) != 'b');
+    /// ```
+    pub fn update_weights(&mut self, updates: &[(usize, u32)]) {
+        let mut total = self.total_weight();
+        for &(index, weight) in updates {
+            assert!(index < self.items.len(),
+                    "WeightedChoice::update_weights called with an index out of bounds");
+            let old = self.weight(index);
+            if weight >= old {
+                let delta = weight - old;
+                total = match total.checked_add(delta) {
+                    Some(n) => n,
+                    None => panic!("WeightedChoice::update_weights called with a \
+                                   total weight larger than a u32 can contain")
+                };
+                self.add(index, delta);
+            } else {
+                // No partial sum containing `index` is less than `old`, so
+                // the wrapping subtraction does not actually wrap.
+                let delta = (old - weight).wrapping_neg();
+                total -= old - weight;
+                self.add(index, delta);
+            }
+        }
+        assert!(total != 0, "WeightedChoice::update_weights called with a total weight of 0");
+        self.weight_range = Range::new(0, total);
+    }
+
+    // The sum of all weights.
+    fn total_weight(&self) -> u32 {
+        let mut sum = 0;
+        let mut i = self.items.len();
+        while i > 0 {
+            sum += self.items[i - 1].weight;
+            i -= lowbit(i);
+        }
+        sum
+    }
+
+    // The weight of item `index`.
+    fn weight(&self, index: usize) -> u32 {
+        // Item `index + 1` (1-based) holds the sum of the weights of a range
+        // ending at `index`; subtract the nodes covering the rest of it.
+        let i = index + 1;
+        let mut weight = self.items[i - 1].weight;
+        let start = i - lowbit(i);
+        let mut j = i - 1;
+        while j > start {
+            weight -= self.items[j - 1].weight;
+            j -= lowbit(j);
+        }
+        weight
+    }
+
+    // Add `delta` (wrapping) to the weight of item `index`.
+    fn add(&mut self, index: usize, delta: u32) {
+        let mut i = index + 1;
+        while i <= self.items.len() {
+            self.items[i - 1].weight = self.items[i - 1].weight.wrapping_add(delta);
+            i += lowbit(i);
         }
     }
 }
@@ -274,44 +895,45 @@ impl<'a, T: Clone> WeightedChoice<'a, T> {
 impl<'a, T: Clone> Distribution<T> for WeightedChoice<'a, T> {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
         // we want to find the first element that has cumulative
-        // weight > sample_weight, which we do by binary since the
-        // cumulative weights of self.items are sorted.
+        // weight > sample_weight.
 
         // choose a weight in [0, total_weight)
         let sample_weight = self.weight_range.sample(rng);
 
-        // short circuit when it's the first item
-        if sample_weight < self.items[0].weight {
-            return self.items[0].item.clone();
-        }
-
-        let mut idx = 0;
-        let mut modifier = self.items.len();
-
-        // now we know that every possibility has an element to the
-        // left, so we can just search for the last element that has
-        // cumulative weight <= sample_weight, then the next one will
-        // be "it". (Note that this greatest element will never be the
-        // last element of the vector, since sample_weight is chosen
-        // in [0, total_weight) and the cumulative weight of the last
-        // one is exactly the total weight.)
-        while modifier > 1 {
-            let i = idx + modifier / 2;
-            if self.items[i].weight <= sample_weight {
-                // we're small, so look to the right, but allow this
-                // exact element still.
-                idx = i;
-                // we need the `/ 2` to round up otherwise we'll drop
-                // the trailing elements when `modifier` is odd.
-                modifier += 1;
-            } else {
-                // otherwise we're too big, so go left. (i.e. do
-                // nothing)
-            }
-            modifier /= 2;
+        // The element after the last position with cumulative weight <=
+        // sample_weight is "it". (This is never past the end, since
+        // sample_weight is less than the total weight.)
+        let pos = fenwick_search(self.items.len(), sample_weight,
+                                 |i| self.items[i - 1].weight);
+        self.items[pos].item.clone()
+    }
+}
+
+// The lowest set bit of `i`.
+#[inline]
+fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+// Descend a Fenwick tree of `len` nodes, where `node(i)` is the value of the
+// 1-based node `i`, to find the last (1-based) position whose cumulative
+// weight is at most `target`, or 0 if there is none.
+fn fenwick_search<W, F>(len: usize, mut target: W, node: F) -> usize
+    where W: Copy + PartialOrd + ops::SubAssign, F: Fn(usize) -> W
+{
+    let mut pos = 0;
+    let mut step = 1;
+    while step * 2 <= len {
+        step *= 2;
+    }
+    while step > 0 {
+        if pos + step <= len && node(pos + step) <= target {
+            pos += step;
+            target -= node(pos);
         }
-        return self.items[idx + 1].item.clone();
+        step /= 2;
     }
+    pos
 }
 
 /// Sample a random number using the Ziggurat method (specifically the
@@ -334,8 +956,8 @@ impl<'a, T: Clone> Distribution<T> for WeightedChoice<'a, T> {
 fn ziggurat<R: Rng + ?Sized, P, Z>(
             rng: &mut R,
             symmetric: bool,
-            x_tab: ziggurat_tables::ZigTable,
-            f_tab: ziggurat_tables::ZigTable,
+            x_tab: &[f64; 257],
+            f_tab: &[f64; 257],
             mut pdf: P,
             mut zero_case: Z)
             -> f64 where P: FnMut(f64) -> f64, Z: FnMut(&mut R, f64) -> f64 {
@@ -377,12 +999,172 @@ fn ziggurat<R: Rng + ?Sized, P, Z>(
     }
 }
 
+// The same as `ziggurat`, for `f32`: a single `u32` provides both the 8 bits
+// for `i` and the 23 bits of the fraction.
+#[cfg(feature="std")]
+#[inline(always)]
+fn ziggurat_f32<R: Rng + ?Sized, P, Z>(
+            rng: &mut R,
+            symmetric: bool,
+            x_tab: &[f32; 257],
+            f_tab: &[f32; 257],
+            mut pdf: P,
+            mut zero_case: Z)
+            -> f32 where P: FnMut(f32) -> f32, Z: FnMut(&mut R, f32) -> f32 {
+    loop {
+        let bits = rng.next_u32();
+        let i = bits as usize & 0xff;
+
+        let u = if symmetric {
+            // A value in the range [2,4), minus 3 to get [-1,1)
+            (bits >> 9).into_float_with_exponent(1) - 3.0
+        } else {
+            // A value in the range [1,2), minus 1 - EPSILON/2 to get (0,1)
+            (bits >> 9).into_float_with_exponent(0)
+            - (1.0 - ::core::f32::EPSILON / 2.0)
+        };
+        let x = u * x_tab[i];
+
+        let test_x = if symmetric { x.abs() } else {x};
+
+        if test_x < x_tab[i + 1] {
+            return x;
+        }
+        if i == 0 {
+            return zero_case(rng, u);
+        }
+        if f_tab[i + 1] + (f_tab[i] - f_tab[i + 1]) * rng.gen::<f32>() < pdf(x) {
+            return x;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use Rng;
     use mock::StepRng;
     use super::{WeightedChoice, Weighted, Distribution};
 
+    #[test]
+    #[cfg(feature="std")]
+    fn test_sample_iter() {
+        let mut rng = ::test::rng(210);
+        let v: Vec<u32> = super::Range::new(0u32, 10).sample_iter(&mut rng)
+            .take(100).collect();
+        assert_eq!(v.len(), 100);
+        assert!(v.iter().all(|&x| x < 10));
+
+        // The iterator may own both the distribution and the RNG.
+        let iter = super::Uniform.sample_iter(::test::rng(211));
+        let w: Vec<u8> = iter.take(10).collect();
+        let x: Vec<u8> = ::test::rng(211).sample_iter(super::Uniform).take(10)
+            .collect();
+        assert_eq!(w, x);
+    }
+
+    #[test]
+    fn test_map() {
+        let mut rng = ::test::rng(212);
+        let double = super::Range::new(0u32, 10).map(|x| x * 2);
+        for _ in 0..100 {
+            let x = double.sample(&mut rng);
+            assert!(x % 2 == 0 && x < 20);
+        }
+        let is_small = (&double).map(|x| x < 10);
+        let _: bool = rng.sample(is_small);
+    }
+
+    #[test]
+    fn test_from_fn() {
+        let mut rng = ::test::rng(213);
+        let pair = super::from_fn(|rng| {
+            let x: u8 = rng.gen();
+            (x, !x)
+        });
+        for _ in 0..10 {
+            let (a, b) = pair.sample(&mut rng);
+            assert_eq!(a, !b);
+        }
+        let _ = (&mut rng as &mut dyn (::RngCore)).sample(&pair);
+    }
+
+    #[test]
+    #[cfg(any(feature="std", feature="alloc"))]
+    fn test_dyn_distribution() {
+        use super::{Range, Uniform, DynDistribution};
+        #[cfg(all(not(feature="std"), feature="alloc"))] use alloc::boxed::Box;
+        #[cfg(all(not(feature="std"), feature="alloc"))] use alloc::vec::Vec;
+
+        let distrs: Vec<Box<dyn DynDistribution<u32>>> = vec![
+            Range::new(0u32, 10).boxed(),
+            Box::new(Range::new(100u32, 110)),
+            Uniform.map(|x: u8| x as u32).boxed(),
+        ];
+        let mut rng = ::test::rng(214);
+        for _ in 0..100 {
+            let v: Vec<u32> = distrs.iter().map(|d| d.sample(&mut rng)).collect();
+            assert!(v[0] < 10);
+            assert!(v[1] >= 100 && v[1] < 110);
+            assert!(v[2] < 256);
+        }
+
+        // The same values as sampling directly, including via `sample_fill`
+        let d = Range::new(0u32, 1000);
+        let erased: &dyn DynDistribution<u32> = &d;
+        let (mut a, mut b) = ([0u32; 20], [0u32; 20]);
+        d.sample_fill(&mut ::test::rng(215), &mut a);
+        erased.sample_fill(&mut ::test::rng(215), &mut b);
+        assert_eq!(a, b);
+        assert_eq!(erased.sample(&mut ::test::rng(216)),
+                   d.sample(&mut ::test::rng(216)));
+        let mut dyn_rng = ::test::rng(217);
+        let _ = (&mut dyn_rng as &mut dyn (::RngCore)).sample(&distrs[0]);
+    }
+
+    #[test]
+    fn test_tuples() {
+        use super::{Range, Uniform};
+        let mut rng = ::test::rng(214);
+        let (a, b) = Range::new(0u8, 10).zip(Range::new(20i64, 30))
+            .sample(&mut rng);
+        assert!(a < 10 && b >= 20 && b < 30);
+        let (c, d, e): (u32, bool, u8) =
+            (Range::new(5u32, 6), Uniform, Range::new(7u8, 8)).sample(&mut rng);
+        assert_eq!((c, e), (5, 7));
+        let _ = d;
+        // `Uniform` samples many types; the pair's type is picked by the
+        // annotation
+        let (f, g): (i32, char) = Range::new(0, 10).zip(Uniform).sample(&mut rng);
+        assert!(f >= 0 && f < 10);
+        let _ = g;
+    }
+
+    #[test]
+    fn test_sample_fill() {
+        use super::{Range, Uniform};
+        let mut rng = ::test::rng(215);
+
+        let mut ints = [0u16; 100];
+        Uniform.sample_fill(&mut rng, &mut ints[..]);
+        assert!(ints.iter().any(|&x| x != 0));
+
+        let mut floats = [0.0f32; 100];
+        Uniform.sample_fill(&mut rng, &mut floats[..]);
+        assert!(floats.iter().all(|&x| 0.0 < x && x < 1.0));
+
+        let mut ranged = [0i64; 1000];
+        Range::new(-3i64, 7).sample_fill(&mut rng, &mut ranged[..]);
+        assert!(ranged.iter().all(|&x| x >= -3 && x < 7));
+        assert!(ranged.iter().any(|&x| x == -3) && ranged.iter().any(|&x| x == 6));
+
+        let mut small = [0u8; 300];
+        Range::new(0u8, 3).sample_fill(&mut rng, &mut small[..]);
+        assert!(small.iter().all(|&x| x < 3));
+
+        let mut bools = [false; 10];
+        (&Uniform).sample_fill(&mut rng, &mut bools[..]);
+    }
+
     #[test]
     fn test_weighted_choice() {
         // this makes assumptions about the internal implementation of
@@ -442,6 +1224,64 @@ mod tests {
            [50, 54, 51, 55, 52, 56, 53]);
     }
 
+    #[test]
+    #[cfg(feature="std")]
+    fn test_weighted_choice_update_weights() {
+        // Updating must give the same choices as building from scratch
+        fn check(weights: &[u32], updates: &[(usize, u32)]) {
+            let mut items = weights.iter().enumerate()
+                .map(|(i, &w)| Weighted { weight: w, item: i })
+                .collect::<Vec<_>>();
+            let mut expected = items.clone();
+            for &(i, w) in updates {
+                expected[i].weight = w;
+            }
+            let total = expected.iter().map(|item| item.weight as u64).sum::<u64>();
+            let mut wc = WeightedChoice::new(&mut items);
+            wc.update_weights(updates);
+            let fresh = WeightedChoice::new(&mut expected);
+
+            let mut a = StepRng::new(0, !0 / total);
+            let mut b = a.clone();
+            for _ in 0..total {
+                assert_eq!(wc.sample(&mut a), fresh.sample(&mut b));
+            }
+        }
+
+        check(&[1, 2, 3], &[(0, 3), (2, 1)]);
+        check(&[5], &[(0, 1)]);
+        check(&[1, 1, 1, 1, 1, 1, 1], &[(6, 0), (3, 4), (3, 2), (0, 0)]);
+        check(&[0, 3, 0, 2, 7, 1, 0, 4, 2, 1, 9],
+              &[(10, 0), (0, 4), (4, 1), (7, 7), (8, 0)]);
+
+        // Weights of 0 are never chosen
+        let mut items = [Weighted { weight: 1, item: 'a' },
+                         Weighted { weight: 1, item: 'b' },
+                         Weighted { weight: 1, item: 'c' }];
+        let mut wc = WeightedChoice::new(&mut items);
+        wc.update_weights(&[(0, 0), (2, 0)]);
+        let mut rng = ::test::rng(216);
+        for _ in 0..100 {
+            assert_eq!(wc.sample(&mut rng), 'b');
+        }
+    }
+
+    #[test] #[should_panic]
+    fn test_weighted_choice_update_zero_weight() {
+        let mut items = [Weighted { weight: 1, item: 0 },
+                         Weighted { weight: 2, item: 1 }];
+        let mut wc = WeightedChoice::new(&mut items);
+        wc.update_weights(&[(1, 0), (0, 0)]);
+    }
+
+    #[test] #[should_panic]
+    fn test_weighted_choice_update_weight_overflows() {
+        let mut items = [Weighted { weight: 1, item: 0 },
+                         Weighted { weight: 2, item: 1 }];
+        let mut wc = WeightedChoice::new(&mut items);
+        wc.update_weights(&[(1, ::core::u32::MAX)]);
+    }
+
     #[test]
     fn test_weighted_clone_initialization() {
         let initial : Weighted<u32> = Weighted {weight: 1, item: 1};
@@ -476,6 +1316,29 @@ mod tests {
         WeightedChoice::new(&mut [Weighted { weight: 0, item: 0},
                                   Weighted { weight: 0, item: 1}]);
     }
+    #[test]
+    fn test_weighted_choice_try_new() {
+        use super::ParamError;
+        let mut items = [Weighted { weight: 0, item: 0 },
+                         Weighted { weight: ::core::u32::MAX, item: 1 },
+                         Weighted { weight: 1, item: 2 }];
+        assert_eq!(WeightedChoice::try_new(&mut items).unwrap_err(),
+                   ParamError::OutOfRange("items"));
+        assert_eq!(items[1].weight, ::core::u32::MAX);
+        assert_eq!(WeightedChoice::try_new(&mut items[..1]).unwrap_err(),
+                   ParamError::NotPositive("items"));
+        assert!(WeightedChoice::try_new(&mut items[1..2]).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_param_error_display() {
+        use super::ParamError;
+        assert_eq!(ParamError::NotPositive("lambda").to_string(),
+                   "`lambda` must be positive");
+        assert_eq!(ParamError::OutOfRange("p").to_string(), "`p` is out of range");
+    }
+
     #[test] #[should_panic]
     fn test_weighted_choice_weight_overflows() {
         let x = ::core::u32::MAX / 2; // x + x + 2 is the overflow
@@ -509,4 +1372,77 @@ mod tests {
         let sampler = Exp::new(1.0);
         sampler.ind_sample(&mut ::test::rng(235));
     }
+
+    #[test]
+    #[cfg(all(feature="serde-1", feature="std"))]
+    fn test_distributions_serde() {
+        use bincode;
+        use std::net::Ipv4Addr;
+        use distributions::{Range, Normal, LogNormal, Exp, ExpF32, Bernoulli,
+                            Gamma, ChiSquared, FisherF, StudentT,
+                            HighPrecision, Ipv4Prefix};
+        use distributions::range::{RangeInt, RangeFloat, RangeChar};
+
+        fn roundtrip<D, T>(d: D)
+            where D: Distribution<T> + ::serde::Serialize
+                     + ::serde::de::DeserializeOwned,
+                  T: PartialEq + ::core::fmt::Debug
+        {
+            let buf = bincode::serialize(&d).expect("Could not serialize");
+            let d2: D = bincode::deserialize(&buf).expect("Could not deserialize");
+            let mut rng = ::test::rng(236);
+            let mut rng2 = ::test::rng(236);
+            for _ in 0..10 {
+                assert_eq!(d.sample(&mut rng), d2.sample(&mut rng2));
+            }
+        }
+
+        roundtrip(Range::new(-5i32, 17));
+        roundtrip(Range::new(0.5f64, 3.0));
+        roundtrip(Range::new_inclusive('a', 'z'));
+        roundtrip(Normal::new(2.0, 0.5));
+        roundtrip(Exp::new(1.5));
+        roundtrip(Bernoulli::new(0.3));
+        roundtrip(Gamma::new(0.5, 2.0));
+        roundtrip(Gamma::new(3.0, 2.0));
+        roundtrip(Range::new_inclusive(0u8, 255));
+        roundtrip(Range::new_inclusive(::core::i64::MIN, ::core::i64::MAX));
+        roundtrip(Range::new_constant_time(-3i16, 1000));
+        roundtrip(Range::new_inclusive('\u{D000}', '\u{E0FF}'));
+        roundtrip(Range::new(1e-3f32, 0.1));
+        roundtrip(Range::new_inclusive(2.0f64, 2.0));
+        roundtrip(Range::new_inclusive(-1.5f32, -1.5));
+        roundtrip(LogNormal::new(2.0, 0.5));
+        roundtrip(ExpF32::new(0.3));
+        roundtrip(Bernoulli::new(1e-10));
+        roundtrip(Bernoulli::new(1.0));
+        roundtrip(Bernoulli::from_ratio(2, 3));
+        roundtrip(Gamma::new(1.0, 3.0));
+        roundtrip(ChiSquared::new(1.0));
+        roundtrip(ChiSquared::new(0.3));
+        roundtrip(FisherF::new(2.0, 32.0));
+        roundtrip(StudentT::new(11.0));
+        roundtrip(HighPrecision::<f64>::new(0.0, 1e-3));
+        roundtrip(Ipv4Prefix::new(Ipv4Addr::new(10, 1, 2, 3), 12));
+        roundtrip(Ipv4Prefix::new(Ipv4Addr::new(10, 1, 2, 3), 32));
+
+        // Deserializing goes through the checked constructors
+        fn rejects<D: ::serde::de::DeserializeOwned, P: ::serde::Serialize>(params: P) {
+            let buf = bincode::serialize(&params).expect("Could not serialize");
+            assert!(bincode::deserialize::<D>(&buf).is_err());
+        }
+        rejects::<Range<RangeInt<i32>>, _>((5i32, 3i32));
+        rejects::<Range<RangeFloat<f64>>, _>((3.0f64, 2.0f64));
+        rejects::<Range<RangeFloat<f64>>, _>((0.0f64, ::core::f64::INFINITY));
+        rejects::<Range<RangeChar>, _>(('z', 'a'));
+        rejects::<Normal, _>((0.0f64, -1.0f64));
+        rejects::<Exp, _>((0.0f64,));
+        // `Bernoulli::Probability(1.5)`
+        rejects::<Bernoulli, _>((0u32, 1.5f64));
+        // `Bernoulli::Ratio { numerator: 4, denominator: 3 }`
+        rejects::<Bernoulli, _>((1u32, 4u32, 3u32));
+        rejects::<Gamma, _>((1.0f64, ::std::f64::INFINITY));
+        rejects::<HighPrecision<f64>, _>((1.0f64, 0.0f64));
+        rejects::<Ipv4Prefix, _>((Ipv4Addr::new(10, 0, 0, 0), 33u8));
+    }
 }
@@ -0,0 +1,237 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Distributions over network addresses.
+//!
+//! The `Uniform` distribution is implemented for `Ipv4Addr`, `Ipv6Addr`,
+//! `IpAddr` and `SocketAddr`. To sample addresses within a network, see
+//! [`Ipv4Prefix`] and [`Ipv6Prefix`].
+//!
+//! [`Ipv4Prefix`]: struct.Ipv4Prefix.html
+//! [`Ipv6Prefix`]: struct.Ipv6Prefix.html
+
+use std::net::{Ipv4Addr, Ipv6Addr, IpAddr, SocketAddr};
+
+use Rng;
+use distributions::{Distribution, Uniform, ParamError};
+
+impl Distribution<Ipv4Addr> for Uniform {
+    /// Generate an address uniformly over the whole IPv4 address space.
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv4Addr {
+        Ipv4Addr::from(rng.next_u32())
+    }
+}
+
+impl Distribution<Ipv6Addr> for Uniform {
+    /// Generate an address uniformly over the whole IPv6 address space.
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv6Addr {
+        Ipv6Addr::from(rng.gen::<u128>())
+    }
+}
+
+impl Distribution<IpAddr> for Uniform {
+    /// Generate an IPv4 or an IPv6 address, each with probability 0.5.
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> IpAddr {
+        if rng.gen::<bool>() {
+            IpAddr::V4(rng.gen())
+        } else {
+            IpAddr::V6(rng.gen())
+        }
+    }
+}
+
+impl Distribution<SocketAddr> for Uniform {
+    /// Generate a random `IpAddr` together with a uniformly random port.
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SocketAddr {
+        let ip: IpAddr = rng.gen();
+        SocketAddr::new(ip, rng.gen())
+    }
+}
+
+/// Samples IPv4 addresses uniformly from within a network given in CIDR
+/// notation, e.g. `10.0.0.0/8`.
+///
+/// The network and broadcast addresses are not excluded.
+///
+/// # Example
+///
+/// ```rust
+/// use std::net::Ipv4Addr;
+/// use rand::distributions::{Distribution, Ipv4Prefix};
+///
+/// let net = Ipv4Prefix::new(Ipv4Addr::new(192, 168, 0, 0), 16);
+/// let addr = net.sample(&mut rand::thread_rng());
+/// assert_eq!(&addr.octets()[..2], &[192, 168]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Ipv4Prefix {
+    base: u32,
+    mask: u32,
+}
+
+impl Ipv4Prefix {
+    /// Construct a new `Ipv4Prefix` for the network `addr/prefix_len`.
+    ///
+    /// Bits of `addr` beyond the prefix are ignored. Panics if
+    /// `prefix_len > 32`.
+    pub fn new(addr: Ipv4Addr, prefix_len: u8) -> Ipv4Prefix {
+        Ipv4Prefix::try_new(addr, prefix_len)
+            .unwrap_or_else(|e| panic!("Ipv4Prefix::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct a new `Ipv4Prefix` for the network `addr/prefix_len`, or
+    /// return an error if `prefix_len > 32`.
+    pub fn try_new(addr: Ipv4Addr, prefix_len: u8) -> Result<Ipv4Prefix, ParamError> {
+        if prefix_len > 32 {
+            return Err(ParamError::OutOfRange("prefix_len"));
+        }
+        let mask = (!0u32).checked_shr(prefix_len as u32).unwrap_or(0);
+        Ok(Ipv4Prefix { base: u32::from(addr) & !mask, mask: mask })
+    }
+}
+
+impl Distribution<Ipv4Addr> for Ipv4Prefix {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv4Addr {
+        Ipv4Addr::from(self.base | (rng.next_u32() & self.mask))
+    }
+}
+
+/// Samples IPv6 addresses uniformly from within a network given in CIDR
+/// notation, e.g. `fd00::/8`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::net::Ipv6Addr;
+/// use rand::distributions::{Distribution, Ipv6Prefix};
+///
+/// let net = Ipv6Prefix::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32);
+/// let addr = net.sample(&mut rand::thread_rng());
+/// assert_eq!(&addr.segments()[..2], &[0x2001, 0xdb8]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Ipv6Prefix {
+    base: u128,
+    mask: u128,
+}
+
+impl Ipv6Prefix {
+    /// Construct a new `Ipv6Prefix` for the network `addr/prefix_len`.
+    ///
+    /// Bits of `addr` beyond the prefix are ignored. Panics if
+    /// `prefix_len > 128`.
+    pub fn new(addr: Ipv6Addr, prefix_len: u8) -> Ipv6Prefix {
+        Ipv6Prefix::try_new(addr, prefix_len)
+            .unwrap_or_else(|e| panic!("Ipv6Prefix::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct a new `Ipv6Prefix` for the network `addr/prefix_len`, or
+    /// return an error if `prefix_len > 128`.
+    pub fn try_new(addr: Ipv6Addr, prefix_len: u8) -> Result<Ipv6Prefix, ParamError> {
+        if prefix_len > 128 {
+            return Err(ParamError::OutOfRange("prefix_len"));
+        }
+        let mask = (!0u128).checked_shr(prefix_len as u32).unwrap_or(0);
+        Ok(Ipv6Prefix { base: u128::from(addr) & !mask, mask: mask })
+    }
+}
+
+impl Distribution<Ipv6Addr> for Ipv6Prefix {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv6Addr {
+        Ipv6Addr::from(self.base | (rng.gen::<u128>() & self.mask))
+    }
+}
+
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "IpPrefix")]
+struct IpPrefixParams<A> {
+    addr: A,
+    prefix_len: u8,
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { Ipv4Prefix, IpPrefixParams<Ipv4Addr>,
+    |d| IpPrefixParams {
+        addr: Ipv4Addr::from(d.base),
+        prefix_len: d.mask.leading_zeros() as u8,
+    },
+    |p| Ipv4Prefix::try_new(p.addr, p.prefix_len) }
+#[cfg(feature="serde-1")]
+serde_via_params! { Ipv6Prefix, IpPrefixParams<Ipv6Addr>,
+    |d| IpPrefixParams {
+        addr: Ipv6Addr::from(d.base),
+        prefix_len: d.mask.leading_zeros() as u8,
+    },
+    |p| Ipv6Prefix::try_new(p.addr, p.prefix_len) }
+
+#[cfg(test)]
+mod test {
+    use std::net::{Ipv4Addr, Ipv6Addr, IpAddr, SocketAddr};
+    use Rng;
+    use distributions::Uniform;
+    use super::{Ipv4Prefix, Ipv6Prefix};
+
+    #[test]
+    fn test_uniform() {
+        let mut rng = ::test::rng(830);
+        rng.sample::<Ipv4Addr, _>(Uniform);
+        rng.sample::<Ipv6Addr, _>(Uniform);
+        rng.sample::<IpAddr, _>(Uniform);
+        rng.sample::<SocketAddr, _>(Uniform);
+    }
+
+    #[test]
+    fn test_ipv4_prefix() {
+        let mut rng = ::test::rng(831);
+        let net = Ipv4Prefix::new(Ipv4Addr::new(10, 1, 2, 3), 8);
+        for _ in 0..100 {
+            assert_eq!(rng.sample(net).octets()[0], 10);
+        }
+        let host = Ipv4Prefix::new(Ipv4Addr::new(10, 1, 2, 3), 32);
+        assert_eq!(rng.sample(host), Ipv4Addr::new(10, 1, 2, 3));
+        let all = Ipv4Prefix::new(Ipv4Addr::new(10, 1, 2, 3), 0);
+        rng.sample(all);
+    }
+
+    #[test]
+    fn test_ipv6_prefix() {
+        let mut rng = ::test::rng(832);
+        let addr = Ipv6Addr::new(0xfd12, 0x3456, 0, 0, 0, 0, 0, 1);
+        let net = Ipv6Prefix::new(addr, 32);
+        for _ in 0..100 {
+            let segments = rng.sample(net).segments();
+            assert_eq!(&segments[..2], &[0xfd12, 0x3456]);
+        }
+        assert_eq!(rng.sample(Ipv6Prefix::new(addr, 128)), addr);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ipv4_prefix_too_long() {
+        Ipv4Prefix::new(Ipv4Addr::new(10, 0, 0, 0), 33);
+    }
+
+    #[test]
+    fn test_prefix_try_new() {
+        use distributions::ParamError;
+        assert!(Ipv4Prefix::try_new(Ipv4Addr::new(10, 0, 0, 0), 32).is_ok());
+        assert_eq!(Ipv4Prefix::try_new(Ipv4Addr::new(10, 0, 0, 0), 33).unwrap_err(),
+                   ParamError::OutOfRange("prefix_len"));
+        assert!(Ipv6Prefix::try_new(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 0), 0).is_ok());
+        assert!(Ipv6Prefix::try_new(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 0), 129).is_err());
+    }
+}
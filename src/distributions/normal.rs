@@ -11,7 +11,9 @@
 //! The normal and derived distributions.
 
 use {Rng};
-use distributions::{ziggurat, ziggurat_tables, Distribution, Uniform};
+use distributions::{ziggurat, ziggurat_f32, ziggurat_tables, Distribution, Uniform,
+                    ParamError};
+use distributions::float::IntoFloat;
 
 /// Samples floating-point numbers according to the normal distribution
 /// `N(0, 1)` (a.k.a.  a standard normal, or Gaussian). This is equivalent to
@@ -35,40 +37,177 @@ use distributions::{ziggurat, ziggurat_tables, Distribution, Uniform};
 /// println!("{}", val);
 /// ```
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
 pub struct StandardNormal;
 
 impl Distribution<f64> for StandardNormal {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        ziggurat(rng, true, // this is symmetric
+                 &ziggurat_tables::ZIG_NORM_X,
+                 &ziggurat_tables::ZIG_NORM_F,
+                 pdf, zero_case)
+    }
+
+    /// Fill `dest` with samples.
+    ///
+    /// The random words for the first step of the Ziggurat method, which
+    /// accepts about 99% of all samples, are generated in bulk with
+    /// `fill_u64_slice`; with the `simd_support` feature this step is also
+    /// evaluated for four values at a time. The remaining values are
+    /// completed one by one, so the results have the same distribution as
+    /// from `sample`.
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [f64]) {
+        let mut bits = [0u64; 64];
+        for chunk in dest.chunks_mut(64) {
+            let bits = &mut bits[..chunk.len()];
+            rng.fill_u64_slice(bits);
+            fill_from_bits(rng, chunk, bits);
+        }
+    }
+}
+
+// The first step of `ziggurat` for a single value.
+#[inline(always)]
+fn first_step(bits: u64) -> (bool, usize, f64, f64) {
+    let x_tab = &ziggurat_tables::ZIG_NORM_X;
+    let i = bits as usize & 0xff;
+    let u = (bits >> 12).into_float_with_exponent(1) - 3.0;
+    let x = u * x_tab[i];
+    (x.abs() < x_tab[i + 1], i, u, x)
+}
+
+#[cfg(not(feature="simd_support"))]
+fn fill_from_bits<R: Rng + ?Sized>(rng: &mut R, dest: &mut [f64], bits: &[u64]) {
+    for (x, &bits) in dest.iter_mut().zip(bits.iter()) {
+        let (accept, i, u, value) = first_step(bits);
+        *x = if accept { value } else { slow_path(rng, i, u, value) };
+    }
+}
+
+#[cfg(feature="simd_support")]
+fn fill_from_bits<R: Rng + ?Sized>(rng: &mut R, dest: &mut [f64], bits: &[u64]) {
+    use core::simd::{f64x4, u64x4};
+    use core::simd::prelude::*;
+
+    let x_tab = &ziggurat_tables::ZIG_NORM_X;
+    let mut chunks = dest.chunks_exact_mut(4);
+    let mut bit_chunks = bits.chunks_exact(4);
+    for (chunk, bits) in (&mut chunks).zip(&mut bit_chunks) {
+        let bits = u64x4::from_slice(bits);
+        let i = (bits & u64x4::splat(0xff)).to_array();
+        // A value in the range [2,4), minus 3 to get [-1,1)
+        let u = f64x4::from_bits((bits >> u64x4::splat(12))
+                                 | u64x4::splat(1024 << 52))
+                - f64x4::splat(3.0);
+        let lower = f64x4::from_array([x_tab[i[0] as usize], x_tab[i[1] as usize],
+                                       x_tab[i[2] as usize], x_tab[i[3] as usize]]);
+        let upper = f64x4::from_array([x_tab[i[0] as usize + 1], x_tab[i[1] as usize + 1],
+                                       x_tab[i[2] as usize + 1], x_tab[i[3] as usize + 1]]);
+        let x = u * lower;
+        let accept = x.abs().simd_lt(upper);
+        x.copy_to_slice(chunk);
+        if !accept.all() {
+            let u = u.to_array();
+            for lane in 0..4 {
+                if !accept.test(lane) {
+                    chunk[lane] = slow_path(rng, i[lane] as usize, u[lane], chunk[lane]);
+                }
+            }
+        }
+    }
+    for (x, &bits) in chunks.into_remainder().iter_mut().zip(bit_chunks.remainder()) {
+        let (accept, i, u, value) = first_step(bits);
+        *x = if accept { value } else { slow_path(rng, i, u, value) };
+    }
+}
+
+// Complete a sample rejected by the first step of `ziggurat`.
+#[cold]
+fn slow_path<R: Rng + ?Sized>(rng: &mut R, i: usize, u: f64, x: f64) -> f64 {
+    let f_tab = &ziggurat_tables::ZIG_NORM_F;
+    if i == 0 {
+        return zero_case(rng, u);
+    }
+    if f_tab[i + 1] + (f_tab[i] - f_tab[i + 1]) * rng.gen::<f64>() < pdf(x) {
+        return x;
+    }
+    // Start over with a new sample.
+    StandardNormal.sample(rng)
+}
+
+#[inline]
+fn pdf(x: f64) -> f64 {
+    (-x*x/2.0).exp()
+}
+
+#[inline]
+fn zero_case<R: Rng + ?Sized>(rng: &mut R, u: f64) -> f64 {
+    // compute a random number in the tail by hand
+
+    // strange initial conditions, because the loop is not
+    // do-while, so the condition should be true on the first
+    // run, they get overwritten anyway (0 < 1, so these are
+    // good).
+    let mut x = 1.0f64;
+    let mut y = 0.0f64;
+
+    while -2.0 * y < x * x {
+        let x_: f64 = rng.sample(Uniform);
+        let y_: f64 = rng.sample(Uniform);
+
+        x = x_.ln() / ziggurat_tables::ZIG_NORM_R;
+        y = y_.ln();
+    }
+
+    if u < 0.0 { x - ziggurat_tables::ZIG_NORM_R } else { ziggurat_tables::ZIG_NORM_R - x }
+}
+
+/// Samples `f32` numbers according to the standard normal distribution
+/// `N(0, 1)`.
+///
+/// This is the single-precision counterpart of [`StandardNormal`]: it uses
+/// the same Ziggurat method, but with `f32` tables and arithmetic, and
+/// consumes one `u32` instead of one `u64` per sample in the common case.
+/// This is faster than sampling `f64` values and converting them, at the
+/// cost of the lower precision of `f32`.
+///
+/// # Example
+/// ```rust
+/// use rand::{thread_rng, Rng};
+/// use rand::distributions::StandardNormalF32;
+///
+/// let val: f32 = thread_rng().sample(StandardNormalF32);
+/// println!("{}", val);
+/// ```
+///
+/// [`StandardNormal`]: struct.StandardNormal.html
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct StandardNormalF32;
+
+impl Distribution<f32> for StandardNormalF32 {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
         #[inline]
-        fn pdf(x: f64) -> f64 {
+        fn pdf(x: f32) -> f32 {
             (-x*x/2.0).exp()
         }
         #[inline]
-        fn zero_case<R: Rng + ?Sized>(rng: &mut R, u: f64) -> f64 {
-            // compute a random number in the tail by hand
-
-            // strange initial conditions, because the loop is not
-            // do-while, so the condition should be true on the first
-            // run, they get overwritten anyway (0 < 1, so these are
-            // good).
-            let mut x = 1.0f64;
-            let mut y = 0.0f64;
-
-            while -2.0 * y < x * x {
-                let x_: f64 = rng.sample(Uniform);
-                let y_: f64 = rng.sample(Uniform);
-
-                x = x_.ln() / ziggurat_tables::ZIG_NORM_R;
-                y = y_.ln();
+        fn zero_case<R: Rng + ?Sized>(rng: &mut R, u: f32) -> f32 {
+            // As for `StandardNormal`, sample from the tail by rejection.
+            const R: f32 = ziggurat_tables::ZIG_NORM_R as f32;
+            loop {
+                let x = rng.sample::<f32, _>(Uniform).ln() / R;
+                let y = rng.sample::<f32, _>(Uniform).ln();
+                if -2.0 * y >= x * x {
+                    return if u < 0.0 { x - R } else { R - x };
+                }
             }
-
-            if u < 0.0 { x - ziggurat_tables::ZIG_NORM_R } else { ziggurat_tables::ZIG_NORM_R - x }
         }
 
-        ziggurat(rng, true, // this is symmetric
-                 &ziggurat_tables::ZIG_NORM_X,
-                 &ziggurat_tables::ZIG_NORM_F,
-                 pdf, zero_case)
+        ziggurat_f32(rng, true,
+                     &ziggurat_tables::ZIG_NORM_X_F32,
+                     &ziggurat_tables::ZIG_NORM_F_F32,
+                     pdf, zero_case)
     }
 }
 
@@ -99,14 +238,28 @@ impl Normal {
     ///
     /// # Panics
     ///
-    /// Panics if `std_dev < 0`.
+    /// Panics if `std_dev < 0` or either parameter is NaN.
     #[inline]
     pub fn new(mean: f64, std_dev: f64) -> Normal {
-        assert!(std_dev >= 0.0, "Normal::new called with `std_dev` < 0");
-        Normal {
+        Normal::try_new(mean, std_dev)
+            .unwrap_or_else(|e| panic!("Normal::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct a new `Normal` distribution with the given mean and
+    /// standard deviation, or return an error if `std_dev < 0` or either
+    /// parameter is NaN.
+    #[inline]
+    pub fn try_new(mean: f64, std_dev: f64) -> Result<Normal, ParamError> {
+        if mean.is_nan() {
+            return Err(ParamError::NaN("mean"));
+        }
+        if !(std_dev >= 0.0) {
+            return Err(ParamError::Negative("std_dev"));
+        }
+        Ok(Normal {
             mean: mean,
             std_dev: std_dev
-        }
+        })
     }
 }
 impl Distribution<f64> for Normal {
@@ -114,6 +267,13 @@ impl Distribution<f64> for Normal {
         let n = rng.sample(StandardNormal);
         self.mean + self.std_dev * n
     }
+
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [f64]) {
+        StandardNormal.sample_fill(rng, dest);
+        for x in dest.iter_mut() {
+            *x = self.mean + self.std_dev * *x;
+        }
+    }
 }
 
 
@@ -143,23 +303,67 @@ impl LogNormal {
     ///
     /// # Panics
     ///
-    /// Panics if `std_dev < 0`.
+    /// Panics if `std_dev < 0` or either parameter is NaN.
     #[inline]
     pub fn new(mean: f64, std_dev: f64) -> LogNormal {
-        assert!(std_dev >= 0.0, "LogNormal::new called with `std_dev` < 0");
-        LogNormal { norm: Normal::new(mean, std_dev) }
+        LogNormal::try_new(mean, std_dev)
+            .unwrap_or_else(|e| panic!("LogNormal::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct a new `LogNormal` distribution with the given mean and
+    /// standard deviation, or return an error if `std_dev < 0` or either
+    /// parameter is NaN.
+    #[inline]
+    pub fn try_new(mean: f64, std_dev: f64) -> Result<LogNormal, ParamError> {
+        Normal::try_new(mean, std_dev).map(|norm| LogNormal { norm: norm })
     }
 }
 impl Distribution<f64> for LogNormal {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
         self.norm.sample(rng).exp()
     }
+
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [f64]) {
+        self.norm.sample_fill(rng, dest);
+        for x in dest.iter_mut() {
+            *x = x.exp();
+        }
+    }
 }
 
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Normal")]
+struct NormalParams {
+    mean: f64,
+    std_dev: f64,
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { Normal, NormalParams,
+    |d| NormalParams { mean: d.mean, std_dev: d.std_dev },
+    |p| Normal::try_new(p.mean, p.std_dev) }
+#[cfg(feature="serde-1")]
+serde_via_params! { LogNormal, NormalParams,
+    |d| NormalParams { mean: d.norm.mean, std_dev: d.norm.std_dev },
+    |p| LogNormal::try_new(p.mean, p.std_dev) }
+
 #[cfg(test)]
 mod tests {
-    use distributions::Distribution;
-    use super::{Normal, LogNormal};
+    use distributions::{Distribution, ParamError};
+    use super::{Normal, LogNormal, StandardNormalF32};
+
+    // The complementary error function, with a relative error below 1.2e-7
+    // (Numerical Recipes, `erfcc`)
+    fn erfc(x: f64) -> f64 {
+        let t = 1.0 / (1.0 + 0.5 * x.abs());
+        let poly = -1.26551223 + t * (1.00002368 + t * (0.37409196
+            + t * (0.09678418 + t * (-0.18628806 + t * (0.27886807
+            + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223
+            + t * 0.17087277))))))));
+        let r = t * (-x * x + poly).exp();
+        if x >= 0.0 { r } else { 2.0 - r }
+    }
 
     #[test]
     fn test_normal() {
@@ -169,11 +373,77 @@ mod tests {
             norm.sample(&mut rng);
         }
     }
+    #[test]
+    fn test_normal_sample_fill() {
+        let norm = Normal::new(10.0, 2.0);
+        let mut rng = ::test::rng(212);
+        // Not a multiple of 4, to include the remainder
+        let mut buf = vec![0.0; 10001];
+        norm.sample_fill(&mut rng, &mut buf);
+        let n = buf.len() as f64;
+        let mean = buf.iter().sum::<f64>() / n;
+        let var = buf.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+        assert!((mean - 10.0).abs() < 0.1, "{}", mean);
+        assert!((var - 4.0).abs() < 0.2, "{}", var);
+        // Values from the wedges and tail beyond 2 standard deviations
+        let tail = buf.iter().filter(|&&x| (x - 10.0).abs() > 4.0).count();
+        assert!(tail > 350 && tail < 560, "{}", tail);
+    }
+
+    #[test]
+    fn test_normal_goodness_of_fit() {
+        use testing::kolmogorov_smirnov;
+        let mut rng = ::test::rng(213);
+        let result = kolmogorov_smirnov(&mut rng, &Normal::new(-1.0, 3.0), 10000,
+            |x| 0.5 * erfc(-(x + 1.0) / (3.0 * ::std::f64::consts::SQRT_2)));
+        assert!(result.passes(0.001), "{:?}", result);
+    }
+
+    #[test]
+    fn test_standard_normal_f32() {
+        let mut rng = ::test::rng(214);
+        let n = 20000;
+        let (mut sum, mut sum_sq, mut tail) = (0.0f64, 0.0f64, 0);
+        for _ in 0..n {
+            let x: f32 = StandardNormalF32.sample(&mut rng);
+            assert!(x.is_finite());
+            sum += x as f64;
+            sum_sq += (x * x) as f64;
+            if x.abs() > 3.6541529 { tail += 1; }
+        }
+        let mean = sum / n as f64;
+        let var = sum_sq / n as f64 - mean * mean;
+        assert!(mean.abs() < 0.03, "{}", mean);
+        assert!((var - 1.0).abs() < 0.04, "{}", var);
+        // P(|x| > r) is about 2.6e-4
+        assert!(tail < 20, "{}", tail);
+    }
+
+    #[test]
+    fn test_standard_normal_f32_goodness_of_fit() {
+        use testing::kolmogorov_smirnov;
+        let mut rng = ::test::rng(215);
+        let distr = StandardNormalF32.map(|x: f32| x as f64);
+        let result = kolmogorov_smirnov(&mut rng, &distr, 10000,
+            |x| 0.5 * erfc(-x / ::std::f64::consts::SQRT_2));
+        assert!(result.passes(0.001), "{:?}", result);
+    }
+
     #[test]
     #[should_panic]
     fn test_normal_invalid_sd() {
         Normal::new(10.0, -1.0);
     }
+    #[test]
+    fn test_normal_try_new() {
+        assert!(Normal::try_new(10.0, 0.0).is_ok());
+        assert_eq!(Normal::try_new(10.0, -1.0).unwrap_err(),
+                   ParamError::Negative("std_dev"));
+        assert_eq!(Normal::try_new(::std::f64::NAN, 1.0).unwrap_err(),
+                   ParamError::NaN("mean"));
+        assert_eq!(LogNormal::try_new(0.0, ::std::f64::NAN).unwrap_err(),
+                   ParamError::Negative("std_dev"));
+    }
 
 
     #[test]
@@ -11,9 +11,13 @@
 //! The implementations of the `Uniform` distribution for other built-in types.
 
 use core::char;
+use core::num::Wrapping;
+#[cfg(all(not(feature="std"), feature="alloc"))] use alloc::string::String;
 
 use {Rng};
-use distributions::{Distribution, Uniform, Range};
+use distributions::{Distribution, Uniform, Range, ParamError};
+use distributions::range::RangeInt;
+#[cfg(any(feature="std", feature="alloc"))] use distributions::DistString;
 
 // ----- Sampling distributions -----
 
@@ -34,9 +38,196 @@ use distributions::{Distribution, Uniform, Range};
 ///         .collect();
 /// println!("Random chars: {}", chars);
 /// ```
+///
+/// Or, more conveniently, via [`DistString`]:
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::distributions::{Alphanumeric, DistString};
+///
+/// let string = Alphanumeric.sample_string(&mut thread_rng(), 7);
+/// println!("Random string: {}", string);
+/// ```
+///
+/// [`DistString`]: trait.DistString.html
 #[derive(Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
 pub struct Alphanumeric;
 
+/// Sample a `char`, uniformly distributed over upper-case ASCII letters and
+/// numbers: A-Z and 0-9.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::distributions::{AlphanumericUpper, DistString};
+///
+/// let code = AlphanumericUpper.sample_string(&mut thread_rng(), 6);
+/// println!("Random code: {}", code);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct AlphanumericUpper;
+
+/// Sample a `char`, uniformly distributed over lower-case ASCII letters and
+/// numbers: a-z and 0-9.
+#[derive(Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct AlphanumericLower;
+
+/// Sample a `char`, uniformly distributed over lower-case hexadecimal digits:
+/// 0-9 and a-f.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::distributions::{HexDigit, DistString};
+///
+/// let token = HexDigit.sample_string(&mut thread_rng(), 32);
+/// println!("Random token: {}", token);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct HexDigit;
+
+/// Sample a `char`, uniformly distributed over the URL- and filename-safe
+/// Base64 alphabet (RFC 4648): A-Z, a-z, 0-9, `-` and `_`.
+///
+/// Each char carries exactly 6 bits of entropy. Use [`Base64Url::token`] to
+/// generate a token string with a given amount of entropy.
+///
+/// [`Base64Url::token`]: struct.Base64Url.html#method.token
+#[derive(Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct Base64Url;
+
+impl Base64Url {
+    /// Generate a random URL-safe token with at least `bits` bits of entropy.
+    ///
+    /// The token has `ceil(bits / 6)` chars.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::thread_rng;
+    /// use rand::distributions::Base64Url;
+    ///
+    /// let token = Base64Url.token(&mut thread_rng(), 128);
+    /// assert_eq!(token.len(), 22);
+    /// ```
+    #[cfg(any(feature="std", feature="alloc"))]
+    pub fn token<R: Rng + ?Sized>(&self, rng: &mut R, bits: usize) -> String {
+        self.sample_string(rng, (bits + 5) / 6)
+    }
+}
+
+/// Generate the 16 bytes of a random (version 4) UUID, as specified by
+/// RFC 4122.
+///
+/// All bits are random except for the 4 version bits, which are set to
+/// `0100`, and the 2 variant bits, which are set to `10`; this leaves 122
+/// random bits. The bytes are in the standard (big-endian) UUID order, so
+/// they can be passed to e.g. `uuid::Uuid::from_bytes`.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, Rng};
+/// use rand::distributions::UuidV4;
+///
+/// let uuid: [u8; 16] = thread_rng().sample(UuidV4);
+/// assert_eq!(uuid[6] >> 4, 4);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct UuidV4;
+
+/// Sample a `char` uniformly from a user-supplied set of characters.
+///
+/// The set is borrowed, so constructing and sampling from a `Charset` never
+/// allocates. Every entry of the set is equally likely; if the set contains
+/// duplicates, those characters are correspondingly more likely.
+///
+/// When constructed from a `&str` containing only ASCII characters, sampling
+/// takes constant time. For other strings, sampling walks the string to find
+/// the chosen character; use `Charset::from_chars` for large non-ASCII sets.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::distributions::{Charset, DistString};
+///
+/// // Crockford's base32 alphabet
+/// let base32 = Charset::new("0123456789ABCDEFGHJKMNPQRSTVWXYZ");
+/// let id = base32.sample_string(&mut thread_rng(), 26);
+/// println!("Random id: {}", id);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Charset<'a> {
+    set: CharsetRepr<'a>,
+    range: Range<RangeInt<usize>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum CharsetRepr<'a> {
+    Ascii(&'a [u8]),
+    Str(&'a str),
+    Chars(&'a [char]),
+}
+
+impl<'a> Charset<'a> {
+    /// Construct a `Charset` sampling from the characters of `chars`.
+    ///
+    /// Panics if `chars` is empty.
+    pub fn new(chars: &'a str) -> Charset<'a> {
+        Charset::try_new(chars)
+            .unwrap_or_else(|e| panic!("Charset::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct a `Charset` sampling from the characters of `chars`, or
+    /// return an error if `chars` is empty.
+    pub fn try_new(chars: &'a str) -> Result<Charset<'a>, ParamError> {
+        if chars.is_empty() {
+            return Err(ParamError::NotPositive("chars"));
+        }
+        if chars.is_ascii() {
+            let bytes = chars.as_bytes();
+            Ok(Charset {
+                set: CharsetRepr::Ascii(bytes),
+                range: Range::new(0, bytes.len()),
+            })
+        } else {
+            Ok(Charset {
+                set: CharsetRepr::Str(chars),
+                range: Range::new(0, chars.chars().count()),
+            })
+        }
+    }
+
+    /// Construct a `Charset` sampling from the slice `chars`.
+    ///
+    /// Panics if `chars` is empty.
+    pub fn from_chars(chars: &'a [char]) -> Charset<'a> {
+        Charset::try_from_chars(chars)
+            .unwrap_or_else(|e| panic!("Charset::from_chars called with invalid parameters: {}", e))
+    }
+
+    /// Construct a `Charset` sampling from the slice `chars`, or return an
+    /// error if `chars` is empty.
+    pub fn try_from_chars(chars: &'a [char]) -> Result<Charset<'a>, ParamError> {
+        if chars.is_empty() {
+            return Err(ParamError::NotPositive("chars"));
+        }
+        Ok(Charset {
+            set: CharsetRepr::Chars(chars),
+            range: Range::new(0, chars.len()),
+        })
+    }
+}
+
 
 // ----- Implementations of distributions -----
 
@@ -71,6 +262,133 @@ impl Distribution<char> for Alphanumeric {
     }
 }
 
+// Sample an index in `0..36` by multiply-shift. Only products whose low half
+// is below `2^32 % 36 = 4` are rejected, where a 6-bit mask would reject 28
+// of every 64 draws.
+#[inline]
+fn sample_base36<R: Rng + ?Sized>(rng: &mut R) -> usize {
+    const RANGE: u64 = 26 + 10;
+    const THRESHOLD: u32 = ((1u64 << 32) % RANGE) as u32;
+    loop {
+        let m = rng.next_u32() as u64 * RANGE;
+        if m as u32 >= THRESHOLD {
+            return (m >> 32) as usize;
+        }
+    }
+}
+
+impl Distribution<char> for AlphanumericUpper {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
+        const CHARSET: &'static [u8] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        CHARSET[sample_base36(rng)] as char
+    }
+}
+
+impl Distribution<char> for AlphanumericLower {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
+        const CHARSET: &'static [u8] =
+            b"abcdefghijklmnopqrstuvwxyz0123456789";
+        CHARSET[sample_base36(rng)] as char
+    }
+}
+
+impl Distribution<char> for HexDigit {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
+        const CHARSET: &'static [u8] = b"0123456789abcdef";
+        // There are exactly 16 digits, so no rejection is needed.
+        CHARSET[(rng.next_u32() >> 28) as usize] as char
+    }
+}
+
+impl<'a> Distribution<char> for Charset<'a> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
+        let i = self.range.sample(rng);
+        match self.set {
+            CharsetRepr::Ascii(bytes) => bytes[i] as char,
+            CharsetRepr::Str(s) => s.chars().nth(i).unwrap(),
+            CharsetRepr::Chars(chars) => chars[i],
+        }
+    }
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl<'a> DistString for Charset<'a> {
+    fn append_string<R: Rng + ?Sized>(&self, rng: &mut R, string: &mut String,
+                                      len: usize)
+    {
+        string.reserve(len);
+        for _ in 0..len {
+            string.push(self.sample(rng));
+        }
+    }
+}
+
+impl Distribution<char> for Base64Url {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
+        const CHARSET: &'static [u8] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+                abcdefghijklmnopqrstuvwxyz\
+                0123456789-_";
+        // There are exactly 64 chars, so no rejection is needed.
+        CHARSET[(rng.next_u32() >> 26) as usize] as char
+    }
+}
+
+impl Distribution<[u8; 16]> for UuidV4 {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        rng.fill_bytes(&mut bytes);
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        bytes
+    }
+}
+
+macro_rules! impl_ascii_dist_string {
+    ($($ty:ty),*) => {
+        $(
+            #[cfg(any(feature="std", feature="alloc"))]
+            impl DistString for $ty {
+                fn append_string<R: Rng + ?Sized>(&self, rng: &mut R,
+                                                  string: &mut String,
+                                                  len: usize)
+                {
+                    // All chars are ASCII, so the length in bytes is known.
+                    string.reserve(len);
+                    for _ in 0..len {
+                        string.push(self.sample(rng));
+                    }
+                }
+            }
+        )*
+    }
+}
+
+impl_ascii_dist_string!(Alphanumeric, AlphanumericUpper, AlphanumericLower,
+                        HexDigit, Base64Url);
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl DistString for Uniform {
+    fn append_string<R: Rng + ?Sized>(&self, rng: &mut R, string: &mut String,
+                                      len: usize)
+    {
+        // All but 63488 of the 1112064 Unicode scalar values take 4 bytes in
+        // UTF-8, so this is close to the final length.
+        string.reserve(len.saturating_mul(4));
+        for _ in 0..len {
+            let c: char = self.sample(rng);
+            string.push(c);
+        }
+    }
+}
+
 impl Distribution<bool> for Uniform {
     #[inline]
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
@@ -82,6 +400,33 @@ impl Distribution<bool> for Uniform {
     }
 }
 
+#[cfg(feature="simd_support")]
+macro_rules! simd_mask_impl {
+    ($($mask:ident, $ity:ident;)*) => {
+        $(
+        impl Distribution<::core::simd::$mask> for Uniform {
+            /// Generate a mask with each lane set with probability 0.5,
+            /// using the most significant bit of each random lane as for
+            /// `bool`.
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ::core::simd::$mask {
+                use core::simd::prelude::*;
+                let v: ::core::simd::$ity = Uniform.sample(rng);
+                v.simd_lt(::core::simd::$ity::splat(0))
+            }
+        }
+        )*
+    }
+}
+
+#[cfg(feature="simd_support")]
+simd_mask_impl! {
+    mask8x16, i8x16; mask8x32, i8x32; mask8x64, i8x64;
+    mask16x8, i16x8; mask16x16, i16x16; mask16x32, i16x32;
+    mask32x4, i32x4; mask32x8, i32x8; mask32x16, i32x16;
+    mask64x2, i64x2; mask64x4, i64x4; mask64x8, i64x8;
+}
+
 macro_rules! tuple_impl {
     // use variables to indicate the arity of the tuple
     ($($tyvar:ident),* ) => {
@@ -145,6 +490,29 @@ macro_rules! array_impl {
 
 array_impl!{32, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T,}
 
+// Spelling every element out as above does not scale to larger arrays.
+// Instead, larger arrays of `Copy + Default` elements are generated in place.
+macro_rules! large_array_impl {
+    ($($n:expr),*) => {
+        $(
+            impl<T> Distribution<[T; $n]> for Uniform
+                where T: Copy + Default, Uniform: Distribution<T>
+            {
+                #[inline]
+                fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> [T; $n] {
+                    let mut array = [T::default(); $n];
+                    for x in array.iter_mut() {
+                        *x = rng.gen();
+                    }
+                    array
+                }
+            }
+        )*
+    }
+}
+
+large_array_impl!{33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 96, 128, 256, 512, 1024, 2048, 4096}
+
 impl<T> Distribution<Option<T>> for Uniform where Uniform: Distribution<T> {
     #[inline]
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<T> {
@@ -157,18 +525,38 @@ impl<T> Distribution<Option<T>> for Uniform where Uniform: Distribution<T> {
     }
 }
 
+impl<T> Distribution<Wrapping<T>> for Uniform where Uniform: Distribution<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Wrapping<T> {
+        Wrapping(rng.gen())
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
     use {Rng, RngCore, Uniform};
-    #[cfg(all(not(feature="std"), feature="alloc"))] use alloc::String;
+    #[cfg(all(not(feature="std"), feature="alloc"))] use alloc::string::String;
     
+    #[test]
+    fn test_large_arrays() {
+        let mut rng = ::test::rng(821);
+        let a: [u8; 64] = rng.gen();
+        assert!(a.iter().any(|&x| x != 0));
+        let b: [f64; 128] = rng.gen();
+        assert!(b.iter().all(|&x| 0.0 < x && x < 1.0));
+        let _: [u32; 33] = rng.gen();
+        let _: [bool; 4096] = rng.gen();
+    }
+
     #[test]
     fn test_misc() {
         let mut rng: &mut RngCore = &mut ::test::rng(820);
         
         rng.sample::<char, _>(Uniform);
         rng.sample::<bool, _>(Uniform);
+        rng.sample::<Option<u32>, _>(Uniform);
+        rng.sample::<::core::num::Wrapping<i64>, _>(Uniform);
     }
     
     #[cfg(any(feature="std", feature="alloc"))]
@@ -185,4 +573,105 @@ mod tests {
                 .map(|()| rng.sample(Alphanumeric)).take(5).collect();
         assert_eq!(word.len(), 5);
     }
+
+    #[cfg(any(feature="std", feature="alloc"))]
+    #[test]
+    fn test_dist_string() {
+        use distributions::{Alphanumeric, DistString};
+        let mut rng = ::test::rng(806);
+
+        let s = Alphanumeric.sample_string(&mut rng, 20);
+        assert_eq!(s.len(), 20);
+        assert!(s.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        let mut s = String::from("prefix-");
+        Uniform.append_string(&mut rng, &mut s, 10);
+        assert!(s.starts_with("prefix-"));
+        assert_eq!(s.chars().count(), 17);
+    }
+
+    #[cfg(any(feature="std", feature="alloc"))]
+    #[test]
+    fn test_ascii_variants() {
+        use distributions::{AlphanumericUpper, AlphanumericLower, HexDigit,
+                            DistString};
+        let mut rng = ::test::rng(807);
+
+        let s = AlphanumericUpper.sample_string(&mut rng, 100);
+        assert!(s.chars().all(|c| (c >= 'A' && c <= 'Z') || (c >= '0' && c <= '9')));
+        let s = AlphanumericLower.sample_string(&mut rng, 100);
+        assert!(s.chars().all(|c| (c >= 'a' && c <= 'z') || (c >= '0' && c <= '9')));
+        // Almost never more than one draw per character
+        let mut limited = ::limit::LimitedRng::with_calls(&mut rng, 1000);
+        let s = AlphanumericUpper.sample_string(&mut limited, 1000);
+        assert!("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789".chars().all(|c| s.contains(c)));
+        let s = HexDigit.sample_string(&mut rng, 100);
+        assert!(s.chars().all(|c| (c >= 'a' && c <= 'f') || (c >= '0' && c <= '9')));
+        assert!(s.contains('f') && s.contains('0'));
+    }
+
+    #[test]
+    fn test_charset() {
+        use distributions::Charset;
+        let mut rng = ::test::rng(808);
+
+        let ascii = Charset::new("xyz");
+        for _ in 0..100 {
+            let c = rng.sample(ascii);
+            assert!(c == 'x' || c == 'y' || c == 'z');
+        }
+
+        let kana = Charset::new("あいうえお");
+        for _ in 0..100 {
+            assert!("あいうえお".contains(rng.sample(kana)));
+        }
+
+        let chars = ['α', 'b', 'ç'];
+        let set = Charset::from_chars(&chars);
+        for _ in 0..100 {
+            assert!(chars.contains(&rng.sample(set)));
+        }
+
+        assert_eq!(rng.sample(Charset::new("q")), 'q');
+    }
+
+    #[test]
+    fn test_charset_try_new() {
+        use distributions::{Charset, ParamError};
+        assert!(Charset::try_new("abc").is_ok());
+        assert_eq!(Charset::try_new("").unwrap_err(), ParamError::NotPositive("chars"));
+        assert!(Charset::try_from_chars(&['a']).is_ok());
+        assert_eq!(Charset::try_from_chars(&[]).unwrap_err(),
+                   ParamError::NotPositive("chars"));
+    }
+
+    #[cfg(any(feature="std", feature="alloc"))]
+    #[test]
+    fn test_base64url_token() {
+        use distributions::Base64Url;
+        let mut rng = ::test::rng(809);
+        let token = Base64Url.token(&mut rng, 128);
+        assert_eq!(token.len(), 22);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(Base64Url.token(&mut rng, 0).len(), 0);
+        assert_eq!(Base64Url.token(&mut rng, 6).len(), 1);
+    }
+
+    #[test]
+    fn test_uuid_v4() {
+        use distributions::UuidV4;
+        let mut rng = ::test::rng(810);
+        for _ in 0..10 {
+            let uuid = rng.sample(UuidV4);
+            assert_eq!(uuid[6] >> 4, 0x4);
+            assert_eq!(uuid[8] >> 6, 0x2);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_charset_empty() {
+        use distributions::Charset;
+        Charset::new("");
+    }
 }
@@ -9,10 +9,72 @@
 // except according to those terms.
 
 //! A distribution generating numbers within a given range.
+//!
+//! [`Range`] is the distribution; [`Rng::gen_range`] samples a single value
+//! from `low..high` or `low..=high` without keeping a `Range` around.
+//!
+//! # Extending `Range` to user types
+//!
+//! Both work with any type implementing [`SampleRange`], which names the
+//! [`RangeImpl`] doing the actual work. These traits are a stable, public
+//! extension point: downstream crates can implement them for their own
+//! types (fixed-point numbers, money, timestamps, ...), usually by
+//! delegating to one of the implementations for primitive types, such as
+//! [`RangeInt`] or [`RangeFloat`]. Types backed by an integer, like the one
+//! below, can instead implement [`ScaledInt`] and use [`RangeScaled`].
+//!
+//! ```rust
+//! use rand::{Rng, thread_rng};
+//! use rand::distributions::range::{SampleRange, RangeImpl, RangeInt};
+//!
+//! /// An amount of money, in cents.
+//! #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+//! struct Cents(u64);
+//!
+//! #[derive(Clone, Copy, Debug)]
+//! struct RangeCents(RangeInt<u64>);
+//!
+//! impl RangeImpl for RangeCents {
+//!     type X = Cents;
+//!     fn new(low: Cents, high: Cents) -> Self {
+//!         RangeCents(RangeInt::<u64>::new(low.0, high.0))
+//!     }
+//!     fn new_inclusive(low: Cents, high: Cents) -> Self {
+//!         RangeCents(RangeInt::<u64>::new_inclusive(low.0, high.0))
+//!     }
+//!     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Cents {
+//!         Cents(self.0.sample(rng))
+//!     }
+//! }
+//!
+//! impl SampleRange for Cents {
+//!     type T = RangeCents;
+//! }
+//!
+//! let price = thread_rng().gen_range(Cents(100)..=Cents(999));
+//! assert!(price >= Cents(100) && price <= Cents(999));
+//! ```
+//!
+//! Only `new`, `new_inclusive` and `sample` are required; the other methods
+//! of `RangeImpl` have default implementations which may be overridden for
+//! efficiency.
+//!
+//! [`Range`]: struct.Range.html
+//! [`Rng::gen_range`]: ../../trait.Rng.html#method.gen_range
+//! [`SampleRange`]: trait.SampleRange.html
+//! [`RangeImpl`]: trait.RangeImpl.html
+//! [`RangeInt`]: struct.RangeInt.html
+//! [`RangeFloat`]: struct.RangeFloat.html
+//! [`ScaledInt`]: trait.ScaledInt.html
+//! [`RangeScaled`]: struct.RangeScaled.html
 
-use Rng;
+use core::{fmt, ops};
+
+use {Rng, RngCore, Error};
 use distributions::{Distribution, Uniform};
 use distributions::float::IntoFloat;
+#[cfg(feature="f16_support")] use distributions::float::next_u16;
+#[cfg(feature="f16_support")] use half::f16;
 
 /// Sample values uniformly between two bounds.
 ///
@@ -52,6 +114,7 @@ use distributions::float::IntoFloat;
 /// }
 /// ```
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
 pub struct Range<T: RangeImpl> {
     inner: T,
 }
@@ -68,9 +131,71 @@ impl Range<RangeInt<i32>> {
     }
 
     /// Create a new `Range` instance which samples uniformly from the closed
-    /// range `[low, high]` (inclusive). Panics if `low >= high`.
+    /// range `[low, high]` (inclusive). Panics if `low > high`.
     pub fn new_inclusive<X: SampleRange>(low: X, high: X) -> Range<X::T> {
-        assert!(low < high, "Range::new called with `low >= high`");
+        assert!(low <= high, "Range::new_inclusive called with `low > high`");
+        Range { inner: RangeImpl::new_inclusive(low, high) }
+    }
+
+    /// Create a new `Range` instance which samples uniformly from the half
+    /// open range `[low, high)` (excluding `high`).
+    ///
+    /// Unlike `new`, this returns an error instead of panicking if
+    /// `low >= high` (or if the bounds are not comparable, e.g. NaN), which
+    /// makes it suitable for bounds taken from untrusted input. Ranges which
+    /// cannot be sampled are rejected as well, such as floating-point ranges
+    /// with infinite bounds; see [`RangeImpl::check_bounds`].
+    ///
+    /// [`RangeImpl::check_bounds`]: trait.RangeImpl.html#method.check_bounds
+    pub fn try_new<X: SampleRange>(low: X, high: X)
+        -> Result<Range<X::T>, RangeError>
+    {
+        if !(low < high) {
+            return Err(RangeError::EmptyRange);
+        }
+        X::T::check_bounds(&low, &high)?;
+        Ok(Range { inner: RangeImpl::new(low, high) })
+    }
+
+    /// Create a new `Range` instance which samples uniformly from the closed
+    /// range `[low, high]` (inclusive).
+    ///
+    /// Unlike `new_inclusive`, this returns an error instead of panicking if
+    /// `low > high` (or if the bounds are not comparable, e.g. NaN), or if
+    /// the range cannot be sampled, like `try_new`.
+    pub fn try_new_inclusive<X: SampleRange>(low: X, high: X)
+        -> Result<Range<X::T>, RangeError>
+    {
+        if !(low <= high) {
+            return Err(RangeError::EmptyRange);
+        }
+        X::T::check_bounds(&low, &high)?;
+        Ok(Range { inner: RangeImpl::new_inclusive(low, high) })
+    }
+
+    /// Create a new `Range` instance which samples uniformly from the half
+    /// open range `[low, high)` in constant time, using
+    /// [`RangeConstantTime`]. Panics if `low >= high`.
+    ///
+    /// [`RangeConstantTime`]: struct.RangeConstantTime.html
+    pub fn new_constant_time<X>(low: X, high: X) -> Range<RangeConstantTime<X>>
+        where X: PartialOrd, RangeConstantTime<X>: RangeImpl<X = X>
+    {
+        assert!(low < high, "Range::new_constant_time called with `low >= high`");
+        Range { inner: RangeImpl::new(low, high) }
+    }
+
+    /// Create a new `Range` instance which samples uniformly from the closed
+    /// range `[low, high]` in constant time, using [`RangeConstantTime`].
+    /// Panics if `low > high`.
+    ///
+    /// [`RangeConstantTime`]: struct.RangeConstantTime.html
+    pub fn new_inclusive_constant_time<X>(low: X, high: X)
+        -> Range<RangeConstantTime<X>>
+        where X: PartialOrd, RangeConstantTime<X>: RangeImpl<X = X>
+    {
+        assert!(low <= high,
+                "Range::new_inclusive_constant_time called with `low > high`");
         Range { inner: RangeImpl::new_inclusive(low, high) }
     }
 
@@ -80,24 +205,195 @@ impl Range<RangeInt<i32>> {
         assert!(low < high, "Range::sample_single called with low >= high");
         X::T::sample_single(low, high, rng)
     }
+
+    /// Sample a single value uniformly from `[low, high]`.
+    /// Panics if `low > high`.
+    pub fn sample_single_inclusive<X: SampleRange, R: Rng + ?Sized>(low: X, high: X, rng: &mut R) -> X {
+        assert!(low <= high, "Range::sample_single_inclusive called with low > high");
+        X::T::sample_single_inclusive(low, high, rng)
+    }
 }
 
 impl<T: RangeImpl> Distribution<T::X> for Range<T> {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T::X {
         self.inner.sample(rng)
     }
+
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [T::X]) {
+        self.inner.sample_fill(rng, dest)
+    }
+}
+
+/// An error returned by [`Range::try_new`] and [`Range::try_new_inclusive`],
+/// and as part of a [`GenRangeError`].
+///
+/// [`Range::try_new`]: struct.Range.html#method.try_new
+/// [`Range::try_new_inclusive`]: struct.Range.html#method.try_new_inclusive
+/// [`GenRangeError`]: enum.GenRangeError.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    /// The range does not contain any values.
+    EmptyRange,
+    /// A bound of a floating-point range is not finite, or the range is so
+    /// large that sampling from it would overflow.
+    NonFinite,
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+impl RangeError {
+    fn description(&self) -> &'static str {
+        match *self {
+            RangeError::EmptyRange => "empty range",
+            RangeError::NonFinite => "range bounds or width are not finite",
+            RangeError::__Nonexhaustive => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+#[cfg(feature="std")]
+impl ::std::error::Error for RangeError {
+    fn description(&self) -> &str {
+        self.description()
+    }
+}
+
+/// An error returned by [`Rng::try_gen_range`]: either the range was invalid,
+/// or the random number generator failed.
+///
+/// [`Rng::try_gen_range`]: ../../trait.Rng.html#method.try_gen_range
+#[derive(Debug)]
+pub enum GenRangeError {
+    /// The range is empty or cannot be sampled.
+    Range(RangeError),
+    /// The random number generator failed.
+    Rng(Error),
+}
+
+impl From<RangeError> for GenRangeError {
+    fn from(e: RangeError) -> GenRangeError {
+        GenRangeError::Range(e)
+    }
+}
+
+impl From<Error> for GenRangeError {
+    fn from(e: Error) -> GenRangeError {
+        GenRangeError::Rng(e)
+    }
+}
+
+impl fmt::Display for GenRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GenRangeError::Range(ref e) => write!(f, "invalid range: {}", e),
+            GenRangeError::Rng(ref e) => write!(f, "RNG error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature="std")]
+impl ::std::error::Error for GenRangeError {
+    fn description(&self) -> &str {
+        match *self {
+            GenRangeError::Range(ref e) => e.description(),
+            GenRangeError::Rng(ref e) => ::std::error::Error::description(e),
+        }
+    }
+
+    fn cause(&self) -> Option<&::std::error::Error> {
+        match *self {
+            GenRangeError::Range(ref e) => Some(e),
+            GenRangeError::Rng(ref e) => Some(e),
+        }
+    }
+}
+
+/// Range types accepted by [`Rng::gen_range`]: the half-open `low..high` and
+/// the closed `low..=high`.
+///
+/// Sampling a single value only requires `low` and `high` to implement
+/// [`SampleRange`]; the rest is handled by [`RangeImpl::sample_single`] and
+/// [`RangeImpl::sample_single_inclusive`].
+///
+/// [`Rng::gen_range`]: ../../trait.Rng.html#method.gen_range
+/// [`SampleRange`]: trait.SampleRange.html
+/// [`RangeImpl::sample_single`]: trait.RangeImpl.html#method.sample_single
+/// [`RangeImpl::sample_single_inclusive`]: trait.RangeImpl.html#method.sample_single_inclusive
+pub trait GenRange<X> {
+    /// Sample a single value uniformly from this range.
+    ///
+    /// Panics if the range is empty.
+    fn sample_single<R: Rng + ?Sized>(self, rng: &mut R) -> X;
+
+    /// Sample a single value uniformly from this range, returning an error
+    /// instead of panicking if the range is empty or cannot be sampled (see
+    /// [`RangeImpl::check_bounds`]).
+    ///
+    /// [`RangeImpl::check_bounds`]: trait.RangeImpl.html#method.check_bounds
+    fn checked_sample_single<R: Rng + ?Sized>(self, rng: &mut R)
+        -> Result<X, RangeError>;
+}
+
+impl<X: SampleRange> GenRange<X> for ops::Range<X> {
+    #[inline]
+    fn sample_single<R: Rng + ?Sized>(self, rng: &mut R) -> X {
+        Range::sample_single(self.start, self.end, rng)
+    }
+
+    #[inline]
+    fn checked_sample_single<R: Rng + ?Sized>(self, rng: &mut R)
+        -> Result<X, RangeError>
+    {
+        if !(self.start < self.end) {
+            return Err(RangeError::EmptyRange);
+        }
+        X::T::check_bounds(&self.start, &self.end)?;
+        Ok(X::T::sample_single(self.start, self.end, rng))
+    }
+}
+
+impl<X: SampleRange> GenRange<X> for ops::RangeInclusive<X> {
+    #[inline]
+    fn sample_single<R: Rng + ?Sized>(self, rng: &mut R) -> X {
+        let (low, high) = self.into_inner();
+        Range::sample_single_inclusive(low, high, rng)
+    }
+
+    #[inline]
+    fn checked_sample_single<R: Rng + ?Sized>(self, rng: &mut R)
+        -> Result<X, RangeError>
+    {
+        let (low, high) = self.into_inner();
+        if !(low <= high) {
+            return Err(RangeError::EmptyRange);
+        }
+        X::T::check_bounds(&low, &high)?;
+        Ok(X::T::sample_single_inclusive(low, high, rng))
+    }
 }
 
 /// Helper trait for creating objects using the correct implementation of
-/// `RangeImpl` for the sampling type; this enables `Range::new(a, b)` to work.
+/// `RangeImpl` for the sampling type; this enables `Range::new(a, b)` and
+/// `rng.gen_range(a..b)` to work.
+///
+/// See the [module documentation](index.html#extending-range-to-user-types)
+/// on how to implement this for your own types.
 pub trait SampleRange: PartialOrd+Sized {
+    /// The `RangeImpl` implementation supporting this type.
     type T: RangeImpl<X = Self>;
 }
 
 /// Helper trait handling actual range sampling.
 ///
 /// If you want to implement `Range` sampling for your own type, then
-/// implement both this trait and `SampleRange`:
+/// implement both this trait and `SampleRange`, as in this example or the
+/// [module documentation](index.html#extending-range-to-user-types):
 ///
 /// ```rust
 /// use rand::{Rng, thread_rng};
@@ -148,13 +444,34 @@ pub trait RangeImpl: Sized {
     /// Construct self, with inclusive bounds `[low, high]`.
     ///
     /// Usually users should not call this directly but instead use
-    /// `Range::new_inclusive`, which asserts that `low < high` before calling
+    /// `Range::new_inclusive`, which asserts that `low <= high` before calling
     /// this.
     fn new_inclusive(low: Self::X, high: Self::X) -> Self;
 
+    /// Check that a non-empty range with bounds `low` and `high` can be
+    /// sampled.
+    ///
+    /// `Range::try_new` and the other fallible constructors call this after
+    /// checking that the range is not empty, and return its error instead of
+    /// calling `new`. The default implementation accepts every range; the
+    /// floating-point implementations reject non-finite bounds, and bounds
+    /// so far apart that sampling would overflow.
+    fn check_bounds(_low: &Self::X, _high: &Self::X) -> Result<(), RangeError> {
+        Ok(())
+    }
+
     /// Sample a value.
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X;
 
+    /// Fill `dest` with sampled values.
+    ///
+    /// The default implementation calls `sample` for each element.
+    fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R, dest: &mut [Self::X]) {
+        for x in dest.iter_mut() {
+            *x = self.sample(rng);
+        }
+    }
+
     /// Sample a single value uniformly from a range with inclusive lower bound
     /// and exclusive upper bound `[low, high)`.
     ///
@@ -172,6 +489,32 @@ pub trait RangeImpl: Sized {
         let range: Self = RangeImpl::new(low, high);
         range.sample(rng)
     }
+
+    /// Sample a single value uniformly from a range with inclusive lower and
+    /// upper bounds `[low, high]`.
+    ///
+    /// Usually users should not call this directly but instead use
+    /// `Range::sample_single_inclusive`, which asserts that `low <= high`
+    /// before calling this.
+    ///
+    /// The default implementation just sets up a range with
+    /// `RangeImpl::new_inclusive` and samples from that.
+    fn sample_single_inclusive<R: Rng + ?Sized>(low: Self::X, high: Self::X,
+                                                rng: &mut R) -> Self::X
+    {
+        let range: Self = RangeImpl::new_inclusive(low, high);
+        range.sample(rng)
+    }
+}
+
+// The bounds of a range as serialized; inclusive for the integer and `char`
+// ranges, and half-open for the float ranges.
+#[cfg(feature="serde-1")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Range")]
+struct RangeParams<X> {
+    low: X,
+    high: X,
 }
 
 /// Implementation of `RangeImpl` for integer types.
@@ -288,32 +631,89 @@ macro_rules! range_int_impl {
                 }
             }
 
+            fn sample_fill<R: Rng + ?Sized>(&self, rng: &mut R,
+                                            dest: &mut [Self::X])
+            {
+                let range = self.range as $unsigned as $u_large;
+                if range == 0 {
+                    return Uniform.sample_fill(rng, dest);
+                }
+                let zone = self.zone as $signed as $i_large as $u_large;
+                // Generate the random numbers in batches; the rare rejected
+                // values are replaced by individually sampled ones.
+                let mut buf = [0 as $u_large; 32];
+                for chunk in dest.chunks_mut(32) {
+                    let buf = &mut buf[..chunk.len()];
+                    rng.fill(buf);
+                    for (x, &v) in chunk.iter_mut().zip(buf.iter()) {
+                        let (hi, lo) = v.wmul(range);
+                        *x = if lo <= zone {
+                            self.low.wrapping_add(hi as $ty)
+                        } else {
+                            self.sample(rng)
+                        };
+                    }
+                }
+            }
+
             fn sample_single<R: Rng + ?Sized>(low: Self::X,
                                                   high: Self::X,
                                                   rng: &mut R) -> Self::X
             {
                 let range = (high as $u_large)
                             .wrapping_sub(low as $u_large);
-                let zone =
-                    if ::core::$unsigned::MAX <= ::core::u16::MAX as $unsigned {
-                        // Using a modulus is faster than the approximation for
-                        // i8 and i16. I suppose we trade the cost of one
-                        // modulus for near-perfect branch prediction.
-                        let unsigned_max: $u_large = ::core::$u_large::MAX;
-                        let ints_to_reject = (unsigned_max - range + 1) % range;
-                        unsigned_max - ints_to_reject
-                    } else {
-                        // conservative but fast approximation
-                       range << range.leading_zeros()
-                    };
+                RangeInt::<$ty>::sample_single_range(low, range, rng)
+            }
 
-                loop {
-                    let v: $u_large = Uniform.sample(rng);
-                    let (hi, lo) = v.wmul(range);
-                    if lo <= zone {
-                        return low.wrapping_add(hi as $ty);
+            fn sample_single_inclusive<R: Rng + ?Sized>(low: Self::X,
+                                                        high: Self::X,
+                                                        rng: &mut R) -> Self::X
+            {
+                let range = (high as $u_large)
+                            .wrapping_sub(low as $u_large)
+                            .wrapping_add(1);
+                if range == 0 {
+                    // Sample from the entire integer range.
+                    return Uniform.sample(rng);
+                }
+                RangeInt::<$ty>::sample_single_range(low, range, rng)
+            }
+        }
+
+        #[cfg(feature="serde-1")]
+        serde_via_params! { RangeInt<$ty>, RangeParams<$ty>,
+            |r| RangeParams {
+                low: r.low,
+                high: (r.low as $unsigned)
+                      .wrapping_add((r.range as $unsigned).wrapping_sub(1)) as $ty,
+            },
+            |p| Range::try_new_inclusive(p.low, p.high).map(|r| r.inner) }
+
+        impl RangeInt<$ty> {
+            // Sample from `[low, low + range)`, where `range` is non-zero.
+            //
+            // This uses Lemire's nearly-divisionless method: the low word of
+            // the widening multiply can only fall in the biased zone if it is
+            // less than `range`, so the exact number of integers to reject
+            // (which needs a modulus) is only computed in that rare case.
+            #[inline]
+            fn sample_single_range<R: Rng + ?Sized>(low: $ty,
+                                                    range: $u_large,
+                                                    rng: &mut R) -> $ty
+            {
+                let v: $u_large = Uniform.sample(rng);
+                let (mut hi, mut lo) = v.wmul(range);
+                if lo < range {
+                    // ints_to_reject = (unsigned_max + 1) % range
+                    let ints_to_reject = range.wrapping_neg() % range;
+                    while lo < ints_to_reject {
+                        let v: $u_large = Uniform.sample(rng);
+                        let (h, l) = v.wmul(range);
+                        hi = h;
+                        lo = l;
                     }
                 }
+                low.wrapping_add(hi as $ty)
             }
         }
     }
@@ -323,7 +723,6 @@ range_int_impl! { i8, i8, u8, i32, u32 }
 range_int_impl! { i16, i16, u16, i32, u32 }
 range_int_impl! { i32, i32, u32, i32, u32 }
 range_int_impl! { i64, i64, u64, i64, u64 }
-#[cfg(feature = "i128_support")]
 range_int_impl! { i128, i128, u128, u128, u128 }
 range_int_impl! { isize, isize, usize, isize, usize }
 range_int_impl! { u8, i8, u8, i32, u32 }
@@ -331,7 +730,6 @@ range_int_impl! { u16, i16, u16, i32, u32 }
 range_int_impl! { u32, i32, u32, i32, u32 }
 range_int_impl! { u64, i64, u64, i64, u64 }
 range_int_impl! { usize, isize, usize, isize, usize }
-#[cfg(feature = "i128_support")]
 range_int_impl! { u128, u128, u128, i128, u128 }
 
 
@@ -358,7 +756,6 @@ macro_rules! wmul_impl {
 wmul_impl! { u8, u16, 8 }
 wmul_impl! { u16, u32, 16 }
 wmul_impl! { u32, u64, 32 }
-#[cfg(feature = "i128_support")]
 wmul_impl! { u64, u128, 64 }
 
 // This code is a translation of the __mulddi3 function in LLVM's
@@ -394,9 +791,6 @@ macro_rules! wmul_impl_large {
     }
 }
 
-#[cfg(not(feature = "i128_support"))]
-wmul_impl_large! { u64, 32 }
-#[cfg(feature = "i128_support")]
 wmul_impl_large! { u128, 64 }
 
 
@@ -420,16 +814,146 @@ wmul_impl_usize! { u32 }
 wmul_impl_usize! { u64 }
 
 
+/// Implementation of `RangeImpl` for integer types, sampling in constant
+/// time.
+///
+/// [`RangeInt`] rejects random values falling in a biased zone, so the time
+/// it takes, and the number of values it takes from the RNG, depend on the
+/// values drawn. This can leak information when sampling secrets such as
+/// nonces or indices in cryptographic code. `RangeConstantTime` instead uses
+/// a fixed number of random bits (128 for types of up to 64 bits, 192 for
+/// `u128` and `i128`) and a widening multiply without branches on the result.
+///
+/// This is not exactly uniform: every value has a probability within a factor
+/// `1 ± 2^-64` of `1 / range`, which is negligible for all practical purposes.
+/// The timing only depends on the bounds, which are not considered secret,
+/// and on the RNG.
+///
+/// Create a `Range` using this with [`Range::new_constant_time`] or
+/// [`Range::new_inclusive_constant_time`].
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, thread_rng};
+/// use rand::distributions::Range;
+///
+/// let index = Range::new_constant_time(0usize, 1000);
+/// let i = thread_rng().sample(index);
+/// assert!(i < 1000);
+/// ```
+///
+/// [`RangeInt`]: struct.RangeInt.html
+/// [`Range::new_constant_time`]: struct.Range.html#method.new_constant_time
+/// [`Range::new_inclusive_constant_time`]: struct.Range.html#method.new_inclusive_constant_time
+#[derive(Clone, Copy, Debug)]
+pub struct RangeConstantTime<X> {
+    low: X,
+    // The size of the range as the unsigned type; 0 for the full range
+    range: X,
+}
+
+// `floor(v * range / 2^128)` for a random 128-bit `v`.
+#[inline]
+fn mul_high_128<R: Rng + ?Sized>(rng: &mut R, range: u128) -> u128 {
+    let v: u128 = Uniform.sample(rng);
+    v.wmul(range).0
+}
+
+// `floor(v * range / 2^192)` for a random 192-bit `v = hi * 2^64 + lo`.
+#[inline]
+fn mul_high_192<R: Rng + ?Sized>(rng: &mut R, range: u128) -> u128 {
+    let hi: u128 = Uniform.sample(rng);
+    let lo: u64 = Uniform.sample(rng);
+    // v * range = (h1 * 2^128 + l1) * 2^64 + (h2 * 2^128 + l2), with h2 < 2^64
+    let (h1, l1) = hi.wmul(range);
+    let (h2, l2) = (lo as u128).wmul(range);
+    let mid = (h2 << 64) | (l2 >> 64);
+    let (_, carry) = l1.overflowing_add(mid);
+    h1 + carry as u128
+}
+
+macro_rules! range_constant_time_impl {
+    ($ty:ty, $unsigned:ident, $mul_high:ident) => {
+        impl RangeImpl for RangeConstantTime<$ty> {
+            type X = $ty;
+
+            fn new(low: Self::X, high: Self::X) -> Self {
+                RangeImpl::new_inclusive(low, high - 1)
+            }
+
+            fn new_inclusive(low: Self::X, high: Self::X) -> Self {
+                let range = (high as $unsigned)
+                            .wrapping_sub(low as $unsigned)
+                            .wrapping_add(1);
+                RangeConstantTime { low: low, range: range as $ty }
+            }
+
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+                let range = self.range as $unsigned;
+                let offset = if range == 0 {
+                    let v: $unsigned = Uniform.sample(rng);
+                    v
+                } else {
+                    $mul_high(rng, range as u128) as $unsigned
+                };
+                self.low.wrapping_add(offset as $ty)
+            }
+        }
+
+        #[cfg(feature="serde-1")]
+        serde_via_params! { RangeConstantTime<$ty>, RangeParams<$ty>,
+            |r| RangeParams {
+                low: r.low,
+                high: (r.low as $unsigned)
+                      .wrapping_add((r.range as $unsigned).wrapping_sub(1)) as $ty,
+            },
+            |p| if p.low <= p.high {
+                Ok(RangeImpl::new_inclusive(p.low, p.high))
+            } else {
+                Err(RangeError::EmptyRange)
+            } }
+    }
+}
+
+range_constant_time_impl! { i8, u8, mul_high_128 }
+range_constant_time_impl! { i16, u16, mul_high_128 }
+range_constant_time_impl! { i32, u32, mul_high_128 }
+range_constant_time_impl! { i64, u64, mul_high_128 }
+range_constant_time_impl! { i128, u128, mul_high_192 }
+range_constant_time_impl! { isize, usize, mul_high_128 }
+range_constant_time_impl! { u8, u8, mul_high_128 }
+range_constant_time_impl! { u16, u16, mul_high_128 }
+range_constant_time_impl! { u32, u32, mul_high_128 }
+range_constant_time_impl! { u64, u64, mul_high_128 }
+range_constant_time_impl! { u128, u128, mul_high_192 }
+range_constant_time_impl! { usize, usize, mul_high_128 }
+
+
 
 /// Implementation of `RangeImpl` for float types.
+///
+/// Values are computed as `low + (high - low) * u` for a random `u` in
+/// `[0, 1)`, which may round up to `high` even for a half-open range. With
+/// only 11 bits of precision, this is far from rare for `f16`.
+///
+/// For `f16`, `Range::new` panics for a range so large that sampling from it
+/// would overflow, e.g. `-60000.0..60000.0`; `Range::try_new` returns an
+/// error for these instead, as it does for all float types.
 #[derive(Clone, Copy, Debug)]
 pub struct RangeFloat<X> {
+    // The bounds as given, since they can't be recovered exactly from
+    // `scale` and `offset`; only needed for serialization.
+    #[cfg_attr(not(feature="serde-1"), allow(dead_code))]
+    low: X,
+    #[cfg_attr(not(feature="serde-1"), allow(dead_code))]
+    high: X,
     scale: X,
     offset: X,
 }
 
 macro_rules! range_float_impl {
-    ($ty:ty, $bits_to_discard:expr, $next_u:ident) => {
+    ($ty:ty, $bits_to_discard:expr, $next_u:path, $check_scale:expr) => {
         impl SampleRange for $ty {
             type T = RangeFloat<$ty>;
         }
@@ -439,8 +963,16 @@ macro_rules! range_float_impl {
 
             fn new(low: Self::X, high: Self::X) -> Self {
                 let scale = high - low;
+                // This easily overflows for `f16`; larger types are not
+                // checked, to keep `new` cheap.
+                if $check_scale {
+                    assert!(Self::check_bounds(&low, &high).is_ok(),
+                            "Range::new called with a range too large for the type");
+                }
                 let offset = low - scale;
                 RangeFloat {
+                    low: low,
+                    high: high,
                     scale: scale,
                     offset: offset,
                 }
@@ -452,9 +984,22 @@ macro_rules! range_float_impl {
                 RangeImpl::new(low, high)
             }
 
+            fn check_bounds(low: &Self::X, high: &Self::X) -> Result<(), RangeError> {
+                // `sample` computes `value1_2 * scale + offset`, with
+                // `value1_2` in `[1, 2)`
+                let scale = *high - *low;
+                if low.is_finite() && high.is_finite() && (scale + scale).is_finite()
+                    && (*low - scale).is_finite()
+                {
+                    Ok(())
+                } else {
+                    Err(RangeError::NonFinite)
+                }
+            }
+
             fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
                 // Generate a value in the range [1, 2)
-                let value1_2 = (rng.$next_u() >> $bits_to_discard)
+                let value1_2 = ($next_u(rng) >> $bits_to_discard)
                                .into_float_with_exponent(0);
                 // Doing multiply before addition allows some architectures to
                 // use a single instruction.
@@ -464,14 +1009,501 @@ macro_rules! range_float_impl {
     }
 }
 
-range_float_impl! { f32, 32 - 23, next_u32 }
-range_float_impl! { f64, 64 - 52, next_u64 }
+range_float_impl! { f32, 32 - 23, RngCore::next_u32, false }
+range_float_impl! { f64, 64 - 52, RngCore::next_u64, false }
+// Float ranges are the same whether created by `new` or `new_inclusive`, so
+// rebuilding with `try_new_inclusive` also accepts `low == high`.
+#[cfg(feature="serde-1")]
+serde_via_params! { RangeFloat<f32>, RangeParams<f32>,
+    |r| RangeParams { low: r.low, high: r.high },
+    |p| Range::try_new_inclusive(p.low, p.high).map(|r| r.inner) }
+#[cfg(feature="serde-1")]
+serde_via_params! { RangeFloat<f64>, RangeParams<f64>,
+    |r| RangeParams { low: r.low, high: r.high },
+    |p| Range::try_new_inclusive(p.low, p.high).map(|r| r.inner) }
+#[cfg(feature="f16_support")] range_float_impl! { f16, 16 - 10, next_u16, true }
+
+#[cfg(feature="simd_support")]
+macro_rules! range_simd_float_impl {
+    ($ty:ident, $uty:ident, $scalar:ty, $u_scalar:ty, $fraction_bits:expr,
+     $exponent_bias:expr) => {
+        impl SampleRange for ::core::simd::$ty {
+            type T = RangeFloat<::core::simd::$ty>;
+        }
+
+        /// Samples each lane independently from the range given by the
+        /// corresponding lanes of `low` and `high`.
+        impl RangeImpl for RangeFloat<::core::simd::$ty> {
+            type X = ::core::simd::$ty;
+
+            fn new(low: Self::X, high: Self::X) -> Self {
+                use core::simd::prelude::*;
+                assert!(low.simd_lt(high).all(),
+                        "Range::new called with `low >= high` in some lane");
+                let scale = high - low;
+                let offset = low - scale;
+                RangeFloat {
+                    low: low,
+                    high: high,
+                    scale: scale,
+                    offset: offset,
+                }
+            }
+
+            fn new_inclusive(low: Self::X, high: Self::X) -> Self {
+                use core::simd::prelude::*;
+                assert!(low.simd_le(high).all(),
+                        "Range::new_inclusive called with `low > high` in some lane");
+                let scale = high - low;
+                let offset = low - scale;
+                RangeFloat {
+                    low: low,
+                    high: high,
+                    scale: scale,
+                    offset: offset,
+                }
+            }
+
+            fn check_bounds(low: &Self::X, high: &Self::X) -> Result<(), RangeError> {
+                use core::simd::prelude::*;
+                let scale = *high - *low;
+                if (low.is_finite() & high.is_finite() & (scale + scale).is_finite()
+                    & (*low - scale).is_finite()).all()
+                {
+                    Ok(())
+                } else {
+                    Err(RangeError::NonFinite)
+                }
+            }
+
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+                use core::simd::{$ty, $uty};
+                use core::simd::prelude::*;
+                let float_size = ::core::mem::size_of::<$scalar>() * 8;
+
+                // Generate values in the range [1, 2)
+                let value: $uty = Uniform.sample(rng);
+                let fraction = value >> $uty::splat((float_size - $fraction_bits) as $u_scalar);
+                let exponent_bits = $uty::splat(($exponent_bias as $u_scalar) << $fraction_bits);
+                let value1_2 = $ty::from_bits(fraction | exponent_bits);
+                value1_2 * self.scale + self.offset
+            }
+        }
+    }
+}
+
+#[cfg(feature="simd_support")] range_simd_float_impl! { f32x4, u32x4, f32, u32, 23, 127 }
+#[cfg(feature="simd_support")] range_simd_float_impl! { f32x8, u32x8, f32, u32, 23, 127 }
+#[cfg(feature="simd_support")] range_simd_float_impl! { f32x16, u32x16, f32, u32, 23, 127 }
+#[cfg(feature="simd_support")] range_simd_float_impl! { f64x2, u64x2, f64, u64, 52, 1023 }
+#[cfg(feature="simd_support")] range_simd_float_impl! { f64x4, u64x4, f64, u64, 52, 1023 }
+#[cfg(feature="simd_support")] range_simd_float_impl! { f64x8, u64x8, f64, u64, 52, 1023 }
+
+#[cfg(feature="simd_support")]
+macro_rules! wmul_simd_impl {
+    ($ty:ident, $scalar:ty, $wide_scalar:ty, $shift:expr) => {
+        impl WideningMultiply for ::core::simd::$ty {
+            type Output = (::core::simd::$ty, ::core::simd::$ty);
+
+            #[inline(always)]
+            fn wmul(self, x: ::core::simd::$ty) -> Self::Output {
+                use core::simd::Simd;
+                use core::simd::prelude::*;
+                let tmp = self.cast::<$wide_scalar>() * x.cast::<$wide_scalar>();
+                ((tmp >> Simd::splat($shift)).cast::<$scalar>(),
+                 tmp.cast::<$scalar>())
+            }
+        }
+    }
+}
+
+#[cfg(feature="simd_support")] wmul_simd_impl! { u8x16, u8, u16, 8 }
+#[cfg(feature="simd_support")] wmul_simd_impl! { u8x32, u8, u16, 8 }
+#[cfg(feature="simd_support")] wmul_simd_impl! { u8x64, u8, u16, 8 }
+#[cfg(feature="simd_support")] wmul_simd_impl! { u16x8, u16, u32, 16 }
+#[cfg(feature="simd_support")] wmul_simd_impl! { u16x16, u16, u32, 16 }
+#[cfg(feature="simd_support")] wmul_simd_impl! { u16x32, u16, u32, 16 }
+#[cfg(feature="simd_support")] wmul_simd_impl! { u32x4, u32, u64, 32 }
+#[cfg(feature="simd_support")] wmul_simd_impl! { u32x8, u32, u64, 32 }
+#[cfg(feature="simd_support")] wmul_simd_impl! { u32x16, u32, u64, 32 }
+
+// There are no vectors of `u128`, so the high half of a 64-bit product is
+// assembled from 32-bit halves, as in `wmul_impl_large`.
+#[cfg(feature="simd_support")]
+macro_rules! wmul_simd_impl_large {
+    ($ty:ident) => {
+        impl WideningMultiply for ::core::simd::$ty {
+            type Output = (::core::simd::$ty, ::core::simd::$ty);
+
+            #[inline(always)]
+            fn wmul(self, b: ::core::simd::$ty) -> Self::Output {
+                use core::simd::$ty;
+                let lower_mask = $ty::splat(0xffff_ffff);
+                let half = $ty::splat(32);
+
+                let (a_lo, a_hi) = (self & lower_mask, self >> half);
+                let (b_lo, b_hi) = (b & lower_mask, b >> half);
+
+                let t = a_lo * b_lo;
+                let t = a_hi * b_lo + (t >> half);
+                let (w1, w2) = (t & lower_mask, t >> half);
+                let t = a_lo * b_hi + w1;
+                let hi = a_hi * b_hi + w2 + (t >> half);
+                (hi, self * b)
+            }
+        }
+    }
+}
+
+#[cfg(feature="simd_support")] wmul_simd_impl_large! { u64x2 }
+#[cfg(feature="simd_support")] wmul_simd_impl_large! { u64x4 }
+#[cfg(feature="simd_support")] wmul_simd_impl_large! { u64x8 }
+
+#[cfg(feature="simd_support")]
+macro_rules! range_simd_int_impl {
+    ($ty:ident, $uty:ident, $u_scalar:ident) => {
+        impl SampleRange for ::core::simd::$ty {
+            type T = RangeInt<::core::simd::$ty>;
+        }
+
+        /// Samples each lane independently from the range given by the
+        /// corresponding lanes of `low` and `high`.
+        ///
+        /// This works like the scalar implementation, with one `zone` per
+        /// lane. Lanes which are rejected are resampled together, until all
+        /// lanes have been accepted.
+        impl RangeImpl for RangeInt<::core::simd::$ty> {
+            type X = ::core::simd::$ty;
+
+            fn new(low: Self::X, high: Self::X) -> Self {
+                use core::simd::prelude::*;
+                assert!(low.simd_lt(high).all(),
+                        "Range::new called with `low >= high` in some lane");
+                RangeImpl::new_inclusive(low, high - Self::X::splat(1))
+            }
+
+            fn new_inclusive(low: Self::X, high: Self::X) -> Self {
+                use core::simd::{$uty, Select};
+                use core::simd::prelude::*;
+                assert!(low.simd_le(high).all(),
+                        "Range::new_inclusive called with `low > high` in some lane");
+                // A `range` of 0 stands for the whole range of the lane
+                // type; avoid dividing by it.
+                let range = high.cast::<$u_scalar>() - low.cast::<$u_scalar>()
+                            + $uty::splat(1);
+                let divisor = range.simd_eq($uty::splat(0))
+                                   .select($uty::splat(1), range);
+                // ints_to_reject = (unsigned_max + 1) % range
+                let ints_to_reject = ($uty::splat(0) - range) % divisor;
+                let zone = $uty::splat(::core::$u_scalar::MAX) - ints_to_reject;
+                RangeInt {
+                    low: low,
+                    range: range.cast(),
+                    zone: zone.cast(),
+                }
+            }
+
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+                use core::simd::{$uty, Mask, Select};
+                use core::simd::prelude::*;
+                let range = self.range.cast::<$u_scalar>();
+                let zone = self.zone.cast::<$u_scalar>();
+                let low = self.low.cast::<$u_scalar>();
+                let full = range.simd_eq($uty::splat(0));
+
+                let mut result = $uty::splat(0);
+                let mut done = Mask::splat(false);
+                loop {
+                    let v: $uty = Uniform.sample(rng);
+                    let (hi, lo) = v.wmul(range);
+                    let accept = lo.simd_le(zone) & !done;
+                    result = accept.select(full.select(v, low + hi), result);
+                    done |= accept;
+                    if done.all() {
+                        return result.cast();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature="simd_support")] range_simd_int_impl! { u8x16, u8x16, u8 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u8x32, u8x32, u8 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u8x64, u8x64, u8 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u16x8, u16x8, u16 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u16x16, u16x16, u16 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u16x32, u16x32, u16 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u32x4, u32x4, u32 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u32x8, u32x8, u32 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u32x16, u32x16, u32 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u64x2, u64x2, u64 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u64x4, u64x4, u64 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { u64x8, u64x8, u64 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i8x16, u8x16, u8 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i8x32, u8x32, u8 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i8x64, u8x64, u8 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i16x8, u16x8, u16 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i16x16, u16x16, u16 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i16x32, u16x32, u16 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i32x4, u32x4, u32 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i32x8, u32x8, u32 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i32x16, u32x16, u32 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i64x2, u64x2, u64 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i64x4, u64x4, u64 }
+#[cfg(feature="simd_support")] range_simd_int_impl! { i64x8, u64x8, u64 }
+
+
+/// Implementation of `RangeImpl` for `char`.
+///
+/// Samples uniformly over the Unicode scalar values in the range, i.e. the
+/// surrogate code points `0xD800...0xDFFF` are never produced, and do not
+/// count towards the size of the range.
+#[derive(Clone, Copy, Debug)]
+pub struct RangeChar {
+    sampler: RangeInt<u32>,
+}
+
+// The surrogate code points, which are not valid `char`s
+const CHAR_SURROGATE_START: u32 = 0xD800;
+const CHAR_SURROGATE_LEN: u32 = 0xE000 - CHAR_SURROGATE_START;
+
+// Map a `char` to a `u32` where the surrogate gap has been closed.
+#[inline]
+fn char_to_comp_u32(c: char) -> u32 {
+    match c as u32 {
+        c if c >= CHAR_SURROGATE_START => c - CHAR_SURROGATE_LEN,
+        c => c,
+    }
+}
+
+// The inverse of `char_to_comp_u32`. `x` must be at most the mapped value of
+// some valid `char`.
+#[inline]
+fn comp_u32_to_char(mut x: u32) -> char {
+    if x >= CHAR_SURROGATE_START {
+        x += CHAR_SURROGATE_LEN;
+    }
+    // Safe: the surrogate range has been skipped above, and `x` is at most a
+    // valid `char`.
+    unsafe { ::core::char::from_u32_unchecked(x) }
+}
+
+impl SampleRange for char {
+    type T = RangeChar;
+}
+
+impl RangeImpl for RangeChar {
+    type X = char;
+
+    #[inline]
+    fn new(low: Self::X, high: Self::X) -> Self {
+        let low = char_to_comp_u32(low);
+        let high = char_to_comp_u32(high);
+        RangeChar { sampler: RangeImpl::new(low, high) }
+    }
+
+    #[inline]
+    fn new_inclusive(low: Self::X, high: Self::X) -> Self {
+        let low = char_to_comp_u32(low);
+        let high = char_to_comp_u32(high);
+        RangeChar { sampler: RangeImpl::new_inclusive(low, high) }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+        // `x` is at most the (valid) upper bound
+        comp_u32_to_char(self.sampler.sample(rng))
+    }
+}
+
+#[cfg(feature="serde-1")]
+serde_via_params! { RangeChar, RangeParams<char>,
+    |r| RangeParams {
+        low: comp_u32_to_char(r.sampler.low),
+        high: comp_u32_to_char(r.sampler.low + (r.sampler.range - 1)),
+    },
+    |p| Range::try_new_inclusive(p.low, p.high).map(|r| r.inner) }
+
+
+
+/// Types represented exactly by an integer count of some unit, such as
+/// fixed-point numbers, decimal amounts of money or timestamps.
+///
+/// Implementing this trait and setting `SampleRange::T` to
+/// [`RangeScaled<Self>`] makes `Range` and `Rng::gen_range` sample such a
+/// type exactly, by sampling the underlying integer uniformly; no conversion
+/// through floating point is involved.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, thread_rng};
+/// use rand::distributions::range::{SampleRange, ScaledInt, RangeScaled};
+///
+/// /// A decimal amount with four fractional digits.
+/// #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+/// struct Amount(i64);
+///
+/// impl ScaledInt for Amount {
+///     type Repr = i64;
+///     fn to_repr(self) -> i64 { self.0 }
+///     fn from_repr(repr: i64) -> Self { Amount(repr) }
+/// }
+///
+/// impl SampleRange for Amount {
+///     type T = RangeScaled<Amount>;
+/// }
+///
+/// // Between 12.5000 and 99.9999
+/// let x = thread_rng().gen_range(Amount(12_5000)..Amount(100_0000));
+/// assert!(x >= Amount(12_5000) && x < Amount(100_0000));
+/// ```
+///
+/// [`RangeScaled<Self>`]: struct.RangeScaled.html
+pub trait ScaledInt: Copy + PartialOrd {
+    /// The underlying integer type.
+    type Repr: SampleRange;
+
+    /// Convert into the underlying integer.
+    fn to_repr(self) -> Self::Repr;
+
+    /// Convert from the underlying integer.
+    fn from_repr(repr: Self::Repr) -> Self;
+}
+
+/// Implementation of `RangeImpl` for types implementing [`ScaledInt`].
+///
+/// [`ScaledInt`]: trait.ScaledInt.html
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+#[cfg_attr(feature="serde-1", serde(bound(
+    serialize="<T::Repr as SampleRange>::T: ::serde::Serialize",
+    deserialize="<T::Repr as SampleRange>::T: ::serde::Deserialize<'de>")))]
+pub struct RangeScaled<T: ScaledInt> {
+    inner: <T::Repr as SampleRange>::T,
+}
+
+impl<T: ScaledInt> Clone for RangeScaled<T>
+    where <T::Repr as SampleRange>::T: Clone
+{
+    fn clone(&self) -> Self {
+        RangeScaled { inner: self.inner.clone() }
+    }
+}
+
+impl<T: ScaledInt> Copy for RangeScaled<T>
+    where <T::Repr as SampleRange>::T: Copy {}
+
+impl<T: ScaledInt> fmt::Debug for RangeScaled<T>
+    where <T::Repr as SampleRange>::T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RangeScaled")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<T: ScaledInt> RangeImpl for RangeScaled<T> {
+    type X = T;
 
+    fn new(low: T, high: T) -> Self {
+        RangeScaled { inner: RangeImpl::new(low.to_repr(), high.to_repr()) }
+    }
+
+    fn new_inclusive(low: T, high: T) -> Self {
+        RangeScaled {
+            inner: RangeImpl::new_inclusive(low.to_repr(), high.to_repr()),
+        }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        T::from_repr(self.inner.sample(rng))
+    }
+
+    fn sample_single<R: Rng + ?Sized>(low: T, high: T, rng: &mut R) -> T {
+        T::from_repr(<T::Repr as SampleRange>::T::sample_single(
+            low.to_repr(), high.to_repr(), rng))
+    }
+
+    fn sample_single_inclusive<R: Rng + ?Sized>(low: T, high: T, rng: &mut R)
+        -> T
+    {
+        T::from_repr(<T::Repr as SampleRange>::T::sample_single_inclusive(
+            low.to_repr(), high.to_repr(), rng))
+    }
+}
+
+/// Sample a big unsigned integer uniformly from `[0, bound)`.
+///
+/// Both numbers are given as little-endian `u32` limbs: `dest` receives the
+/// result and must have the same length as `bound`. This is the building
+/// block for implementing `RangeImpl` for arbitrary-precision integer types
+/// such as those from `num-bigint`: random limbs are generated up to the
+/// most significant bit of `bound`, and the whole number is rejected if it is
+/// not below `bound`, so on average fewer than two attempts are needed and
+/// the result is exactly uniform.
+///
+/// Panics if `bound` is zero or if the lengths differ.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::distributions::range::sample_limbs_below;
+///
+/// // 2^64 + 5
+/// let bound = [5, 0, 1];
+/// let mut x = [0u32; 3];
+/// sample_limbs_below(&mut thread_rng(), &bound, &mut x);
+/// assert!(x[2] < 1 || (x[2] == 1 && x[1] == 0 && x[0] < 5));
+/// ```
+pub fn sample_limbs_below<R: Rng + ?Sized>(rng: &mut R, bound: &[u32],
+                                           dest: &mut [u32])
+{
+    assert_eq!(bound.len(), dest.len(),
+               "sample_limbs_below called with different lengths");
+    let top = match bound.iter().rposition(|&limb| limb != 0) {
+        Some(top) => top,
+        None => panic!("sample_limbs_below called with `bound` == 0"),
+    };
+    let mask = !0 >> bound[top].leading_zeros();
+    for x in dest[top + 1..].iter_mut() {
+        *x = 0;
+    }
+    loop {
+        rng.fill(&mut dest[..top + 1]);
+        dest[top] &= mask;
+        // Compare from the most significant limb
+        let less = dest[..top + 1].iter().rev()
+            .zip(bound[..top + 1].iter().rev())
+            .find(|&(x, b)| x != b)
+            .map_or(false, |(x, b)| x < b);
+        if less {
+            return;
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use Rng;
-    use distributions::range::{Range, RangeImpl, RangeFloat, SampleRange};
+    use distributions::range::{Range, RangeImpl, RangeFloat, SampleRange, RangeError,
+                               ScaledInt, RangeScaled, RangeConstantTime,
+                               sample_limbs_below};
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_goodness_of_fit() {
+        use testing::{chi_squared, kolmogorov_smirnov};
+        let mut rng = ::test::rng(260);
+        // A range size that is not a power of 2, so some values are rejected
+        let result = chi_squared(&mut rng, &Range::new(-3i8, 7), 10000,
+                                 &[0.1; 10], |&x| (x + 3) as usize);
+        assert!(result.passes(0.001), "{:?}", result);
+        let result = kolmogorov_smirnov(&mut rng, &Range::new(-2.0, 6.0), 10000,
+                                        |x| (x + 2.0) / 8.0);
+        assert!(result.passes(0.001), "{:?}", result);
+    }
 
     #[should_panic]
     #[test]
@@ -485,6 +1517,20 @@ mod tests {
         Range::new(10, 5);
     }
 
+    #[test]
+    fn test_range_inclusive_equal_limits() {
+        let mut rng = ::test::rng(250);
+        let range = Range::new_inclusive(10, 10);
+        assert_eq!(rng.sample(range), 10);
+        assert_eq!(Range::sample_single_inclusive(10, 10, &mut rng), 10);
+    }
+
+    #[should_panic]
+    #[test]
+    fn test_range_inclusive_bad_limits_flipped() {
+        Range::new_inclusive(10, 5);
+    }
+
     #[test]
     fn test_integers() {
         let mut rng = ::test::rng(251);
@@ -500,16 +1546,128 @@ mod tests {
                             let v: $ty = rng.sample(my_range);
                             assert!(low <= v && v < high);
                         }
+
+                        let my_range = Range::new_inclusive(low, high);
+                        for _ in 0..1000 {
+                            let v: $ty = rng.sample(my_range);
+                            assert!(low <= v && v <= high);
+                        }
+
+                        for _ in 0..1000 {
+                            let v: $ty = Range::sample_single(low, high, &mut rng);
+                            assert!(low <= v && v < high);
+                            let v: $ty = Range::sample_single_inclusive(low, high, &mut rng);
+                            assert!(low <= v && v <= high);
+                        }
                     }
                  )*
             }}
         }
         t!(i8, i16, i32, i64, isize,
            u8, u16, u32, u64, usize);
-        #[cfg(feature = "i128_support")]
         t!(i128, u128)
     }
 
+    #[test]
+    fn test_128bit() {
+        let mut rng = ::test::rng(253);
+        let high = ::core::u128::MAX - 5;
+        let range = Range::new(0u128, high);
+        let mut large = false;
+        for _ in 0..100 {
+            let v = rng.sample(range);
+            assert!(v < high);
+            large |= v > ::core::u64::MAX as u128;
+        }
+        assert!(large);
+
+        let v = rng.gen_range(::core::i128::MIN..=::core::i128::MAX);
+        let _: i128 = v;
+        let v = rng.gen_range(-(1i128 << 100)..(1i128 << 64));
+        assert!(-(1i128 << 100) <= v && v < (1i128 << 64));
+    }
+
+    #[test]
+    fn test_constant_time() {
+        use mock::StepRng;
+        use record::CountingRng;
+
+        let mut rng = CountingRng::new(::test::rng(270));
+        macro_rules! t {
+            ($($ty:ident),*) => {{
+                $(
+                let v: &[($ty, $ty)] = &[(0, 10), (10, 127), (::core::$ty::MIN, ::core::$ty::MAX)];
+                for &(low, high) in v.iter() {
+                    let range = Range::new_constant_time(low, high);
+                    let inclusive = Range::new_inclusive_constant_time(low, high);
+                    for _ in 0..100 {
+                        let v = rng.sample(range);
+                        assert!(low <= v && v < high);
+                        let v = rng.sample(inclusive);
+                        assert!(low <= v && v <= high);
+                    }
+                }
+                let full = Range::new_inclusive_constant_time(::core::$ty::MIN,
+                                                              ::core::$ty::MAX);
+                rng.sample(full);
+
+                // The extreme random values map to the bounds
+                let range = Range::new_constant_time(low_of::<$ty>(), 100);
+                assert_eq!(StepRng::new(0, 0).sample(range), low_of::<$ty>());
+                assert_eq!(StepRng::new(!0, 0).sample(range), 99);
+                )*
+            }}
+        }
+        fn low_of<T: From<u8>>() -> T { T::from(3) }
+        t!(u8, u16, u32, u64, u128);
+        t!(i16, i32, i64, i128);
+
+        // A fixed number of values is used, independently of the result
+        let range = Range::new_constant_time(0u32, 3);
+        let calls = rng.counts().calls();
+        let mut counts = [0; 3];
+        for _ in 0..300 {
+            counts[rng.sample(range) as usize] += 1;
+        }
+        assert_eq!(rng.counts().calls() - calls, 600);
+        assert!(counts.iter().all(|&c| c > 50));
+        let range = Range::new_constant_time(0u128, 3);
+        let next_u64 = rng.counts().next_u64;
+        rng.sample(range);
+        assert_eq!(rng.counts().next_u64 - next_u64, 3);
+
+        let range = RangeConstantTime::<i8>::new(-5, 5);
+        assert!(range.sample(&mut rng) >= -5);
+    }
+
+    #[test]
+    fn test_char() {
+        let mut rng = ::test::rng(254);
+        let range = Range::new_inclusive('a', 'z');
+        for _ in 0..100 {
+            let c = rng.sample(range);
+            assert!('a' <= c && c <= 'z');
+        }
+
+        // The surrogate gap is skipped.
+        let low = '\u{D7FF}';
+        let high = '\u{E000}';
+        let range = Range::new_inclusive(low, high);
+        let (mut seen_low, mut seen_high) = (false, false);
+        for _ in 0..100 {
+            let c = rng.sample(range);
+            assert!(c == low || c == high);
+            seen_low |= c == low;
+            seen_high |= c == high;
+        }
+        assert!(seen_low && seen_high);
+        assert_eq!(rng.gen_range(low..high), low);
+
+        let c = rng.gen_range('\u{3040}'..='\u{30FF}');
+        assert!('\u{3040}' <= c && c <= '\u{30FF}');
+        let _ = rng.gen_range('\0'..=::core::char::MAX);
+    }
+
     #[test]
     fn test_floats() {
         let mut rng = ::test::rng(252);
@@ -533,6 +1691,220 @@ mod tests {
 
         t!(f32, f64)
     }
+    #[test]
+    fn test_sample_single_uniform() {
+        let mut rng = ::test::rng(806);
+        let mut counts = [0; 3];
+        for _ in 0..3000 {
+            counts[Range::sample_single(0u32, 3, &mut rng) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&n| n > 900 && n < 1100), "{:?}", counts);
+        // A range using almost all of `u64`, where about half of the values
+        // have to be rejected.
+        let high = (1u64 << 63) + 1;
+        for _ in 0..100 {
+            assert!(Range::sample_single(0, high, &mut rng) < high);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="simd_support")]
+    fn test_simd_floats() {
+        use core::simd::f32x4;
+        let mut rng = ::test::rng(807);
+        let low = f32x4::from_array([-10.0, 0.0, 1e-3, 100.0]);
+        let high = f32x4::from_array([-9.0, 1.0, 2e-3, 1e6]);
+        let range = Range::new(low, high);
+        for _ in 0..100 {
+            let v = rng.sample(range);
+            for i in 0..4 {
+                let x = v.as_array()[i];
+                assert!(low.as_array()[i] <= x && x < high.as_array()[i]);
+            }
+            let v = rng.gen_range(low..high);
+            assert!(low.as_array()[0] <= v.as_array()[0]);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="f16_support")]
+    fn test_f16() {
+        use half::f16;
+        let f = f16::from_f32;
+        let mut rng = ::test::rng(811);
+        let range = Range::new(f(0.0), f(1.0));
+        for _ in 0..100 {
+            let x = rng.sample(range);
+            assert!(f(0.0) <= x && x < f(1.0));
+            // With only 10 fraction bits, rounding may produce `high`.
+            let y = rng.gen_range(f(-2.0)..f(3.0));
+            assert!(f(-2.0) <= y && y <= f(3.0));
+        }
+        let mut max = ::mock::StepRng::new(!0, 0);
+        assert_eq!(max.sample(range), f(1.0 - 1.0 / 1024.0));
+        // Rounded up to `high`
+        assert_eq!(max.sample(Range::new(f(1000.0), f(1001.0))), f(1001.0));
+
+        // Sampling these would overflow
+        assert_eq!(Range::try_new(f(-60000.0), f(60000.0)).unwrap_err(),
+                   RangeError::NonFinite);
+        assert_eq!(Range::try_new(f(-20000.0), f(20000.0)).unwrap_err(),
+                   RangeError::NonFinite);
+        assert_eq!(Range::try_new_inclusive(f(0.0), f16::INFINITY).unwrap_err(),
+                   RangeError::NonFinite);
+        let v = rng.sample(Range::try_new(f(-10000.0), f(10000.0)).unwrap());
+        assert!(v.is_finite());
+    }
+
+    #[test]
+    #[cfg(feature="f16_support")]
+    #[should_panic]
+    fn test_f16_too_large() {
+        use half::f16;
+        Range::new(f16::from_f32(-60000.0), f16::from_f32(60000.0));
+    }
+
+    #[test]
+    #[cfg(feature="simd_support")]
+    fn test_simd_integers() {
+        use core::simd::{u8x16, i16x8, u32x4, i64x2, u64x4};
+        let mut rng = ::test::rng(812);
+
+        let low = i16x8::from_array([-300, 0, 5, 5, -1, i16::MIN, 0, 7]);
+        let high = i16x8::from_array([-200, 1, 6, 100, 1, i16::MAX, 30000, 8]);
+        let range = Range::new_inclusive(low, high);
+        for _ in 0..100 {
+            let v = rng.sample(range);
+            for i in 0..8 {
+                let x = v.as_array()[i];
+                assert!(low.as_array()[i] <= x && x <= high.as_array()[i]);
+            }
+        }
+
+        // Each lane, including a full-range one, covers its whole range
+        let range = Range::new_inclusive(u8x16::splat(0),
+            u8x16::from_array([2, 255, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2]));
+        let mut seen = [[false; 256]; 2];
+        for _ in 0..5000 {
+            let v = rng.sample(range);
+            seen[0][v.as_array()[0] as usize] = true;
+            seen[1][v.as_array()[1] as usize] = true;
+        }
+        assert!(seen[0][..3].iter().all(|&s| s) && !seen[0][3]);
+        assert!(seen[1].iter().all(|&s| s));
+
+        let v = rng.gen_range(u32x4::splat(10)..u32x4::from_array([11, 20, 1000, !0]));
+        assert_eq!(v.as_array()[0], 10);
+        let high = u64x4::from_array([3, 1 << 40, !0, (1 << 63) + 1]);
+        for _ in 0..100 {
+            let v = rng.gen_range(u64x4::splat(1)..high);
+            for i in 0..4 {
+                assert!(v.as_array()[i] >= 1 && v.as_array()[i] < high.as_array()[i]);
+            }
+            let v = rng.gen_range(i64x2::splat(-5)..=i64x2::splat(-4));
+            assert!(v.as_array().iter().all(|&x| x == -5 || x == -4));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature="simd_support")]
+    fn test_simd_invalid_lane() {
+        use core::simd::f64x2;
+        Range::new(f64x2::from_array([0.0, 1.0]), f64x2::from_array([1.0, 0.5]));
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature="simd_support")]
+    fn test_simd_invalid_int_lane() {
+        use core::simd::i32x4;
+        Range::new(i32x4::splat(0), i32x4::from_array([1, 1, 0, 1]));
+    }
+
+    #[test]
+    fn test_scaled_int() {
+        // A fixed-point number with 16 fractional bits
+        #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+        struct Fix(i32);
+        impl ScaledInt for Fix {
+            type Repr = i32;
+            fn to_repr(self) -> i32 { self.0 }
+            fn from_repr(repr: i32) -> Self { Fix(repr) }
+        }
+        impl SampleRange for Fix {
+            type T = RangeScaled<Fix>;
+        }
+
+        let mut rng = ::test::rng(810);
+        let (low, high) = (Fix(-3 << 16), Fix(1 << 15));
+        let range = Range::new(low, high);
+        for _ in 0..100 {
+            let x = rng.sample(range);
+            assert!(low <= x && x < high);
+            let x = rng.gen_range(low..=high);
+            assert!(low <= x && x <= high);
+        }
+        assert_eq!(rng.gen_range(Fix(7)..=Fix(7)), Fix(7));
+    }
+
+    #[test]
+    fn test_sample_limbs_below() {
+        let mut rng = ::test::rng(808);
+        let mut x = [0u32; 1];
+        for _ in 0..10 {
+            sample_limbs_below(&mut rng, &[1], &mut x);
+            assert_eq!(x, [0]);
+        }
+
+        // 2^64 + 2, with a zero limb on top
+        let bound = [2, 0, 1, 0];
+        let mut counts = [0; 3];
+        let mut x = [!0u32; 4];
+        for _ in 0..1000 {
+            sample_limbs_below(&mut rng, &bound, &mut x);
+            assert_eq!(x[3], 0);
+            assert!(x[2] == 0 || (x[2] == 1 && x[1] == 0 && x[0] < 2));
+            if x[2] == 1 { counts[x[0] as usize] += 1; } else { counts[2] += 1; }
+        }
+        // The two values >= 2^64 are very unlikely
+        assert!(counts[0] + counts[1] < 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_limbs_below_zero() {
+        sample_limbs_below(&mut ::test::rng(809), &[0, 0], &mut [0, 0]);
+    }
+
+    #[test]
+    fn test_try_new() {
+        let mut rng = ::test::rng(805);
+        assert_eq!(Range::try_new(10, 10).unwrap_err(), RangeError::EmptyRange);
+        assert_eq!(Range::try_new(10, 5).unwrap_err(), RangeError::EmptyRange);
+        assert_eq!(Range::try_new_inclusive(10, 9).unwrap_err(), RangeError::EmptyRange);
+        assert_eq!(Range::try_new(0.0, ::core::f64::NAN).unwrap_err(),
+                   RangeError::EmptyRange);
+        assert_eq!(rng.sample(Range::try_new_inclusive(10, 10).unwrap()), 10);
+        let v = rng.sample(Range::try_new(-1.0, 1.0).unwrap());
+        assert!(-1.0 <= v && v < 1.0);
+
+        use core::f64::{INFINITY, NEG_INFINITY, MIN, MAX};
+        for &(low, high) in &[(0.0, INFINITY), (NEG_INFINITY, 0.0), (MIN, MAX),
+                              (NEG_INFINITY, INFINITY)] {
+            assert_eq!(Range::try_new(low, high).unwrap_err(), RangeError::NonFinite);
+            assert_eq!(Range::try_new_inclusive(low, high).unwrap_err(),
+                       RangeError::NonFinite);
+        }
+        assert_eq!(Range::try_new_inclusive(INFINITY, INFINITY).unwrap_err(),
+                   RangeError::NonFinite);
+        assert_eq!(Range::try_new(::core::f32::MIN, ::core::f32::MAX).unwrap_err(),
+                   RangeError::NonFinite);
+        assert_eq!(Range::try_new(MIN / 2.0, MAX / 2.0).unwrap_err(), RangeError::NonFinite);
+        let v = rng.sample(Range::try_new(MIN / 4.0, MAX / 4.0).unwrap());
+        assert!(v.is_finite());
+    }
+
     #[test]
     fn test_custom_range() {
         #[derive(Clone, Copy, PartialEq, PartialOrd)]
@@ -568,5 +1940,11 @@ mod tests {
             let x: MyF32 = rng.sample(range);
             assert!(low <= x && x < high);
         }
+        for _ in 0..100 {
+            let x = rng.gen_range(low..high);
+            assert!(low <= x && x < high);
+            let x = Range::sample_single_inclusive(low, high, &mut rng);
+            assert!(low <= x && x <= high);
+        }
     }
 }
@@ -0,0 +1,582 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Distributions over times and durations.
+//!
+//! `Duration` and `SystemTime` implement [`SampleRange`], with nanosecond
+//! resolution, so `rng.gen_range(start..end)` and `Range::new(start, end)`
+//! work for them. To sample times within a recurring window, such as office
+//! hours on each of the next 30 days, see [`Recurring`].
+//!
+//! With the `chrono` feature, the same holds for the `NaiveDate`,
+//! `NaiveDateTime`, `DateTime<Utc>` and `TimeDelta` types of the [chrono]
+//! crate, and with the `time` feature for the `Date`, `PrimitiveDateTime`,
+//! `OffsetDateTime` and `Duration` types of the [time] crate.
+//!
+//! ```rust
+//! use std::time::{Duration, SystemTime};
+//! use rand::{thread_rng, Rng};
+//!
+//! let now = SystemTime::now();
+//! let next_week = now + Duration::from_secs(7 * 24 * 3600);
+//! let t = thread_rng().gen_range(now..next_week);
+//! assert!(now <= t && t < next_week);
+//! ```
+//!
+//! [`SampleRange`]: ../range/trait.SampleRange.html
+//! [`Recurring`]: struct.Recurring.html
+//! [chrono]: https://docs.rs/chrono
+//! [time]: https://docs.rs/time
+
+use std::ops;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use Rng;
+use distributions::{Distribution, Range, ParamError};
+use distributions::range::{SampleRange, ScaledInt, RangeInt, RangeScaled};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+const SECS_PER_DAY: u64 = 24 * 3600;
+
+// `Duration::as_nanos` requires Rust 1.33.
+fn duration_to_nanos(d: Duration) -> u128 {
+    d.as_secs() as u128 * NANOS_PER_SEC + d.subsec_nanos() as u128
+}
+
+fn duration_from_nanos(nanos: u128) -> Duration {
+    Duration::new((nanos / NANOS_PER_SEC) as u64,
+                  (nanos % NANOS_PER_SEC) as u32)
+}
+
+// The representation of `time + nanos`, or `None` if the sum overflows.
+//
+// `SystemTime::checked_add` requires Rust 1.34, so this instead conservatively
+// limits times to those a 64-bit `time_t` can represent.
+fn checked_add_nanos(time: SystemTime, nanos: u128) -> Option<i128> {
+    const MAX_NANOS: i128 = ::core::i64::MAX as i128 * NANOS_PER_SEC as i128;
+    if nanos > MAX_NANOS as u128 {
+        return None;
+    }
+    let sum = time.to_repr() + nanos as i128;
+    if sum <= MAX_NANOS { Some(sum) } else { None }
+}
+
+impl ScaledInt for Duration {
+    type Repr = u128;
+
+    #[inline]
+    fn to_repr(self) -> u128 {
+        duration_to_nanos(self)
+    }
+
+    #[inline]
+    fn from_repr(repr: u128) -> Self {
+        duration_from_nanos(repr)
+    }
+}
+
+impl SampleRange for Duration {
+    type T = RangeScaled<Duration>;
+}
+
+/// Times are represented in nanoseconds relative to `UNIX_EPOCH`, so times
+/// before the epoch are supported as well.
+impl ScaledInt for SystemTime {
+    type Repr = i128;
+
+    #[inline]
+    fn to_repr(self) -> i128 {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(d) => duration_to_nanos(d) as i128,
+            Err(e) => -(duration_to_nanos(e.duration()) as i128),
+        }
+    }
+
+    #[inline]
+    fn from_repr(repr: i128) -> Self {
+        if repr >= 0 {
+            UNIX_EPOCH + duration_from_nanos(repr as u128)
+        } else {
+            // `-repr` without overflowing for `i128::MIN`
+            UNIX_EPOCH - duration_from_nanos((-(repr + 1)) as u128 + 1)
+        }
+    }
+}
+
+impl SampleRange for SystemTime {
+    type T = RangeScaled<SystemTime>;
+}
+
+// Split nanoseconds into seconds and non-negative nanoseconds.
+#[cfg(feature="chrono")]
+fn split_nanos(nanos: i128) -> (i64, u32) {
+    let secs = nanos.div_euclid(NANOS_PER_SEC as i128);
+    (secs as i64, nanos.rem_euclid(NANOS_PER_SEC as i128) as u32)
+}
+
+/// Dates are represented by their day number counted from 0001-01-01.
+#[cfg(feature="chrono")]
+impl ScaledInt for ::chrono::NaiveDate {
+    type Repr = i32;
+
+    #[inline]
+    fn to_repr(self) -> i32 {
+        use chrono::Datelike;
+        self.num_days_from_ce()
+    }
+
+    #[inline]
+    fn from_repr(repr: i32) -> Self {
+        ::chrono::NaiveDate::from_num_days_from_ce_opt(repr)
+            .expect("date out of range")
+    }
+}
+
+#[cfg(feature="chrono")]
+impl SampleRange for ::chrono::NaiveDate {
+    type T = RangeScaled<::chrono::NaiveDate>;
+}
+
+/// Times are represented in nanoseconds relative to the Unix epoch. A leap
+/// second is represented like the second following it, so it is never
+/// sampled.
+#[cfg(feature="chrono")]
+impl ScaledInt for ::chrono::NaiveDateTime {
+    type Repr = i128;
+
+    #[inline]
+    fn to_repr(self) -> i128 {
+        self.and_utc().to_repr()
+    }
+
+    #[inline]
+    fn from_repr(repr: i128) -> Self {
+        <::chrono::DateTime<::chrono::Utc> as ScaledInt>::from_repr(repr)
+            .naive_utc()
+    }
+}
+
+#[cfg(feature="chrono")]
+impl SampleRange for ::chrono::NaiveDateTime {
+    type T = RangeScaled<::chrono::NaiveDateTime>;
+}
+
+/// Times are represented in nanoseconds relative to the Unix epoch. A leap
+/// second is represented like the second following it, so it is never
+/// sampled.
+#[cfg(feature="chrono")]
+impl ScaledInt for ::chrono::DateTime<::chrono::Utc> {
+    type Repr = i128;
+
+    #[inline]
+    fn to_repr(self) -> i128 {
+        self.timestamp() as i128 * NANOS_PER_SEC as i128
+            + self.timestamp_subsec_nanos() as i128
+    }
+
+    #[inline]
+    fn from_repr(repr: i128) -> Self {
+        let (secs, nanos) = split_nanos(repr);
+        ::chrono::DateTime::from_timestamp(secs, nanos)
+            .expect("time out of range")
+    }
+}
+
+#[cfg(feature="chrono")]
+impl SampleRange for ::chrono::DateTime<::chrono::Utc> {
+    type T = RangeScaled<::chrono::DateTime<::chrono::Utc>>;
+}
+
+#[cfg(feature="chrono")]
+impl ScaledInt for ::chrono::TimeDelta {
+    type Repr = i128;
+
+    #[inline]
+    fn to_repr(self) -> i128 {
+        self.num_seconds() as i128 * NANOS_PER_SEC as i128
+            + self.subsec_nanos() as i128
+    }
+
+    #[inline]
+    fn from_repr(repr: i128) -> Self {
+        let (secs, nanos) = split_nanos(repr);
+        ::chrono::TimeDelta::new(secs, nanos).expect("duration out of range")
+    }
+}
+
+#[cfg(feature="chrono")]
+impl SampleRange for ::chrono::TimeDelta {
+    type T = RangeScaled<::chrono::TimeDelta>;
+}
+
+/// Dates are represented by their Julian day number.
+#[cfg(feature="time")]
+impl ScaledInt for ::time::Date {
+    type Repr = i32;
+
+    #[inline]
+    fn to_repr(self) -> i32 {
+        self.to_julian_day()
+    }
+
+    #[inline]
+    fn from_repr(repr: i32) -> Self {
+        ::time::Date::from_julian_day(repr).expect("date out of range")
+    }
+}
+
+#[cfg(feature="time")]
+impl SampleRange for ::time::Date {
+    type T = RangeScaled<::time::Date>;
+}
+
+/// Times are represented in nanoseconds relative to the Unix epoch, taking
+/// them to be in UTC.
+#[cfg(feature="time")]
+impl ScaledInt for ::time::PrimitiveDateTime {
+    type Repr = i128;
+
+    #[inline]
+    fn to_repr(self) -> i128 {
+        self.assume_utc().unix_timestamp_nanos()
+    }
+
+    #[inline]
+    fn from_repr(repr: i128) -> Self {
+        let t = ::time::OffsetDateTime::from_repr(repr);
+        ::time::PrimitiveDateTime::new(t.date(), t.time())
+    }
+}
+
+#[cfg(feature="time")]
+impl SampleRange for ::time::PrimitiveDateTime {
+    type T = RangeScaled<::time::PrimitiveDateTime>;
+}
+
+/// Times are represented in nanoseconds relative to the Unix epoch. The
+/// offset of the bounds is not kept: sampled times are in UTC.
+#[cfg(feature="time")]
+impl ScaledInt for ::time::OffsetDateTime {
+    type Repr = i128;
+
+    #[inline]
+    fn to_repr(self) -> i128 {
+        self.unix_timestamp_nanos()
+    }
+
+    #[inline]
+    fn from_repr(repr: i128) -> Self {
+        ::time::OffsetDateTime::from_unix_timestamp_nanos(repr)
+            .expect("time out of range")
+    }
+}
+
+#[cfg(feature="time")]
+impl SampleRange for ::time::OffsetDateTime {
+    type T = RangeScaled<::time::OffsetDateTime>;
+}
+
+#[cfg(feature="time")]
+impl ScaledInt for ::time::Duration {
+    type Repr = i128;
+
+    #[inline]
+    fn to_repr(self) -> i128 {
+        self.whole_nanoseconds()
+    }
+
+    #[inline]
+    fn from_repr(repr: i128) -> Self {
+        ::time::Duration::nanoseconds_i128(repr)
+    }
+}
+
+#[cfg(feature="time")]
+impl SampleRange for ::time::Duration {
+    type T = RangeScaled<::time::Duration>;
+}
+
+/// Samples times uniformly from a window repeating with a fixed period,
+/// e.g. between 9:00 and 17:00 on each of the next 30 days.
+///
+/// A period is chosen uniformly from the `count` periods following `start`,
+/// and then a time uniformly from `window`, an offset into that period.
+/// `start` is used as given: to align the windows with midnight in some
+/// time zone, pass a `start` at midnight in that zone.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use rand::distributions::{Distribution, Recurring};
+///
+/// // Office hours (UTC) in the 30 days from 2018-01-01
+/// let start = UNIX_EPOCH + Duration::from_secs(1514764800);
+/// let hours = Duration::from_secs(9 * 3600)..Duration::from_secs(17 * 3600);
+/// let meeting = Recurring::daily(start, 30, hours);
+/// let t = meeting.sample(&mut rand::thread_rng());
+/// let secs_of_day = t.duration_since(start).unwrap().as_secs() % 86400;
+/// assert!(secs_of_day >= 9 * 3600 && secs_of_day < 17 * 3600);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Recurring {
+    start: SystemTime,
+    period: u128,
+    index: Range<RangeInt<u64>>,
+    offset: Range<RangeScaled<Duration>>,
+}
+
+impl Recurring {
+    /// Construct a new `Recurring` distribution over `window` within each of
+    /// the `count` periods of length `period` starting at `start`.
+    ///
+    /// Panics if `count == 0`, if `window` is empty or if it ends after
+    /// `period`, or if the last window ends too far in the future to be
+    /// represented.
+    pub fn new(start: SystemTime, period: Duration, count: u64,
+               window: ops::Range<Duration>) -> Recurring
+    {
+        Recurring::try_new(start, period, count, window)
+            .unwrap_or_else(|e| panic!("Recurring::new called with invalid parameters: {}", e))
+    }
+
+    /// Construct a new `Recurring` distribution over `window` within each of
+    /// the `count` periods of length `period` starting at `start`, or return
+    /// an error if `count == 0`, if `window` is empty or if it ends after
+    /// `period`, or if the last window ends too far in the future to be
+    /// represented.
+    pub fn try_new(start: SystemTime, period: Duration, count: u64,
+                   window: ops::Range<Duration>) -> Result<Recurring, ParamError>
+    {
+        if count == 0 {
+            return Err(ParamError::NotPositive("count"));
+        }
+        if !(window.start < window.end && window.end <= period) {
+            return Err(ParamError::OutOfRange("window"));
+        }
+        // The latest time sampled is before the end of the window in the
+        // last period, which must not overflow `SystemTime`.
+        let end = duration_to_nanos(period).checked_mul((count - 1) as u128)
+            .and_then(|n| n.checked_add(duration_to_nanos(window.end)));
+        if end.and_then(|n| checked_add_nanos(start, n)).is_none() {
+            return Err(ParamError::OutOfRange("count"));
+        }
+        Ok(Recurring {
+            start: start,
+            period: duration_to_nanos(period),
+            index: Range::new(0, count),
+            offset: Range::new(window.start, window.end),
+        })
+    }
+
+    /// Construct a new `Recurring` distribution over `window` on each of
+    /// `days` days from `start`.
+    ///
+    /// See [`new`](#method.new) for details.
+    pub fn daily(start: SystemTime, days: u64, window: ops::Range<Duration>)
+        -> Recurring
+    {
+        Recurring::new(start, Duration::from_secs(SECS_PER_DAY), days, window)
+    }
+
+    /// Construct a new `Recurring` distribution over `window` in each of
+    /// `weeks` weeks from `start`. The window may span several days, e.g.
+    /// `0..5 * 24h` for the working days if `start` is a Monday.
+    ///
+    /// See [`new`](#method.new) for details.
+    pub fn weekly(start: SystemTime, weeks: u64, window: ops::Range<Duration>)
+        -> Recurring
+    {
+        Recurring::new(start, Duration::from_secs(7 * SECS_PER_DAY), weeks,
+                       window)
+    }
+}
+
+impl Distribution<SystemTime> for Recurring {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SystemTime {
+        let index = self.index.sample(rng) as u128;
+        let offset = self.offset.sample(rng);
+        self.start + duration_from_nanos(self.period * index) + offset
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use Rng;
+    use distributions::Range;
+    use super::Recurring;
+
+    #[test]
+    fn test_duration_range() {
+        let mut rng = ::test::rng(840);
+        let low = Duration::new(1, 999_999_990);
+        let high = Duration::new(2, 10);
+        for _ in 0..100 {
+            let d = rng.gen_range(low..high);
+            assert!(low <= d && d < high);
+            let d = rng.gen_range(low..=low);
+            assert_eq!(d, low);
+        }
+        let range = Range::new(Duration::from_secs(0), Duration::from_secs(60));
+        assert!(rng.sample(range) < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_system_time_range() {
+        let mut rng = ::test::rng(841);
+        // A range spanning the epoch
+        let low = UNIX_EPOCH - Duration::from_millis(1500);
+        let high = UNIX_EPOCH + Duration::new(0, 1);
+        let mut before = false;
+        for _ in 0..100 {
+            let t = rng.gen_range(low..high);
+            assert!(low <= t && t < high);
+            before |= t < UNIX_EPOCH;
+        }
+        assert!(before);
+
+        let now = SystemTime::now();
+        assert_eq!(rng.gen_range(now..=now), now);
+    }
+
+    #[test]
+    fn test_recurring() {
+        let mut rng = ::test::rng(842);
+        let start = UNIX_EPOCH + Duration::from_secs(1514764800);
+        let window = Duration::from_secs(9 * 3600)..Duration::from_secs(17 * 3600);
+        let meeting = Recurring::daily(start, 30, window);
+        let mut days = [0; 30];
+        for _ in 0..3000 {
+            let secs = rng.sample(meeting).duration_since(start).unwrap().as_secs();
+            assert!(secs % 86400 >= 9 * 3600 && secs % 86400 < 17 * 3600);
+            days[(secs / 86400) as usize] += 1;
+        }
+        assert!(days.iter().all(|&n| n > 50));
+
+        let workdays = Recurring::weekly(start, 2,
+            Duration::from_secs(0)..Duration::from_secs(5 * 86400));
+        for _ in 0..100 {
+            let secs = rng.sample(workdays).duration_since(start).unwrap().as_secs();
+            assert!(secs < 14 * 86400 && secs % (7 * 86400) < 5 * 86400);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_recurring_window_too_long() {
+        Recurring::daily(UNIX_EPOCH, 1,
+            Duration::from_secs(0)..Duration::from_secs(86401));
+    }
+
+    #[test]
+    fn test_recurring_try_new() {
+        use distributions::ParamError;
+        let day = Duration::from_secs(86400);
+        let hour = Duration::from_secs(3600);
+        assert!(Recurring::try_new(UNIX_EPOCH, day, 1, hour..day).is_ok());
+        assert_eq!(Recurring::try_new(UNIX_EPOCH, day, 0, hour..day).unwrap_err(),
+                   ParamError::NotPositive("count"));
+        assert_eq!(Recurring::try_new(UNIX_EPOCH, day, 1, hour..hour).unwrap_err(),
+                   ParamError::OutOfRange("window"));
+        assert_eq!(Recurring::try_new(UNIX_EPOCH, hour, 1, hour..day).unwrap_err(),
+                   ParamError::OutOfRange("window"));
+
+        // The last period must not overflow
+        let now = SystemTime::now();
+        let zero = Duration::from_secs(0);
+        assert_eq!(Recurring::try_new(now, day, u64::max_value(), zero..hour).unwrap_err(),
+                   ParamError::OutOfRange("count"));
+        assert_eq!(Recurring::try_new(now, Duration::new(u64::max_value(), 0), 2, zero..hour)
+                       .unwrap_err(),
+                   ParamError::OutOfRange("count"));
+        let max = Duration::from_secs(i64::max_value() as u64);
+        assert!(Recurring::try_new(UNIX_EPOCH, max, 1, zero..max).is_ok());
+        assert!(Recurring::try_new(UNIX_EPOCH + hour, max, 1, zero..max).is_err());
+        assert!(Recurring::try_new(now, day, 1000, zero..hour).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature="chrono")]
+    fn test_chrono_ranges() {
+        use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+        let mut rng = ::test::rng(843);
+
+        let low = NaiveDate::from_ymd_opt(2018, 2, 27).unwrap();
+        let high = NaiveDate::from_ymd_opt(2018, 3, 2).unwrap();
+        let mut march = false;
+        for _ in 0..100 {
+            let d = rng.gen_range(low..high);
+            assert!(low <= d && d < high);
+            march |= d == NaiveDate::from_ymd_opt(2018, 3, 1).unwrap();
+        }
+        assert!(march);
+        assert_eq!(rng.gen_range(NaiveDate::MIN..=NaiveDate::MIN), NaiveDate::MIN);
+        let d = rng.gen_range(NaiveDate::MIN..=NaiveDate::MAX);
+        assert!(NaiveDate::MIN <= d && d <= NaiveDate::MAX);
+
+        // A range spanning the epoch
+        let low = DateTime::from_timestamp(-1, 999_999_990).unwrap();
+        let high = DateTime::from_timestamp(0, 10).unwrap();
+        let mut before = false;
+        for _ in 0..100 {
+            let t: DateTime<Utc> = rng.gen_range(low..high);
+            assert!(low <= t && t < high);
+            before |= t.timestamp() < 0;
+            let n = rng.gen_range(low.naive_utc()..high.naive_utc());
+            assert!(low.naive_utc() <= n && n < high.naive_utc());
+        }
+        assert!(before);
+
+        let low = TimeDelta::new(-1, 500).unwrap();
+        let high = TimeDelta::new(1, 0).unwrap();
+        for _ in 0..100 {
+            let d = rng.gen_range(low..high);
+            assert!(low <= d && d < high);
+        }
+        assert_eq!(rng.gen_range(TimeDelta::MIN..=TimeDelta::MIN), TimeDelta::MIN);
+    }
+
+    #[test]
+    #[cfg(feature="time")]
+    fn test_time_ranges() {
+        use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+        let mut rng = ::test::rng(844);
+
+        let low = Date::from_calendar_date(2018, Month::February, 27).unwrap();
+        let high = Date::from_calendar_date(2018, Month::March, 2).unwrap();
+        for _ in 0..100 {
+            let d = rng.gen_range(low..high);
+            assert!(low <= d && d < high);
+        }
+        let d = rng.gen_range(Date::MIN..=Date::MAX);
+        assert!(Date::MIN <= d && d <= Date::MAX);
+
+        let low = OffsetDateTime::from_unix_timestamp_nanos(-10).unwrap();
+        let high = OffsetDateTime::from_unix_timestamp_nanos(10).unwrap()
+            .to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+        for _ in 0..100 {
+            let t = rng.gen_range(low..high);
+            assert!(low <= t && t < high);
+            assert!(t.offset().is_utc());
+        }
+
+        let low = PrimitiveDateTime::new(low.date(), low.time());
+        let high = low + Duration::nanoseconds(5);
+        for _ in 0..100 {
+            let t = rng.gen_range(low..high);
+            assert!(low <= t && t < high);
+        }
+
+        let d = rng.gen_range(Duration::MIN..=Duration::MAX);
+        assert!(Duration::MIN <= d && d <= Duration::MAX);
+        assert_eq!(rng.gen_range(Duration::MAX..=Duration::MAX), Duration::MAX);
+    }
+}
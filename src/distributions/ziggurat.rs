@@ -0,0 +1,243 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Ziggurat method, for implementing samplers of custom distributions.
+//!
+//! `StandardNormal` and `Exp1` are implemented with the ZIGNOR variant[1] of
+//! the Ziggurat method, using tables generated by `utils/ziggurat_tables.py`.
+//! [`ZigguratTables`] exposes the same machinery for any distribution with a
+//! density that is monotonically decreasing on `[0, ∞)`, either one-sided or
+//! symmetric around 0.
+//!
+//! [1]: Jurgen A. Doornik (2005). [*An Improved Ziggurat Method to
+//! Generate Normal Random
+//! Samples*](https://www.doornik.com/research/ziggurat.pdf). Nuffield
+//! College, Oxford
+//!
+//! [`ZigguratTables`]: struct.ZigguratTables.html
+
+use core::fmt;
+
+use Rng;
+use distributions::ParamError;
+use distributions::ziggurat as sample_ziggurat;
+
+// The number of layers; the tables have one more entry.
+const LAYERS: usize = 256;
+
+/// Tables for sampling a distribution with the Ziggurat method.
+///
+/// The distribution is covered by 256 layers of equal area `v`: the bottom
+/// layer consists of a rectangle from 0 to `r` and the tail beyond `r`, the
+/// others are rectangles stacked on top. The tables are computed once by
+/// [`new`] from the density, after which [`sample`] generates values
+/// quickly.
+///
+/// # Example
+///
+/// Sampling from `Exp(1)`, using the constants for `r` and `v` of
+/// `utils/ziggurat_tables.py`:
+///
+/// ```rust
+/// use rand::distributions::ziggurat::ZigguratTables;
+/// use rand::Rng;
+///
+/// fn pdf(x: f64) -> f64 { (-x).exp() }
+/// fn pdf_inv(y: f64) -> f64 { -y.ln() }
+///
+/// let tables = ZigguratTables::new(7.69711747013104972,
+///                                  0.0039496598225815571993,
+///                                  pdf, pdf_inv);
+/// let r = tables.r();
+/// let mut rng = rand::thread_rng();
+/// let x = tables.sample(&mut rng, false, pdf,
+///                       |rng, _| r - rng.gen::<f64>().ln());
+/// assert!(x > 0.0);
+/// ```
+///
+/// [`new`]: #method.new
+/// [`sample`]: #method.sample
+#[derive(Clone)]
+pub struct ZigguratTables {
+    x: [f64; LAYERS + 1],
+    f: [f64; LAYERS + 1],
+}
+
+impl ZigguratTables {
+    /// Compute the tables for a distribution.
+    ///
+    /// - `r` is the start of the tail, i.e. the right edge of the
+    ///   rectangle in the bottom layer.
+    /// - `v` is the area of each layer: `r * pdf(r)` plus the integral of
+    ///   `pdf` over the tail `[r, ∞)`.
+    /// - `pdf` is the density, which need not be normalized but must be
+    ///   monotonically decreasing on `[0, ∞)`, and `pdf_inv` its inverse.
+    ///
+    /// `r` and `v` must be consistent for 256 layers, such that the top layer
+    /// ends at `pdf(0)`, as computed for the ZIGNOR method.
+    ///
+    /// Panics if `r` or `v` is not positive, or if the layers computed from
+    /// them are not finite and decreasing.
+    pub fn new<P, I>(r: f64, v: f64, pdf: P, pdf_inv: I) -> ZigguratTables
+        where P: FnMut(f64) -> f64, I: FnMut(f64) -> f64
+    {
+        ZigguratTables::try_new(r, v, pdf, pdf_inv).unwrap_or_else(|e| {
+            panic!("ZigguratTables::new called with invalid parameters: {}", e)
+        })
+    }
+
+    /// Compute the tables for a distribution, as [`new`], or return an error
+    /// if `r` or `v` is not positive, or if the layers computed from them are
+    /// not finite and decreasing (which indicates that `r` and `v` do not
+    /// match `pdf`).
+    ///
+    /// [`new`]: #method.new
+    pub fn try_new<P, I>(r: f64, v: f64, mut pdf: P, mut pdf_inv: I)
+        -> Result<ZigguratTables, ParamError>
+        where P: FnMut(f64) -> f64, I: FnMut(f64) -> f64
+    {
+        if !(r > 0.0) {
+            return Err(ParamError::NotPositive("r"));
+        }
+        if !(v > 0.0) {
+            return Err(ParamError::NotPositive("v"));
+        }
+        let mut x = [0.0; LAYERS + 1];
+        x[0] = v / pdf(r);
+        x[1] = r;
+        for i in 2..LAYERS {
+            let last = x[i - 1];
+            x[i] = pdf_inv(v / last + pdf(last));
+        }
+
+        let mut f = [0.0; LAYERS + 1];
+        for i in 0..(LAYERS + 1) {
+            f[i] = pdf(x[i]);
+        }
+        for i in 0..LAYERS {
+            if !(x[i].is_finite() && x[i] > x[i + 1] && f[i].is_finite()) {
+                return Err(ParamError::OutOfRange("v"));
+            }
+        }
+        Ok(ZigguratTables { x: x, f: f })
+    }
+
+    /// The start of the tail.
+    pub fn r(&self) -> f64 {
+        self.x[1]
+    }
+
+    /// Generate a sample.
+    ///
+    /// - `symmetric` selects whether values are in `(-∞, ∞)`, the density
+    ///   being symmetric around 0, or in `[0, ∞)`.
+    /// - `pdf` must be the density the tables were computed for.
+    /// - `zero_case` samples from the tail beyond `r()`. It receives the
+    ///   uniform value in `(-1, 1)` (or `(0, 1)` if not symmetric) used to
+    ///   pick the point; for symmetric distributions its sign is the sign of
+    ///   the sample.
+    #[inline]
+    pub fn sample<R, P, Z>(&self, rng: &mut R, symmetric: bool, pdf: P,
+                           zero_case: Z) -> f64
+        where R: Rng + ?Sized,
+              P: FnMut(f64) -> f64,
+              Z: FnMut(&mut R, f64) -> f64
+    {
+        sample_ziggurat(rng, symmetric, &self.x, &self.f, pdf, zero_case)
+    }
+}
+
+impl fmt::Debug for ZigguratTables {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZigguratTables")
+            .field("r", &self.r())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use Rng;
+    use distributions::{ziggurat_tables, ParamError};
+    use super::ZigguratTables;
+
+    #[test]
+    fn test_tables_match_static() {
+        let norm = ZigguratTables::new(3.6541528853610088, 0.00492867323399,
+                                       |x| (-x * x / 2.0).exp(),
+                                       |y| (-2.0 * y.ln()).sqrt());
+        let exp = ZigguratTables::new(7.69711747013104972,
+                                      0.0039496598225815571993,
+                                      |x| (-x).exp(), |y| -y.ln());
+        let cases = [(&norm, ziggurat_tables::ZIG_NORM_X, ziggurat_tables::ZIG_NORM_F),
+                     (&exp, ziggurat_tables::ZIG_EXP_X, ziggurat_tables::ZIG_EXP_F)];
+        for &(tables, x, f) in cases.iter() {
+            for i in 0..257 {
+                assert!((tables.x[i] - x[i]).abs() <= 1e-12 * x[i]);
+                assert!((tables.f[i] - f[i]).abs() <= 1e-12 * f[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_f32_tables_match_f64() {
+        use distributions::ziggurat_tables::*;
+        let cases = [(ZIG_NORM_X, ZIG_NORM_X_F32), (ZIG_NORM_F, ZIG_NORM_F_F32),
+                     (ZIG_EXP_X, ZIG_EXP_X_F32), (ZIG_EXP_F, ZIG_EXP_F_F32)];
+        for &(t64, t32) in cases.iter() {
+            for i in 0..257 {
+                assert_eq!(t64[i] as f32, t32[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_half_normal() {
+        let r = 3.6541528853610088;
+        let tables = ZigguratTables::new(r, 0.00492867323399,
+                                         |x| (-x * x / 2.0).exp(),
+                                         |y| (-2.0 * y.ln()).sqrt());
+        let mut rng = ::test::rng(237);
+        let mut sum = 0.0;
+        for _ in 0..10000 {
+            let x = tables.sample(&mut rng, false, |x| (-x * x / 2.0).exp(),
+                                  |rng, _| {
+                // The tail of the normal distribution, as in `StandardNormal`
+                loop {
+                    let x = rng.gen::<f64>().ln() / r;
+                    let y = rng.gen::<f64>().ln();
+                    if -2.0 * y >= x * x {
+                        return r - x;
+                    }
+                }
+            });
+            assert!(x >= 0.0);
+            sum += x;
+        }
+        // The mean of the half-normal distribution is sqrt(2 / pi)
+        let mean = sum / 10000.0;
+        assert!((mean - 0.7979).abs() < 0.02, "{}", mean);
+    }
+
+    #[test]
+    fn test_try_new() {
+        fn pdf(x: f64) -> f64 { (-x).exp() }
+        fn pdf_inv(y: f64) -> f64 { -y.ln() }
+        let (r, v) = (7.69711747013104972, 0.0039496598225815571993);
+        assert!(ZigguratTables::try_new(r, v, pdf, pdf_inv).is_ok());
+        assert_eq!(ZigguratTables::try_new(0.0, v, pdf, pdf_inv).unwrap_err(),
+                   ParamError::NotPositive("r"));
+        assert_eq!(ZigguratTables::try_new(r, ::std::f64::NAN, pdf, pdf_inv).unwrap_err(),
+                   ParamError::NotPositive("v"));
+        // Layers this large overshoot `pdf(0)`
+        assert_eq!(ZigguratTables::try_new(r, 0.1, pdf, pdf_inv).unwrap_err(),
+                   ParamError::OutOfRange("v"));
+    }
+}
@@ -11,7 +11,6 @@
 // Tables for distributions which are sampled using the ziggurat
 // algorithm. Autogenerated by `ziggurat_tables.py`.
 
-pub type ZigTable = &'static [f64; 257];
 pub const ZIG_NORM_R: f64 = 3.654152885361008796;
 pub static ZIG_NORM_X: [f64; 257] =
     [3.910757959537090045, 3.654152885361008796, 3.449278298560964462, 3.320244733839166074,
@@ -278,3 +277,267 @@ pub static ZIG_EXP_F: [f64; 257] =
      0.775956852040116218, 0.791527636972496285, 0.808421651523009044, 0.826993296643051101,
      0.847785500623990496, 0.871704332381204705, 0.900469929925747703, 0.938143680862176477,
      1.000000000000000000];
+pub static ZIG_NORM_X_F32: [f32; 257] =
+    [3.910757960e+00, 3.654152885e+00, 3.449278299e+00, 3.320244734e+00,
+     3.224575052e+00, 3.147889290e+00, 3.083526132e+00, 3.027837792e+00,
+     2.978603280e+00, 2.934366867e+00, 2.894121054e+00, 2.857138731e+00,
+     2.822877397e+00, 2.790921174e+00, 2.760944005e+00, 2.732685359e+00,
+     2.705933656e+00, 2.680514643e+00, 2.656283038e+00, 2.633116394e+00,
+     2.610910518e+00, 2.589575987e+00, 2.569035453e+00, 2.549221550e+00,
+     2.530075232e+00, 2.511544442e+00, 2.493583041e+00, 2.476149940e+00,
+     2.459208374e+00, 2.442725318e+00, 2.426670985e+00, 2.411018414e+00,
+     2.395743120e+00, 2.380822795e+00, 2.366237057e+00, 2.351967227e+00,
+     2.337996149e+00, 2.324308019e+00, 2.310888251e+00, 2.297723349e+00,
+     2.284800803e+00, 2.272108990e+00, 2.259637095e+00, 2.247375033e+00,
+     2.235313385e+00, 2.223443340e+00, 2.211756643e+00, 2.200245547e+00,
+     2.188902772e+00, 2.177721468e+00, 2.166695180e+00, 2.155817820e+00,
+     2.145083634e+00, 2.134487183e+00, 2.124023316e+00, 2.113687151e+00,
+     2.103474056e+00, 2.093379631e+00, 2.083399694e+00, 2.073530264e+00,
+     2.063767548e+00, 2.054107932e+00, 2.044547965e+00, 2.035084354e+00,
+     2.025713948e+00, 2.016433735e+00, 2.007240831e+00, 1.998132471e+00,
+     1.989106008e+00, 1.980158897e+00, 1.971288698e+00, 1.962493065e+00,
+     1.953769742e+00, 1.945116560e+00, 1.936531428e+00, 1.928012334e+00,
+     1.919557337e+00, 1.911164564e+00, 1.902832209e+00, 1.894558526e+00,
+     1.886341829e+00, 1.878180486e+00, 1.870072921e+00, 1.862017605e+00,
+     1.854013060e+00, 1.846057850e+00, 1.838150587e+00, 1.830289920e+00,
+     1.822474540e+00, 1.814703176e+00, 1.806974591e+00, 1.799287585e+00,
+     1.791640987e+00, 1.784033660e+00, 1.776464496e+00, 1.768932415e+00,
+     1.761436365e+00, 1.753975320e+00, 1.746548278e+00, 1.739154261e+00,
+     1.731792314e+00, 1.724461503e+00, 1.717160915e+00, 1.709889657e+00,
+     1.702646855e+00, 1.695431652e+00, 1.688243209e+00, 1.681080705e+00,
+     1.673943331e+00, 1.666830296e+00, 1.659740823e+00, 1.652674147e+00,
+     1.645629518e+00, 1.638606197e+00, 1.631603457e+00, 1.624620583e+00,
+     1.617656870e+00, 1.610711622e+00, 1.603784156e+00, 1.596873794e+00,
+     1.589979870e+00, 1.583101723e+00, 1.576238703e+00, 1.569390163e+00,
+     1.562555468e+00, 1.555733983e+00, 1.548925085e+00, 1.542128153e+00,
+     1.535342571e+00, 1.528567729e+00, 1.521803021e+00, 1.515047843e+00,
+     1.508301596e+00, 1.501563685e+00, 1.494833516e+00, 1.488110497e+00,
+     1.481394040e+00, 1.474683556e+00, 1.467978459e+00, 1.461278163e+00,
+     1.454582082e+00, 1.447889631e+00, 1.441200225e+00, 1.434513276e+00,
+     1.427828197e+00, 1.421144399e+00, 1.414461290e+00, 1.407778277e+00,
+     1.401094764e+00, 1.394410151e+00, 1.387723836e+00, 1.381035211e+00,
+     1.374343666e+00, 1.367648584e+00, 1.360949343e+00, 1.354245317e+00,
+     1.347535871e+00, 1.340820366e+00, 1.334098153e+00, 1.327368578e+00,
+     1.320630975e+00, 1.313884673e+00, 1.307128989e+00, 1.300363230e+00,
+     1.293586694e+00, 1.286798664e+00, 1.279998416e+00, 1.273185208e+00,
+     1.266358287e+00, 1.259516886e+00, 1.252660222e+00, 1.245787496e+00,
+     1.238897891e+00, 1.231990575e+00, 1.225064694e+00, 1.218119375e+00,
+     1.211153726e+00, 1.204166830e+00, 1.197157748e+00, 1.190125515e+00,
+     1.183069143e+00, 1.175987612e+00, 1.168879877e+00, 1.161744859e+00,
+     1.154581450e+00, 1.147388505e+00, 1.140164844e+00, 1.132909249e+00,
+     1.125620459e+00, 1.118297174e+00, 1.110938046e+00, 1.103541679e+00,
+     1.096106628e+00, 1.088631391e+00, 1.081114410e+00, 1.073554066e+00,
+     1.065948675e+00, 1.058296483e+00, 1.050595665e+00, 1.042844313e+00,
+     1.035040440e+00, 1.027181966e+00, 1.019266717e+00, 1.011292417e+00,
+     1.003256680e+00, 9.951569996e-01, 9.869907471e-01, 9.787551553e-01,
+     9.704473111e-01, 9.620641432e-01, 9.536024099e-01, 9.450586845e-01,
+     9.364293403e-01, 9.277105334e-01, 9.188981836e-01, 9.099879535e-01,
+     9.009752245e-01, 8.918550707e-01, 8.826222296e-01, 8.732710681e-01,
+     8.637955455e-01, 8.541891710e-01, 8.444449549e-01, 8.345553541e-01,
+     8.245122087e-01, 8.143066701e-01, 8.039291170e-01, 7.933690588e-01,
+     7.826150233e-01, 7.716544242e-01, 7.604734064e-01, 7.490566620e-01,
+     7.373872114e-01, 7.254461409e-01, 7.132122852e-01, 7.006618411e-01,
+     6.877678928e-01, 6.744998228e-01, 6.608225742e-01, 6.466957149e-01,
+     6.320722364e-01, 6.168969900e-01, 6.011046177e-01, 5.846167661e-01,
+     5.673382570e-01, 5.491517023e-01, 5.299097206e-01, 5.094233296e-01,
+     4.874439661e-01, 4.636343368e-01, 4.375184022e-01, 4.083891346e-01,
+     3.751213329e-01, 3.357375192e-01, 2.861745917e-01, 2.152418959e-01,
+     0.000000000e+00];
+pub static ZIG_NORM_F_F32: [f32; 257] =
+    [4.774677646e-04, 1.260285930e-03, 2.609072746e-03, 4.037972593e-03,
+     5.522403299e-03, 7.050875471e-03, 8.616582769e-03, 1.021497144e-02,
+     1.184275786e-02, 1.349745060e-02, 1.517708831e-02, 1.688008315e-02,
+     1.860512128e-02, 2.035109623e-02, 2.211706271e-02, 2.390220331e-02,
+     2.570580401e-02, 2.752723567e-02, 2.936593976e-02, 3.122141719e-02,
+     3.309321946e-02, 3.498094146e-02, 3.688421569e-02, 3.880270740e-02,
+     4.073611066e-02, 4.268414492e-02, 4.464655225e-02, 4.662309490e-02,
+     4.861355322e-02, 5.061772386e-02, 5.263541828e-02, 5.466646133e-02,
+     5.671069011e-02, 5.876795292e-02, 6.083810835e-02, 6.292102444e-02,
+     6.501657797e-02, 6.712465383e-02, 6.924514440e-02, 7.137794906e-02,
+     7.352297371e-02, 7.568013036e-02, 7.784933670e-02, 8.003051581e-02,
+     8.222359581e-02, 8.442850957e-02, 8.664519445e-02, 8.887359207e-02,
+     9.111364807e-02, 9.336531191e-02, 9.562853671e-02, 9.790327904e-02,
+     1.001894988e-01, 1.024871589e-01, 1.047962256e-01, 1.071166678e-01,
+     1.094484571e-01, 1.117915682e-01, 1.141459778e-01, 1.165116656e-01,
+     1.188886134e-01, 1.212768055e-01, 1.236762282e-01, 1.260868702e-01,
+     1.285087223e-01, 1.309417772e-01, 1.333860297e-01, 1.358414766e-01,
+     1.383081164e-01, 1.407859498e-01, 1.432749790e-01, 1.457752080e-01,
+     1.482866427e-01, 1.508092907e-01, 1.533431611e-01, 1.558882647e-01,
+     1.584446142e-01, 1.610122234e-01, 1.635911082e-01, 1.661812858e-01,
+     1.687827748e-01, 1.713955956e-01, 1.740197701e-01, 1.766553214e-01,
+     1.793022745e-01, 1.819606556e-01, 1.846304924e-01, 1.873118142e-01,
+     1.900046517e-01, 1.927090369e-01, 1.954250035e-01, 1.981525865e-01,
+     2.008918225e-01, 2.036427493e-01, 2.064054064e-01, 2.091798346e-01,
+     2.119660763e-01, 2.147641753e-01, 2.175741767e-01, 2.203961275e-01,
+     2.232300758e-01, 2.260760713e-01, 2.289341654e-01, 2.318044108e-01,
+     2.346868619e-01, 2.375815744e-01, 2.404886059e-01, 2.434080154e-01,
+     2.463398635e-01, 2.492842124e-01, 2.522411261e-01, 2.552106700e-01,
+     2.581929113e-01, 2.611879191e-01, 2.641957640e-01, 2.672165183e-01,
+     2.702502564e-01, 2.732970541e-01, 2.763569893e-01, 2.794301418e-01,
+     2.825165931e-01, 2.856164268e-01, 2.887297285e-01, 2.918565856e-01,
+     2.949970878e-01, 2.981513267e-01, 3.013193961e-01, 3.045013920e-01,
+     3.076974125e-01, 3.109075581e-01, 3.141319316e-01, 3.173706380e-01,
+     3.206237850e-01, 3.238914824e-01, 3.271738428e-01, 3.304709814e-01,
+     3.337830158e-01, 3.371100666e-01, 3.404522570e-01, 3.438097131e-01,
+     3.471825640e-01, 3.505709415e-01, 3.539749808e-01, 3.573948201e-01,
+     3.608306010e-01, 3.642824681e-01, 3.677505698e-01, 3.712350577e-01,
+     3.747360871e-01, 3.782538172e-01, 3.817884109e-01, 3.853400348e-01,
+     3.889088600e-01, 3.924950615e-01, 3.960988185e-01, 3.997203150e-01,
+     4.033597392e-01, 4.070172843e-01, 4.106931483e-01, 4.143875340e-01,
+     4.181006498e-01, 4.218327092e-01, 4.255839313e-01, 4.293545410e-01,
+     4.331447691e-01, 4.369548525e-01, 4.407850347e-01, 4.446355654e-01,
+     4.485067015e-01, 4.523987069e-01, 4.563118527e-01, 4.602464178e-01,
+     4.642026891e-01, 4.681809614e-01, 4.721815385e-01, 4.762047327e-01,
+     4.802508659e-01, 4.843202694e-01, 4.884132847e-01, 4.925302636e-01,
+     4.966715691e-01, 5.008375751e-01, 5.050286679e-01, 5.092452460e-01,
+     5.134877207e-01, 5.177565172e-01, 5.220520747e-01, 5.263748472e-01,
+     5.307253044e-01, 5.351039324e-01, 5.395112343e-01, 5.439477312e-01,
+     5.484139633e-01, 5.529104904e-01, 5.574378936e-01, 5.619967758e-01,
+     5.665877633e-01, 5.712115067e-01, 5.758686830e-01, 5.805599961e-01,
+     5.852861793e-01, 5.900479963e-01, 5.948462438e-01, 5.996817526e-01,
+     6.045553907e-01, 6.094680649e-01, 6.144207239e-01, 6.194143606e-01,
+     6.244500156e-01, 6.295287799e-01, 6.346517993e-01, 6.398202775e-01,
+     6.450354808e-01, 6.502987431e-01, 6.556114706e-01, 6.609751478e-01,
+     6.663913439e-01, 6.718617199e-01, 6.773880362e-01, 6.829721616e-01,
+     6.886160830e-01, 6.943219161e-01, 7.000919181e-01, 7.059285013e-01,
+     7.118342489e-01, 7.178119326e-01, 7.238645335e-01, 7.299952646e-01,
+     7.362075981e-01, 7.425052963e-01, 7.488924472e-01, 7.553735065e-01,
+     7.619533468e-01, 7.686373158e-01, 7.754313050e-01, 7.823418327e-01,
+     7.893761436e-01, 7.965423304e-01, 8.038494832e-01, 8.113078743e-01,
+     8.189291916e-01, 8.267268340e-01, 8.347162930e-01, 8.429156531e-01,
+     8.513462585e-01, 8.600336212e-01, 8.690086880e-01, 8.783096558e-01,
+     8.879846608e-01, 8.980959219e-01, 9.087264401e-01, 9.199915050e-01,
+     9.320600760e-01, 9.451989535e-01, 9.598790918e-01, 9.771017013e-01,
+     1.000000000e+00];
+pub static ZIG_EXP_X_F32: [f32; 257] =
+    [8.697117470e+00, 7.697117470e+00, 6.941033629e+00, 6.478378494e+00,
+     6.144164666e+00, 5.882144316e+00, 5.666410167e+00, 5.482890628e+00,
+     5.323090506e+00, 5.181487281e+00, 5.054288490e+00, 4.938777086e+00,
+     4.832939741e+00, 4.735242997e+00, 4.644491885e+00, 4.559737062e+00,
+     4.480211747e+00, 4.405287693e+00, 4.334443680e+00, 4.267242480e+00,
+     4.203313714e+00, 4.142340866e+00, 4.084051310e+00, 4.028208545e+00,
+     3.974606067e+00, 3.923062500e+00, 3.873417670e+00, 3.825529419e+00,
+     3.779270992e+00, 3.734528894e+00, 3.691201090e+00, 3.649195516e+00,
+     3.608428813e+00, 3.568825266e+00, 3.530315889e+00, 3.492837655e+00,
+     3.456332821e+00, 3.420748357e+00, 3.386035442e+00, 3.352149031e+00,
+     3.319047471e+00, 3.286692172e+00, 3.255047309e+00, 3.224079565e+00,
+     3.193757903e+00, 3.164053358e+00, 3.134938858e+00, 3.106389062e+00,
+     3.078380215e+00, 3.050890017e+00, 3.023897504e+00, 2.997382950e+00,
+     2.971327760e+00, 2.945714395e+00, 2.920526287e+00, 2.895747769e+00,
+     2.871364012e+00, 2.847360966e+00, 2.823725302e+00, 2.800444370e+00,
+     2.777506146e+00, 2.754899197e+00, 2.732612636e+00, 2.710636096e+00,
+     2.688959689e+00, 2.667573981e+00, 2.646469963e+00, 2.625639027e+00,
+     2.605072939e+00, 2.584763820e+00, 2.564704126e+00, 2.544886627e+00,
+     2.525304390e+00, 2.505950764e+00, 2.486819362e+00, 2.467904050e+00,
+     2.449198933e+00, 2.430698339e+00, 2.412396813e+00, 2.394289100e+00,
+     2.376370141e+00, 2.358635057e+00, 2.341079148e+00, 2.323697874e+00,
+     2.306486858e+00, 2.289441871e+00, 2.272558826e+00, 2.255833774e+00,
+     2.239262898e+00, 2.222842503e+00, 2.206569013e+00, 2.190438967e+00,
+     2.174449010e+00, 2.158595893e+00, 2.142876465e+00, 2.127287671e+00,
+     2.111826546e+00, 2.096490212e+00, 2.081275874e+00, 2.066180819e+00,
+     2.051202409e+00, 2.036338080e+00, 2.021585338e+00, 2.006941758e+00,
+     1.992404978e+00, 1.977972701e+00, 1.963642688e+00, 1.949412758e+00,
+     1.935280786e+00, 1.921244701e+00, 1.907302480e+00, 1.893452153e+00,
+     1.879691795e+00, 1.866019528e+00, 1.852433516e+00, 1.838931967e+00,
+     1.825513129e+00, 1.812175289e+00, 1.798916770e+00, 1.785735935e+00,
+     1.772631179e+00, 1.759600931e+00, 1.746643652e+00, 1.733757835e+00,
+     1.720942003e+00, 1.708194706e+00, 1.695514524e+00, 1.682900063e+00,
+     1.670349954e+00, 1.657862853e+00, 1.645437439e+00, 1.633072417e+00,
+     1.620766509e+00, 1.608518462e+00, 1.596327041e+00, 1.584191033e+00,
+     1.572109239e+00, 1.560080484e+00, 1.548103604e+00, 1.536177455e+00,
+     1.524300908e+00, 1.512472849e+00, 1.500692177e+00, 1.488957806e+00,
+     1.477268661e+00, 1.465623682e+00, 1.454021819e+00, 1.442462032e+00,
+     1.430943293e+00, 1.419464583e+00, 1.408024892e+00, 1.396623218e+00,
+     1.385258568e+00, 1.373929956e+00, 1.362636403e+00, 1.351376933e+00,
+     1.340150581e+00, 1.328956381e+00, 1.317793376e+00, 1.306660610e+00,
+     1.295557132e+00, 1.284481990e+00, 1.273434238e+00, 1.262412929e+00,
+     1.251417116e+00, 1.240445854e+00, 1.229498196e+00, 1.218573192e+00,
+     1.207669893e+00, 1.196787346e+00, 1.185924593e+00, 1.175080674e+00,
+     1.164254623e+00, 1.153445467e+00, 1.142652228e+00, 1.131873919e+00,
+     1.121109548e+00, 1.110358109e+00, 1.099618589e+00, 1.088889962e+00,
+     1.078171192e+00, 1.067461226e+00, 1.056759002e+00, 1.046063436e+00,
+     1.035373432e+00, 1.024687873e+00, 1.014005624e+00, 1.003325528e+00,
+     9.926464055e-01, 9.819670531e-01, 9.712862410e-01, 9.606027117e-01,
+     9.499151778e-01, 9.392223200e-01, 9.285227847e-01, 9.178151821e-01,
+     9.070980827e-01, 8.963700156e-01, 8.856294648e-01, 8.748748663e-01,
+     8.641046048e-01, 8.533170098e-01, 8.425103518e-01, 8.316828377e-01,
+     8.208326066e-01, 8.099577241e-01, 7.990561774e-01, 7.881258689e-01,
+     7.771646098e-01, 7.661701127e-01, 7.551399842e-01, 7.440717155e-01,
+     7.329626736e-01, 7.218100903e-01, 7.106110509e-01, 6.993624811e-01,
+     6.880611328e-01, 6.767035680e-01, 6.652861414e-01, 6.538049798e-01,
+     6.422559604e-01, 6.306346849e-01, 6.189364514e-01, 6.071562216e-01,
+     5.952885843e-01, 5.833277127e-01, 5.712673165e-01, 5.591005855e-01,
+     5.468201252e-01, 5.344178812e-01, 5.218850516e-01, 5.092119824e-01,
+     4.963880455e-01, 4.834014917e-01, 4.702392751e-01, 4.568868409e-01,
+     4.433278661e-01, 4.295439402e-01, 4.155141696e-01, 4.012146789e-01,
+     3.866179779e-01, 3.716921453e-01, 3.563997603e-01, 3.406964811e-01,
+     3.245291170e-01, 3.078329547e-01, 2.905279555e-01, 2.725131855e-01,
+     2.536583634e-01, 2.337904831e-01, 2.126715106e-01, 1.899586896e-01,
+     1.651276226e-01, 1.373049809e-01, 1.048385076e-01, 6.385216382e-02,
+     0.000000000e+00];
+pub static ZIG_EXP_F_F32: [f32; 257] =
+    [1.670666923e-04, 4.541343538e-04, 9.672692823e-04, 1.536299780e-03,
+     2.145967744e-03, 2.788798794e-03, 3.460264778e-03, 4.157295121e-03,
+     4.877655984e-03, 5.619642207e-03, 6.381905937e-03, 7.163353184e-03,
+     7.963077438e-03, 8.780314986e-03, 9.614413643e-03, 1.046481018e-02,
+     1.133101360e-02, 1.221259243e-02, 1.310916493e-02, 1.402039140e-02,
+     1.494596801e-02, 1.588562184e-02, 1.683910683e-02, 1.780620041e-02,
+     1.878670074e-02, 1.978042434e-02, 2.078720407e-02, 2.180688750e-02,
+     2.283933541e-02, 2.388442051e-02, 2.494202642e-02, 2.601204665e-02,
+     2.709438378e-02, 2.818894876e-02, 2.929566022e-02, 3.041444391e-02,
+     3.154523217e-02, 3.268796351e-02, 3.384258215e-02, 3.500903770e-02,
+     3.618728478e-02, 3.737728277e-02, 3.857899550e-02, 3.979239102e-02,
+     4.101744138e-02, 4.225412241e-02, 4.350241357e-02, 4.476229773e-02,
+     4.603376108e-02, 4.731679291e-02, 4.861138557e-02, 4.991753428e-02,
+     5.123523706e-02, 5.256449459e-02, 5.390531020e-02, 5.525768968e-02,
+     5.662164128e-02, 5.799717563e-02, 5.938430563e-02, 6.078304645e-02,
+     6.219341541e-02, 6.361543200e-02, 6.504911779e-02, 6.649449639e-02,
+     6.795159342e-02, 6.942043650e-02, 7.090105516e-02, 7.239348088e-02,
+     7.389774699e-02, 7.541388873e-02, 7.694194317e-02, 7.848194920e-02,
+     8.003394754e-02, 8.159798071e-02, 8.317409301e-02, 8.476233053e-02,
+     8.636274114e-02, 8.797537447e-02, 8.960028191e-02, 9.123751663e-02,
+     9.288713356e-02, 9.454918938e-02, 9.622374255e-02, 9.791085331e-02,
+     9.961058367e-02, 1.013229974e-01, 1.030481602e-01, 1.047861393e-01,
+     1.065370041e-01, 1.083008255e-01, 1.100776764e-01, 1.118676317e-01,
+     1.136707679e-01, 1.154871636e-01, 1.173168992e-01, 1.191600572e-01,
+     1.210167218e-01, 1.228869795e-01, 1.247709186e-01, 1.266686294e-01,
+     1.285802045e-01, 1.305057385e-01, 1.324453279e-01, 1.343990717e-01,
+     1.363670709e-01, 1.383494289e-01, 1.403462511e-01, 1.423576454e-01,
+     1.443837222e-01, 1.464245939e-01, 1.484803756e-01, 1.505511850e-01,
+     1.526371420e-01, 1.547383694e-01, 1.568549924e-01, 1.589871390e-01,
+     1.611349399e-01, 1.632985288e-01, 1.654780419e-01, 1.676736186e-01,
+     1.698854013e-01, 1.721135353e-01, 1.743581692e-01, 1.766194546e-01,
+     1.788975466e-01, 1.811926035e-01, 1.835047871e-01, 1.858342628e-01,
+     1.881811994e-01, 1.905457697e-01, 1.929281500e-01, 1.953285207e-01,
+     1.977470661e-01, 2.001839747e-01, 2.026394391e-01, 2.051136563e-01,
+     2.076068277e-01, 2.101191594e-01, 2.126508620e-01, 2.152021511e-01,
+     2.177732471e-01, 2.203643758e-01, 2.229757681e-01, 2.256076601e-01,
+     2.282602939e-01, 2.309339172e-01, 2.336287834e-01, 2.363451525e-01,
+     2.390832903e-01, 2.418434694e-01, 2.446259691e-01, 2.474310757e-01,
+     2.502590824e-01, 2.531102900e-01, 2.559850070e-01, 2.588835497e-01,
+     2.618062427e-01, 2.647534188e-01, 2.677254199e-01, 2.707225968e-01,
+     2.737453097e-01, 2.767939284e-01, 2.798688332e-01, 2.829704145e-01,
+     2.860990737e-01, 2.892552235e-01, 2.924392882e-01, 2.956517043e-01,
+     2.988929210e-01, 3.021634007e-01, 3.054636192e-01, 3.087940669e-01,
+     3.121552488e-01, 3.155476852e-01, 3.189719128e-01, 3.224284850e-01,
+     3.259179724e-01, 3.294409643e-01, 3.329980688e-01, 3.365899140e-01,
+     3.402171491e-01, 3.438804447e-01, 3.475804946e-01, 3.513180164e-01,
+     3.550937529e-01, 3.589084729e-01, 3.627629734e-01, 3.666580798e-01,
+     3.705946484e-01, 3.745735676e-01, 3.785957594e-01, 3.826621815e-01,
+     3.867738291e-01, 3.909317370e-01, 3.951369818e-01, 3.993906845e-01,
+     4.036940125e-01, 4.080481832e-01, 4.124544660e-01, 4.169141864e-01,
+     4.214287290e-01, 4.259995411e-01, 4.306281373e-01, 4.353161032e-01,
+     4.400651008e-01, 4.448768734e-01, 4.497532512e-01, 4.546961575e-01,
+     4.597076156e-01, 4.647897563e-01, 4.699448253e-01, 4.751751930e-01,
+     4.804833639e-01, 4.858719873e-01, 4.913438696e-01, 4.969019872e-01,
+     5.025495018e-01, 5.082897764e-01, 5.141263938e-01, 5.200631774e-01,
+     5.261042140e-01, 5.322538803e-01, 5.385168720e-01, 5.448982377e-01,
+     5.514034165e-01, 5.580382823e-01, 5.648091929e-01, 5.717230487e-01,
+     5.787873586e-01, 5.860103185e-01, 5.934009017e-01, 6.009689664e-01,
+     6.087253821e-01, 6.166821809e-01, 6.248527387e-01, 6.332519942e-01,
+     6.418967164e-01, 6.508058334e-01, 6.600008411e-01, 6.695063167e-01,
+     6.793505723e-01, 6.895664961e-01, 7.001926551e-01, 7.112747608e-01,
+     7.228676596e-01, 7.350380924e-01, 7.478686220e-01, 7.614633888e-01,
+     7.759568520e-01, 7.915276370e-01, 8.084216515e-01, 8.269932966e-01,
+     8.477855006e-01, 8.717043324e-01, 9.004699299e-01, 9.381436809e-01,
+     1.000000000e+00];
@@ -10,7 +10,7 @@
 
 //! Entropy generator, or wrapper around external generators
 
-use rand_core::{RngCore, Error, impls};
+use rand_core::{CryptoRng, RngCore, Error, impls};
 use {OsRng, JitterRng};
 
 /// An RNG provided specifically for seeding PRNGs.
@@ -51,6 +51,12 @@ impl EntropyRng {
     }
 }
 
+impl Default for EntropyRng {
+    fn default() -> Self {
+        EntropyRng::new()
+    }
+}
+
 impl RngCore for EntropyRng {
     fn next_u32(&mut self) -> u32 {
         impls::next_u32_via_fill(self)
@@ -140,6 +146,8 @@ impl RngCore for EntropyRng {
     }
 }
 
+impl CryptoRng for EntropyRng {}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,219 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A C interface to a seedable generator.
+//!
+//! This module exports `extern "C"` functions operating on an opaque handle
+//! to a [`ChaChaRng`] (ChaCha with 20 rounds): its output for a given seed is
+//! reproducible across platforms and versions of this library, and the same
+//! as that of `ChaChaRng` used from Rust. Handles are not thread-safe; each
+//! must be used from one thread at a time.
+//!
+//! The functions are only exported from a binary that links this crate, e.g.
+//! a `staticlib` or `cdylib` crate depending on `rand` with the `ffi`
+//! feature. The corresponding C declarations are:
+//!
+//! ```c
+//! #include <stdbool.h>
+//! #include <stddef.h>
+//! #include <stdint.h>
+//!
+//! typedef struct RandRng RandRng;
+//!
+//! /* Returns NULL if `seed` is NULL. `seed` must point to 32 bytes. */
+//! RandRng *rand_rng_new(const uint8_t *seed);
+//! /* Seeded from `EntropyRng`; returns NULL on failure. */
+//! RandRng *rand_rng_new_from_entropy(void);
+//! /* Returns false, leaving `rng` unchanged, if `seed` is NULL. */
+//! bool rand_rng_seed(RandRng *rng, const uint8_t *seed);
+//! uint32_t rand_rng_next_u32(RandRng *rng);
+//! uint64_t rand_rng_next_u64(RandRng *rng);
+//! /* A value in [0, 1), with 53 bits of precision. */
+//! double rand_rng_next_f64(RandRng *rng);
+//! void rand_rng_fill_bytes(RandRng *rng, uint8_t *dest, size_t len);
+//! void rand_rng_free(RandRng *rng);
+//! ```
+//!
+//! All functions taking a `RandRng *` require a valid handle returned by
+//! `rand_rng_new` or `rand_rng_new_from_entropy` and not yet freed, except
+//! that `rand_rng_free` accepts `NULL`.
+//!
+//! [`ChaChaRng`]: ../chacha/struct.ChaChaRng.html
+
+use std::ptr;
+use std::slice;
+
+use {RngCore, SeedableRng, EntropyRng, ChaChaRng};
+
+/// The opaque generator behind a handle of the C interface.
+#[derive(Debug)]
+pub struct RandRng {
+    rng: ChaChaRng,
+}
+
+unsafe fn read_seed(seed: *const u8) -> [u8; 32] {
+    let mut buf = [0u8; 32];
+    buf.copy_from_slice(slice::from_raw_parts(seed, 32));
+    buf
+}
+
+/// Create a generator from the 32-byte `seed`.
+///
+/// Returns null if `seed` is null.
+///
+/// # Safety
+///
+/// A non-null `seed` must point to 32 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_rng_new(seed: *const u8) -> *mut RandRng {
+    if seed.is_null() {
+        return ptr::null_mut();
+    }
+    let rng = ChaChaRng::from_seed(read_seed(seed));
+    Box::into_raw(Box::new(RandRng { rng: rng }))
+}
+
+/// Create a generator seeded from `EntropyRng`.
+///
+/// Returns null if no entropy is available.
+#[no_mangle]
+pub extern "C" fn rand_rng_new_from_entropy() -> *mut RandRng {
+    match ChaChaRng::from_rng(&mut EntropyRng::new()) {
+        Ok(rng) => Box::into_raw(Box::new(RandRng { rng: rng })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Reseed the generator from the 32-byte `seed`, as if created by
+/// `rand_rng_new`.
+///
+/// Returns false, and leaves the generator unchanged, if `seed` is null.
+///
+/// # Safety
+///
+/// `rng` must be a valid handle, as described in the module documentation.
+/// A non-null `seed` must point to 32 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_rng_seed(rng: *mut RandRng, seed: *const u8)
+    -> bool
+{
+    if seed.is_null() {
+        return false;
+    }
+    (*rng).rng = ChaChaRng::from_seed(read_seed(seed));
+    true
+}
+
+/// Return the next random `u32`.
+///
+/// # Safety
+///
+/// `rng` must be a valid handle, as described in the module documentation.
+#[no_mangle]
+pub unsafe extern "C" fn rand_rng_next_u32(rng: *mut RandRng) -> u32 {
+    (*rng).rng.next_u32()
+}
+
+/// Return the next random `u64`.
+///
+/// # Safety
+///
+/// `rng` must be a valid handle, as described in the module documentation.
+#[no_mangle]
+pub unsafe extern "C" fn rand_rng_next_u64(rng: *mut RandRng) -> u64 {
+    (*rng).rng.next_u64()
+}
+
+/// Return a random `f64` in `[0, 1)`, with 53 bits of precision.
+///
+/// This is the upper 53 bits of `next_u64` scaled by 2<sup>-53</sup>, as is
+/// usual in C. Note that it differs from `rng.gen::<f64>()`, which samples
+/// the open interval `(0, 1)` with 52 bits of precision.
+///
+/// # Safety
+///
+/// `rng` must be a valid handle, as described in the module documentation.
+#[no_mangle]
+pub unsafe extern "C" fn rand_rng_next_f64(rng: *mut RandRng) -> f64 {
+    const SCALE: f64 = 1.0 / (1u64 << 53) as f64;
+    ((*rng).rng.next_u64() >> 11) as f64 * SCALE
+}
+
+/// Fill the `len` bytes at `dest` with random data.
+///
+/// # Safety
+///
+/// `rng` must be a valid handle, as described in the module documentation.
+/// `dest` must point to `len` writable bytes, unless `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn rand_rng_fill_bytes(rng: *mut RandRng, dest: *mut u8,
+                                             len: usize) {
+    if len > 0 {
+        (*rng).rng.fill_bytes(slice::from_raw_parts_mut(dest, len))
+    }
+}
+
+/// Destroy a generator. Does nothing if `rng` is null.
+///
+/// # Safety
+///
+/// A non-null `rng` must be a valid handle, which must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rand_rng_free(rng: *mut RandRng) {
+    if !rng.is_null() {
+        drop(Box::from_raw(rng));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ptr;
+
+    use {RngCore, SeedableRng, ChaChaRng};
+    use super::*;
+
+    #[test]
+    fn test_ffi_matches_chacha() {
+        let seed = [7u8; 32];
+        let mut reference = ChaChaRng::from_seed(seed);
+        unsafe {
+            let rng = rand_rng_new(seed.as_ptr());
+            assert!(!rng.is_null());
+            assert_eq!(rand_rng_next_u32(rng), reference.next_u32());
+            assert_eq!(rand_rng_next_u64(rng), reference.next_u64());
+            let x = rand_rng_next_f64(rng);
+            assert_eq!(x, (reference.next_u64() >> 11) as f64 / (1u64 << 53) as f64);
+            assert!(0.0 <= x && x < 1.0);
+            let mut buf = [0u8; 37];
+            let mut expected = [0u8; 37];
+            rand_rng_fill_bytes(rng, buf.as_mut_ptr(), buf.len());
+            reference.fill_bytes(&mut expected);
+            assert_eq!(&buf[..], &expected[..]);
+            rand_rng_fill_bytes(rng, ptr::null_mut(), 0);
+
+            assert!(rand_rng_seed(rng, seed.as_ptr()));
+            assert_eq!(rand_rng_next_u64(rng),
+                       ChaChaRng::from_seed(seed).next_u64());
+            assert!(!rand_rng_seed(rng, ptr::null()));
+            let mut reference = ChaChaRng::from_seed(seed);
+            reference.next_u64();
+            assert_eq!(rand_rng_next_u64(rng), reference.next_u64());
+            rand_rng_free(rng);
+
+            assert!(rand_rng_new(ptr::null()).is_null());
+            rand_rng_free(ptr::null_mut());
+
+            let rng = rand_rng_new_from_entropy();
+            assert!(!rng.is_null());
+            rand_rng_free(rng);
+        }
+    }
+}
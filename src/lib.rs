@@ -35,6 +35,19 @@
 //! extern crate rand;
 //! ```
 //!
+//! # `no_std` support
+//!
+//! The `std` feature is enabled by default. Without it, the crate builds with
+//! `#![no_std]`: the core traits, all PRNGs and the distributions not needing
+//! `exp` or `ln` remain available, but `OsRng`, `thread_rng` and `EntropyRng`
+//! do not. Enabling the `alloc` feature as well restores the functions
+//! returning a `Vec` or `Box`, such as most of the [`seq`] module.
+//!
+//! ```toml
+//! [dependencies]
+//! rand = { version = "0.5", default-features = false, features = ["alloc"] }
+//! ```
+//!
 //! # Thread-local RNG
 //!
 //! There is built-in support for a RNG associated with each thread stored
@@ -234,6 +247,7 @@
 //! ```
 //!
 //! [`Uniform`]: distributions/struct.Uniform.html
+//! [`seq`]: seq/index.html
 
 #![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk.png",
        html_favicon_url = "https://www.rust-lang.org/favicon.ico",
@@ -242,12 +256,11 @@
 #![deny(missing_debug_implementations)]
 
 #![cfg_attr(not(feature="std"), no_std)]
-#![cfg_attr(all(feature="alloc", not(feature="std")), feature(alloc))]
-#![cfg_attr(feature = "i128_support", feature(i128_type, i128))]
+#![cfg_attr(feature="simd_support", feature(portable_simd))]
 #![cfg_attr(all(target_arch = "wasm32", not(target_os = "emscripten")), recursion_limit="128")]
 
 #[cfg(feature="std")] extern crate std as core;
-#[cfg(all(feature = "alloc", not(feature="std")))] extern crate alloc;
+#[cfg(all(feature = "alloc", not(feature="std")))] #[macro_use] extern crate alloc;
 
 #[cfg(test)] #[cfg(feature="serde-1")] extern crate bincode;
 #[cfg(feature="serde-1")] extern crate serde;
@@ -258,6 +271,11 @@
 extern crate stdweb;
 
 extern crate rand_core;
+#[cfg(all(feature="rayon", feature="std"))] extern crate rayon;
+#[cfg(all(feature="ndarray", feature="std"))] extern crate ndarray;
+#[cfg(feature="chrono")] extern crate chrono;
+#[cfg(feature="time")] extern crate time;
+#[cfg(feature="f16_support")] extern crate half;
 
 #[cfg(feature = "log")] #[macro_use] extern crate log;
 #[cfg(not(feature = "log"))] macro_rules! trace { ($($x:tt)*) => () }
@@ -268,6 +286,7 @@ extern crate rand_core;
 
 
 use core::{marker, mem, slice};
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
 
 // re-exports from rand-core
 pub use rand_core::{RngCore, CryptoRng, SeedableRng};
@@ -281,24 +300,46 @@ pub use jitter::JitterRng;
 pub use isaac::{IsaacRng, Isaac64Rng};
 pub use chacha::ChaChaRng;
 pub use prng::XorShiftRng;
+pub use prng::Xoroshiro128PlusRng;
 pub use prng::Hc128Rng;
+pub use prng::Mt19937Rng;
+pub use prng::{StateSerializable, StateError, STATE_VERSION};
 
 // convenience and derived rngs
 #[cfg(feature="std")] pub use entropy_rng::EntropyRng;
-#[cfg(feature="std")] pub use thread_rng::{ThreadRng, thread_rng};
+#[cfg(feature="std")] pub use thread_rng::{ThreadRng, TaskRng, thread_rng};
+#[cfg(feature="std")] pub use thread_rng::{ThreadRngConfig, ThreadRngAlgorithm,
+                                           ThreadRngConfigError,
+                                           configure_thread_rng};
 #[cfg(feature="std")] #[allow(deprecated)] pub use thread_rng::random;
 
-use distributions::{Distribution, Uniform, Range};
-use distributions::range::SampleRange;
+use distributions::{Distribution, Uniform};
+use distributions::range::GenRange;
 
 // public modules
+#[cfg(all(feature="ndarray", feature="std"))] pub mod array;
+pub mod chain;
+pub mod compat;
 pub mod distributions;
+#[cfg(feature="ffi")] pub mod ffi;
 pub mod jitter;
+pub mod limit;
 pub mod mock;
 #[cfg(feature="std")] pub mod os;
+#[cfg(all(feature="rayon", feature="std"))] pub mod par;
+#[cfg(all(feature="prime", any(feature="std", feature="alloc")))] pub mod prime;
+#[cfg(any(feature="std", feature="alloc"))] pub mod quasi;
+pub mod ratchet;
 #[cfg(feature="std")] pub mod read;
+pub mod record;
 pub mod reseeding;
-#[cfg(any(feature="std", feature = "alloc"))] pub mod seq;
+#[cfg(feature="std")] pub mod retry;
+#[cfg(any(feature="std", feature="alloc"))] pub mod sampling;
+pub mod seq;
+pub mod split;
+pub mod stream;
+#[cfg(all(feature="std", any(test, feature="testing")))] pub mod testing;
+pub mod xor;
 
 // These tiny modules are here to avoid API breakage, probably only temporarily
 pub mod chacha {
@@ -314,6 +355,7 @@ pub mod isaac {
 #[cfg(feature="std")] mod entropy_rng;
 mod prng;
 #[cfg(feature="std")] mod thread_rng;
+mod zeroize;
 
 
 /// A type that can be randomly generated using an `Rng`.
@@ -371,7 +413,9 @@ pub trait Rand : Sized {
 pub trait Rng: RngCore {
     /// Fill `dest` entirely with random bytes (uniform value distribution),
     /// where `dest` is any type supporting [`AsByteSliceMut`], namely slices
-    /// and arrays over primitive integer types (`i8`, `i16`, `u32`, etc.).
+    /// and arrays over primitive integer types (`i8`, `i16`, `u32`, etc.)
+    /// and floating-point types. Floats are filled with values uniformly
+    /// distributed in `[0, 1)`.
     /// 
     /// On big-endian platforms this performs byte-swapping to ensure
     /// portability of results from reproducible generators.
@@ -399,7 +443,9 @@ pub trait Rng: RngCore {
     
     /// Fill `dest` entirely with random bytes (uniform value distribution),
     /// where `dest` is any type supporting [`AsByteSliceMut`], namely slices
-    /// and arrays over primitive integer types (`i8`, `i16`, `u32`, etc.).
+    /// and arrays over primitive integer types (`i8`, `i16`, `u32`, etc.)
+    /// and floating-point types. Floats are filled with values uniformly
+    /// distributed in `[0, 1)`.
     /// 
     /// On big-endian platforms this performs byte-swapping to ensure
     /// portability of results from reproducible generators.
@@ -433,7 +479,56 @@ pub trait Rng: RngCore {
         dest.to_le();
         Ok(())
     }
-    
+
+    /// Return a `Vec` of `len` random bytes.
+    ///
+    /// This is a convenience wrapper around [`fill_bytes`], e.g. for
+    /// generating tokens or nonces whose length is only known at runtime.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::{thread_rng, Rng};
+    ///
+    /// let token = thread_rng().gen_bytes(24);
+    /// assert_eq!(token.len(), 24);
+    /// ```
+    ///
+    /// [`fill_bytes`]: https://docs.rs/rand-core/0.1/rand-core/trait.RngCore.html#method.fill_bytes
+    #[cfg(any(feature="std", feature="alloc"))]
+    fn gen_bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; len];
+        self.fill_bytes(&mut bytes);
+        bytes
+    }
+
+    /// Return an array filled with random data, as by [`fill`].
+    ///
+    /// `A` is any array supporting [`AsByteSliceMut`] and `Default`, i.e.
+    /// arrays of up to 32 primitive integers or floats. Unlike [`gen`] this
+    /// fills the whole array with a single call to [`fill_bytes`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::{thread_rng, Rng};
+    ///
+    /// let mut rng = thread_rng();
+    /// let nonce = rng.gen_array::<[u8; 12]>();
+    /// let key: [u32; 8] = rng.gen_array();
+    /// # let _ = (nonce, key);
+    /// ```
+    ///
+    /// [`fill`]: trait.Rng.html#method.fill
+    /// [`gen`]: trait.Rng.html#method.gen
+    /// [`fill_bytes`]: https://docs.rs/rand-core/0.1/rand-core/trait.RngCore.html#method.fill_bytes
+    /// [`AsByteSliceMut`]: trait.AsByteSliceMut.html
+    fn gen_array<A: AsByteSliceMut + Default>(&mut self) -> A {
+        let mut array = A::default();
+        self.fill(&mut array);
+        array
+    }
+
     /// Sample a new value, using the given distribution.
     /// 
     /// ### Example
@@ -448,6 +543,42 @@ pub trait Rng: RngCore {
     fn sample<T, D: Distribution<T>>(&mut self, distr: D) -> T {
         distr.sample(self)
     }
+
+    /// Create an iterator that generates values using the given distribution.
+    ///
+    /// This takes the RNG by value; call it on `&mut rng` to keep using the
+    /// RNG afterwards. See also [`Distribution::sample_iter`].
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use rand::{thread_rng, Rng};
+    /// use rand::distributions::{Alphanumeric, Range, Uniform};
+    ///
+    /// let mut rng = thread_rng();
+    ///
+    /// // Vec of 16 x f32:
+    /// let v: Vec<f32> = (&mut rng).sample_iter(Uniform).take(16).collect();
+    ///
+    /// // String:
+    /// let s: String = (&mut rng).sample_iter(Alphanumeric).take(7).collect();
+    ///
+    /// // Dice-rolling:
+    /// let die_range = Range::new_inclusive(1, 6);
+    /// let mut roll_die = rng.sample_iter(die_range);
+    /// while roll_die.next().unwrap() != 6 {
+    ///     println!("Not a 6; rolling again!");
+    /// }
+    /// # let _ = (v, s);
+    /// ```
+    ///
+    /// [`Distribution::sample_iter`]: distributions/trait.Distribution.html#method.sample_iter
+    fn sample_iter<T, D: Distribution<T>>(self, distr: D)
+        -> distributions::DistIter<D, Self, T>
+        where Self: Sized
+    {
+        distr.sample_iter(self)
+    }
     
     /// Return a random value supporting the [`Uniform`] distribution.
     /// 
@@ -483,13 +614,17 @@ pub trait Rng: RngCore {
     ///                     .collect::<Vec<(f64, bool)>>());
     /// ```
     #[allow(deprecated)]
-    #[deprecated(since="0.5.0", note="use iter::repeat instead")]
+    #[deprecated(since="0.5.0", note="use Rng::sample_iter(Uniform) instead")]
     fn gen_iter<T>(&mut self) -> Generator<T, &mut Self> where Uniform: Distribution<T> {
         Generator { rng: self, _marker: marker::PhantomData }
     }
 
-    /// Generate a random value in the range [`low`, `high`), i.e. inclusive of
-    /// `low` and exclusive of `high`.
+    /// Generate a random value in the given range.
+    ///
+    /// Accepts both half-open ranges `low..high`, which are inclusive of `low`
+    /// and exclusive of `high`, and closed ranges `low..=high`, which include
+    /// `high` as well. A closed range may extend up to the type's maximum
+    /// value, e.g. `0..=u8::MAX`.
     ///
     /// This is a convenience wrapper around
     /// `distributions::Range`. If this function will be called
@@ -499,7 +634,8 @@ pub trait Rng: RngCore {
     ///
     /// # Panics
     ///
-    /// Panics if `low >= high`.
+    /// Panics if the range is empty, i.e. if `low >= high` for `low..high`
+    /// or `low > high` for `low..=high`.
     ///
     /// # Example
     ///
@@ -507,13 +643,96 @@ pub trait Rng: RngCore {
     /// use rand::{thread_rng, Rng};
     ///
     /// let mut rng = thread_rng();
-    /// let n: u32 = rng.gen_range(0, 10);
+    /// let n: u32 = rng.gen_range(0..10);
     /// println!("{}", n);
-    /// let m: f64 = rng.gen_range(-40.0f64, 1.3e5f64);
+    /// let m: f64 = rng.gen_range(-40.0f64..1.3e5f64);
     /// println!("{}", m);
+    /// let d: u8 = rng.gen_range(1..=6);
+    /// println!("{}", d);
+    /// ```
+    fn gen_range<T, B: GenRange<T>>(&mut self, range: B) -> T {
+        range.sample_single(self)
+    }
+
+    /// Sample a new value using the given distribution, forwarding any RNG
+    /// errors instead of panicking.
+    ///
+    /// This is the fallible counterpart of [`sample`], for use with RNGs
+    /// which may fail, such as `OsRng` and `ReadRng`. All randomness is
+    /// requested via [`try_fill_bytes`]; if any request fails, the first
+    /// error is returned and the sampled value is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rand::Error;
+    /// use rand::{thread_rng, Rng};
+    /// use rand::distributions::Range;
+    ///
+    /// # fn try_inner() -> Result<(), Error> {
+    /// let x: i32 = thread_rng().try_sample(Range::new(10, 15))?;
+    /// # let _ = x;
+    /// # Ok(())
+    /// # }
+    /// # try_inner().unwrap()
+    /// ```
+    ///
+    /// [`sample`]: trait.Rng.html#method.sample
+    /// [`try_fill_bytes`]: https://docs.rs/rand-core/0.1/rand-core/trait.RngCore.html#method.try_fill_bytes
+    fn try_sample<T, D: Distribution<T>>(&mut self, distr: D)
+        -> Result<T, Error>
+    {
+        let mut rng = TryRng::new(self);
+        let value = distr.sample(&mut rng);
+        rng.finish(value)
+    }
+
+    /// Return a random value supporting the [`Uniform`] distribution,
+    /// forwarding any RNG errors instead of panicking.
+    ///
+    /// See [`try_sample`] for details.
+    ///
+    /// [`Uniform`]: struct.Uniform.html
+    /// [`try_sample`]: trait.Rng.html#method.try_sample
+    fn try_gen<T>(&mut self) -> Result<T, Error> where Uniform: Distribution<T> {
+        self.try_sample(Uniform)
+    }
+
+    /// Generate a random value in the given range, returning an error
+    /// instead of panicking if the range is invalid or the RNG fails.
+    ///
+    /// This is otherwise identical to [`gen_range`], and is meant for ranges
+    /// built from untrusted input such as configuration files or requests.
+    /// An empty range, bounds which cannot be compared (e.g. a NaN) and
+    /// floating-point ranges which cannot be sampled (e.g. with an infinite
+    /// bound) give a [`GenRangeError::Range`]. RNG errors are forwarded as a
+    /// [`GenRangeError::Rng`], as in [`try_sample`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::{thread_rng, Rng};
+    /// use rand::distributions::range::{GenRangeError, RangeError};
+    ///
+    /// let mut rng = thread_rng();
+    /// let n = rng.try_gen_range(1..=6u32).unwrap();
+    /// assert!(n >= 1 && n <= 6);
+    /// match rng.try_gen_range(10..10) {
+    ///     Err(GenRangeError::Range(RangeError::EmptyRange)) => {}
+    ///     _ => panic!("10..10 is empty"),
+    /// }
     /// ```
-    fn gen_range<T: PartialOrd + SampleRange>(&mut self, low: T, high: T) -> T {
-        Range::sample_single(low, high, self)
+    ///
+    /// [`gen_range`]: trait.Rng.html#method.gen_range
+    /// [`try_sample`]: trait.Rng.html#method.try_sample
+    /// [`GenRangeError::Range`]: distributions/range/enum.GenRangeError.html#variant.Range
+    /// [`GenRangeError::Rng`]: distributions/range/enum.GenRangeError.html#variant.Rng
+    fn try_gen_range<T, B: GenRange<T>>(&mut self, range: B)
+        -> Result<T, distributions::range::GenRangeError>
+    {
+        let mut rng = TryRng::new(self);
+        let value = range.checked_sample_single(&mut rng);
+        Ok(rng.finish(value)??)
     }
 
     /// Return a bool with a 1 in n chance of true
@@ -534,7 +753,41 @@ pub trait Rng: RngCore {
     /// ```
     fn gen_weighted_bool(&mut self, n: u32) -> bool {
         // Short-circuit after `n <= 1` to avoid panic in `gen_range`
-        n <= 1 || self.gen_range(0, n) == 0
+        n <= 1 || self.gen_range(0..n) == 0
+    }
+
+    /// Return a bool with a probability of exactly `numerator / denominator`
+    /// of being true.
+    ///
+    /// Unlike going through a floating-point probability, this uses only
+    /// integer arithmetic, so no rounding is involved: `gen_ratio(2, 3)` is
+    /// true with probability exactly 2/3.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator == 0` or `numerator > denominator`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::{thread_rng, Rng};
+    ///
+    /// let mut rng = thread_rng();
+    /// assert_eq!(rng.gen_ratio(0, 5), false);
+    /// assert_eq!(rng.gen_ratio(5, 5), true);
+    /// // true with a 2 in 3 chance
+    /// println!("{}", rng.gen_ratio(2, 3));
+    /// ```
+    fn gen_ratio(&mut self, numerator: u32, denominator: u32) -> bool {
+        assert!(denominator > 0, "Rng::gen_ratio called with denominator == 0");
+        assert!(numerator <= denominator,
+                "Rng::gen_ratio called with numerator > denominator");
+        // Short-circuit the certain cases; this also avoids consuming
+        // randomness when the result is already known.
+        if numerator == denominator {
+            return true;
+        }
+        numerator > 0 && self.gen_range(0..denominator) < numerator
     }
 
     /// Return an iterator of random characters from the set A-Z,a-z,0-9.
@@ -572,7 +825,7 @@ pub trait Rng: RngCore {
         if values.is_empty() {
             None
         } else {
-            Some(&values[self.gen_range(0, values.len())])
+            Some(&values[self.gen_range(0..values.len())])
         }
     }
 
@@ -584,7 +837,7 @@ pub trait Rng: RngCore {
             None
         } else {
             let len = values.len();
-            Some(&mut values[self.gen_range(0, len)])
+            Some(&mut values[self.gen_range(0..len)])
         }
     }
 
@@ -593,6 +846,12 @@ pub trait Rng: RngCore {
     /// This applies Durstenfeld's algorithm for the [Fisher–Yates shuffle](https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle#The_modern_algorithm)
     /// which produces an unbiased permutation.
     ///
+    /// The resulting permutation is not guaranteed to be the same across
+    /// platforms or releases, even with a seeded RNG; use
+    /// [`seq::portable_shuffle`] where that matters.
+    ///
+    /// [`seq::portable_shuffle`]: seq/fn.portable_shuffle.html
+    ///
     /// # Example
     ///
     /// ```rust
@@ -606,17 +865,104 @@ pub trait Rng: RngCore {
     /// println!("{:?}", y);
     /// ```
     fn shuffle<T>(&mut self, values: &mut [T]) {
-        let mut i = values.len();
-        while i >= 2 {
-            // invariant: elements with index >= i have been locked in place.
-            i -= 1;
-            // lock element i in place.
-            values.swap(i, self.gen_range(0, i + 1));
+        seq::batched_shuffle(self, values)
+    }
+
+    /// Shuffle a random selection of `amount` elements to the front of a
+    /// mutable slice.
+    ///
+    /// This is a partial Fisher–Yates shuffle: only `amount` swaps are
+    /// performed, so it takes `O(amount)` time rather than `O(len)`.
+    ///
+    /// Returns a tuple of the `amount` randomly chosen elements, in random
+    /// order, and the remaining elements, in no particular order. If
+    /// `amount >= values.len()`, the whole slice is shuffled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::{thread_rng, Rng};
+    ///
+    /// let mut rng = thread_rng();
+    /// let mut y: Vec<u32> = (0..1000).collect();
+    /// let (chosen, rest) = rng.partial_shuffle(&mut y, 3);
+    /// assert_eq!(chosen.len(), 3);
+    /// assert_eq!(rest.len(), 997);
+    /// ```
+    fn partial_shuffle<'a, T>(&mut self, values: &'a mut [T], amount: usize)
+        -> (&'a mut [T], &'a mut [T])
+    {
+        let len = values.len();
+        let amount = if amount < len { amount } else { len };
+        for i in 0..amount {
+            // Elements with index < i have been chosen; pick the next one
+            // from the remainder.
+            values.swap(i, self.gen_range(i..len));
+        }
+        values.split_at_mut(amount)
+    }
+}
+
+impl<R: RngCore + ?Sized> Rng for R {}
+
+// Wrapper used by the `try_*` methods of `Rng`, turning all requests into calls
+// to `try_fill_bytes` and recording the first error.
+//
+// After an error, values are taken from a simple Weyl sequence instead, so
+// rejection sampling loops in distributions still terminate. Those values are
+// never returned to the user.
+struct TryRng<'a, R: RngCore + ?Sized + 'a> {
+    rng: &'a mut R,
+    error: Option<Error>,
+    fallback: u64,
+}
+
+impl<'a, R: RngCore + ?Sized> TryRng<'a, R> {
+    fn new(rng: &'a mut R) -> TryRng<'a, R> {
+        TryRng { rng: rng, error: None, fallback: 0 }
+    }
+
+    fn finish<T>(self, value: T) -> Result<T, Error> {
+        match self.error {
+            None => Ok(value),
+            Some(e) => Err(e),
         }
     }
 }
 
-impl<R: RngCore> Rng for R {}
+impl<'a, R: RngCore + ?Sized> RngCore for TryRng<'a, R> {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u32; 1];
+        self.fill(&mut buf);
+        buf[0]
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u64; 1];
+        self.fill(&mut buf);
+        buf[0]
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if self.error.is_none() {
+            match self.rng.try_fill_bytes(dest) {
+                Ok(()) => return,
+                Err(e) => self.error = Some(e),
+            }
+        }
+        for chunk in dest.chunks_mut(8) {
+            self.fallback = self.fallback.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut word = [self.fallback.to_le()];
+            let len = chunk.len();
+            chunk.copy_from_slice(&word.as_byte_slice_mut()[..len]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
 
 /// Trait for casting types to byte slices
 /// 
@@ -629,6 +975,9 @@ pub trait AsByteSliceMut {
     fn as_byte_slice_mut<'a>(&'a mut self) -> &'a mut [u8];
     
     /// Call `to_le` on each element (i.e. byte-swap on Big Endian platforms).
+    ///
+    /// For floating-point slices this also converts the random bits of each
+    /// element into a value in `[0, 1)`.
     fn to_le(&mut self);
 }
 
@@ -665,13 +1014,13 @@ macro_rules! impl_as_byte_slice {
 impl_as_byte_slice!(u16);
 impl_as_byte_slice!(u32);
 impl_as_byte_slice!(u64);
-#[cfg(feature="i128_support")] impl_as_byte_slice!(u128);
+impl_as_byte_slice!(u128);
 impl_as_byte_slice!(usize);
 impl_as_byte_slice!(i8);
 impl_as_byte_slice!(i16);
 impl_as_byte_slice!(i32);
 impl_as_byte_slice!(i64);
-#[cfg(feature="i128_support")] impl_as_byte_slice!(i128);
+impl_as_byte_slice!(i128);
 impl_as_byte_slice!(isize);
 
 macro_rules! impl_as_byte_slice_arrays {
@@ -692,6 +1041,23 @@ macro_rules! impl_as_byte_slice_arrays {
 }
 impl_as_byte_slice_arrays!(32, N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,);
 
+macro_rules! impl_as_byte_slice_large_arrays {
+    ($($n:expr),*) => {
+        $(
+            impl<T> AsByteSliceMut for [T; $n] where [T]: AsByteSliceMut {
+                fn as_byte_slice_mut<'a>(&'a mut self) -> &'a mut [u8] {
+                    self[..].as_byte_slice_mut()
+                }
+
+                fn to_le(&mut self) {
+                    self[..].to_le()
+                }
+            }
+        )*
+    }
+}
+impl_as_byte_slice_large_arrays!(33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 96, 128, 256, 512, 1024, 2048, 4096);
+
 /// Iterator which will generate a stream of random items.
 ///
 /// This iterator is created via the [`gen_iter`] method on [`Rng`].
@@ -700,7 +1066,7 @@ impl_as_byte_slice_arrays!(32, N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N
 /// [`Rng`]: trait.Rng.html
 #[derive(Debug)]
 #[allow(deprecated)]
-#[deprecated(since="0.5.0", note="use iter::repeat instead")]
+#[deprecated(since="0.5.0", note="use DistIter via Rng::sample_iter instead")]
 pub struct Generator<T, R: RngCore> {
     rng: R,
     _marker: marker::PhantomData<fn() -> T>,
@@ -757,7 +1123,7 @@ impl<R: RngCore> Iterator for AsciiGenerator<R> {
 /// use rand::{StdRng, Rng, NewRng};
 ///
 /// let mut rng = StdRng::new();
-/// println!("Random die roll: {}", rng.gen_range(1, 7));
+/// println!("Random die roll: {}", rng.gen_range(1..7));
 /// ```
 ///
 /// [`SeedableRng`]: https://docs.rs/rand-core/0.1/rand-core/trait.SeedableRng.html
@@ -783,7 +1149,7 @@ pub trait NewRng: SeedableRng {
     ///     // This uses StdRng, but is valid for any R: SeedableRng
     ///     let mut rng = StdRng::from_rng(&mut EntropyRng::new())?;
     ///     
-    ///     println!("random number: {}", rng.gen_range(1, 10));
+    ///     println!("random number: {}", rng.gen_range(1..10));
     ///     Ok(())
     /// }
     /// ```
@@ -812,6 +1178,7 @@ impl<R: SeedableRng> NewRng for R {
 ///
 /// [HC-128]: struct.Hc128Rng.html
 #[derive(Clone, Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
 pub struct StdRng(Hc128Rng);
 
 impl RngCore for StdRng {
@@ -830,8 +1197,18 @@ impl RngCore for StdRng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         self.0.try_fill_bytes(dest)
     }
+
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        self.0.fill_u32_slice(dest)
+    }
+
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        self.0.fill_u64_slice(dest)
+    }
 }
 
+impl CryptoRng for StdRng {}
+
 impl SeedableRng for StdRng {
     type Seed = <Hc128Rng as SeedableRng>::Seed;
 
@@ -839,11 +1216,30 @@ impl SeedableRng for StdRng {
         StdRng(Hc128Rng::from_seed(seed))
     }
 
-    fn from_rng<R: Rng>(rng: &mut R) -> Result<Self, Error> {
+    fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Result<Self, Error> {
         Hc128Rng::from_rng(rng).map(|rng| StdRng(rng))
     }
 }
 
+/// The encoding is that of the current internal generator, so saved states
+/// can not be restored by a future version using a different algorithm.
+impl StateSerializable for StdRng {
+    const STATE_LEN: usize = Hc128Rng::STATE_LEN;
+
+    fn to_state_bytes(&self, dest: &mut [u8]) {
+        self.0.to_state_bytes(dest)
+    }
+
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        Hc128Rng::from_state_bytes(bytes).map(|rng| StdRng(rng))
+    }
+}
+
+#[cfg(target_pointer_width = "64")]
+type SmallRngInner = Xoroshiro128PlusRng;
+#[cfg(not(target_pointer_width = "64"))]
+type SmallRngInner = XorShiftRng;
+
 /// An RNG recommended when small state, cheap initialization and good
 /// performance are required. The PRNG algorithm in `SmallRng` is choosen to be
 /// efficient on the current platform, **without consideration for cryptography
@@ -851,11 +1247,13 @@ impl SeedableRng for StdRng {
 ///
 /// Reproducibility of output from this generator is however not required, thus
 /// future library versions may use a different internal generator with
-/// different output. Further, this generator may not be portable and can
-/// produce different output depending on the architecture. If you require
+/// different output. Further, this generator is not portable: it produces
+/// different output depending on the architecture, and its serialized state
+/// can only be restored on the same kind of platform. If you require
 /// reproducible output, use a named RNG, for example `XorShiftRng`.
 ///
-/// The current algorithm used on all platforms is [Xorshift].
+/// The current algorithm is [xoroshiro128+] on 64-bit platforms, and
+/// [Xorshift] on all other platforms, where it is faster.
 ///
 /// # Examples
 ///
@@ -883,9 +1281,11 @@ impl SeedableRng for StdRng {
 /// let mut small_rng = SmallRng::from_rng(&mut thread_rng).unwrap();
 /// ```
 ///
+/// [xoroshiro128+]: struct.Xoroshiro128PlusRng.html
 /// [Xorshift]: struct.XorShiftRng.html
 #[derive(Clone, Debug)]
-pub struct SmallRng(XorShiftRng);
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct SmallRng(SmallRngInner);
 
 impl RngCore for SmallRng {
     fn next_u32(&mut self) -> u32 {
@@ -903,17 +1303,39 @@ impl RngCore for SmallRng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         self.0.try_fill_bytes(dest)
     }
+
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        self.0.fill_u32_slice(dest)
+    }
+
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        self.0.fill_u64_slice(dest)
+    }
 }
 
 impl SeedableRng for SmallRng {
-    type Seed = <XorShiftRng as SeedableRng>::Seed;
+    type Seed = <SmallRngInner as SeedableRng>::Seed;
 
     fn from_seed(seed: Self::Seed) -> Self {
-        SmallRng(XorShiftRng::from_seed(seed))
+        SmallRng(SmallRngInner::from_seed(seed))
     }
 
-    fn from_rng<R: Rng>(rng: &mut R) -> Result<Self, Error> {
-        XorShiftRng::from_rng(rng).map(|rng| SmallRng(rng))
+    fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Result<Self, Error> {
+        SmallRngInner::from_rng(rng).map(|rng| SmallRng(rng))
+    }
+}
+
+/// The encoding is that of the current internal generator, so saved states
+/// can not be restored by a future version using a different algorithm.
+impl StateSerializable for SmallRng {
+    const STATE_LEN: usize = SmallRngInner::STATE_LEN;
+
+    fn to_state_bytes(&self, dest: &mut [u8]) {
+        self.0.to_state_bytes(dest)
+    }
+
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        SmallRngInner::from_state_bytes(bytes).map(|rng| SmallRng(rng))
     }
 }
 
@@ -953,7 +1375,7 @@ pub fn weak_rng() -> XorShiftRng {
 #[deprecated(since="0.4.0", note="renamed to seq::sample_iter")]
 pub fn sample<T, I, R>(rng: &mut R, iterable: I, amount: usize) -> Vec<T>
     where I: IntoIterator<Item=T>,
-          R: Rng,
+          R: Rng + ?Sized,
 {
     // the legacy sample didn't care whether amount was met
     seq::sample_iter(rng, iterable, amount)
@@ -1038,39 +1460,116 @@ mod test {
         rng.fill(&mut array[..]);
         assert_eq!(array, [x as u32, (x >> 32) as u32]);
         assert_eq!(rng.next_u32(), x as u32);
+
+        // Arrays longer than 32 elements
+        let mut array = [0u64; 64];
+        rng.fill(&mut array);
+        assert_eq!(array[63], x);
+    }
+
+    #[test]
+    fn test_gen_bytes_array() {
+        let x = 9041086907909331047;    // a random u64
+        let mut rng = StepRng::new(x, 0);
+
+        let array: [u32; 4] = rng.gen_array();
+        assert_eq!(array, [x as u32, (x >> 32) as u32,
+                           x as u32, (x >> 32) as u32]);
+        // The little-endian bytes of `x`
+        let mut x_bytes = [0u8; 8];
+        for (i, b) in x_bytes.iter_mut().enumerate() {
+            *b = (x >> (8 * i)) as u8;
+        }
+        let bytes = rng.gen_array::<[u8; 8]>();
+        assert_eq!(bytes, x_bytes);
+
+        #[cfg(any(feature="std", feature="alloc"))]
+        {
+            let v = rng.gen_bytes(11);
+            assert_eq!(v.len(), 11);
+            assert_eq!(&v[..8], &x_bytes[..]);
+            assert!(rng.gen_bytes(0).is_empty());
+        }
     }
 
     #[test]
     fn test_gen_range() {
         let mut r = rng(101);
         for _ in 0..1000 {
-            let a = r.gen_range(-3, 42);
+            let a = r.gen_range(-3..42);
             assert!(a >= -3 && a < 42);
-            assert_eq!(r.gen_range(0, 1), 0);
-            assert_eq!(r.gen_range(-12, -11), -12);
+            assert_eq!(r.gen_range(0..1), 0);
+            assert_eq!(r.gen_range(-12..-11), -12);
         }
 
         for _ in 0..1000 {
-            let a = r.gen_range(10, 42);
+            let a = r.gen_range(10..42);
             assert!(a >= 10 && a < 42);
-            assert_eq!(r.gen_range(0, 1), 0);
-            assert_eq!(r.gen_range(3_000_000, 3_000_001), 3_000_000);
+            assert_eq!(r.gen_range(0..1), 0);
+            assert_eq!(r.gen_range(3_000_000..3_000_001), 3_000_000);
         }
 
+        for _ in 0..1000 {
+            let a = r.gen_range(-3..=42);
+            assert!(a >= -3 && a <= 42);
+            assert_eq!(r.gen_range(7..=7), 7);
+            let b = r.gen_range(200u8..=::core::u8::MAX);
+            assert!(b >= 200);
+            let _: u64 = r.gen_range(0..=::core::u64::MAX);
+            let _: i16 = r.gen_range(::core::i16::MIN..=::core::i16::MAX);
+            let f = r.gen_range(-1.5f64..=2.5);
+            assert!(f >= -1.5 && f <= 2.5);
+        }
+    }
+
+    #[test]
+    fn test_try_gen_range() {
+        use distributions::range::{GenRangeError, RangeError};
+        fn range_err<T>(r: Result<T, GenRangeError>) -> RangeError {
+            match r {
+                Err(GenRangeError::Range(e)) => e,
+                _ => panic!("expected a range error"),
+            }
+        }
+
+        let mut r = rng(102);
+        for _ in 0..100 {
+            let a = r.try_gen_range(-4711..17).unwrap();
+            assert!(a >= -4711 && a < 17);
+            r.try_gen_range(0u8..=255).unwrap();
+            let c = r.try_gen_range(-1.5f64..2.5).unwrap();
+            assert!(c >= -1.5 && c < 2.5);
+        }
+        assert_eq!(r.try_gen_range(3..=3).unwrap(), 3);
+        assert_eq!(range_err(r.try_gen_range(5..5)), RangeError::EmptyRange);
+        assert_eq!(range_err(r.try_gen_range(5usize..=4)), RangeError::EmptyRange);
+        assert_eq!(range_err(r.try_gen_range(0.0..::core::f64::NAN)),
+                   RangeError::EmptyRange);
+        assert_eq!(range_err(r.try_gen_range(0.0..::core::f64::INFINITY)),
+                   RangeError::NonFinite);
+        assert_eq!(range_err(r.try_gen_range(::core::f64::MIN..=::core::f64::MAX)),
+                   RangeError::NonFinite);
     }
 
     #[test]
     #[should_panic]
     fn test_gen_range_panic_int() {
         let mut r = rng(102);
-        r.gen_range(5, -2);
+        r.gen_range(5..-2);
     }
 
     #[test]
     #[should_panic]
     fn test_gen_range_panic_usize() {
         let mut r = rng(103);
-        r.gen_range(5, 2);
+        r.gen_range(5..2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gen_range_panic_inclusive() {
+        let mut r = rng(103);
+        r.gen_range(5..=4);
     }
 
     #[test]
@@ -1080,6 +1579,85 @@ mod test {
         assert_eq!(r.gen_weighted_bool(1), true);
     }
 
+    #[test]
+    #[cfg(feature="std")]
+    fn test_try_methods() {
+        use read::ReadRng;
+        use distributions::Range;
+
+        let data = [1u8, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0];
+        let mut rng = ReadRng::new(&data[..]);
+        assert_eq!(rng.try_gen::<u64>().unwrap(), 1);
+        assert!(rng.try_gen::<u64>().is_err());
+        // A `u32` only takes 4 bytes
+        let mut rng = ReadRng::new(&data[8..]);
+        assert_eq!(rng.try_gen::<u32>().unwrap(), 2);
+
+        let mut rng = ReadRng::new(&[][..]);
+        assert!(rng.try_sample(Range::new(0, 10)).is_err());
+        match rng.try_gen_range(0..10) {
+            Err(::distributions::range::GenRangeError::Rng(_)) => {}
+            _ => panic!("expected an RNG error"),
+        }
+        // Rejection sampling still terminates after an error.
+        assert!(rng.try_gen::<::core::num::NonZeroU32>().is_err());
+
+        let mut rng = ::test::rng(109);
+        let x = rng.try_gen_range(10..20).unwrap();
+        assert!(x >= 10 && x < 20);
+    }
+
+    #[test]
+    fn test_partial_shuffle() {
+        let mut r = rng(110);
+        let mut v = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        {
+            let (chosen, rest) = r.partial_shuffle(&mut v, 4);
+            assert_eq!(chosen.len(), 4);
+            assert_eq!(rest.len(), 6);
+        }
+        let mut sorted = v;
+        sorted.sort();
+        assert_eq!(sorted, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let (chosen, rest) = r.partial_shuffle(&mut v, 20);
+        assert_eq!((chosen.len(), rest.len()), (10, 0));
+
+        let empty: &mut [u8] = &mut [];
+        let (chosen, rest) = r.partial_shuffle(empty, 1);
+        assert_eq!((chosen.len(), rest.len()), (0, 0));
+    }
+
+    #[test]
+    fn test_gen_ratio() {
+        let mut r = rng(105);
+        assert_eq!(r.gen_ratio(0, 1), false);
+        assert_eq!(r.gen_ratio(1, 1), true);
+        assert_eq!(r.gen_ratio(::core::u32::MAX, ::core::u32::MAX), true);
+
+        let mut sum = 0;
+        for _ in 0..1000 {
+            if r.gen_ratio(1, 4) {
+                sum += 1;
+            }
+        }
+        assert!(sum > 150 && sum < 350);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gen_ratio_panic_zero_denominator() {
+        let mut r = rng(106);
+        r.gen_ratio(0, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gen_ratio_panic_numerator_too_large() {
+        let mut r = rng(106);
+        r.gen_ratio(3, 2);
+    }
+
     #[test]
     fn test_choose() {
         let mut r = rng(107);
@@ -1120,7 +1698,7 @@ mod test {
         r.shuffle(&mut v);
         let b: &[_] = &[1, 1, 1];
         assert_eq!(v, b);
-        assert_eq!(r.gen_range(0, 1), 0);
+        assert_eq!(r.gen_range(0..1), 0);
         let _c: u8 = Uniform.sample(&mut r);
     }
 
@@ -1136,10 +1714,51 @@ mod test {
         r.shuffle(&mut v);
         let b: &[_] = &[1, 1, 1];
         assert_eq!(v, b);
-        assert_eq!(r.gen_range(0, 1), 0);
+        assert_eq!(r.gen_range(0..1), 0);
         let _c: u8 = Uniform.sample(&mut r);
     }
 
+    #[test]
+    #[cfg(any(feature="std", feature="alloc"))]
+    fn test_rng_trait_object_generic() {
+        fn use_rng<R: Rng + ?Sized>(rng: &mut R) -> usize {
+            seq::sample_indices(rng, 10, 3).len()
+        }
+
+        let mut rng = rng(111);
+        {
+            let r: &mut RngCore = &mut rng;
+            assert_eq!(use_rng(r), 3);
+            assert_eq!(seq::sample_slice(r, &[1, 2, 3], 2).len(), 2);
+            XorShiftRng::from_rng(r).unwrap();
+        }
+        let mut boxed: Box<RngCore> = Box::new(rng);
+        assert_eq!(use_rng(&mut boxed), 3);
+        assert_eq!(use_rng(&mut *boxed), 3);
+        XorShiftRng::from_rng(&mut *boxed).unwrap();
+
+        fn is_crypto<R: CryptoRng + ?Sized>(_: &R) {}
+        let mut crypto: Box<Hc128Rng> = Box::new(Hc128Rng::from_rng(&mut boxed).unwrap());
+        is_crypto(&crypto);
+        is_crypto(&&mut *crypto);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_crypto_rng_impls() {
+        fn is_crypto<R: CryptoRng + ?Sized>(_: &R) {}
+        let mut os_rng = OsRng::new().unwrap();
+        let std_rng = StdRng::from_rng(&mut os_rng).unwrap();
+        is_crypto(&std_rng);
+        is_crypto(&EntropyRng::new());
+        is_crypto(&thread_rng());
+        let reseeding = reseeding::ReseedingRng::new(std_rng, 32, EntropyRng::new());
+        is_crypto(&reseeding);
+        is_crypto(&os_rng);
+        let boxed: Box<CryptoRng> = Box::new(os_rng);
+        is_crypto(&*boxed);
+    }
+
     #[test]
     fn test_stdrng_construction() {
         let seed = [1,0,0,0, 23,0,0,0, 200,1,0,0, 210,30,0,0,
@@ -1150,4 +1769,24 @@ mod test {
         let mut rng2 = StdRng::from_rng(&mut rng1).unwrap();
         assert_eq!(rng2.next_u64(), 6766915756997287454);
     }
+
+    #[test]
+    #[cfg(all(feature="serde-1", feature="std"))]
+    fn test_std_small_rng_serde() {
+        use bincode;
+
+        let mut std_rng = StdRng::from_seed([3; 32]);
+        let mut small_rng = SmallRng::from_seed([3; 16]);
+        std_rng.next_u32();
+        small_rng.next_u32();
+
+        let buf = bincode::serialize(&std_rng).expect("Could not serialize");
+        let mut std_rng2: StdRng = bincode::deserialize(&buf).unwrap();
+        let buf = bincode::serialize(&small_rng).expect("Could not serialize");
+        let mut small_rng2: SmallRng = bincode::deserialize(&buf).unwrap();
+        for _ in 0..100 {
+            assert_eq!(std_rng.next_u64(), std_rng2.next_u64());
+            assert_eq!(small_rng.next_u64(), small_rng2.next_u64());
+        }
+    }
 }
@@ -0,0 +1,219 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A wrapper limiting the amount of randomness an RNG may produce.
+
+use {RngCore, CryptoRng, Error, ErrorKind};
+
+/// A wrapper around an RNG which serves a limited budget of random data, and
+/// fails once it is used up.
+///
+/// The budget counts either bytes ([`with_bytes`]; `next_u32` uses 4 bytes
+/// and `next_u64` 8) or calls ([`with_calls`]; every method call uses 1). A
+/// request which does not fit in the remaining budget fails as a whole and
+/// uses nothing: `try_fill_bytes` returns an error of kind
+/// `ErrorKind::Unavailable`, and `next_u32`, `next_u64` and `fill_bytes`
+/// panic. With [`panic_on_exhaustion`], `try_fill_bytes` panics as well. A
+/// `try_fill_bytes` call for which the inner generator fails uses nothing
+/// either.
+///
+/// This is useful to enforce a randomness budget, e.g. in a protocol which
+/// must not draw more than a fixed number of nonces, and in tests checking
+/// how many values an algorithm draws.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, RngCore};
+/// use rand::limit::LimitedRng;
+/// use rand::mock::StepRng;
+///
+/// let mut rng = LimitedRng::with_bytes(StepRng::new(0, 1), 12);
+/// let _: u64 = rng.gen();
+/// let _: u32 = rng.gen();
+/// assert_eq!(rng.remaining(), 0);
+/// assert!(rng.try_fill_bytes(&mut [0u8; 1]).is_err());
+/// ```
+///
+/// [`with_bytes`]: #method.with_bytes
+/// [`with_calls`]: #method.with_calls
+/// [`panic_on_exhaustion`]: #method.panic_on_exhaustion
+#[derive(Clone, Debug)]
+pub struct LimitedRng<R> {
+    rng: R,
+    remaining: u64,
+    used: u64,
+    count_bytes: bool,
+    panic: bool,
+}
+
+impl<R: RngCore> LimitedRng<R> {
+    /// Create a `LimitedRng` wrapping `rng`, allowing at most `bytes` bytes
+    /// of output.
+    pub fn with_bytes(rng: R, bytes: u64) -> LimitedRng<R> {
+        LimitedRng { rng: rng, remaining: bytes, used: 0, count_bytes: true,
+                     panic: false }
+    }
+
+    /// Create a `LimitedRng` wrapping `rng`, allowing at most `calls` calls
+    /// of its methods, regardless of their size.
+    pub fn with_calls(rng: R, calls: u64) -> LimitedRng<R> {
+        LimitedRng { rng: rng, remaining: calls, used: 0, count_bytes: false,
+                     panic: false }
+    }
+
+    /// Make `try_fill_bytes` panic instead of returning an error when the
+    /// budget is exhausted.
+    pub fn panic_on_exhaustion(mut self) -> LimitedRng<R> {
+        self.panic = true;
+        self
+    }
+
+    /// The remaining budget, in bytes or calls.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// The budget used so far, in bytes or calls.
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+
+    /// Unwrap the inner generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+
+    // The cost of a request of `bytes` bytes, if it fits in the budget.
+    fn cost(&self, bytes: usize) -> Result<u64, Error> {
+        let cost = if self.count_bytes { bytes as u64 } else { 1 };
+        if cost > self.remaining {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "LimitedRng: budget exhausted"));
+        }
+        Ok(cost)
+    }
+
+    // Take the cost of a request of `bytes` bytes from the budget.
+    fn spend(&mut self, bytes: usize) -> Result<(), Error> {
+        let cost = self.cost(bytes)?;
+        self.remaining -= cost;
+        self.used += cost;
+        Ok(())
+    }
+}
+
+impl<R: RngCore> RngCore for LimitedRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.spend(4).unwrap_or_else(|e| panic!("{}", e));
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.spend(8).unwrap_or_else(|e| panic!("{}", e));
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.spend(dest.len()).unwrap_or_else(|e| panic!("{}", e));
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        let cost = match self.cost(dest.len()) {
+            Ok(cost) => cost,
+            Err(e) => {
+                if self.panic {
+                    panic!("{}", e);
+                }
+                return Err(e);
+            }
+        };
+        self.rng.try_fill_bytes(dest)?;
+        self.remaining -= cost;
+        self.used += cost;
+        Ok(())
+    }
+}
+
+impl<R: RngCore + CryptoRng> CryptoRng for LimitedRng<R> {}
+
+#[cfg(test)]
+mod test {
+    use {Rng, RngCore, ErrorKind};
+    use mock::StepRng;
+    use super::LimitedRng;
+
+    #[test]
+    fn test_limited_bytes() {
+        let mut rng = LimitedRng::with_bytes(StepRng::new(1, 1), 20);
+        assert_eq!(rng.next_u32(), 1);
+        assert_eq!(rng.next_u64(), 2);
+        let mut buf = [0u8; 9];
+        assert_eq!(rng.try_fill_bytes(&mut buf).unwrap_err().kind,
+                   ErrorKind::Unavailable);
+        // A failed request uses nothing
+        assert_eq!((rng.used(), rng.remaining()), (12, 8));
+        rng.fill_bytes(&mut buf[..8]);
+        assert_eq!(rng.remaining(), 0);
+        assert!(rng.try_fill_bytes(&mut []).is_ok());
+        assert_eq!(rng.into_inner().next_u64(), 4);
+    }
+
+    #[test]
+    fn test_limited_calls() {
+        let mut rng = LimitedRng::with_calls(StepRng::new(0, 1), 3);
+        let mut buf = [0u8; 100];
+        rng.fill_bytes(&mut buf);
+        rng.next_u64();
+        rng.try_fill_bytes(&mut buf).unwrap();
+        assert_eq!(rng.used(), 3);
+        assert!(rng.try_fill_bytes(&mut buf).is_err());
+
+        // Exactly one call per value
+        let mut rng = LimitedRng::with_calls(StepRng::new(0, 1), 10);
+        for _ in 0..10 {
+            let _: u32 = rng.gen();
+        }
+        assert_eq!(rng.remaining(), 0);
+    }
+
+    #[test]
+    fn test_limited_inner_error() {
+        use mock::FaultyRng;
+        let inner = FaultyRng::new(StepRng::new(0, 1), |call| {
+            if call == 0 { Some(ErrorKind::NotReady) } else { None }
+        });
+        let mut rng = LimitedRng::with_bytes(inner, 8);
+        let mut buf = [0u8; 8];
+        assert_eq!(rng.try_fill_bytes(&mut buf).unwrap_err().kind,
+                   ErrorKind::NotReady);
+        // Only successful requests use the budget
+        assert_eq!((rng.used(), rng.remaining()), (0, 8));
+        rng.try_fill_bytes(&mut buf).unwrap();
+        assert_eq!((rng.used(), rng.remaining()), (8, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "budget exhausted")]
+    fn test_limited_panic() {
+        let mut rng = LimitedRng::with_bytes(StepRng::new(0, 1), 7)
+            .panic_on_exhaustion();
+        let _ = rng.try_fill_bytes(&mut [0u8; 8]);
+    }
+
+    #[test]
+    #[should_panic(expected = "budget exhausted")]
+    fn test_limited_next_panics() {
+        let mut rng = LimitedRng::with_calls(StepRng::new(0, 1), 1);
+        rng.next_u32();
+        rng.next_u32();
+    }
+}
@@ -8,9 +8,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! Mock random number generator
+//! Mock random number generators
 
-use rand_core::{RngCore, Error, impls};
+use core::fmt;
+use rand_core::{RngCore, Error, ErrorKind, impls};
 
 /// A simple implementation of `RngCore` for testing purposes.
 /// 
@@ -59,3 +60,291 @@ impl RngCore for StepRng {
         Ok(self.fill_bytes(dest))
     }
 }
+
+/// What `fill_bytes` does with the bytes of a value that were not needed.
+///
+/// Values are written to the destination in little-endian order, eight bytes
+/// per value. When the length of the destination is not a multiple of 8, the
+/// last value is only partially used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Remainder {
+    /// Discard the unused bytes; the next call starts with a new value. This
+    /// is what most generators do, and the behaviour of `StepRng`.
+    Discard,
+    /// Keep the unused bytes, and use them first in the next call to
+    /// `fill_bytes`. The bytes then form one continuous stream, however the
+    /// requests are split up. `next_u32` and `next_u64` always take a new
+    /// value, and do not disturb kept bytes.
+    Keep,
+}
+
+// The state for `fill_bytes` shared by the mock generators.
+#[derive(Debug, Clone)]
+struct Bytes {
+    remainder: Remainder,
+    // Unused bytes of the last value, in the low bits.
+    kept: u64,
+    kept_len: usize,
+}
+
+impl Bytes {
+    fn new(remainder: Remainder) -> Bytes {
+        Bytes { remainder: remainder, kept: 0, kept_len: 0 }
+    }
+
+    fn fill<F: FnMut() -> u64>(&mut self, dest: &mut [u8], mut next: F) {
+        for byte in dest.iter_mut() {
+            if self.kept_len == 0 {
+                self.kept = next();
+                self.kept_len = 8;
+            }
+            *byte = self.kept as u8;
+            self.kept >>= 8;
+            self.kept_len -= 1;
+        }
+        if self.remainder == Remainder::Discard {
+            self.kept_len = 0;
+        }
+    }
+}
+
+/// A mock RNG cycling through an explicit list of values.
+///
+/// `next_u64` returns the values in order, starting again with the first
+/// after the last; `next_u32` returns the low 32 bits of the next value. The
+/// handling of bytes in `fill_bytes` is configurable with [`Remainder`].
+///
+/// The list can be anything implementing `AsRef<[u64]>`, such as an array, a
+/// slice or a `Vec`.
+///
+/// ```rust
+/// use rand::{Rng, RngCore};
+/// use rand::mock::{PatternRng, Remainder};
+///
+/// let mut rng = PatternRng::new([1, 2, 3]);
+/// let sample: [u64; 4] = rng.gen();
+/// assert_eq!(sample, [1, 2, 3, 1]);
+///
+/// let mut rng = PatternRng::with_remainder([0x0807060504030201], Remainder::Keep);
+/// let mut bytes = [0u8; 3];
+/// rng.fill_bytes(&mut bytes);
+/// assert_eq!(bytes, [1, 2, 3]);
+/// rng.fill_bytes(&mut bytes);
+/// assert_eq!(bytes, [4, 5, 6]);
+/// ```
+///
+/// [`Remainder`]: enum.Remainder.html
+#[derive(Debug, Clone)]
+pub struct PatternRng<T> {
+    values: T,
+    index: usize,
+    bytes: Bytes,
+}
+
+impl<T: AsRef<[u64]>> PatternRng<T> {
+    /// Create a `PatternRng` cycling through `values`, which discards unused
+    /// bytes in `fill_bytes`.
+    ///
+    /// Panics if `values` is empty.
+    pub fn new(values: T) -> Self {
+        PatternRng::with_remainder(values, Remainder::Discard)
+    }
+
+    /// Create a `PatternRng` cycling through `values`, with the given
+    /// handling of unused bytes in `fill_bytes`.
+    ///
+    /// Panics if `values` is empty.
+    pub fn with_remainder(values: T, remainder: Remainder) -> Self {
+        assert!(!values.as_ref().is_empty(),
+                "PatternRng::new called with no values");
+        PatternRng { values: values, index: 0, bytes: Bytes::new(remainder) }
+    }
+}
+
+impl<T: AsRef<[u64]>> RngCore for PatternRng<T> {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let values = self.values.as_ref();
+        let result = values[self.index];
+        self.index = (self.index + 1) % values.len();
+        result
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let values = self.values.as_ref();
+        let index = &mut self.index;
+        self.bytes.fill(dest, || {
+            let result = values[*index];
+            *index = (*index + 1) % values.len();
+            result
+        });
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+/// A mock RNG calling a closure for every value.
+///
+/// `next_u64` returns the result of the closure, and `next_u32` its low 32
+/// bits. The handling of bytes in `fill_bytes` is configurable with
+/// [`Remainder`].
+///
+/// ```rust
+/// use rand::Rng;
+/// use rand::mock::FnRng;
+///
+/// // Powers of two
+/// let mut x = 1;
+/// let mut rng = FnRng::new(|| { let r = x; x *= 2; r });
+/// let sample: [u64; 4] = rng.gen();
+/// assert_eq!(sample, [1, 2, 4, 8]);
+/// ```
+///
+/// [`Remainder`]: enum.Remainder.html
+#[derive(Clone)]
+pub struct FnRng<F> {
+    f: F,
+    bytes: Bytes,
+}
+
+impl<F: FnMut() -> u64> FnRng<F> {
+    /// Create a `FnRng` calling `f`, which discards unused bytes in
+    /// `fill_bytes`.
+    pub fn new(f: F) -> Self {
+        FnRng::with_remainder(f, Remainder::Discard)
+    }
+
+    /// Create a `FnRng` calling `f`, with the given handling of unused bytes
+    /// in `fill_bytes`.
+    pub fn with_remainder(f: F, remainder: Remainder) -> Self {
+        FnRng { f: f, bytes: Bytes::new(remainder) }
+    }
+}
+
+impl<F> fmt::Debug for FnRng<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FnRng")
+            .field("bytes", &self.bytes)
+            .finish()
+    }
+}
+
+impl<F: FnMut() -> u64> RngCore for FnRng<F> {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.f)()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let f = &mut self.f;
+        self.bytes.fill(dest, f);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+/// A mock RNG wrapping another RNG, which injects errors according to a
+/// schedule.
+///
+/// Before every call to one of the `RngCore` methods, the schedule is called
+/// with the number of previous calls. If it returns `None`, the call is
+/// passed on to the wrapped RNG. If it returns an `ErrorKind`,
+/// `try_fill_bytes` returns an error of that kind, while `next_u32`,
+/// `next_u64` and `fill_bytes` panic, like the infallible methods of a real
+/// source of entropy.
+///
+/// This allows testing how code handles a failing source of randomness, e.g.
+/// the delays of `ReseedingRng` after an error of each kind.
+///
+/// ```rust
+/// use rand::{RngCore, ErrorKind};
+/// use rand::mock::{FaultyRng, StepRng};
+///
+/// // Not ready for the first two calls, and unavailable from the fifth
+/// let mut rng = FaultyRng::new(StepRng::new(0, 1), |call| match call {
+///     0 | 1 => Some(ErrorKind::NotReady),
+///     2 | 3 => None,
+///     _ => Some(ErrorKind::Unavailable),
+/// });
+/// let mut buf = [0u8; 4];
+/// assert_eq!(rng.try_fill_bytes(&mut buf).unwrap_err().kind, ErrorKind::NotReady);
+/// assert_eq!(rng.try_fill_bytes(&mut buf).unwrap_err().kind, ErrorKind::NotReady);
+/// assert!(rng.try_fill_bytes(&mut buf).is_ok());
+/// assert_eq!(rng.next_u64(), 1);
+/// assert_eq!(rng.try_fill_bytes(&mut buf).unwrap_err().kind, ErrorKind::Unavailable);
+/// assert_eq!(rng.calls(), 5);
+/// ```
+#[derive(Clone)]
+pub struct FaultyRng<R, F> {
+    rng: R,
+    schedule: F,
+    calls: usize,
+}
+
+impl<R: RngCore, F: FnMut(usize) -> Option<ErrorKind>> FaultyRng<R, F> {
+    /// Create a `FaultyRng` wrapping `rng`, failing when `schedule` returns
+    /// an error kind.
+    pub fn new(rng: R, schedule: F) -> Self {
+        FaultyRng { rng: rng, schedule: schedule, calls: 0 }
+    }
+
+    /// The number of calls so far, including the failed ones.
+    pub fn calls(&self) -> usize {
+        self.calls
+    }
+
+    /// Unwrap the inner generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+
+    fn fault(&mut self) -> Result<(), Error> {
+        let call = self.calls;
+        self.calls += 1;
+        match (self.schedule)(call) {
+            Some(kind) => Err(Error::new(kind, "FaultyRng: injected error")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<R: fmt::Debug, F> fmt::Debug for FaultyRng<R, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FaultyRng")
+            .field("rng", &self.rng)
+            .field("calls", &self.calls)
+            .finish()
+    }
+}
+
+impl<R: RngCore, F: FnMut(usize) -> Option<ErrorKind>> RngCore for FaultyRng<R, F> {
+    fn next_u32(&mut self) -> u32 {
+        self.fault().unwrap_or_else(|e| panic!("{}", e));
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.fault().unwrap_or_else(|e| panic!("{}", e));
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.fault().unwrap_or_else(|e| panic!("{}", e));
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fault()?;
+        self.rng.try_fill_bytes(dest)
+    }
+}
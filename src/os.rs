@@ -17,7 +17,7 @@ use std::io::Read;
 #[allow(unused)] use std::path::Path;
 #[allow(unused)] use std::sync::{Once, Mutex, ONCE_INIT};
 
-use rand_core::{RngCore, Error, ErrorKind, impls};
+use rand_core::{CryptoRng, RngCore, Error, ErrorKind, impls};
 
 /// A random number generator that retrieves randomness straight from
 /// the operating system.
@@ -32,6 +32,12 @@ use rand_core::{RngCore, Error, ErrorKind, impls};
 ///   `SystemFunction036`.
 /// - iOS: calls SecRandomCopyBytes as /dev/(u)random is sandboxed.
 ///
+/// When reading from a device such as `/dev/urandom`, a single descriptor is
+/// opened by the first `OsRng` and shared by all instances in the process. It
+/// is reopened if it gets closed, e.g. by a daemon closing all descriptors.
+/// Running out of file descriptors is reported as `ErrorKind::NotReady`,
+/// since it is usually temporary.
+///
 /// This usually does not block. On some systems (e.g. FreeBSD, OpenBSD,
 /// Max OS X, and modern Linux) this may block very early in the init
 /// process, if the CSPRNG has not been seeded yet.[1]
@@ -115,69 +121,186 @@ impl RngCore for OsRng {
     }
 }
 
+impl CryptoRng for OsRng {}
+
 // Specialisation of `ReadRng` for our purposes
 // 
-// Note: all instances use a single internal file handle
+// Note: all instances use a single internal file handle, which is opened
+// lazily by the first instance and kept open for the life of the process.
+#[derive(Debug)]
+#[allow(unused)]    // not used by all targets
+struct ReadRng {
+    path: &'static str,
+}
+
+// The random device shared by all `ReadRng` instances.
 #[derive(Debug)]
 #[allow(unused)]    // not used by all targets
-struct ReadRng {}
+struct ReadRngFile {
+    file: File,
+    // The device and inode numbers of the file, to detect the descriptor
+    // being closed (e.g. by a daemon closing all descriptors) and possibly
+    // reused for a different file.
+    #[cfg(unix)]
+    id: (u64, u64),
+}
 
-// TODO: remove outer Option when `Mutex::new(None)` is a constant expression
-static mut READ_RNG_FILE: Option<Mutex<Option<File>>> = None;
+// TODO: use a plain `Mutex` when `Mutex::new(None)` is a constant expression.
+// The mutex is leaked by `READ_RNG_ONCE`, and this pointer to it is only
+// copied, never borrowed.
+static mut READ_RNG_FILE: *const Mutex<Option<ReadRngFile>> = 0 as *const _;
 static READ_RNG_ONCE: Once = ONCE_INIT;
 
+#[allow(unused)]    // not used by all targets
+impl ReadRngFile {
+    // Open the random device at `path`.
+    //
+    // `File::open` sets `O_CLOEXEC` on Unix, so the descriptor is not leaked
+    // into child processes.
+    fn open(path: &str) -> Result<ReadRngFile, Error> {
+        info!("OsRng: opening random device {}", path);
+        let file = File::open(path).map_err(|err| {
+            use std::io::ErrorKind::*;
+            match err.kind() {
+                Interrupted => Error::new(ErrorKind::Transient, "interrupted"),
+                WouldBlock => Error::with_cause(ErrorKind::NotReady,
+                        "opening random device would block", err),
+                _ if is_fd_exhaustion(&err) => Error::with_cause(
+                        ErrorKind::NotReady,
+                        "too many open files to open random device", err),
+                _ => Error::with_cause(ErrorKind::Unavailable,
+                        "error while opening random device", err)
+            }
+        })?;
+        ReadRngFile::from_file(file)
+    }
+
+    #[cfg(unix)]
+    fn from_file(file: File) -> Result<ReadRngFile, Error> {
+        let id = file_id(&file).map_err(|err| Error::with_cause(
+                ErrorKind::Unavailable, "error inspecting random device", err))?;
+        Ok(ReadRngFile { file: file, id: id })
+    }
+
+    #[cfg(not(unix))]
+    fn from_file(file: File) -> Result<ReadRngFile, Error> {
+        Ok(ReadRngFile { file: file })
+    }
+
+    // Check that our descriptor still refers to the file we opened.
+    #[cfg(unix)]
+    fn is_valid(&self) -> bool {
+        file_id(&self.file).ok() == Some(self.id)
+    }
+
+    #[cfg(not(unix))]
+    fn is_valid(&self) -> bool { true }
+
+    // Drop the cached file without closing its descriptor: that was already
+    // closed by someone else, and may have been reused for another file.
+    fn invalidate(self) {
+        warn!("OsRng: random device descriptor was closed; reopening");
+        ::std::mem::forget(self.file);
+    }
+}
+
+#[cfg(unix)]
+fn file_id(file: &File) -> ::std::io::Result<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    file.metadata().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(unix)]
+fn is_fd_exhaustion(err: &::std::io::Error) -> bool {
+    extern crate libc;
+    match err.raw_os_error() {
+        Some(libc::EMFILE) | Some(libc::ENFILE) => true,
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_fd_exhaustion(_err: &::std::io::Error) -> bool { false }
+
 #[allow(unused)]    // not used by all targets
 impl ReadRng {
-    // Open a `File` for the given `path`.
+    // Create a `ReadRng` for the given `path`, opening it unless it is
+    // already open.
     // 
-    // Uses a mutex on a static object to limit `OsRng` to a single file descriptor.
-    fn open<P: AsRef<Path>>(path: P) -> Result<ReadRng, Error> {
+    // Uses a mutex on a static object to limit `OsRng` to a single file
+    // descriptor, which is only reopened if it was closed behind our back.
+    fn open(path: &'static str) -> Result<ReadRng, Error> {
         READ_RNG_ONCE.call_once(|| {
-            unsafe { READ_RNG_FILE = Some(Mutex::new(None)) }
+            let mutex = Box::new(Mutex::new(None));
+            unsafe { READ_RNG_FILE = Box::into_raw(mutex) }
         });
         
         // We try opening the file outside the `call_once` fn because we cannot
         // clone the error, thus we must retry on failure.
         
-        let mutex = unsafe{ READ_RNG_FILE.as_ref().unwrap() };
-        let mut guard = mutex.lock().unwrap();
-        if (*guard).is_none() {
-            info!("OsRng: opening random device {}", path.as_ref().display());
-            let file = File::open(path).map_err(|err| {
-                use std::io::ErrorKind::*;
-                match err.kind() {
-                    Interrupted => Error::new(ErrorKind::Transient, "interrupted"),
-                    WouldBlock => Error::with_cause(ErrorKind::NotReady,
-                            "opening random device would block", err),
-                    _ => Error::with_cause(ErrorKind::Unavailable,
-                            "error while opening random device", err)
-                }
-            })?;
-            *guard = Some(file);
+        let mut guard = ReadRng::lock();
+        ReadRng::ensure_open(&mut guard, path)?;
+        Ok(ReadRng { path: path })
+    }
+
+    fn lock() -> ::std::sync::MutexGuard<'static, Option<ReadRngFile>> {
+        // Since `READ_RNG_ONCE` has run, our memory was set with a valid
+        // object.
+        let mutex = unsafe { &*READ_RNG_FILE };
+        // A panic while holding the lock cannot leave the file in an
+        // invalid state.
+        mutex.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // Make sure `cached` holds a valid file, reopening it if necessary.
+    fn ensure_open<'a>(cached: &'a mut Option<ReadRngFile>, path: &str)
+        -> Result<&'a mut ReadRngFile, Error>
+    {
+        if cached.as_ref().map_or(false, |f| !f.is_valid()) {
+            cached.take().unwrap().invalidate();
         }
-        
-        Ok(ReadRng {})
+        if cached.is_none() {
+            *cached = Some(ReadRngFile::open(path)?);
+        }
+        Ok(cached.as_mut().unwrap())
     }
     
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         trace!("OsRng: reading {} bytes from random device", dest.len());
         if dest.len() == 0 { return Ok(()); }
-        
-        // Since we have an instance of Self, we can assume that our memory was
-        // set with a valid object.
-        let mutex = unsafe{ READ_RNG_FILE.as_ref().unwrap() };
-        let mut guard = mutex.lock().unwrap();
-        let mut file = (*guard).as_mut().unwrap();
-        // Use `std::io::read_exact`, which retries on `ErrorKind::Interrupted`.
-        file.read_exact(dest).map_err(|err| {
-            match err.kind() {
-                ::std::io::ErrorKind::WouldBlock => Error::with_cause(
-                    ErrorKind::NotReady,
-                    "reading from random device would block", err),
-                _ => Error::with_cause(ErrorKind::Unavailable,
-                    "error reading random device", err)
+
+        let mut guard = ReadRng::lock();
+        ReadRng::fill(&mut guard, self.path, dest)
+    }
+
+    // Fill `dest` from the file in `cached`, reopening it if necessary.
+    fn fill(cached: &mut Option<ReadRngFile>, path: &str, dest: &mut [u8])
+        -> Result<(), Error>
+    {
+        // The descriptor may be closed by someone else and reused for
+        // another file between checking and reading it, in which case the
+        // read may fail or, worse, return the contents of that file. So we
+        // check it again after reading, and redo the read once on change.
+        for _ in 0..2 {
+            // Use `std::io::read_exact`, which retries on
+            // `ErrorKind::Interrupted`.
+            let result = ReadRng::ensure_open(cached, path)?
+                .file.read_exact(dest);
+            if !cached.as_ref().unwrap().is_valid() {
+                continue;
             }
-        })
+            return result.map_err(|err| {
+                match err.kind() {
+                    ::std::io::ErrorKind::WouldBlock => Error::with_cause(
+                        ErrorKind::NotReady,
+                        "reading from random device would block", err),
+                    _ => Error::with_cause(ErrorKind::Unavailable,
+                        "error reading random device", err)
+                }
+            });
+        }
+        Err(Error::new(ErrorKind::Transient,
+                       "random device descriptor was replaced while reading"))
     }
 }
 
@@ -699,6 +822,74 @@ mod test {
         assert!(n_diff_bits >= v1.len() as u32);
     }
 
+    // Check the descriptor is not inherited by child processes.
+    #[cfg(target_os = "linux")]
+    fn assert_cloexec(fd: i32) {
+        use std::fs;
+        let info = fs::read_to_string(format!("/proc/self/fdinfo/{}", fd)).unwrap();
+        let flags = info.lines().find(|l| l.starts_with("flags:")).unwrap();
+        let flags = u32::from_str_radix(flags[6..].trim(), 8).unwrap();
+        const O_CLOEXEC: u32 = 0o2000000;
+        assert!(flags & O_CLOEXEC != 0);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn assert_cloexec(_fd: i32) {}
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_rng_shared_fd() {
+        use std::os::unix::io::AsRawFd;
+        use super::ReadRng;
+
+        let fd = || ReadRng::lock().as_ref().unwrap().file.as_raw_fd();
+        let mut a = ReadRng::open("/dev/urandom").unwrap();
+        let first = fd();
+        let mut b = ReadRng::open("/dev/urandom").unwrap();
+        let mut v = [0u8; 32];
+        a.try_fill_bytes(&mut v).unwrap();
+        b.try_fill_bytes(&mut v).unwrap();
+        // Both instances share the descriptor, opened once
+        assert_eq!(fd(), first);
+        assert_cloexec(first);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_rng_reopens_replaced_fd() {
+        extern crate libc;
+        use std::fs::File;
+        use std::os::unix::io::{AsRawFd, FromRawFd};
+        use super::ReadRng;
+
+        // Uses a private file rather than the one shared by `OsRng`, which
+        // other tests may be reading from.
+        let mut cached = None;
+        let mut v = [0u8; 32];
+        ReadRng::fill(&mut cached, "/dev/urandom", &mut v).unwrap();
+        let fd = cached.as_ref().unwrap().file.as_raw_fd();
+
+        // Replace the file behind the descriptor, as if it was closed and
+        // reused by someone else. `dup2` does this atomically, so no other
+        // thread can get the descriptor in between.
+        let other = File::open("Cargo.toml").unwrap();
+        assert_eq!(unsafe { libc::dup2(other.as_raw_fd(), fd) }, fd);
+
+        let mut v = [0u8; 4096];
+        ReadRng::fill(&mut cached, "/dev/urandom", &mut v).unwrap();
+        let reopened = cached.as_ref().unwrap().file.as_raw_fd();
+        assert!(reopened != fd);
+        assert_cloexec(reopened);
+        // Nothing was read from the other file
+        let mut contents = String::new();
+        ::std::io::Read::read_to_string(&mut &other, &mut contents).unwrap();
+        assert!(contents.starts_with("[package]"));
+
+        // `ReadRng` leaves the replaced descriptor open, since it no longer
+        // owns it; it is ours to close.
+        drop(unsafe { File::from_raw_fd(fd) });
+    }
+
     #[cfg(not(any(target_arch = "wasm32", target_arch = "asmjs")))]
     #[test]
     fn test_os_rng_tasks() {
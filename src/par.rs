@@ -0,0 +1,374 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parallel random generation with [Rayon].
+//!
+//! The functions of this module split their work into chunks of a fixed
+//! size, and give chunk `i` the stream `i` of a [`StreamFactory`]. The
+//! results thus only depend on the master seed of the factory and the
+//! arguments, not on the number of threads or the order in which the chunks
+//! are processed, so parallel Monte Carlo simulations are reproducible.
+//!
+//! [Rayon]: https://docs.rs/rayon
+//! [`StreamFactory`]: ../stream/struct.StreamFactory.html
+
+use core::{cmp, fmt, marker, ptr};
+use rayon::prelude::*;
+use rayon::iter::plumbing::UnindexedConsumer;
+
+use Rng;
+use distributions::Distribution;
+use distributions::range::Range;
+use stream::StreamFactory;
+
+// The number of values generated from one stream, and the largest slice
+// which is shuffled sequentially.
+const CHUNK: usize = 1 << 16;
+
+// The largest number of buckets `scatter_shuffle` distributes values to.
+const MAX_BUCKETS: usize = 256;
+
+/// Create a parallel iterator generating `amount` values sampled from
+/// `distr`.
+///
+/// Value `i` is sampled with the generator of stream `i / 65536` of
+/// `factory`, so the values are the same however many threads are used.
+/// Collecting the iterator keeps this order.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate rand;
+/// extern crate rayon;
+///
+/// use rand::distributions::Uniform;
+/// use rand::par::par_sample_iter;
+/// use rand::stream::StreamFactory;
+/// use rayon::prelude::*;
+///
+/// # fn main() {
+/// let factory = StreamFactory::new([42; 32]);
+/// let inside = par_sample_iter(factory, Uniform, 1_000_000)
+///     .filter(|&(x, y): &(f64, f64)| x * x + y * y < 1.0)
+///     .count();
+/// println!("pi is about {}", 4.0 * inside as f64 / 1_000_000.0);
+/// # }
+/// ```
+pub fn par_sample_iter<D, T>(factory: StreamFactory, distr: D, amount: usize)
+    -> ParSampleIter<D, T>
+    where D: Distribution<T> + Send + Sync, T: Send
+{
+    ParSampleIter {
+        factory: factory,
+        distr: distr,
+        amount: amount,
+        phantom: marker::PhantomData,
+    }
+}
+
+/// A parallel iterator generating values from a distribution.
+///
+/// This `struct` is created by the [`par_sample_iter`] function. See its
+/// documentation for more.
+///
+/// [`par_sample_iter`]: fn.par_sample_iter.html
+pub struct ParSampleIter<D, T> {
+    factory: StreamFactory,
+    distr: D,
+    amount: usize,
+    phantom: marker::PhantomData<fn() -> T>,
+}
+
+impl<D: fmt::Debug, T> fmt::Debug for ParSampleIter<D, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ParSampleIter")
+            .field("factory", &self.factory)
+            .field("distr", &self.distr)
+            .field("amount", &self.amount)
+            .finish()
+    }
+}
+
+impl<D, T> ParallelIterator for ParSampleIter<D, T>
+    where D: Distribution<T> + Send + Sync, T: Send
+{
+    type Item = T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+        where C: UnindexedConsumer<T>
+    {
+        let ParSampleIter { factory, distr, amount, .. } = self;
+        let chunks = amount / CHUNK + (amount % CHUNK != 0) as usize;
+        (0..chunks).into_par_iter()
+            .flat_map_iter(|chunk| {
+                let len = cmp::min(CHUNK, amount - chunk * CHUNK);
+                let mut rng = factory.rng_for(chunk as u64);
+                let distr = &distr;
+                (0..len).map(move |_| distr.sample(&mut rng))
+            })
+            .drive_unindexed(consumer)
+    }
+}
+
+/// Shuffle a mutable slice in place, in parallel.
+///
+/// Each value is sent to one of up to 256 buckets, chosen uniformly at
+/// random, and the buckets are then shuffled independently; this gives a
+/// uniformly random permutation. Slices of up to 65536 values are shuffled
+/// sequentially. This takes `O(n)` time and uses a temporary buffer as large
+/// as `values`.
+///
+/// The permutation only depends on `factory` and the length of `values`.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::par::par_shuffle;
+/// use rand::stream::StreamFactory;
+///
+/// let factory = StreamFactory::new([42; 32]);
+/// let mut deck = (0..1_000_000).collect::<Vec<u32>>();
+/// par_shuffle(&factory, &mut deck);
+/// ```
+pub fn par_shuffle<T: Send>(factory: &StreamFactory, values: &mut [T]) {
+    let len = values.len();
+    scatter_shuffle(factory, values, len);
+}
+
+/// Randomly sample exactly `amount` distinct indices from `0..length`, in
+/// parallel.
+///
+/// The indices are in random order. This shuffles a vector of all `length`
+/// indices with the algorithm of [`par_shuffle`], stopping once the first
+/// `amount` are chosen, so it takes `O(length)` time and memory; for
+/// `amount` much smaller than `length`, the sequential
+/// [`seq::sample_indices`] is faster.
+///
+/// Panics if `amount > length`.
+///
+/// [`par_shuffle`]: fn.par_shuffle.html
+/// [`seq::sample_indices`]: ../seq/fn.sample_indices.html
+pub fn par_sample_indices(factory: &StreamFactory, length: usize, amount: usize)
+    -> Vec<usize>
+{
+    assert!(amount <= length, "par_sample_indices: amount must be at most length");
+    let mut indices = (0..length).into_par_iter().collect::<Vec<_>>();
+    scatter_shuffle(factory, &mut indices, amount);
+    indices.truncate(amount);
+    indices
+}
+
+/// Randomly sample exactly `amount` references from `slice`, in parallel.
+///
+/// The references are in random order. See [`par_sample_indices`] for the
+/// algorithm used.
+///
+/// Panics if `amount > slice.len()`.
+///
+/// [`par_sample_indices`]: fn.par_sample_indices.html
+pub fn par_sample_slice_ref<'a, T: Sync>(factory: &StreamFactory, slice: &'a [T],
+                                         amount: usize) -> Vec<&'a T>
+{
+    par_sample_indices(factory, slice.len(), amount)
+        .into_par_iter()
+        .map(|i| &slice[i])
+        .collect()
+}
+
+// A pointer which may be shared between the threads writing to disjoint
+// elements through it.
+struct SharedPtr<T>(*mut T);
+
+unsafe impl<T: Send> Send for SharedPtr<T> {}
+unsafe impl<T: Send> Sync for SharedPtr<T> {}
+
+impl<T> SharedPtr<T> {
+    fn get(&self) -> *mut T {
+        self.0
+    }
+}
+
+// Shuffle `values` so that its first `amount` elements are a random sample
+// in random order; the others are left in no particular order.
+fn scatter_shuffle<T: Send>(factory: &StreamFactory, values: &mut [T], amount: usize) {
+    let len = values.len();
+    if len <= CHUNK {
+        factory.rng_for(0).partial_shuffle(values, amount);
+        return;
+    }
+
+    // Pick the bucket of every value, with one stream per part of `values`
+    let buckets = cmp::min(len / CHUNK, MAX_BUCKETS);
+    let part_len = (len - 1) / buckets + 1;
+    let parts = (len - 1) / part_len + 1;
+    let mut bucket_of = vec![0u8; len];
+    bucket_of.par_chunks_mut(part_len).enumerate().for_each(|(part, ids)| {
+        let mut rng = factory.rng_for(part as u64);
+        let range = Range::new_inclusive(0, (buckets - 1) as u8);
+        for id in ids.iter_mut() {
+            *id = range.sample(&mut rng);
+        }
+    });
+
+    // Count the values of each part in each bucket, and turn the counts into
+    // the positions where the values go: the buckets are placed one after
+    // the other, and within a bucket the values keep their order.
+    let mut next = bucket_of.par_chunks(part_len)
+        .flat_map_iter(|ids| {
+            let mut counts = vec![0usize; buckets];
+            for &id in ids {
+                counts[id as usize] += 1;
+            }
+            counts
+        })
+        .collect::<Vec<_>>();
+    let mut bucket_start = vec![0; buckets + 1];
+    let mut total = 0;
+    for bucket in 0..buckets {
+        bucket_start[bucket] = total;
+        for part in 0..parts {
+            let count = next[part * buckets + bucket];
+            next[part * buckets + bucket] = total;
+            total += count;
+        }
+    }
+    bucket_start[buckets] = len;
+
+    // Move the values to their buckets. They are copied to `scattered` and
+    // back; `scattered` stays empty, so the values are never dropped twice,
+    // and `values` still holds all of them if this panics.
+    let mut scattered = Vec::<T>::with_capacity(len);
+    {
+        let src = SharedPtr(values.as_mut_ptr());
+        let dst = SharedPtr(scattered.as_mut_ptr());
+        next.par_chunks_mut(buckets).enumerate().for_each(|(part, next)| {
+            let start = part * part_len;
+            let end = cmp::min(start + part_len, len);
+            for i in start..end {
+                let pos = &mut next[bucket_of[i] as usize];
+                // Safe: the positions of different values are distinct and
+                // less than `len`.
+                unsafe { ptr::copy_nonoverlapping(src.get().add(i), dst.get().add(*pos), 1) };
+                *pos += 1;
+            }
+        });
+    }
+    unsafe { ptr::copy_nonoverlapping(scattered.as_ptr(), values.as_mut_ptr(), len) };
+
+    // Shuffle the buckets holding the first `amount` positions
+    let mut rest = values;
+    let mut to_shuffle = Vec::new();
+    for bucket in 0..buckets {
+        if bucket_start[bucket] >= amount {
+            break;
+        }
+        let (values, tail) = {rest}.split_at_mut(bucket_start[bucket + 1] - bucket_start[bucket]);
+        rest = tail;
+        to_shuffle.push((bucket, values, amount - bucket_start[bucket]));
+    }
+    to_shuffle.into_par_iter().for_each(|(bucket, values, amount)| {
+        factory.rng_for((parts + bucket) as u64).partial_shuffle(values, amount);
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use rayon::ThreadPoolBuilder;
+    use rayon::prelude::*;
+
+    use RngCore;
+    use distributions::{Distribution, Uniform};
+    use stream::StreamFactory;
+    use super::{CHUNK, par_sample_iter, par_shuffle, par_sample_indices, par_sample_slice_ref};
+
+    // Run `f` with 1 and with 4 threads, and check it gives the same result.
+    fn same_with_threads<T, F>(f: F) -> T
+        where T: PartialEq + ::std::fmt::Debug + Send, F: Fn() -> T + Sync
+    {
+        let one = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let four = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let a = one.install(|| f());
+        let b = four.install(|| f());
+        assert_eq!(a, b);
+        a
+    }
+
+    #[test]
+    fn test_par_sample_iter() {
+        let factory = StreamFactory::new([7; 32]);
+        let amount = 3 * CHUNK + 5;
+        let values = same_with_threads(|| {
+            par_sample_iter(factory.clone(), Uniform, amount).collect::<Vec<u64>>()
+        });
+        assert_eq!(values.len(), amount);
+
+        let mut rng = factory.rng_for(0);
+        for &x in &values[..CHUNK] {
+            assert_eq!(x, Uniform.sample(&mut rng));
+        }
+        let mut rng = factory.rng_for(3);
+        for &x in &values[3 * CHUNK..] {
+            assert_eq!(x, rng.next_u64());
+        }
+
+        assert_eq!(par_sample_iter::<_, u8>(factory, Uniform, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_par_shuffle() {
+        let factory = StreamFactory::new([7; 32]);
+        for &len in &[0, 1, 100, 3 * CHUNK + 1, 300 * CHUNK] {
+            let values = same_with_threads(|| {
+                let mut values = (0..len).collect::<Vec<usize>>();
+                par_shuffle(&factory, &mut values);
+                values
+            });
+            let mut sorted = values.clone();
+            sorted.par_sort_unstable();
+            assert!(sorted.iter().enumerate().all(|(i, &x)| i == x));
+
+            if len >= 100 {
+                let fixed = values.iter().enumerate().filter(|&(i, &x)| i == x).count();
+                assert!(fixed < 10);
+                let mean = values[..100].iter().sum::<usize>() as f64 / 100.0;
+                assert!((mean / len as f64 - 0.5).abs() < 0.15);
+            }
+        }
+
+        // The values are moved, not copied
+        let mut values = (0..3 * CHUNK).map(|i| vec![i]).collect::<Vec<_>>();
+        par_shuffle(&factory, &mut values);
+        let mut sorted = values.iter().map(|v| v[0]).collect::<Vec<_>>();
+        sorted.sort();
+        assert!(sorted.iter().enumerate().all(|(i, &x)| i == x));
+    }
+
+    #[test]
+    fn test_par_sample_indices() {
+        let factory = StreamFactory::new([7; 32]);
+        for &(length, amount) in &[(0, 0), (10, 3), (10, 10), (5 * CHUNK, 2 * CHUNK + 3),
+                                   (5 * CHUNK, 5 * CHUNK)] {
+            let indices = same_with_threads(|| {
+                par_sample_indices(&factory, length, amount)
+            });
+            assert_eq!(indices.len(), amount);
+            let mut sorted = indices.clone();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(sorted.len(), amount);
+            assert!(sorted.iter().all(|&i| i < length));
+        }
+
+        let slice = (0..5 * CHUNK).map(|i| i * 2).collect::<Vec<_>>();
+        let sample = par_sample_slice_ref(&factory, &slice, 10);
+        let indices = par_sample_indices(&factory, slice.len(), 10);
+        assert!(sample.iter().zip(indices).all(|(&&x, i)| x == i * 2));
+    }
+}
@@ -0,0 +1,482 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generation of random probable primes.
+//!
+//! [`Prime`] is a distribution of random primes of a given bit length, and
+//! [`is_probable_prime`] the Miller-Rabin test it uses. Big numbers are
+//! represented as little-endian `u32` limbs, as for
+//! [`sample_limbs_below`]; they convert directly to and from the limbs of
+//! arbitrary-precision integer types such as those of `num-bigint`.
+//!
+//! Candidates are sampled uniformly from all odd numbers of the requested
+//! bit length and tested independently, so every prime of that length is
+//! equally likely. (Searching incrementally from a random starting point is
+//! faster, but favours primes following large gaps.)
+//!
+//! For cryptographic use, sample with a [`CryptoRng`] such as `OsRng` or
+//! `StdRng`. The arithmetic is not constant-time.
+//!
+//! This module requires the `prime` feature.
+//!
+//! [`Prime`]: struct.Prime.html
+//! [`is_probable_prime`]: fn.is_probable_prime.html
+//! [`sample_limbs_below`]: ../distributions/range/fn.sample_limbs_below.html
+//! [`CryptoRng`]: ../trait.CryptoRng.html
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+use Rng;
+use distributions::Distribution;
+use distributions::range::sample_limbs_below;
+
+/// The odd primes below 1000, for trial division.
+const SMALL_PRIMES: [u32; 167] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47,
+    53, 59, 61, 67, 71, 73, 79, 83, 89, 97, 101, 103, 107, 109,
+    113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191,
+    193, 197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269,
+    271, 277, 281, 283, 293, 307, 311, 313, 317, 331, 337, 347, 349, 353,
+    359, 367, 373, 379, 383, 389, 397, 401, 409, 419, 421, 431, 433, 439,
+    443, 449, 457, 461, 463, 467, 479, 487, 491, 499, 503, 509, 521, 523,
+    541, 547, 557, 563, 569, 571, 577, 587, 593, 599, 601, 607, 613, 617,
+    619, 631, 641, 643, 647, 653, 659, 661, 673, 677, 683, 691, 701, 709,
+    719, 727, 733, 739, 743, 751, 757, 761, 769, 773, 787, 797, 809, 811,
+    821, 823, 827, 829, 839, 853, 857, 859, 863, 877, 881, 883, 887, 907,
+    911, 919, 929, 937, 941, 947, 953, 967, 971, 977, 983, 991, 997
+];
+
+/// The default number of Miller-Rabin rounds.
+///
+/// Each round lets a composite number pass with probability at most 1/4, so
+/// the default bounds the error by 2<sup>-80</sup>; for random candidates
+/// the actual probability is far smaller.
+pub const DEFAULT_ROUNDS: usize = 40;
+
+/// A distribution of random probable primes with a given bit length.
+///
+/// Samples are little-endian `u32` limbs, `(bits + 31) / 32` of them, with
+/// the most significant bit `bits - 1` set.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, thread_rng};
+/// use rand::prime::Prime;
+///
+/// let mut rng = thread_rng();
+/// let p: Vec<u32> = rng.sample(Prime::new(128));
+/// assert_eq!(p.len(), 4);
+/// assert!(p[3] >= 1 << 31);
+///
+/// // A prime `p` such that `(p - 1) / 2` is prime too
+/// let safe = rng.sample(Prime::safe(64).with_rounds(20));
+/// assert_eq!(safe.len(), 2);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Prime {
+    bits: usize,
+    rounds: usize,
+    safe: bool,
+}
+
+impl Prime {
+    /// Random primes of exactly `bits` bits. Panics if `bits < 2`.
+    pub fn new(bits: usize) -> Prime {
+        assert!(bits >= 2, "Prime::new called with `bits < 2`");
+        Prime { bits: bits, rounds: DEFAULT_ROUNDS, safe: false }
+    }
+
+    /// Random safe primes of exactly `bits` bits: primes `p` such that
+    /// `(p - 1) / 2` is also prime. Panics if `bits < 3`.
+    ///
+    /// Safe primes are rare; generating one of more than a few hundred bits
+    /// can take minutes.
+    pub fn safe(bits: usize) -> Prime {
+        assert!(bits >= 3, "Prime::safe called with `bits < 3`");
+        Prime { bits: bits, rounds: DEFAULT_ROUNDS, safe: true }
+    }
+
+    /// Set the number of Miller-Rabin rounds (default [`DEFAULT_ROUNDS`]).
+    ///
+    /// [`DEFAULT_ROUNDS`]: constant.DEFAULT_ROUNDS.html
+    pub fn with_rounds(self, rounds: usize) -> Prime {
+        Prime { rounds: rounds, ..self }
+    }
+}
+
+impl Distribution<Vec<u32>> for Prime {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<u32> {
+        if !self.safe {
+            loop {
+                let n = random_odd(rng, self.bits);
+                if is_probable_prime(rng, &n, self.rounds) {
+                    return n;
+                }
+            }
+        }
+
+        loop {
+            // p = 2q + 1
+            let q = random_odd(rng, self.bits - 1);
+            if q.len() > 1 || q[0] > 1000 {
+                // Quick check that neither q nor p has a small factor
+                let sieved = SMALL_PRIMES.iter().any(|&d| {
+                    let r = rem_small(&q, d);
+                    r == 0 || (2 * r + 1) % d == 0
+                });
+                if sieved {
+                    continue;
+                }
+            }
+            if !is_probable_prime(rng, &q, self.rounds) {
+                continue;
+            }
+            let mut p = vec![0u32; (self.bits + 31) / 32];
+            let mut carry = 1;
+            for (x, &y) in p.iter_mut().zip(q.iter().chain(Some(&0))) {
+                *x = (y << 1) | carry;
+                carry = y >> 31;
+            }
+            if is_probable_prime(rng, &p, self.rounds) {
+                return p;
+            }
+        }
+    }
+}
+
+/// Test whether `n` (little-endian `u32` limbs) is probably prime, using
+/// trial division and `rounds` rounds of the Miller-Rabin test with random
+/// bases.
+///
+/// A prime always passes; a composite number passes with probability at most
+/// 4<sup>-rounds</sup>. Numbers below 10<sup>6</sup> are classified exactly by
+/// trial division.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::prime::is_probable_prime;
+///
+/// let mut rng = thread_rng();
+/// // 2^61 - 1
+/// assert!(is_probable_prime(&mut rng, &[0xffff_ffff, 0x1fff_ffff], 20));
+/// // 2^64 + 1 = 274177 * 67280421310721
+/// assert!(!is_probable_prime(&mut rng, &[1, 0, 1], 20));
+/// ```
+pub fn is_probable_prime<R: Rng + ?Sized>(rng: &mut R, n: &[u32],
+                                          rounds: usize) -> bool
+{
+    let len = n.iter().rposition(|&limb| limb != 0).map_or(0, |i| i + 1);
+    let n = &n[..len];
+    if len == 0 || (len == 1 && n[0] < 4) {
+        return len == 1 && n[0] >= 2;
+    }
+    if n[0] & 1 == 0 {
+        return false;
+    }
+    for &d in SMALL_PRIMES.iter() {
+        if rem_small(n, d) == 0 {
+            return len == 1 && n[0] == d;
+        }
+    }
+    if len == 1 && n[0] < 1000 * 1000 {
+        // No factor up to its square root
+        return true;
+    }
+
+    // n - 1 = d * 2^s with d odd
+    let mut n_minus_1 = n.to_vec();
+    n_minus_1[0] -= 1;  // n is odd
+    let s = trailing_zeros(&n_minus_1);
+    let d = shr(&n_minus_1, s);
+
+    let mont = Montgomery::new(n);
+    let one = mont.one();
+    let minus_one = mont.neg(&one);
+    // Bases are sampled from [2, n - 2]
+    let mut bound = n.to_vec();
+    sub_small(&mut bound, 3);
+    let mut base = vec![0u32; len];
+
+    'rounds: for _ in 0..rounds {
+        sample_limbs_below(rng, &bound, &mut base);
+        add_small(&mut base, 2);
+        let mut x = mont.pow(&mont.to_mont(&base), &d);
+        if x == one || x == minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = mont.mul(&x, &x);
+            if x == minus_one {
+                continue 'rounds;
+            }
+            if x == one {
+                break;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// A uniformly random odd number of exactly `bits` bits.
+fn random_odd<R: Rng + ?Sized>(rng: &mut R, bits: usize) -> Vec<u32> {
+    let mut n = vec![0u32; (bits + 31) / 32];
+    rng.fill(&mut n[..]);
+    let top = (bits - 1) % 32;
+    let last = n.len() - 1;
+    n[last] &= !0 >> (31 - top);
+    n[last] |= 1 << top;
+    n[0] |= 1;
+    n
+}
+
+// `n mod d`
+fn rem_small(n: &[u32], d: u32) -> u32 {
+    n.iter().rev().fold(0u64, |r, &limb| {
+        ((r << 32) | limb as u64) % d as u64
+    }) as u32
+}
+
+fn add_small(n: &mut [u32], x: u32) {
+    let mut carry = x;
+    for limb in n.iter_mut() {
+        let (sum, overflow) = limb.overflowing_add(carry);
+        *limb = sum;
+        carry = overflow as u32;
+    }
+}
+
+fn sub_small(n: &mut [u32], x: u32) {
+    let mut borrow = x;
+    for limb in n.iter_mut() {
+        let (diff, overflow) = limb.overflowing_sub(borrow);
+        *limb = diff;
+        borrow = overflow as u32;
+    }
+}
+
+// `a -= b`, returning the borrow.
+fn sub_in_place(a: &mut [u32], b: &[u32]) -> bool {
+    let mut borrow = false;
+    for (x, &y) in a.iter_mut().zip(b.iter()) {
+        let (d1, o1) = x.overflowing_sub(y);
+        let (d2, o2) = d1.overflowing_sub(borrow as u32);
+        *x = d2;
+        borrow = o1 || o2;
+    }
+    borrow
+}
+
+// `a >= b`, for numbers of the same length.
+fn ge(a: &[u32], b: &[u32]) -> bool {
+    for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+        if x != y {
+            return x > y;
+        }
+    }
+    true
+}
+
+fn trailing_zeros(n: &[u32]) -> usize {
+    let i = n.iter().position(|&limb| limb != 0).unwrap();
+    i * 32 + n[i].trailing_zeros() as usize
+}
+
+fn shr(n: &[u32], shift: usize) -> Vec<u32> {
+    let (limbs, bits) = (shift / 32, shift % 32);
+    let mut result = n[limbs..].to_vec();
+    if bits > 0 {
+        for i in 0..result.len() {
+            let next = result.get(i + 1).map_or(0, |&x| x << (32 - bits));
+            result[i] = (result[i] >> bits) | next;
+        }
+    }
+    result
+}
+
+// Arithmetic modulo an odd `n` in Montgomery form, with `R = 2^(32 * len)`.
+struct Montgomery<'a> {
+    n: &'a [u32],
+    // -1 / n mod 2^32
+    n0_inv: u32,
+    // R^2 mod n
+    r2: Vec<u32>,
+}
+
+impl<'a> Montgomery<'a> {
+    fn new(n: &'a [u32]) -> Montgomery<'a> {
+        // Newton's iteration doubles the number of correct low bits; any odd
+        // number is its own inverse modulo 8.
+        let mut inv = n[0];
+        for _ in 0..4 {
+            inv = inv.wrapping_mul(2u32.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+
+        // Double 1 until it is R^2 mod n
+        let mut r2 = vec![0u32; n.len()];
+        r2[0] = 1;
+        for _ in 0..(64 * n.len()) {
+            let mut carry = 0;
+            for limb in r2.iter_mut() {
+                let next = *limb >> 31;
+                *limb = (*limb << 1) | carry;
+                carry = next;
+            }
+            if carry == 1 || ge(&r2, n) {
+                sub_in_place(&mut r2, n);
+            }
+        }
+        Montgomery { n: n, n0_inv: inv.wrapping_neg(), r2: r2 }
+    }
+
+    // a * b / R mod n, for a, b < n
+    fn mul(&self, a: &[u32], b: &[u32]) -> Vec<u32> {
+        let k = self.n.len();
+        let mut t = vec![0u32; k + 2];
+        for &ai in a.iter() {
+            let mut carry = 0u64;
+            for (tj, &bj) in t.iter_mut().zip(b.iter()) {
+                let s = *tj as u64 + ai as u64 * bj as u64 + carry;
+                *tj = s as u32;
+                carry = s >> 32;
+            }
+            let s = t[k] as u64 + carry;
+            t[k] = s as u32;
+            t[k + 1] = (s >> 32) as u32;
+
+            // Add m * n to make t divisible by 2^32, and shift by one limb
+            let m = t[0].wrapping_mul(self.n0_inv);
+            let s = t[0] as u64 + m as u64 * self.n[0] as u64;
+            let mut carry = s >> 32;
+            for j in 1..k {
+                let s = t[j] as u64 + m as u64 * self.n[j] as u64 + carry;
+                t[j - 1] = s as u32;
+                carry = s >> 32;
+            }
+            let s = t[k] as u64 + carry;
+            t[k - 1] = s as u32;
+            t[k] = t[k + 1] + (s >> 32) as u32;
+            t[k + 1] = 0;
+        }
+        // t < 2n
+        if t[k] != 0 || ge(&t[..k], self.n) {
+            sub_in_place(&mut t[..k], self.n);
+        }
+        t.truncate(k);
+        t
+    }
+
+    fn to_mont(&self, a: &[u32]) -> Vec<u32> {
+        self.mul(a, &self.r2)
+    }
+
+    // R mod n, the Montgomery form of 1
+    fn one(&self) -> Vec<u32> {
+        let mut one = vec![0u32; self.n.len()];
+        one[0] = 1;
+        self.to_mont(&one)
+    }
+
+    // n - a, for 0 < a < n
+    fn neg(&self, a: &[u32]) -> Vec<u32> {
+        let mut result = self.n.to_vec();
+        sub_in_place(&mut result, a);
+        result
+    }
+
+    // base^exp, with `base` and the result in Montgomery form
+    fn pow(&self, base: &[u32], exp: &[u32]) -> Vec<u32> {
+        let mut result = self.one();
+        for &limb in exp.iter().rev() {
+            for bit in (0..32).rev() {
+                result = self.mul(&result, &result);
+                if (limb >> bit) & 1 == 1 {
+                    result = self.mul(&result, base);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {Rng, SeedableRng, ChaChaRng};
+    use super::{Prime, is_probable_prime, shr};
+
+    fn is_prime_naive(n: u64) -> bool {
+        n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+    }
+
+    fn to_limbs(n: u64) -> [u32; 2] {
+        [n as u32, (n >> 32) as u32]
+    }
+
+    fn bit_length(n: &[u32]) -> usize {
+        let top = n.iter().rposition(|&limb| limb != 0).unwrap();
+        top * 32 + 32 - n[top].leading_zeros() as usize
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        let mut rng = ChaChaRng::from_seed([8; 32]);
+        for n in 0..3000 {
+            assert_eq!(is_probable_prime(&mut rng, &[n as u32], 10),
+                       is_prime_naive(n), "{}", n);
+        }
+        for _ in 0..300 {
+            // Odd 40-bit numbers, past trial division
+            let n = rng.gen::<u64>() >> 24 | 1;
+            assert_eq!(is_probable_prime(&mut rng, &to_limbs(n), 10),
+                       is_prime_naive(n), "{}", n);
+        }
+
+        // Strong pseudoprime to bases 2, 3, 5 and 7
+        assert!(!is_probable_prime(&mut rng, &[3215031751], 10));
+        // 2^127 - 1, with a leading zero limb
+        assert!(is_probable_prime(&mut rng, &[!0, !0, !0, 0x7fff_ffff, 0], 10));
+        // (2^32 - 5) * (2^32 - 17)
+        assert!(!is_probable_prime(&mut rng, &[85, 4294967274], 10));
+        // 2^128 + 1
+        assert!(!is_probable_prime(&mut rng, &[1, 0, 0, 0, 1], 10));
+        assert!(!is_probable_prime(&mut rng, &[], 10));
+    }
+
+    #[test]
+    fn test_prime() {
+        let mut rng = ChaChaRng::from_seed([9; 32]);
+        for &bits in [2, 3, 5, 16, 32, 33, 64, 100, 256].iter() {
+            let p = rng.sample(Prime::new(bits));
+            assert_eq!(p.len(), (bits + 31) / 32);
+            assert_eq!(bit_length(&p), bits);
+            assert!(is_probable_prime(&mut rng, &p, 20));
+            if bits <= 32 {
+                assert!(is_prime_naive(p[0] as u64));
+            }
+        }
+    }
+
+    #[test]
+    fn test_safe_prime() {
+        let mut rng = ChaChaRng::from_seed([10; 32]);
+        for &bits in [3, 4, 10, 33, 64, 96].iter() {
+            let p = rng.sample(Prime::safe(bits).with_rounds(20));
+            assert_eq!(bit_length(&p), bits);
+            assert!(is_probable_prime(&mut rng, &p, 20));
+            let q = shr(&p, 1);
+            assert!(is_probable_prime(&mut rng, &q, 20));
+        }
+        // The only safe prime of 3 bits
+        assert_eq!(rng.sample(Prime::safe(3)), vec![7]);
+    }
+}
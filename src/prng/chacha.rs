@@ -10,8 +10,10 @@
 
 //! The ChaCha random number generator.
 
-use core::fmt;
-use rand_core::{RngCore, CryptoRng, SeedableRng, Error, impls, le};
+use core::{cmp, fmt, mem, slice};
+use rand_core::{RngCore, CryptoRng, SeedableRng, Error, impls};
+use prng::state::{StateSerializable, StateError, StateWriter, StateReader};
+use zeroize::zeroize;
 
 const SEED_WORDS: usize = 8; // 8 words for the 256-bit key
 const STATE_WORDS: usize = 16;
@@ -26,9 +28,11 @@ const STATE_WORDS: usize = 16;
 ///
 /// ChaCha uses add-rotate-xor (ARX) operations as its basis. These are safe
 /// against timing attacks, although that is mostly a concern for ciphers and
-/// not for RNGs. Also it is very suitable for SIMD implementation.
-/// Here we do not provide a SIMD implementation yet, except for what is
-/// provided by auto-vectorisation.
+/// not for RNGs. Also it is very suitable for SIMD implementation: on x86 and
+/// x86-64, `fill_bytes` and `fill_u32_slice` compute 4 blocks at a time with
+/// SSE2, or 8 with AVX2 if the CPU supports it (detected at runtime with the
+/// `std` feature). The output is the same as when generating the blocks one
+/// by one.
 ///
 /// With the ChaCha algorithm it is possible to choose the number of rounds the
 /// core algorithm should run. By default `ChaChaRng` is created as ChaCha20,
@@ -62,6 +66,7 @@ const STATE_WORDS: usize = 16;
 ///
 /// [`set_rounds`]: #method.set_counter
 #[derive(Clone)]
+#[cfg_attr(feature="serde-1", derive(Serialize))]
 pub struct ChaChaRng {
     buffer:  [u32; STATE_WORDS], // Internal buffer of output
     state:   [u32; STATE_WORDS], // Initial state
@@ -124,6 +129,36 @@ impl ChaChaRng {
         ChaChaRng::from_seed([0; SEED_WORDS*4])
     }
 
+    /// Create a `ChaChaRng` from a seed, exactly like
+    /// `SeedableRng::from_seed`.
+    ///
+    /// Being a `const fn`, this can initialize a `const` or `static`
+    /// generator without any code running at startup.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::{RngCore, ChaChaRng};
+    ///
+    /// const SEEDED: ChaChaRng = ChaChaRng::from_seed_const([7; 32]);
+    ///
+    /// let mut rng = SEEDED;
+    /// println!("{}", rng.next_u32());
+    /// ```
+    pub const fn from_seed_const(seed: [u8; SEED_WORDS*4]) -> ChaChaRng {
+        ChaChaRng {
+            buffer: [0; STATE_WORDS],
+            state: [0x61707865, 0x3320646E, 0x79622D32, 0x6B206574, // constants
+                    read_u32_le!(seed, 0), read_u32_le!(seed, 1),   // seed
+                    read_u32_le!(seed, 2), read_u32_le!(seed, 3),   // seed
+                    read_u32_le!(seed, 4), read_u32_le!(seed, 5),   // seed
+                    read_u32_le!(seed, 6), read_u32_le!(seed, 7),   // seed
+                    0, 0, 0, 0], // counter
+            index: STATE_WORDS, // generate on first use
+            rounds: 20,
+        }
+    }
+
     /// Sets the internal 128-bit ChaCha counter to a user-provided value. This
     /// permits jumping arbitrarily ahead (or backwards) in the pseudorandom
     /// stream.
@@ -186,23 +221,57 @@ impl ChaChaRng {
 
     /// Refill the internal output buffer (`self.buffer`)
     fn update(&mut self) {
-        // For some reason extracting this part into a separate function
-        // improves performance by 50%.
-        fn core(results: &mut [u32; STATE_WORDS],
-                state: &[u32; STATE_WORDS],
-                rounds: usize)
+        core(&mut self.buffer, &self.state, self.rounds);
+        self.index = 0;
+        self.increment_counter();
+    }
+
+    /// Write the next block of output to `results`, bypassing `self.buffer`
+    fn generate(&mut self, results: &mut [u32; STATE_WORDS]) {
+        core(results, &self.state, self.rounds);
+        self.increment_counter();
+    }
+
+    /// Write the next `results.len() / STATE_WORDS` blocks to `results`,
+    /// computing several blocks at a time where possible
+    fn generate_blocks(&mut self, results: &mut [u32]) {
+        debug_assert_eq!(results.len() % STATE_WORDS, 0);
+        let mut results = results;
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
         {
-            let mut tmp = *state;
-            for _ in 0..rounds / 2 {
-                double_round!(tmp);
+            if x86::has_avx2() {
+                while results.len() >= x86::AVX2_BLOCKS * STATE_WORDS {
+                    let (head, tail) =
+                        {results}.split_at_mut(x86::AVX2_BLOCKS * STATE_WORDS);
+                    unsafe { x86::core_avx2(head, &self.state, self.rounds) };
+                    self.add_counter(x86::AVX2_BLOCKS as u32);
+                    results = tail;
+                }
             }
-            for i in 0..STATE_WORDS {
-                results[i] = tmp[i].wrapping_add(state[i]);
+            #[cfg(target_feature = "sse2")]
+            while results.len() >= x86::SSE2_BLOCKS * STATE_WORDS {
+                let (head, tail) =
+                    {results}.split_at_mut(x86::SSE2_BLOCKS * STATE_WORDS);
+                unsafe { x86::core_sse2(head, &self.state, self.rounds) };
+                self.add_counter(x86::SSE2_BLOCKS as u32);
+                results = tail;
             }
         }
+        for block in results.chunks_mut(STATE_WORDS) {
+            let block = unsafe {
+                &mut *(block.as_mut_ptr() as *mut [u32; STATE_WORDS])
+            };
+            self.generate(block);
+        }
+    }
 
-        core(&mut self.buffer, &self.state, self.rounds);
-        self.index = 0;
+    fn add_counter(&mut self, n: u32) {
+        for _ in 0..n {
+            self.increment_counter();
+        }
+    }
+
+    fn increment_counter(&mut self) {
         // update 128-bit counter
         self.state[12] = self.state[12].wrapping_add(1);
         if self.state[12] != 0 { return };
@@ -214,6 +283,148 @@ impl ChaChaRng {
     }
 }
 
+// For some reason keeping this part in a separate function improves
+// performance by 50%.
+fn core(results: &mut [u32; STATE_WORDS],
+        state: &[u32; STATE_WORDS],
+        rounds: usize)
+{
+    let mut tmp = *state;
+    for _ in 0..rounds / 2 {
+        double_round!(tmp);
+    }
+    for i in 0..STATE_WORDS {
+        results[i] = tmp[i].wrapping_add(state[i]);
+    }
+}
+
+// The counter words (12 to 15) of `N` consecutive blocks starting with the
+// block of `state`, in the order `[word][block]`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+macro_rules! block_counters {
+    ($state:expr, $n:expr) => {{
+        let mut counters = [[0u32; $n]; 4];
+        let mut counter = [$state[12], $state[13], $state[14], $state[15]];
+        for block in 0..$n {
+            for word in 0..4 {
+                counters[word][block] = counter[word];
+            }
+            for word in 0..4 {
+                counter[word] = counter[word].wrapping_add(1);
+                if counter[word] != 0 { break; }
+            }
+        }
+        counters
+    }}
+}
+
+// Multi-block implementations of `core`. Each vector holds the same word of
+// several consecutive blocks, so the rounds work exactly like in `core`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")] use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")] use core::arch::x86_64::*;
+    use core::ops::BitXorAssign;
+    use super::STATE_WORDS;
+
+    pub const SSE2_BLOCKS: usize = 4;
+    pub const AVX2_BLOCKS: usize = 8;
+
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn has_avx2() -> bool {
+        is_x86_feature_detected!("avx2")
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[inline]
+    pub fn has_avx2() -> bool {
+        cfg!(target_feature = "avx2")
+    }
+
+    // A vector of `u32` lanes with the methods used by `double_round!`. The
+    // methods are only called from functions enabling `$feature`.
+    macro_rules! vector {
+        ($name:ident, $vec:ident, $add:ident, $xor:ident, $or:ident,
+         $sll:ident, $srl:ident) => {
+            #[derive(Clone, Copy)]
+            struct $name($vec);
+
+            impl $name {
+                #[inline(always)]
+                fn wrapping_add(self, other: $name) -> $name {
+                    unsafe { $name($add(self.0, other.0)) }
+                }
+
+                #[inline(always)]
+                fn rotate_left(self, n: u32) -> $name {
+                    unsafe {
+                        let left = _mm_cvtsi32_si128(n as i32);
+                        let right = _mm_cvtsi32_si128(32 - n as i32);
+                        $name($or($sll(self.0, left), $srl(self.0, right)))
+                    }
+                }
+            }
+
+            impl BitXorAssign for $name {
+                #[inline(always)]
+                fn bitxor_assign(&mut self, other: $name) {
+                    unsafe { self.0 = $xor(self.0, other.0) }
+                }
+            }
+        }
+    }
+
+    macro_rules! multi_core {
+        ($fn_name:ident, $feature:expr, $name:ident, $vec:ident, $blocks:expr,
+         $set1:ident, $loadu:ident, $storeu:ident) => {
+            #[target_feature(enable = $feature)]
+            pub unsafe fn $fn_name(results: &mut [u32],
+                                   state: &[u32; STATE_WORDS],
+                                   rounds: usize)
+            {
+                assert_eq!(results.len(), $blocks * STATE_WORDS);
+                let counters = block_counters!(state, $blocks);
+
+                let mut init = [$name($set1(0)); STATE_WORDS];
+                for i in 0..12 {
+                    init[i] = $name($set1(state[i] as i32));
+                }
+                for i in 0..4 {
+                    init[12 + i] =
+                        $name($loadu(counters[i].as_ptr() as *const $vec));
+                }
+
+                let mut tmp = init;
+                for _ in 0..rounds / 2 {
+                    double_round!(tmp);
+                }
+
+                let mut words = [[0u32; $blocks]; STATE_WORDS];
+                for i in 0..STATE_WORDS {
+                    let x = tmp[i].wrapping_add(init[i]);
+                    $storeu(words[i].as_mut_ptr() as *mut $vec, x.0);
+                }
+                for (block, out) in results.chunks_mut(STATE_WORDS).enumerate() {
+                    for i in 0..STATE_WORDS {
+                        out[i] = words[i][block];
+                    }
+                }
+            }
+        }
+    }
+
+    vector!(Sse2Vector, __m128i, _mm_add_epi32, _mm_xor_si128, _mm_or_si128,
+            _mm_sll_epi32, _mm_srl_epi32);
+    multi_core!(core_sse2, "sse2", Sse2Vector, __m128i, SSE2_BLOCKS,
+                _mm_set1_epi32, _mm_loadu_si128, _mm_storeu_si128);
+
+    vector!(Avx2Vector, __m256i, _mm256_add_epi32, _mm256_xor_si256,
+            _mm256_or_si256, _mm256_sll_epi32, _mm256_srl_epi32);
+    multi_core!(core_avx2, "avx2", Avx2Vector, __m256i, AVX2_BLOCKS,
+                _mm256_set1_epi32, _mm256_loadu_si256, _mm256_storeu_si256);
+}
+
 impl RngCore for ChaChaRng {
     #[inline]
     fn next_u32(&mut self) -> u32 {
@@ -235,42 +446,160 @@ impl RngCore for ChaChaRng {
     }
 
     fn fill_bytes(&mut self, dest: &mut [u8]) {
-        let mut read_len = 0;
-        while read_len < dest.len() {
-            if self.index >= self.buffer.len() {
-                self.update();
+        // Continue filling from the current block
+        let (consumed_u32, mut filled) =
+            impls::fill_via_u32_chunks(&self.buffer[self.index..], dest);
+        self.index += consumed_u32;
+
+        // Write whole blocks straight into `dest`, without copying them
+        // through `self.buffer`
+        const BLOCK_BYTES: usize = STATE_WORDS * 4;
+        let len_remainder = (dest.len() - filled) % BLOCK_BYTES;
+        let len_direct = dest.len() - len_remainder;
+        let direct = &mut dest[filled..len_direct];
+        if cfg!(target_endian = "little")
+            && direct.as_ptr() as usize & (mem::align_of::<u32>() - 1) == 0
+        {
+            // The blocks can be generated in place
+            let words = unsafe {
+                slice::from_raw_parts_mut(direct.as_mut_ptr() as *mut u32,
+                                          direct.len() / 4)
+            };
+            self.generate_blocks(words);
+        } else {
+            let mut blocks = [0; 8 * STATE_WORDS];
+            for chunk in direct.chunks_mut(blocks.len() * 4) {
+                let words = &mut blocks[..chunk.len() / 4];
+                self.generate_blocks(words);
+                impls::fill_via_u32_chunks(words, chunk);
             }
+        }
+        filled = len_direct;
 
-            let (consumed_u32, filled_u8) =
-                impls::fill_via_u32_chunks(&self.buffer[self.index..],
-                                           &mut dest[read_len..]);
-
-            self.index += consumed_u32;
-            read_len += filled_u8;
+        if len_remainder > 0 {
+            self.update();
+            let (consumed_u32, _) =
+                impls::fill_via_u32_chunks(&self.buffer, &mut dest[filled..]);
+            self.index = consumed_u32;
         }
     }
 
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         Ok(self.fill_bytes(dest))
     }
+
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        let mut filled = 0;
+        while filled < dest.len() {
+            let left = &mut dest[filled..];
+            if self.index >= STATE_WORDS {
+                if left.len() >= STATE_WORDS {
+                    // Generate whole blocks in place
+                    let n = left.len() - left.len() % STATE_WORDS;
+                    self.generate_blocks(&mut left[..n]);
+                    filled += n;
+                    continue;
+                }
+                self.update();
+            }
+            let n = cmp::min(STATE_WORDS - self.index, left.len());
+            left[..n].copy_from_slice(&self.buffer[self.index..self.index + n]);
+            self.index += n;
+            filled += n;
+        }
+    }
 }
 
 impl CryptoRng for ChaChaRng {}
 
 impl SeedableRng for ChaChaRng {
     type Seed = [u8; SEED_WORDS*4];
-    fn from_seed(seed: Self::Seed) -> Self {
-        let mut seed_le = [0u32; SEED_WORDS];
-        le::read_u32_into(&seed, &mut seed_le);
-        ChaChaRng {
+    fn from_seed(mut seed: Self::Seed) -> Self {
+        let rng = ChaChaRng::from_seed_const(seed);
+        zeroize(&mut seed);
+        rng
+    }
+}
+
+#[cfg(feature="zeroize")]
+impl Drop for ChaChaRng {
+    fn drop(&mut self) {
+        zeroize(&mut self.buffer);
+        zeroize(&mut self.state);
+    }
+}
+
+/// The state is encoded as the 16 `u32` words of the output buffer, the 16
+/// words of the ChaCha state, then the index into the buffer and the number
+/// of rounds, each as a `u32`.
+impl StateSerializable for ChaChaRng {
+    const STATE_LEN: usize = 1 + 4 * (2 * STATE_WORDS + 2);
+
+    fn to_state_bytes(&self, dest: &mut [u8]) {
+        let mut out = StateWriter::new(dest, Self::STATE_LEN);
+        for &x in self.buffer.iter().chain(self.state.iter()) {
+            out.u32(x);
+        }
+        out.usize(self.index);
+        out.usize(self.rounds);
+    }
+
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        let mut r = StateReader::new(bytes, Self::STATE_LEN)?;
+        let mut rng = ChaChaRng {
             buffer: [0; STATE_WORDS],
-            state: [0x61707865, 0x3320646E, 0x79622D32, 0x6B206574, // constants
-                    seed_le[0], seed_le[1], seed_le[2], seed_le[3], // seed
-                    seed_le[4], seed_le[5], seed_le[6], seed_le[7], // seed
-                    0, 0, 0, 0], // counter
-            index: STATE_WORDS, // generate on first use
-            rounds: 20,
-         }
+            state: [0; STATE_WORDS],
+            index: 0,
+            rounds: 0,
+        };
+        for x in rng.buffer.iter_mut().chain(rng.state.iter_mut()) {
+            *x = r.u32();
+        }
+        rng.index = r.usize();
+        rng.rounds = r.usize();
+        if !rng.is_valid() {
+            return Err(StateError::InvalidState);
+        }
+        Ok(rng)
+    }
+}
+
+impl ChaChaRng {
+    // Whether `index` and `rounds` are in range, which a restored state may
+    // not be.
+    fn is_valid(&self) -> bool {
+        self.index <= STATE_WORDS && self.rounds % 4 == 0 && self.rounds <= 20
+            && self.rounds != 0
+    }
+}
+
+// Deserializing checks the state like `from_state_bytes`, as the generator
+// would panic on an out-of-range `index`.
+#[cfg(feature="serde-1")]
+impl<'de> ::serde::Deserialize<'de> for ChaChaRng {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: ::serde::Deserializer<'de>
+    {
+        #[derive(Deserialize)]
+        #[serde(rename="ChaChaRng")]
+        struct State {
+            buffer: [u32; STATE_WORDS],
+            state: [u32; STATE_WORDS],
+            index: usize,
+            rounds: usize,
+        }
+
+        let s = State::deserialize(deserializer)?;
+        let rng = ChaChaRng {
+            buffer: s.buffer,
+            state: s.state,
+            index: s.index,
+            rounds: s.rounds,
+        };
+        if !rng.is_valid() {
+            return Err(::serde::de::Error::custom(StateError::InvalidState));
+        }
+        Ok(rng)
     }
 }
 
@@ -442,4 +771,152 @@ mod test {
             assert_eq!(rng.next_u64(), clone.next_u64());
         }
     }
+
+    #[test]
+    #[cfg(all(feature="serde-1", feature="std"))]
+    fn test_chacha_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        // Serialize in the middle of a block
+        rng.next_u32();
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: ChaChaRng = bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        for _ in 0..40 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+
+        // An out-of-range `index` or `rounds` is rejected
+        let len = buf.len();
+        let mut bad = buf.clone();
+        bad[len - 16] = 17;
+        assert!(bincode::deserialize::<ChaChaRng>(&bad).is_err());
+        let mut bad = buf.clone();
+        bad[len - 8] = 7;
+        assert!(bincode::deserialize::<ChaChaRng>(&bad).is_err());
+    }
+
+    #[test]
+    fn test_chacha_state_bytes() {
+        use prng::StateSerializable;
+
+        let mut rng = ChaChaRng::from_seed([0; 32]);
+        for _ in 0..3 {
+            rng.next_u32();
+        }
+        let mut state = [0u8; ChaChaRng::STATE_LEN];
+        rng.to_state_bytes(&mut state);
+        let mut restored = ChaChaRng::from_state_bytes(&state).unwrap();
+        let mut state2 = [0u8; ChaChaRng::STATE_LEN];
+        restored.to_state_bytes(&mut state2);
+        assert_eq!(&state[..], &state2[..]);
+        for _ in 0..40 {
+            assert_eq!(rng.next_u64(), restored.next_u64());
+        }
+
+        // An invalid number of rounds is rejected
+        state[133] = 7;
+        assert!(ChaChaRng::from_state_bytes(&state).is_err());
+    }
+
+    #[test]
+    fn test_chacha_from_seed_const() {
+        const SEED: [u8; 32] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13,
+                                14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+                                25, 26, 27, 28, 29, 30, 31];
+        static SEEDED: ChaChaRng = ChaChaRng::from_seed_const(SEED);
+        let mut rng1 = SEEDED.clone();
+        let mut rng2 = ChaChaRng::from_seed(SEED);
+        for _ in 0..40 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_chacha_fill_bytes_blocks() {
+        // Filling directly from whole blocks must give the same stream as
+        // going through the buffer word by word.
+        let mut rng1 = ChaChaRng::from_seed([9; 32]);
+        let mut rng2 = ChaChaRng::from_seed([9; 32]);
+        for &len in [0, 3, 64, 100, 128, 7, 1000, 61].iter() {
+            let mut bytes = [0u8; 1000];
+            rng1.fill_bytes(&mut bytes[..len]);
+            let mut expected = [0u8; 1004];
+            for chunk in expected[..(len + 3) / 4 * 4].chunks_mut(4) {
+                let x = rng2.next_u32();
+                for (i, b) in chunk.iter_mut().enumerate() {
+                    *b = (x >> (8 * i)) as u8;
+                }
+            }
+            // A partially used word is discarded by both
+            assert_eq!(&bytes[..len], &expected[..len]);
+        }
+
+        // Aligned and unaligned destinations
+        let mut words = [0u32; 40];
+        ::Rng::fill(&mut rng1, &mut words[..]);
+        for &x in words.iter() {
+            assert_eq!(x.to_le(), rng2.next_u32());
+        }
+        let mut bytes = [0u8; 200];
+        rng1.fill_bytes(&mut bytes[1..193]);
+        for chunk in bytes[1..193].chunks(4) {
+            let x = rng2.next_u32();
+            assert_eq!(chunk, &[x as u8, (x >> 8) as u8, (x >> 16) as u8, (x >> 24) as u8]);
+        }
+    }
+
+    #[test]
+    fn test_chacha_multi_block() {
+        // 20 blocks use every multi-block implementation available, and
+        // must match generating the blocks one by one, also when the
+        // counter carries into the next word.
+        for &(low, high) in [(0, 0), (0xffff_fffd, 0), (!0 - 5, 7),
+                             (!0 - 2, !0)].iter() {
+            for &rounds in [8, 20].iter() {
+                let mut rng1 = ChaChaRng::from_seed([4; 32]);
+                rng1.set_counter(low, high);
+                rng1.set_rounds(rounds);
+                let mut rng2 = rng1.clone();
+
+                let mut words = [0u32; 20 * 16];
+                rng1.fill_u32_slice(&mut words);
+                for &x in words.iter() {
+                    assert_eq!(x, rng2.next_u32());
+                }
+
+                let mut bytes = [0u8; 20 * 64 + 1];
+                rng1.fill_bytes(&mut bytes[1..]);
+                for chunk in bytes[1..].chunks(4) {
+                    let x = rng2.next_u32();
+                    assert_eq!(chunk, &[x as u8, (x >> 8) as u8,
+                                        (x >> 16) as u8, (x >> 24) as u8]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_chacha_fill_u32_slice() {
+        let mut rng1 = ChaChaRng::from_seed([7; 32]);
+        let mut rng2 = ChaChaRng::from_seed([7; 32]);
+        let mut buf = [0u32; 100];
+        for &len in [0, 3, 16, 40, 1, 100, 17].iter() {
+            rng1.fill_u32_slice(&mut buf[..len]);
+            for &x in buf[..len].iter() {
+                assert_eq!(x, rng2.next_u32());
+            }
+            // Mixing with single words
+            rng1.next_u32();
+            rng2.next_u32();
+        }
+    }
 }
@@ -10,8 +10,10 @@
 
 //! The HC-128 random number generator.
 
-use core::fmt;
+use core::{cmp, fmt};
 use rand_core::{RngCore, CryptoRng, SeedableRng, Error, impls, le};
+use prng::state::{StateSerializable, StateError, StateWriter, StateReader};
+use zeroize::zeroize;
 
 const SEED_WORDS: usize = 8; // 128 bit key followed by 128 bit iv
 
@@ -61,6 +63,7 @@ const SEED_WORDS: usize = 8; // 128 bit key followed by 128 bit iv
 /// [5]: Internet Engineering Task Force (Februari 2015),
 ///      ["Prohibiting RC4 Cipher Suites"](https://tools.ietf.org/html/rfc7465).
 #[derive(Clone)]
+#[cfg_attr(feature="serde-1", derive(Serialize))]
 pub struct Hc128Rng {
     state: Hc128,
     results: [u32; 16],
@@ -68,7 +71,9 @@ pub struct Hc128Rng {
 }
 
 #[derive(Copy)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
 struct Hc128 {
+    #[cfg_attr(feature="serde-1",serde(with="super::isaac_serde::hc128_serde"))]
     t: [u32; 1024],
     counter1024: usize,
 }
@@ -92,7 +97,7 @@ impl Hc128Rng {
     // Initialize an HC-128 random number generator. The seed has to be
     // 256 bits in length (`[u32; 8]`), matching the 128 bit `key` followed by
     // 128 bit `iv` when HC-128 where to be used as a stream cipher.
-    fn init(seed: [u32; SEED_WORDS]) -> Self {
+    fn init(mut seed: [u32; SEED_WORDS]) -> Self {
         #[inline]
         fn f1(x: u32) -> u32 {
             x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
@@ -134,6 +139,8 @@ impl Hc128Rng {
             results: [0; 16],
             index: 16, // generate on first use
         };
+        zeroize(&mut t);
+        zeroize(&mut seed);
 
         // run the cipher 1024 steps
         for _ in 0..64 { state.state.sixteen_steps() };
@@ -395,6 +402,27 @@ impl RngCore for Hc128Rng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         Ok(self.fill_bytes(dest))
     }
+
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        let mut filled = 0;
+        while filled < dest.len() {
+            let left = &mut dest[filled..];
+            if self.index >= 16 {
+                if left.len() >= 16 {
+                    // Generate the next 16 words in place
+                    self.state.update(&mut left[..16]);
+                    filled += 16;
+                    continue;
+                }
+                self.state.update(&mut self.results);
+                self.index = 0;
+            }
+            let n = cmp::min(16 - self.index, left.len());
+            left[..n].copy_from_slice(&self.results[self.index..self.index + n]);
+            self.index += n;
+            filled += n;
+        }
+    }
 }
 
 impl CryptoRng for Hc128Rng {}
@@ -405,10 +433,98 @@ impl SeedableRng for Hc128Rng {
     /// Create an HC-128 random number generator with a seed. The seed has to be
     /// 256 bits in length, matching the 128 bit `key` followed by 128 bit `iv`
     /// when HC-128 where to be used as a stream cipher.
-    fn from_seed(seed: Self::Seed) -> Self {
+    fn from_seed(mut seed: Self::Seed) -> Self {
         let mut seed_u32 = [0u32; SEED_WORDS];
         le::read_u32_into(&seed, &mut seed_u32);
-        Hc128Rng::init(seed_u32)
+        let rng = Hc128Rng::init(seed_u32);
+        zeroize(&mut seed);
+        zeroize(&mut seed_u32);
+        rng
+    }
+}
+
+#[cfg(feature="zeroize")]
+impl Drop for Hc128Rng {
+    fn drop(&mut self) {
+        zeroize(&mut self.state.t);
+        zeroize(&mut self.results);
+    }
+}
+
+/// The state is encoded as the 1024 `u32` words of the HC-128 tables, the
+/// step counter, the 16 words of the output buffer and the index into the
+/// buffer, each as a `u32`.
+impl StateSerializable for Hc128Rng {
+    const STATE_LEN: usize = 1 + 4 * (1024 + 1 + 16 + 1);
+
+    fn to_state_bytes(&self, dest: &mut [u8]) {
+        let mut out = StateWriter::new(dest, Self::STATE_LEN);
+        for &x in self.state.t.iter() {
+            out.u32(x);
+        }
+        out.usize(self.state.counter1024);
+        for &x in self.results.iter() {
+            out.u32(x);
+        }
+        out.usize(self.index);
+    }
+
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        let mut r = StateReader::new(bytes, Self::STATE_LEN)?;
+        let mut rng = Hc128Rng {
+            state: Hc128 { t: [0; 1024], counter1024: 0 },
+            results: [0; 16],
+            index: 0,
+        };
+        for x in rng.state.t.iter_mut() {
+            *x = r.u32();
+        }
+        rng.state.counter1024 = r.usize();
+        for x in rng.results.iter_mut() {
+            *x = r.u32();
+        }
+        rng.index = r.usize();
+        if !rng.is_valid() {
+            return Err(StateError::InvalidState);
+        }
+        Ok(rng)
+    }
+}
+
+impl Hc128Rng {
+    // Whether `index` and `counter1024` are in range, which a restored state
+    // may not be.
+    fn is_valid(&self) -> bool {
+        self.state.counter1024 % 16 == 0 && self.state.counter1024 < 1024
+            && self.index <= 16
+    }
+}
+
+// Deserializing checks the state like `from_state_bytes`, as the generator
+// would panic on an out-of-range `index` or `counter1024`.
+#[cfg(feature="serde-1")]
+impl<'de> ::serde::Deserialize<'de> for Hc128Rng {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: ::serde::Deserializer<'de>
+    {
+        #[derive(Deserialize)]
+        #[serde(rename="Hc128Rng")]
+        struct State {
+            state: Hc128,
+            results: [u32; 16],
+            index: usize,
+        }
+
+        let s = State::deserialize(deserializer)?;
+        let rng = Hc128Rng {
+            state: s.state,
+            results: s.results,
+            index: s.index,
+        };
+        if !rng.is_valid() {
+            return Err(::serde::de::Error::custom(StateError::InvalidState));
+        }
+        Ok(rng)
     }
 }
 
@@ -539,4 +655,71 @@ mod test {
             assert_eq!(rng1.next_u32(), rng2.next_u32());
         }
     }
+
+    #[test]
+    #[cfg(all(feature="serde-1", feature="std"))]
+    fn test_hc128_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Hc128Rng::from_seed([7; 32]);
+        // Serialize in the middle of a block
+        rng.next_u32();
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Hc128Rng = bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        for _ in 0..600 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+
+        // An out-of-range `index` or `counter1024` is rejected
+        let len = buf.len();
+        let mut bad = buf.clone();
+        bad[len - 8] = 17;
+        assert!(bincode::deserialize::<Hc128Rng>(&bad).is_err());
+        let mut bad = buf.clone();
+        bad[len - 8 - 16 * 4 - 8] = 3;
+        assert!(bincode::deserialize::<Hc128Rng>(&bad).is_err());
+    }
+
+    #[test]
+    fn test_hc128_state_bytes() {
+        use prng::StateSerializable;
+
+        let mut rng = Hc128Rng::from_seed([0; 32]);
+        for _ in 0..37 {
+            rng.next_u32();
+        }
+        let mut state = [0u8; Hc128Rng::STATE_LEN];
+        rng.to_state_bytes(&mut state);
+        let mut restored = Hc128Rng::from_state_bytes(&state).unwrap();
+        let mut state2 = [0u8; Hc128Rng::STATE_LEN];
+        restored.to_state_bytes(&mut state2);
+        assert_eq!(&state[..], &state2[..]);
+        for _ in 0..600 {
+            assert_eq!(rng.next_u64(), restored.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_hc128_fill_u32_slice() {
+        let mut rng1 = Hc128Rng::from_seed([7; 32]);
+        let mut rng2 = Hc128Rng::from_seed([7; 32]);
+        let mut buf = [0u32; 100];
+        for &len in [0, 3, 16, 40, 1, 100, 17].iter() {
+            rng1.fill_u32_slice(&mut buf[..len]);
+            for &x in buf[..len].iter() {
+                assert_eq!(x, rng2.next_u32());
+            }
+            // Mixing with single words
+            rng1.next_u32();
+            rng2.next_u32();
+        }
+    }
 }
@@ -10,10 +10,11 @@
 
 //! The ISAAC random number generator.
 
-use core::{fmt, slice};
+use core::{cmp, fmt, slice};
 use core::num::Wrapping as w;
 
 use rand_core::{RngCore, SeedableRng, Error, impls, le};
+use prng::state::{StateSerializable, StateError, StateWriter, StateReader};
 
 #[allow(non_camel_case_types)]
 type w32 = w<u32>;
@@ -252,6 +253,21 @@ impl RngCore for IsaacRng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         Ok(self.fill_bytes(dest))
     }
+
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        let mut filled = 0;
+        while filled < dest.len() {
+            if self.index as usize >= RAND_SIZE {
+                self.isaac();
+            }
+
+            let index = self.index as usize;
+            let n = cmp::min(RAND_SIZE - index, dest.len() - filled);
+            dest[filled..filled + n].copy_from_slice(&self.rsl[index..index + n]);
+            self.index += n as u32;
+            filled += n;
+        }
+    }
 }
 
 /// Creates a new ISAAC random number generator.
@@ -349,7 +365,7 @@ impl SeedableRng for IsaacRng {
         init(seed_extended, 2)
     }
 
-    fn from_rng<R: RngCore>(rng: &mut R) -> Result<Self, Error> {
+    fn from_rng<R: RngCore + ?Sized>(rng: &mut R) -> Result<Self, Error> {
         // Custom `from_rng` implementation that fills a seed with the same size
         // as the entire state.
         let mut seed = [w(0u32); RAND_SIZE];
@@ -367,6 +383,53 @@ impl SeedableRng for IsaacRng {
     }
 }
 
+/// The state is encoded as the 256 `u32` words of the output buffer, the 256
+/// words of the internal memory, `a`, `b`, `c` and the index into the buffer,
+/// each as a `u32`.
+impl StateSerializable for IsaacRng {
+    const STATE_LEN: usize = 1 + 4 * (2 * RAND_SIZE + 4);
+
+    fn to_state_bytes(&self, dest: &mut [u8]) {
+        let mut out = StateWriter::new(dest, Self::STATE_LEN);
+        for &x in self.rsl.iter() {
+            out.u32(x);
+        }
+        for x in self.mem.iter() {
+            out.u32(x.0);
+        }
+        out.u32(self.a.0);
+        out.u32(self.b.0);
+        out.u32(self.c.0);
+        out.u32(self.index);
+    }
+
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        let mut r = StateReader::new(bytes, Self::STATE_LEN)?;
+        let mut rng = IsaacRng {
+            rsl: [0; RAND_SIZE],
+            mem: [w(0); RAND_SIZE],
+            a: w(0),
+            b: w(0),
+            c: w(0),
+            index: 0,
+        };
+        for x in rng.rsl.iter_mut() {
+            *x = r.u32();
+        }
+        for x in rng.mem.iter_mut() {
+            *x = w(r.u32());
+        }
+        rng.a = w(r.u32());
+        rng.b = w(r.u32());
+        rng.c = w(r.u32());
+        rng.index = r.u32();
+        if rng.index as usize > RAND_SIZE {
+            return Err(StateError::InvalidState);
+        }
+        Ok(rng)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use {RngCore, SeedableRng};
@@ -501,4 +564,39 @@ mod test {
             assert_eq!(rng.next_u64(), deserialized.next_u64());
         }
     }
+
+    #[test]
+    fn test_isaac_state_bytes() {
+        use prng::StateSerializable;
+
+        let mut rng = IsaacRng::from_seed([1; 32]);
+        for _ in 0..300 {
+            rng.next_u32();
+        }
+        let mut state = [0u8; IsaacRng::STATE_LEN];
+        rng.to_state_bytes(&mut state);
+        let mut restored = IsaacRng::from_state_bytes(&state).unwrap();
+        let mut state2 = [0u8; IsaacRng::STATE_LEN];
+        restored.to_state_bytes(&mut state2);
+        assert_eq!(&state[..], &state2[..]);
+        for _ in 0..600 {
+            assert_eq!(rng.next_u64(), restored.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_isaac_fill_u32_slice() {
+        let mut rng1 = IsaacRng::from_seed([7; 32]);
+        let mut rng2 = IsaacRng::from_seed([7; 32]);
+        let mut buf = [0u32; 600];
+        for &len in [0, 3, 16, 40, 1, 600, 17].iter() {
+            rng1.fill_u32_slice(&mut buf[..len]);
+            for &x in buf[..len].iter() {
+                assert_eq!(x, rng2.next_u32());
+            }
+            // Mixing with single words
+            rng1.next_u32();
+            rng2.next_u32();
+        }
+    }
 }
@@ -10,10 +10,11 @@
 
 //! The ISAAC-64 random number generator.
 
-use core::{fmt, slice};
+use core::{cmp, fmt, slice};
 use core::num::Wrapping as w;
 
 use rand_core::{RngCore, SeedableRng, Error, impls, le};
+use prng::state::{StateSerializable, StateError, StateWriter, StateReader};
 
 #[allow(non_camel_case_types)]
 type w64 = w<u64>;
@@ -256,6 +257,22 @@ impl RngCore for Isaac64Rng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         Ok(self.fill_bytes(dest))
     }
+
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        let mut filled = 0;
+        while filled < dest.len() {
+            if self.index as usize >= RAND_SIZE {
+                self.isaac64();
+            }
+
+            let index = self.index as usize;
+            let n = cmp::min(RAND_SIZE - index, dest.len() - filled);
+            dest[filled..filled + n].copy_from_slice(&self.rsl[index..index + n]);
+            self.index += n as u32;
+            self.half_used = false;
+            filled += n;
+        }
+    }
 }
 
 /// Creates a new ISAAC-64 random number generator.
@@ -325,7 +342,7 @@ impl SeedableRng for Isaac64Rng {
         init(seed_extended, 2)
     }
 
-    fn from_rng<R: RngCore>(rng: &mut R) -> Result<Self, Error> {
+    fn from_rng<R: RngCore + ?Sized>(rng: &mut R) -> Result<Self, Error> {
         // Custom `from_rng` implementation that fills a seed with the same size
         // as the entire state.
         let mut seed = [w(0u64); RAND_SIZE];
@@ -343,6 +360,61 @@ impl SeedableRng for Isaac64Rng {
     }
 }
 
+/// The state is encoded as the 256 `u64` words of the output buffer, the 256
+/// words of the internal memory and `a`, `b` and `c`, followed by the index
+/// into the buffer as a `u32` and a byte marking whether half of the
+/// previous word was used.
+impl StateSerializable for Isaac64Rng {
+    const STATE_LEN: usize = 1 + 8 * (2 * RAND_SIZE + 3) + 4 + 1;
+
+    fn to_state_bytes(&self, dest: &mut [u8]) {
+        let mut out = StateWriter::new(dest, Self::STATE_LEN);
+        for &x in self.rsl.iter() {
+            out.u64(x);
+        }
+        for x in self.mem.iter() {
+            out.u64(x.0);
+        }
+        out.u64(self.a.0);
+        out.u64(self.b.0);
+        out.u64(self.c.0);
+        out.u32(self.index);
+        out.u8(self.half_used as u8);
+    }
+
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        let mut r = StateReader::new(bytes, Self::STATE_LEN)?;
+        let mut rng = Isaac64Rng {
+            rsl: [0; RAND_SIZE],
+            mem: [w(0); RAND_SIZE],
+            a: w(0),
+            b: w(0),
+            c: w(0),
+            index: 0,
+            half_used: false,
+        };
+        for x in rng.rsl.iter_mut() {
+            *x = r.u64();
+        }
+        for x in rng.mem.iter_mut() {
+            *x = w(r.u64());
+        }
+        rng.a = w(r.u64());
+        rng.b = w(r.u64());
+        rng.c = w(r.u64());
+        rng.index = r.u32();
+        rng.half_used = match r.u8() {
+            0 => false,
+            1 => true,
+            _ => return Err(StateError::InvalidState),
+        };
+        if rng.index as usize > RAND_SIZE || (rng.half_used && rng.index == 0) {
+            return Err(StateError::InvalidState);
+        }
+        Ok(rng)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use {RngCore, SeedableRng};
@@ -507,4 +579,39 @@ mod test {
             assert_eq!(rng.next_u64(), deserialized.next_u64());
         }
     }
+
+    #[test]
+    fn test_isaac64_state_bytes() {
+        use prng::StateSerializable;
+
+        let mut rng = Isaac64Rng::from_seed([1; 32]);
+        for _ in 0..301 {
+            rng.next_u32();
+        }
+        let mut state = [0u8; Isaac64Rng::STATE_LEN];
+        rng.to_state_bytes(&mut state);
+        let mut restored = Isaac64Rng::from_state_bytes(&state).unwrap();
+        let mut state2 = [0u8; Isaac64Rng::STATE_LEN];
+        restored.to_state_bytes(&mut state2);
+        assert_eq!(&state[..], &state2[..]);
+        for _ in 0..600 {
+            assert_eq!(rng.next_u64(), restored.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_isaac64_fill_u64_slice() {
+        let mut rng1 = Isaac64Rng::from_seed([7; 32]);
+        let mut rng2 = Isaac64Rng::from_seed([7; 32]);
+        let mut buf = [0u64; 600];
+        for &len in [0, 3, 16, 40, 1, 600, 17].iter() {
+            rng1.fill_u64_slice(&mut buf[..len]);
+            for &x in buf[..len].iter() {
+                assert_eq!(x, rng2.next_u64());
+            }
+            // Mixing with single words
+            rng1.next_u32();
+            rng2.next_u32();
+        }
+    }
 }
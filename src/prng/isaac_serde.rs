@@ -8,72 +8,79 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! ISAAC serde helper functions.
+//! ISAAC, HC-128 and MT19937 serde helper functions.
 
-pub(super) mod rand_size_serde {
-    const RAND_SIZE_LEN: usize = 8;
-    const RAND_SIZE: usize = 1 << RAND_SIZE_LEN;
+macro_rules! array_serde {
+    ($name:ident, $size:expr, $expecting:expr) => {
+        pub(super) mod $name {
+            const RAND_SIZE: usize = $size;
 
-    use serde::{Deserialize, Deserializer, Serialize, Serializer};
-    use serde::de::{Visitor,SeqAccess};
-    use serde::de;
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+            use serde::de::{Visitor,SeqAccess};
+            use serde::de;
 
-    use core::fmt;
+            use core::fmt;
 
-    pub fn serialize<T, S>(arr: &[T;RAND_SIZE], ser: S) -> Result<S::Ok, S::Error> 
-    where
-        T: Serialize,
-        S: Serializer 
-    {
-        use serde::ser::SerializeTuple;
-
-        let mut seq = ser.serialize_tuple(RAND_SIZE)?;
-
-        for e in arr.iter() {
-            seq.serialize_element(&e)?;
-        }
+            pub fn serialize<T, S>(arr: &[T;RAND_SIZE], ser: S) -> Result<S::Ok, S::Error> 
+            where
+                T: Serialize,
+                S: Serializer 
+            {
+                use serde::ser::SerializeTuple;
 
-        seq.end()
-    }
+                let mut seq = ser.serialize_tuple(RAND_SIZE)?;
 
-    #[inline]
-    pub fn deserialize<'de, T, D>(de: D) -> Result<[T;RAND_SIZE], D::Error>
-    where
-        T: Deserialize<'de>+Default+Copy,
-        D: Deserializer<'de>,
-    {
-        use core::marker::PhantomData;
-        struct ArrayVisitor<T> {
-            _pd: PhantomData<T>,
-        };
-        impl<'de,T> Visitor<'de> for ArrayVisitor<T>
-        where
-            T: Deserialize<'de>+Default+Copy
-        {
-            type Value = [T; RAND_SIZE];
+                for e in arr.iter() {
+                    seq.serialize_element(&e)?;
+                }
 
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("Isaac state array")
+                seq.end()
             }
 
             #[inline]
-            fn visit_seq<A>(self, mut seq: A) -> Result<[T; RAND_SIZE], A::Error>
+            pub fn deserialize<'de, T, D>(de: D) -> Result<[T;RAND_SIZE], D::Error>
             where
-                A: SeqAccess<'de>,
+                T: Deserialize<'de>+Default+Copy,
+                D: Deserializer<'de>,
             {
-                let mut out = [Default::default();RAND_SIZE];
+                use core::marker::PhantomData;
+                struct ArrayVisitor<T> {
+                    _pd: PhantomData<T>,
+                }
+                impl<'de,T> Visitor<'de> for ArrayVisitor<T>
+                where
+                    T: Deserialize<'de>+Default+Copy
+                {
+                    type Value = [T; RAND_SIZE];
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str($expecting)
+                    }
+
+                    #[inline]
+                    fn visit_seq<A>(self, mut seq: A) -> Result<[T; RAND_SIZE], A::Error>
+                    where
+                        A: SeqAccess<'de>,
+                    {
+                        let mut out = [Default::default();RAND_SIZE];
 
-                for i in 0..RAND_SIZE {
-                    match seq.next_element()? {
-                        Some(val) => out[i] = val,
-                        None => return Err(de::Error::invalid_length(i, &self)),
-                    };
+                        for i in 0..RAND_SIZE {
+                            match seq.next_element()? {
+                                Some(val) => out[i] = val,
+                                None => return Err(de::Error::invalid_length(i, &self)),
+                            };
+                        }
+
+                        Ok(out)
+                    }
                 }
 
-                Ok(out)
+                de.deserialize_tuple(RAND_SIZE, ArrayVisitor{_pd: PhantomData})
             }
         }
-
-        de.deserialize_tuple(RAND_SIZE, ArrayVisitor{_pd: PhantomData})
     }
 }
+
+array_serde!(rand_size_serde, 1 << 8, "Isaac state array");
+array_serde!(hc128_serde, 1024, "Hc128 state array");
+array_serde!(mt19937_serde, 624, "Mt19937 state array");
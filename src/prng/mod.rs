@@ -40,11 +40,26 @@
 //! same algorithm, it is possible that both will yield the same sequence of
 //! values (with some lag).
 
+// Reads the little-endian `u32` at word `$i` of the byte array `$src`. This
+// is a macro rather than a function so it can be used in `const fn` seeding,
+// which permits neither loops nor slices of arrays.
+macro_rules! read_u32_le {
+    ($src:expr, $i:expr) => {
+        ($src[4 * $i] as u32)
+            | ($src[4 * $i + 1] as u32) << 8
+            | ($src[4 * $i + 2] as u32) << 16
+            | ($src[4 * $i + 3] as u32) << 24
+    }
+}
+
 mod chacha;
 mod hc128;
 mod isaac;
 mod isaac64;
+mod mt19937;
+mod xoroshiro;
 mod xorshift;
+mod state;
 
 #[cfg(feature="serde-1")]
 mod isaac_serde;
@@ -53,4 +68,7 @@ pub use self::chacha::ChaChaRng;
 pub use self::hc128::Hc128Rng;
 pub use self::isaac::IsaacRng;
 pub use self::isaac64::Isaac64Rng;
-pub use self::xorshift::XorShiftRng;
\ No newline at end of file
+pub use self::mt19937::Mt19937Rng;
+pub use self::xoroshiro::Xoroshiro128PlusRng;
+pub use self::xorshift::XorShiftRng;
+pub use self::state::{StateSerializable, StateError, STATE_VERSION};
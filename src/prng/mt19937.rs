@@ -0,0 +1,329 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Mersenne Twister MT19937 generator, compatible with NumPy
+
+use core::{cmp, fmt};
+use rand_core::{RngCore, SeedableRng, Error, impls, le};
+use prng::state::{StateSerializable, StateError, StateWriter, StateReader};
+
+const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908_b0df;
+const UPPER_MASK: u32 = 0x8000_0000;
+const LOWER_MASK: u32 = 0x7fff_ffff;
+
+/// The Mersenne Twister MT19937 random number generator.
+///
+/// MT19937[1] has a period of `2^19937 - 1` and a state of 624 words. It is
+/// not suitable for cryptographic purposes, and both slower and statistically
+/// weaker than the other generators of this library. It is provided to
+/// reproduce results of other software using it, in particular the legacy
+/// `numpy.random.RandomState` of NumPy:
+///
+/// - `Mt19937Rng::from_u32(seed)` is seeded like `RandomState(seed)` with an
+///   integer seed, and `Mt19937Rng::from_key(&key)` like `RandomState(key)`
+///   with an array of integers (the `init_genrand` and `init_by_array`
+///   functions of the reference implementation).
+/// - `next_u32` returns the same values as `RandomState.randint(2**32,
+///   dtype=np.uint32)`, and `next_u64` combines two of these, the first being
+///   the high half.
+/// - [`random_sample`] and [`standard_normal`] return the same values as the
+///   methods of `RandomState` with the same names.
+///
+/// Values of any other distribution sampled with this generator, including
+/// `gen::<f64>()`, use the algorithms of this library and do not match those
+/// of NumPy.
+///
+/// The seeding with `SeedableRng::from_seed` takes the little-endian `u32`
+/// seed of `from_u32`. `SeedableRng::from_rng` fills the whole state by
+/// `init_by_array` with a key of 624 random words.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::Mt19937Rng;
+///
+/// // numpy.random.RandomState(0).random_sample()
+/// let mut rng = Mt19937Rng::from_u32(0);
+/// assert_eq!(rng.random_sample(), 0.5488135039273248);
+/// ```
+///
+/// [1]: Matsumoto, M. and Nishimura, T. (1998). ["Mersenne Twister: A
+/// 623-dimensionally equidistributed uniform pseudo-random number
+/// generator"](http://www.math.sci.hiroshima-u.ac.jp/~m-mat/MT/emt.html).
+/// *ACM Transactions on Modeling and Computer Simulation* 8 (1).
+///
+/// [`random_sample`]: #method.random_sample
+/// [`standard_normal`]: #method.standard_normal
+#[derive(Clone)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct Mt19937Rng {
+    #[cfg_attr(feature="serde-1",serde(with="super::isaac_serde::mt19937_serde"))]
+    mt: [u32; N],
+    index: usize,
+    gauss: Option<f64>,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Mt19937Rng {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Mt19937Rng {{}}")
+    }
+}
+
+impl Mt19937Rng {
+    /// Create a generator seeded with a 32-bit integer, like
+    /// `numpy.random.RandomState(seed)`.
+    pub fn from_u32(seed: u32) -> Mt19937Rng {
+        let mut mt = [0u32; N];
+        mt[0] = seed;
+        for i in 1..N {
+            let prev = mt[i - 1];
+            mt[i] = 1812433253u32.wrapping_mul(prev ^ (prev >> 30))
+                                 .wrapping_add(i as u32);
+        }
+        Mt19937Rng { mt: mt, index: N, gauss: None }
+    }
+
+    /// Create a generator seeded with an array of 32-bit integers, like
+    /// `numpy.random.RandomState(key)`.
+    pub fn from_key(key: &[u32]) -> Mt19937Rng {
+        let mut rng = Mt19937Rng::from_u32(19650218);
+        let mt = &mut rng.mt;
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..cmp::max(N, key.len()) {
+            let prev = mt[i - 1];
+            mt[i] = (mt[i] ^ (prev ^ (prev >> 30)).wrapping_mul(1664525))
+                .wrapping_add(if key.is_empty() { 0 } else { key[j] })
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                mt[0] = mt[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..(N - 1) {
+            let prev = mt[i - 1];
+            mt[i] = (mt[i] ^ (prev ^ (prev >> 30)).wrapping_mul(1566083941))
+                .wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                mt[0] = mt[N - 1];
+                i = 1;
+            }
+        }
+        // The most significant bit assures a non-zero initial state
+        mt[0] = UPPER_MASK;
+        rng
+    }
+
+    /// Return a random `f64` in `[0, 1)`, like `RandomState.random_sample`.
+    ///
+    /// The value is made from the high 27 and 26 bits of two `u32` values,
+    /// giving 53 bits of precision.
+    pub fn random_sample(&mut self) -> f64 {
+        let a = self.next_u32() >> 5;
+        let b = self.next_u32() >> 6;
+        (a as f64 * 67108864.0 + b as f64) * (1.0 / 9007199254740992.0)
+    }
+
+    /// Return a value of the standard normal distribution, like
+    /// `RandomState.standard_normal`.
+    ///
+    /// This uses the polar method of Marsaglia, on values of
+    /// `random_sample`. It generates two values at a time, of which the
+    /// second is kept for the next call; any other use of the generator in
+    /// between does not discard it, as in NumPy.
+    #[cfg(feature="std")]
+    pub fn standard_normal(&mut self) -> f64 {
+        if let Some(x) = self.gauss.take() {
+            return x;
+        }
+        loop {
+            let x1 = 2.0 * self.random_sample() - 1.0;
+            let x2 = 2.0 * self.random_sample() - 1.0;
+            let r2 = x1 * x1 + x2 * x2;
+            if r2 < 1.0 && r2 != 0.0 {
+                let f = (-2.0 * r2.ln() / r2).sqrt();
+                self.gauss = Some(f * x1);
+                return f * x2;
+            }
+        }
+    }
+
+    fn generate(&mut self) {
+        let mt = &mut self.mt;
+        for i in 0..N {
+            let y = (mt[i] & UPPER_MASK) | (mt[(i + 1) % N] & LOWER_MASK);
+            let mag = if y & 1 == 0 { 0 } else { MATRIX_A };
+            mt[i] = mt[(i + M) % N] ^ (y >> 1) ^ mag;
+        }
+        self.index = 0;
+    }
+}
+
+impl RngCore for Mt19937Rng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.generate();
+        }
+        let mut y = self.mt[self.index];
+        self.index += 1;
+        y ^= y >> 11;
+        y ^= (y << 7) & 0x9d2c_5680;
+        y ^= (y << 15) & 0xefc6_0000;
+        y ^ (y >> 18)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let hi = self.next_u32() as u64;
+        (hi << 32) | self.next_u32() as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_u32(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+impl SeedableRng for Mt19937Rng {
+    type Seed = [u8; 4];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut seed_u32 = [0u32; 1];
+        le::read_u32_into(&seed, &mut seed_u32);
+        Mt19937Rng::from_u32(seed_u32[0])
+    }
+
+    fn from_rng<R: RngCore + ?Sized>(rng: &mut R) -> Result<Self, Error> {
+        let mut bytes = [0u8; N * 4];
+        rng.try_fill_bytes(&mut bytes)?;
+        let mut key = [0u32; N];
+        le::read_u32_into(&bytes, &mut key);
+        Ok(Mt19937Rng::from_key(&key))
+    }
+}
+
+/// The state is encoded as the 624 words of the state array and the index
+/// into it, each as a `u32`, followed by a flag byte and the `f64` bits (as a
+/// `u64`) of the normal value kept by `standard_normal`.
+impl StateSerializable for Mt19937Rng {
+    const STATE_LEN: usize = 1 + 4 * (N + 1) + 1 + 8;
+
+    fn to_state_bytes(&self, dest: &mut [u8]) {
+        let mut out = StateWriter::new(dest, Self::STATE_LEN);
+        for &x in self.mt.iter() {
+            out.u32(x);
+        }
+        out.u32(self.index as u32);
+        out.u8(self.gauss.is_some() as u8);
+        out.u64(self.gauss.unwrap_or(0.0).to_bits());
+    }
+
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        let mut r = StateReader::new(bytes, Self::STATE_LEN)?;
+        let mut mt = [0u32; N];
+        for x in mt.iter_mut() {
+            *x = r.u32();
+        }
+        let index = r.u32() as usize;
+        let gauss = match (r.u8(), f64::from_bits(r.u64())) {
+            (0, _) => None,
+            (1, x) => Some(x),
+            _ => return Err(StateError::InvalidState),
+        };
+        if index > N || (mt[0] & UPPER_MASK == 0 && mt[1..].iter().all(|&x| x == 0)) {
+            return Err(StateError::InvalidState);
+        }
+        Ok(Mt19937Rng { mt: mt, index: index, gauss: gauss })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {RngCore, SeedableRng};
+    use super::Mt19937Rng;
+
+    #[test]
+    fn test_mt19937_true_values() {
+        // The default seed of the reference implementation, and of
+        // `std::mt19937` in C++
+        let mut rng = Mt19937Rng::from_u32(5489);
+        let mut results = [0u32; 3];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        assert_eq!(results, [3499211612, 581869302, 3890346734]);
+        for _ in 3..9999 { rng.next_u32(); }
+        assert_eq!(rng.next_u32(), 4123659995);
+
+        // `mt19937ar.out` of the reference implementation
+        let mut rng = Mt19937Rng::from_key(&[0x123, 0x234, 0x345, 0x456]);
+        let mut results = [0u32; 5];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        assert_eq!(results, [1067595299, 955945823, 477289528, 4107218783,
+                             4228976476]);
+
+        // Python's `random.Random(0)`
+        let mut rng = Mt19937Rng::from_key(&[0]);
+        assert_eq!(rng.next_u32(), 3626764237);
+        let mut rng = Mt19937Rng::from_key(&[0]);
+        assert_eq!(rng.random_sample(), 0.8444218515250481);
+
+        let mut rng = Mt19937Rng::from_seed([1, 0, 0, 0]);
+        assert_eq!(rng.next_u64(), 1791095845 << 32 | 4282876139);
+    }
+
+    #[test]
+    fn test_mt19937_numpy() {
+        // numpy.random.RandomState(0).random_sample(4)
+        let mut rng = Mt19937Rng::from_u32(0);
+        let expected = [0.5488135039273248, 0.7151893663724195,
+                        0.6027633760716439, 0.5448831829968969];
+        for &x in expected.iter() {
+            assert_eq!(rng.random_sample(), x);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_mt19937_numpy_normal() {
+        // numpy.random.RandomState(0).standard_normal(4)
+        let mut rng = Mt19937Rng::from_u32(0);
+        let expected = [1.764052345967664, 0.4001572083672233,
+                        0.9787379841057392, 2.240893199201458];
+        for &x in expected.iter() {
+            assert_eq!(rng.standard_normal(), x);
+        }
+    }
+
+    #[test]
+    fn test_mt19937_state_bytes() {
+        use prng::StateSerializable;
+
+        let mut rng = Mt19937Rng::from_rng(&mut ::test::rng(398)).unwrap();
+        for _ in 0..700 { rng.next_u32(); }
+        let mut state = [0u8; Mt19937Rng::STATE_LEN];
+        rng.to_state_bytes(&mut state);
+        let mut restored = Mt19937Rng::from_state_bytes(&state).unwrap();
+        for _ in 0..1000 {
+            assert_eq!(rng.next_u32(), restored.next_u32());
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Saving and restoring the state of PRNGs as raw bytes.
+
+use core::fmt;
+
+/// The version of the state encoding written by `to_state_bytes`.
+pub const STATE_VERSION: u8 = 1;
+
+/// PRNGs whose complete state can be saved to and restored from bytes.
+///
+/// Unlike the optional serde support, this needs no serialization framework
+/// or allocator, which makes it usable for checkpointing on embedded targets
+/// or passing generators across FFI boundaries.
+///
+/// # Encoding
+///
+/// The encoding is stable across platforms and releases:
+///
+/// - The first byte is the format version, currently [`STATE_VERSION`] (1).
+///   Future releases can read all earlier versions.
+/// - It is followed by the fields of the generator, in the order documented
+///   for each implementation. All integers are little-endian; `usize` values
+///   are stored as `u32`, and `bool` as a single byte.
+///
+/// The encoding does not identify the generator type: restoring bytes with a
+/// different type than the one that wrote them fails if the length differs,
+/// but is otherwise not detected.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, SeedableRng, StateSerializable, XorShiftRng};
+///
+/// let mut rng = XorShiftRng::from_seed([1; 16]);
+/// let mut state = [0u8; XorShiftRng::STATE_LEN];
+/// rng.to_state_bytes(&mut state);
+///
+/// let mut restored = XorShiftRng::from_state_bytes(&state).unwrap();
+/// assert_eq!(rng.gen::<u64>(), restored.gen::<u64>());
+/// ```
+///
+/// [`STATE_VERSION`]: constant.STATE_VERSION.html
+pub trait StateSerializable: Sized {
+    /// The length in bytes of the encoded state, including the version byte.
+    const STATE_LEN: usize;
+
+    /// Write the state to `dest`.
+    ///
+    /// Panics if `dest.len() != Self::STATE_LEN`.
+    fn to_state_bytes(&self, dest: &mut [u8]);
+
+    /// Restore a generator from bytes written by `to_state_bytes`.
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError>;
+}
+
+/// An error returned by [`StateSerializable::from_state_bytes`].
+///
+/// [`StateSerializable::from_state_bytes`]: trait.StateSerializable.html#tymethod.from_state_bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// The number of bytes does not match the generator.
+    InvalidLength,
+    /// The format version is not supported.
+    UnsupportedVersion,
+    /// The bytes do not describe a valid state of the generator.
+    InvalidState,
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+impl StateError {
+    fn description(&self) -> &'static str {
+        match *self {
+            StateError::InvalidLength => "invalid length of state bytes",
+            StateError::UnsupportedVersion => "unsupported state version",
+            StateError::InvalidState => "invalid generator state",
+            StateError::__Nonexhaustive => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+#[cfg(feature="std")]
+impl ::std::error::Error for StateError {
+    fn description(&self) -> &str {
+        self.description()
+    }
+}
+
+// Writes the version byte followed by little-endian fields.
+pub(crate) struct StateWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> StateWriter<'a> {
+    pub(crate) fn new(buf: &'a mut [u8], len: usize) -> StateWriter<'a> {
+        assert_eq!(buf.len(), len, "to_state_bytes called with wrong length");
+        buf[0] = STATE_VERSION;
+        StateWriter { buf: buf, pos: 1 }
+    }
+
+    fn bytes(&mut self, n: usize) -> &mut [u8] {
+        self.pos += n;
+        &mut self.buf[self.pos - n..self.pos]
+    }
+
+    pub(crate) fn u8(&mut self, x: u8) {
+        self.bytes(1)[0] = x;
+    }
+
+    pub(crate) fn u32(&mut self, x: u32) {
+        let buf = self.bytes(4);
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = (x >> (8 * i)) as u8;
+        }
+    }
+
+    pub(crate) fn u64(&mut self, x: u64) {
+        self.u32(x as u32);
+        self.u32((x >> 32) as u32);
+    }
+
+    pub(crate) fn usize(&mut self, x: usize) {
+        self.u32(x as u32);
+    }
+}
+
+// Reads the fields written by `StateWriter`, after checking length and version.
+pub(crate) struct StateReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> StateReader<'a> {
+    pub(crate) fn new(buf: &'a [u8], len: usize)
+        -> Result<StateReader<'a>, StateError>
+    {
+        if buf.len() != len {
+            return Err(StateError::InvalidLength);
+        }
+        if buf[0] != STATE_VERSION {
+            return Err(StateError::UnsupportedVersion);
+        }
+        Ok(StateReader { buf: &buf[1..] })
+    }
+
+    fn bytes(&mut self, n: usize) -> &'a [u8] {
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        head
+    }
+
+    pub(crate) fn u8(&mut self) -> u8 {
+        self.bytes(1)[0]
+    }
+
+    pub(crate) fn u32(&mut self) -> u32 {
+        self.bytes(4).iter().rev().fold(0, |acc, &b| (acc << 8) | b as u32)
+    }
+
+    pub(crate) fn u64(&mut self) -> u64 {
+        let lo = self.u32() as u64;
+        let hi = self.u32() as u64;
+        (hi << 32) | lo
+    }
+
+    pub(crate) fn usize(&mut self) -> usize {
+        self.u32() as usize
+    }
+}
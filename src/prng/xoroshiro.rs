@@ -0,0 +1,170 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The xoroshiro128+ generator
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, impls, le};
+use prng::state::{StateSerializable, StateError, StateWriter, StateReader};
+
+/// A xoroshiro128+ random number generator.
+///
+/// The xoroshiro128+ algorithm[1] works natively with 64-bit words and has
+/// a period of `2^128 - 1`. It is not suitable for cryptographic purposes,
+/// but is one of the fastest generators on 64-bit platforms.
+///
+/// The lowest bits of the output fail some linear tests; `next_u32` therefore
+/// returns the high half of each 64-bit output.
+///
+/// [1]: Blackman, David and Vigna, Sebastiano (2016). ["xoroshiro128+"](
+/// http://xoroshiro.di.unimi.it/xoroshiro128plus.c).
+#[derive(Clone)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
+pub struct Xoroshiro128PlusRng {
+    s0: u64,
+    s1: u64,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Xoroshiro128PlusRng {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Xoroshiro128PlusRng {{}}")
+    }
+}
+
+impl RngCore for Xoroshiro128PlusRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let s0 = self.s0;
+        let mut s1 = self.s1;
+        let result = s0.wrapping_add(s1);
+
+        s1 ^= s0;
+        self.s0 = s0.rotate_left(55) ^ s1 ^ (s1 << 14);
+        self.s1 = s1.rotate_left(36);
+        result
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_u64(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+impl SeedableRng for Xoroshiro128PlusRng {
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut seed_u64 = [0u64; 2];
+        le::read_u64_into(&seed, &mut seed_u64);
+
+        // Like Xorshift, xoroshiro cannot be seeded with 0; use a preset
+        // value instead.
+        if seed_u64 == [0, 0] {
+            seed_u64 = [0x0BAD_5EED_0BAD_5EED, 0x0BAD_5EED_0BAD_5EED];
+        }
+
+        Xoroshiro128PlusRng { s0: seed_u64[0], s1: seed_u64[1] }
+    }
+
+    fn from_rng<R: RngCore + ?Sized>(rng: &mut R) -> Result<Self, Error> {
+        let mut seed = [0u8; 16];
+        loop {
+            rng.try_fill_bytes(&mut seed)?;
+            if !seed.iter().all(|&x| x == 0) { break; }
+        }
+        Ok(Xoroshiro128PlusRng::from_seed(seed))
+    }
+}
+
+/// The state is encoded as the two words `s0` and `s1`, each as a `u64`. An
+/// all-zero state is rejected.
+impl StateSerializable for Xoroshiro128PlusRng {
+    const STATE_LEN: usize = 1 + 2 * 8;
+
+    fn to_state_bytes(&self, dest: &mut [u8]) {
+        let mut out = StateWriter::new(dest, Self::STATE_LEN);
+        out.u64(self.s0);
+        out.u64(self.s1);
+    }
+
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        let mut r = StateReader::new(bytes, Self::STATE_LEN)?;
+        let rng = Xoroshiro128PlusRng { s0: r.u64(), s1: r.u64() };
+        if rng.s0 | rng.s1 == 0 {
+            return Err(StateError::InvalidState);
+        }
+        Ok(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {RngCore, SeedableRng};
+    use super::Xoroshiro128PlusRng;
+
+    #[test]
+    fn test_xoroshiro_true_values() {
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        let mut rng = Xoroshiro128PlusRng::from_seed(seed);
+
+        let mut results = [0u64; 6];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        let expected: [u64; 6] = [
+            1735596774209227786, 11173019503406123529, 9028165464277589397,
+            9796675266960426047, 11118640250180765594, 12604666547112140862];
+        assert_eq!(results, expected);
+
+        let mut results = [0u32; 6];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected: [u32; 6] = [
+            2066743297, 2107054096, 349746031, 125365726, 787840523,
+            3054256991];
+        assert_eq!(results, expected);
+
+        let mut results = [0u8; 20];
+        rng.fill_bytes(&mut results);
+        let expected = [156, 220, 254, 183, 207, 2, 180, 133,
+                        225, 197, 204, 219, 36, 144, 48, 75,
+                        133, 164, 5, 208];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_xoroshiro_zero_seed() {
+        let mut rng = Xoroshiro128PlusRng::from_seed([0; 16]);
+        let a = rng.next_u64();
+        let b = rng.next_u64();
+        assert!(a != 0);
+        assert!(b != a);
+    }
+
+    #[test]
+    fn test_xoroshiro_state_bytes() {
+        use prng::StateSerializable;
+
+        let mut rng = Xoroshiro128PlusRng::from_seed([3; 16]);
+        rng.next_u64();
+        let mut state = [0u8; Xoroshiro128PlusRng::STATE_LEN];
+        rng.to_state_bytes(&mut state);
+        let mut restored = Xoroshiro128PlusRng::from_state_bytes(&state).unwrap();
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), restored.next_u64());
+        }
+    }
+}
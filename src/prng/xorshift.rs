@@ -12,7 +12,8 @@
 
 use core::num::Wrapping as w;
 use core::{fmt, slice};
-use rand_core::{RngCore, SeedableRng, Error, impls, le};
+use rand_core::{RngCore, SeedableRng, Error, impls};
+use prng::state::{StateSerializable, StateError, StateWriter, StateReader};
 
 /// An Xorshift[1] random number
 /// generator.
@@ -55,6 +56,42 @@ impl XorShiftRng {
             w: w(0x113ba7bb),
         }
     }
+
+    /// Create a new `XorShiftRng` from a seed, exactly like
+    /// `SeedableRng::from_seed`.
+    ///
+    /// Being a `const fn`, this can initialize a `const` or `static`
+    /// generator without any code running at startup.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rand::{RngCore, XorShiftRng};
+    ///
+    /// const SEEDED: XorShiftRng = XorShiftRng::from_seed_const([7; 16]);
+    ///
+    /// let mut rng = SEEDED;
+    /// println!("{}", rng.next_u32());
+    /// ```
+    pub const fn from_seed_const(seed: [u8; 16]) -> XorShiftRng {
+        XorShiftRng::from_words(read_u32_le!(seed, 0), read_u32_le!(seed, 1),
+                                read_u32_le!(seed, 2), read_u32_le!(seed, 3))
+    }
+
+    const fn from_words(a: u32, b: u32, c: u32, d: u32) -> XorShiftRng {
+        // Xorshift cannot be seeded with 0 and we cannot return an Error, but
+        // also do not wish to panic (because a random seed can legitimately be
+        // 0); our only option is therefore to use a preset value. As `const
+        // fn` allows no branches, it is or-ed into the words if all are zero.
+        XorShiftRng::from_words_or(a, b, c, d,
+                                   ((a | b | c | d) == 0) as u32 * 0xBAD_5EED)
+    }
+
+    const fn from_words_or(a: u32, b: u32, c: u32, d: u32, fix: u32)
+        -> XorShiftRng
+    {
+        XorShiftRng { x: w(a | fix), y: w(b | fix), z: w(c | fix), w: w(d | fix) }
+    }
 }
 
 impl RngCore for XorShiftRng {
@@ -87,25 +124,10 @@ impl SeedableRng for XorShiftRng {
     type Seed = [u8; 16];
 
     fn from_seed(seed: Self::Seed) -> Self {
-        let mut seed_u32 = [0u32; 4];
-        le::read_u32_into(&seed, &mut seed_u32);
-
-        // Xorshift cannot be seeded with 0 and we cannot return an Error, but
-        // also do not wish to panic (because a random seed can legitimately be
-        // 0); our only option is therefore to use a preset value.
-        if seed_u32.iter().all(|&x| x == 0) {
-            seed_u32 = [0xBAD_5EED, 0xBAD_5EED, 0xBAD_5EED, 0xBAD_5EED];
-        }
-
-        XorShiftRng {
-            x: w(seed_u32[0]),
-            y: w(seed_u32[1]),
-            z: w(seed_u32[2]),
-            w: w(seed_u32[3]),
-        }
+        XorShiftRng::from_seed_const(seed)
     }
 
-    fn from_rng<R: RngCore>(rng: &mut R) -> Result<Self, Error> {
+    fn from_rng<R: RngCore + ?Sized>(rng: &mut R) -> Result<Self, Error> {
         let mut seed_u32 = [0u32; 4];
         loop {
             unsafe {
@@ -126,6 +148,33 @@ impl SeedableRng for XorShiftRng {
     }
 }
 
+/// The state is encoded as the four words `x`, `y`, `z` and `w`, each as a
+/// `u32`. An all-zero state is rejected.
+impl StateSerializable for XorShiftRng {
+    const STATE_LEN: usize = 1 + 4 * 4;
+
+    fn to_state_bytes(&self, dest: &mut [u8]) {
+        let mut out = StateWriter::new(dest, Self::STATE_LEN);
+        for x in [self.x, self.y, self.z, self.w].iter() {
+            out.u32(x.0);
+        }
+    }
+
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        let mut r = StateReader::new(bytes, Self::STATE_LEN)?;
+        let rng = XorShiftRng {
+            x: w(r.u32()),
+            y: w(r.u32()),
+            z: w(r.u32()),
+            w: w(r.u32()),
+        };
+        if rng.x.0 | rng.y.0 | rng.z.0 | rng.w.0 == 0 {
+            return Err(StateError::InvalidState);
+        }
+        Ok(rng)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {RngCore, SeedableRng};
@@ -184,6 +233,21 @@ mod tests {
         assert!(b != a);
     }
 
+    #[test]
+    fn test_xorshift_from_seed_const() {
+        const SEED: [u8; 16] = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        static SEEDED: XorShiftRng = XorShiftRng::from_seed_const(SEED);
+        const ZERO: XorShiftRng = XorShiftRng::from_seed_const([0; 16]);
+        let mut rng1 = SEEDED.clone();
+        let mut rng2 = XorShiftRng::from_seed(SEED);
+        let mut zero = ZERO;
+        assert_eq!(zero.x.0, 0xBAD_5EED);
+        assert!(zero.next_u64() != 0);
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
     #[test]
     fn test_xorshift_clone() {
         let seed = [1,2,3,4, 5,5,7,8, 8,7,6,5, 4,3,2,1];
@@ -220,4 +284,39 @@ mod tests {
             assert_eq!(rng.next_u64(), deserialized.next_u64());
         }
     }
+
+    #[test]
+    fn test_xorshift_state_bytes() {
+        use prng::{StateSerializable, StateError};
+
+        let mut rng = XorShiftRng::from_seed([1; 16]);
+        for _ in 0..3 {
+            rng.next_u32();
+        }
+        let mut state = [0u8; XorShiftRng::STATE_LEN];
+        rng.to_state_bytes(&mut state);
+        let mut restored = XorShiftRng::from_state_bytes(&state).unwrap();
+        let mut state2 = [0u8; XorShiftRng::STATE_LEN];
+        restored.to_state_bytes(&mut state2);
+        assert_eq!(&state[..], &state2[..]);
+        for _ in 0..40 {
+            assert_eq!(rng.next_u64(), restored.next_u64());
+        }
+
+        // The encoding is little-endian and starts with the version
+        assert_eq!(XorShiftRng::STATE_LEN, 17);
+        let rng = XorShiftRng::from_seed([1, 0, 0, 0, 2, 3, 0, 0, 4, 0, 0, 0, 5, 0, 0, 0]);
+        rng.to_state_bytes(&mut state);
+        assert_eq!(state, [1, 1, 0, 0, 0, 2, 3, 0, 0, 4, 0, 0, 0, 5, 0, 0, 0]);
+
+        assert_eq!(XorShiftRng::from_state_bytes(&state[..16]).unwrap_err(),
+                   StateError::InvalidLength);
+        state[0] = 0;
+        assert_eq!(XorShiftRng::from_state_bytes(&state).unwrap_err(),
+                   StateError::UnsupportedVersion);
+        let mut zero = [0u8; 17];
+        zero[0] = 1;
+        assert_eq!(XorShiftRng::from_state_bytes(&zero).unwrap_err(),
+                   StateError::InvalidState);
+    }
 }
@@ -0,0 +1,229 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Halton sequence.
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+use Rng;
+use super::QuasiRandom;
+
+/// The Halton low-discrepancy sequence, optionally with random digit
+/// permutations.
+///
+/// Dimension `k` is the van der Corput sequence in the `k`-th prime base
+/// `b`: the digits of the index in base `b` are mirrored at the radix point,
+/// so index `i = d_0 + d_1 b + d_2 b^2 + ...` maps to
+/// `d_0 / b + d_1 / b^2 + d_2 / b^3 + ...`. For every `m`, the points
+/// `k * b^m .. (k + 1) * b^m` are evenly stratified in that dimension: every
+/// interval `[j / b^m, (j + 1) / b^m)` contains exactly one of them.
+///
+/// The plain sequence is simple and extensible, but its high dimensions, with
+/// large bases, are strongly correlated with each other for the first
+/// thousands of points. [`new_scrambled`] fixes this by applying an
+/// independent random permutation of the digits to every digit position of
+/// every dimension, with the permutations taken from an `Rng`. This keeps the
+/// stratification and makes every coordinate uniformly distributed. The
+/// plain Halton sequence works best for up to a few dozen dimensions; for
+/// more, prefer [`Sobol`].
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::quasi::{QuasiRandom, Halton};
+///
+/// let halton = Halton::new(2);
+/// assert_eq!(halton.sample(1, 0), 0.5);
+/// assert_eq!(halton.sample(3, 1), 1.0 / 9.0);
+///
+/// let scrambled = Halton::new_scrambled(10, &mut thread_rng());
+/// let mut point = [0.0; 10];
+/// scrambled.sample_point(42, &mut point);
+/// ```
+///
+/// [`new_scrambled`]: #method.new_scrambled
+/// [`Sobol`]: struct.Sobol.html
+#[derive(Clone, Debug)]
+pub struct Halton {
+    bases: Vec<u32>,
+    // For every dimension, a permutation of `0..base` per digit position,
+    // concatenated, if scrambling
+    permutations: Option<Vec<Vec<u32>>>,
+}
+
+impl Halton {
+    /// Create a Halton sequence with `dims` dimensions, using the first
+    /// `dims` primes as bases.
+    ///
+    /// Panics if `dims` is 0.
+    pub fn new(dims: usize) -> Halton {
+        assert!(dims > 0, "Halton::new called with `dims == 0`");
+        let mut bases = Vec::with_capacity(dims);
+        let mut n = 2;
+        while bases.len() < dims {
+            if bases.iter().take_while(|&&p| p * p <= n).all(|&p| n % p != 0) {
+                bases.push(n);
+            }
+            n += 1;
+        }
+        Halton { bases: bases, permutations: None }
+    }
+
+    /// Create a Halton sequence with `dims` dimensions and random digit
+    /// permutations taken from `rng`.
+    ///
+    /// Panics if `dims` is 0.
+    pub fn new_scrambled<R: Rng + ?Sized>(dims: usize, rng: &mut R) -> Halton {
+        let mut halton = Halton::new(dims);
+        let permutations = halton.bases.iter().map(|&base| {
+            let digits = num_digits(base);
+            let mut permutation = Vec::with_capacity(digits * base as usize);
+            for _ in 0..digits {
+                let start = permutation.len();
+                permutation.extend(0..base);
+                rng.shuffle(&mut permutation[start..]);
+            }
+            permutation
+        }).collect();
+        halton.permutations = Some(permutations);
+        halton
+    }
+
+    /// The base used for dimension `dim`.
+    ///
+    /// Panics if `dim >= self.dimensions()`.
+    pub fn base(&self, dim: usize) -> u32 {
+        self.bases[dim]
+    }
+}
+
+impl QuasiRandom for Halton {
+    fn dimensions(&self) -> usize {
+        self.bases.len()
+    }
+
+    fn sample(&self, index: u32, dim: usize) -> f64 {
+        assert!(dim < self.bases.len(), "dimension out of range");
+        let base = self.bases[dim];
+        let inv_base = 1.0 / base as f64;
+        let mut factor = inv_base;
+        let mut result = 0.0;
+        let mut rest = index;
+        match self.permutations {
+            Some(ref permutations) => {
+                // Zero digits are permuted too, up to the number of digits
+                // any index can have.
+                for digits in permutations[dim].chunks(base as usize) {
+                    result += digits[(rest % base) as usize] as f64 * factor;
+                    rest /= base;
+                    factor *= inv_base;
+                }
+            }
+            None => {
+                while rest != 0 {
+                    result += (rest % base) as f64 * factor;
+                    rest /= base;
+                    factor *= inv_base;
+                }
+            }
+        }
+        result
+    }
+}
+
+// The number of digits of `u32::MAX` in base `base`.
+fn num_digits(base: u32) -> usize {
+    let mut digits = 0;
+    let mut power = 1u64;
+    while power <= ::core::u32::MAX as u64 {
+        power *= base as u64;
+        digits += 1;
+    }
+    digits
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+    use {SeedableRng, ChaChaRng};
+    use quasi::QuasiRandom;
+    use super::{Halton, num_digits};
+
+    // Check that each of `first..first + base^m` lies in a different
+    // interval `[j / base^m, (j + 1) / base^m)` of dimension `dim`.
+    fn check_stratification(halton: &Halton, dim: usize, first: u32, m: u32) {
+        let n = halton.base(dim).pow(m);
+        let mut seen = vec![false; n as usize];
+        for i in first..first + n {
+            let x = halton.sample(i, dim);
+            assert!(x >= 0.0 && x < 1.0);
+            // Allow for rounding errors at the interval boundaries
+            let j = ((x * n as f64) + 1e-10).floor() as usize;
+            assert!(!seen[j], "dimension {}, point {}", dim, i);
+            seen[j] = true;
+        }
+    }
+
+    #[test]
+    fn test_halton() {
+        let halton = Halton::new(100);
+        assert_eq!(halton.dimensions(), 100);
+        assert_eq!(&(0..10).map(|d| halton.base(d)).collect::<Vec<_>>()[..],
+                   &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(halton.base(99), 541);
+
+        let expected = [0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0 / 9.0, 4.0 / 9.0,
+                        7.0 / 9.0, 2.0 / 9.0, 5.0 / 9.0, 8.0 / 9.0, 1.0 / 27.0];
+        for (i, &x) in expected.iter().enumerate() {
+            assert!((halton.sample(i as u32, 1) - x).abs() < 1e-15);
+        }
+        for i in 0..1000u32 {
+            let reversed = (0..32).fold(0, |r, k| r | ((i >> k) & 1) << (31 - k));
+            assert_eq!(halton.sample(i, 0), reversed as f64 / 4294967296.0);
+        }
+        assert!(halton.sample(::core::u32::MAX, 0) < 1.0);
+
+        for dim in 0..6 {
+            check_stratification(&halton, dim, 0, 4);
+        }
+        check_stratification(&halton, 99, 0, 1);
+
+        let mut point = vec![0.0; 100];
+        halton.sample_point(2018, &mut point);
+        assert_eq!(point[50], halton.sample(2018, 50));
+    }
+
+    #[test]
+    fn test_halton_scrambled() {
+        assert_eq!(num_digits(2), 32);
+        assert_eq!(num_digits(3), 21);
+        assert_eq!(num_digits(65536), 2);
+
+        let mut rng = ChaChaRng::from_seed([12; 32]);
+        let halton = Halton::new_scrambled(100, &mut rng);
+        let plain = Halton::new(100);
+        for dim in 0..6 {
+            check_stratification(&halton, dim, 0, 4);
+            check_stratification(&halton, dim, 1000, 3);
+        }
+        check_stratification(&halton, 99, 7, 1);
+        assert!((0..10).any(|i| halton.sample(i, 3) != plain.sample(i, 3)));
+        assert!(halton.sample(::core::u32::MAX, 0) < 1.0);
+
+        // Integrate a product over 5 dimensions
+        let n = 1 << 12;
+        let sum: f64 = halton.points().take(n)
+            .map(|p| p[..5].iter().map(|x| 2.0 * x).product::<f64>())
+            .sum();
+        assert!((sum / n as f64 - 1.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Low-discrepancy (quasi-random) sequences.
+//!
+//! The points of a low-discrepancy sequence cover the unit hypercube
+//! `[0, 1)^d` much more evenly than independent random points. Estimating an
+//! integral by averaging a smooth function over the first `n` points has an
+//! error close to `O(1 / n)`, instead of the `O(1 / sqrt(n))` of plain Monte
+//! Carlo integration.
+//!
+//! Two sequences are provided: [`Sobol`], which is the usual choice, in
+//! particular for many dimensions, and [`Halton`].
+//!
+//! The sequences are deterministic: point `i` is a function of `i` alone, and
+//! every coordinate can be computed independently via
+//! [`QuasiRandom::sample`]. Randomised ("scrambled") variants keep the
+//! uniformity of the points, but make each coordinate uniformly distributed
+//! over `[0, 1)`, which allows estimating the error from independent
+//! replications.
+//!
+//! Unlike the generators of this crate, the points are strongly correlated
+//! with each other; they must not be used where independent random numbers
+//! are expected.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rand::quasi::{QuasiRandom, Sobol};
+//!
+//! // Estimate the integral of x * y * z over the unit cube (1/8)
+//! let sobol = Sobol::new(3);
+//! let n = 1 << 12;
+//! let sum: f64 = sobol.points().take(n)
+//!     .map(|p| p[0] * p[1] * p[2])
+//!     .sum();
+//! assert!((sum / n as f64 - 0.125).abs() < 1e-3);
+//! ```
+//!
+//! [`Sobol`]: struct.Sobol.html
+//! [`Halton`]: struct.Halton.html
+//! [`QuasiRandom::sample`]: trait.QuasiRandom.html#tymethod.sample
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+pub use self::halton::Halton;
+pub use self::sobol::Sobol;
+
+mod halton;
+mod sobol;
+
+/// A low-discrepancy sequence of points in the unit hypercube `[0, 1)^d`.
+pub trait QuasiRandom {
+    /// The number of dimensions `d` of the points.
+    fn dimensions(&self) -> usize;
+
+    /// Coordinate `dim` of the point with the given `index`, in `[0, 1)`.
+    ///
+    /// Panics if `dim >= self.dimensions()`.
+    fn sample(&self, index: u32, dim: usize) -> f64;
+
+    /// Write the point with the given `index` to `point`.
+    ///
+    /// Panics if `point.len() != self.dimensions()`.
+    fn sample_point(&self, index: u32, point: &mut [f64]) {
+        assert_eq!(point.len(), self.dimensions(),
+                   "point length does not match the number of dimensions");
+        for (dim, x) in point.iter_mut().enumerate() {
+            *x = self.sample(index, dim);
+        }
+    }
+
+    /// An iterator over the points of the sequence, starting at index 0.
+    fn points<'a>(&'a self) -> Points<'a, Self> {
+        Points { seq: self, index: 0 }
+    }
+}
+
+impl<'a, S: QuasiRandom + ?Sized> QuasiRandom for &'a S {
+    fn dimensions(&self) -> usize {
+        (**self).dimensions()
+    }
+
+    fn sample(&self, index: u32, dim: usize) -> f64 {
+        (**self).sample(index, dim)
+    }
+
+    fn sample_point(&self, index: u32, point: &mut [f64]) {
+        (**self).sample_point(index, point)
+    }
+}
+
+/// An iterator over the points of a [`QuasiRandom`] sequence.
+///
+/// This struct is created by [`QuasiRandom::points`]. It ends after `2^32`
+/// points.
+///
+/// [`QuasiRandom`]: trait.QuasiRandom.html
+/// [`QuasiRandom::points`]: trait.QuasiRandom.html#method.points
+#[derive(Debug)]
+pub struct Points<'a, S: 'a + ?Sized> {
+    seq: &'a S,
+    index: u64,
+}
+
+impl<'a, S: QuasiRandom + ?Sized> Iterator for Points<'a, S> {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index > ::core::u32::MAX as u64 {
+            return None;
+        }
+        let mut point = vec![0.0; self.seq.dimensions()];
+        self.seq.sample_point(self.index as u32, &mut point);
+        self.index += 1;
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (1u64 << 32) - self.index;
+        if remaining > ::core::usize::MAX as u64 {
+            (::core::usize::MAX, None)
+        } else {
+            (remaining as usize, Some(remaining as usize))
+        }
+    }
+}
+
+// A 64-bit mixing function (the finaliser of SplitMix64).
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,362 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Sobol sequence.
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+use Rng;
+use super::{QuasiRandom, mix};
+
+// The initial direction numbers of Joe and Kuo, without the polynomials.
+// Generated by `utils/sobol_table.py`, which describes the format and gives
+// the license of the numbers.
+static DIRECTION_NUMBERS: &[u8] = include_bytes!("sobol_table.bin");
+
+/// The Sobol low-discrepancy sequence, optionally with Owen scrambling.
+///
+/// Dimension 0 is the van der Corput sequence in base 2; the other dimensions
+/// use the primitive polynomials over GF(2) in order of degree, up to degree
+/// 18. For every `m`, the points `k * 2^m .. (k + 1) * 2^m` are evenly
+/// stratified in each dimension: every interval `[j / 2^m, (j + 1) / 2^m)`
+/// contains exactly one of them. Coordinates have 32 bits of precision, and
+/// the sequence has `2^32` points.
+///
+/// The initial direction numbers are those of Joe and Kuo (the file
+/// `new-joe-kuo-6.21201`), chosen to make the 2-dimensional projections of
+/// the sequence as uniform as possible.
+///
+/// With [`new_scrambled`], nested uniform (Owen) scrambling is applied, with
+/// a random seed per dimension taken from an `Rng`: each bit of a coordinate
+/// is flipped depending on a random function of the bits above it. This
+/// keeps the stratification, but makes every point uniformly distributed on
+/// the unit hypercube and removes the bias of the unscrambled sequence (whose
+/// first point is 0 in every dimension).
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::quasi::{QuasiRandom, Sobol};
+///
+/// let sobol = Sobol::new(2);
+/// assert_eq!(sobol.sample(1, 0), 0.5);
+/// assert_eq!(sobol.sample(2, 1), 0.75);
+///
+/// let scrambled = Sobol::new_scrambled(1000, &mut thread_rng());
+/// let mut point = [0.0; 1000];
+/// scrambled.sample_point(7, &mut point);
+/// ```
+///
+/// [`new_scrambled`]: #method.new_scrambled
+#[derive(Clone, Debug)]
+pub struct Sobol {
+    dims: usize,
+    // 32 direction numbers per dimension
+    directions: Vec<u32>,
+    // The per-dimension seeds, if scrambling
+    seeds: Option<Vec<u64>>,
+}
+
+impl Sobol {
+    /// The maximum number of dimensions supported.
+    pub const MAX_DIMENSIONS: usize = 21201;
+
+    /// Create a Sobol sequence with `dims` dimensions.
+    ///
+    /// Panics if `dims` is 0 or greater than [`MAX_DIMENSIONS`].
+    ///
+    /// [`MAX_DIMENSIONS`]: #associatedconstant.MAX_DIMENSIONS
+    pub fn new(dims: usize) -> Sobol {
+        assert!(dims > 0 && dims <= Sobol::MAX_DIMENSIONS,
+                "Sobol::new called with an unsupported number of dimensions");
+        let mut directions = Vec::with_capacity(dims * 32);
+        // The van der Corput sequence
+        directions.extend((0..32).map(|k| 1u32 << (31 - k)));
+
+        let mut polynomials = PrimitivePolynomials::new();
+        let mut bit = 0;
+        for _ in 1..dims {
+            let (poly, degree) = polynomials.next().unwrap();
+            let mut m = [0u32; 32];
+            for (k, m) in m[..degree].iter_mut().enumerate() {
+                // An odd number below 2^(k + 1), of which the `k` upper bits
+                // are stored.
+                let mut upper = 0;
+                for i in 0..k {
+                    upper |= ((DIRECTION_NUMBERS[bit / 8] >> (bit % 8)) as u32 & 1) << i;
+                    bit += 1;
+                }
+                *m = (upper << 1) | 1;
+            }
+            for k in degree..32 {
+                let mut value = m[k - degree] ^ (m[k - degree] << degree);
+                for i in 1..degree {
+                    if (poly >> (degree - i)) & 1 == 1 {
+                        value ^= m[k - i] << i;
+                    }
+                }
+                m[k] = value;
+            }
+            directions.extend(m.iter().enumerate().map(|(k, &m)| m << (31 - k)));
+        }
+        Sobol { dims: dims, directions: directions, seeds: None }
+    }
+
+    /// Create a Sobol sequence with `dims` dimensions and Owen scrambling,
+    /// taking the scrambling seeds from `rng`.
+    ///
+    /// Panics if `dims` is 0 or greater than [`MAX_DIMENSIONS`].
+    ///
+    /// [`MAX_DIMENSIONS`]: #associatedconstant.MAX_DIMENSIONS
+    pub fn new_scrambled<R: Rng + ?Sized>(dims: usize, rng: &mut R) -> Sobol {
+        let mut sobol = Sobol::new(dims);
+        sobol.seeds = Some((0..dims).map(|_| rng.gen()).collect());
+        sobol
+    }
+
+    /// Coordinate `dim` of the point with the given `index`, as a fraction of
+    /// `2^32`.
+    ///
+    /// Panics if `dim >= self.dimensions()`.
+    pub fn sample_u32(&self, index: u32, dim: usize) -> u32 {
+        assert!(dim < self.dims, "dimension out of range");
+        let directions = &self.directions[dim * 32..(dim + 1) * 32];
+        let mut x = 0;
+        let mut bits = index;
+        while bits != 0 {
+            x ^= directions[bits.trailing_zeros() as usize];
+            bits &= bits - 1;
+        }
+        match self.seeds {
+            Some(ref seeds) => owen_scramble(x, seeds[dim]),
+            None => x,
+        }
+    }
+}
+
+impl QuasiRandom for Sobol {
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    fn sample(&self, index: u32, dim: usize) -> f64 {
+        self.sample_u32(index, dim) as f64 / (1u64 << 32) as f64
+    }
+}
+
+// Flip bit `31 - i` of `x` depending on a hash of `seed`, `i` and the bits of
+// `x` above it.
+fn owen_scramble(x: u32, seed: u64) -> u32 {
+    let mut result = x;
+    for i in 0..32 {
+        // A leading 1 bit distinguishes the prefixes of different lengths.
+        let prefix = ((x as u64) >> (32 - i)) | (1 << i);
+        let hash = mix(seed ^ mix(prefix));
+        result ^= ((hash >> 63) as u32) << (31 - i);
+    }
+    result
+}
+
+// An iterator over the primitive polynomials over GF(2), in order of degree
+// and then of their binary representation (with the coefficient of `x^i` as
+// bit `i`), together with their degree.
+struct PrimitivePolynomials {
+    degree: usize,
+    poly: u64,
+    // The prime factors of 2^degree - 1
+    factors: Vec<u64>,
+}
+
+impl PrimitivePolynomials {
+    fn new() -> PrimitivePolynomials {
+        PrimitivePolynomials { degree: 0, poly: 0, factors: Vec::new() }
+    }
+
+    fn is_primitive(&self) -> bool {
+        // `poly` is primitive if and only if `x` has order 2^degree - 1
+        // modulo `poly`.
+        let order = (1u64 << self.degree) - 1;
+        self.x_pow(order) == 1 &&
+            self.factors.iter().all(|&q| self.x_pow(order / q) != 1)
+    }
+
+    // x^exp modulo `poly`
+    fn x_pow(&self, exp: u64) -> u64 {
+        let mut result = 1;
+        for bit in (0..64 - exp.leading_zeros()).rev() {
+            result = self.mul_mod(result, result);
+            if (exp >> bit) & 1 == 1 {
+                result = self.mul_mod(result, 2);
+            }
+        }
+        result
+    }
+
+    fn mul_mod(&self, a: u64, b: u64) -> u64 {
+        let mut product = 0;
+        for i in 0..self.degree + 1 {
+            if (b >> i) & 1 == 1 {
+                product ^= a << i;
+            }
+        }
+        for i in (self.degree..2 * self.degree + 1).rev() {
+            if (product >> i) & 1 == 1 {
+                product ^= self.poly << (i - self.degree);
+            }
+        }
+        product
+    }
+}
+
+impl Iterator for PrimitivePolynomials {
+    type Item = (u64, usize);
+
+    fn next(&mut self) -> Option<(u64, usize)> {
+        loop {
+            // Only odd polynomials can be primitive (apart from `x`).
+            self.poly += 2;
+            if self.poly >> self.degree > 1 {
+                self.degree += 1;
+                if self.degree > 18 {
+                    return None;
+                }
+                self.poly = (1 << self.degree) | 1;
+                let mut n = (1u64 << self.degree) - 1;
+                self.factors.clear();
+                let mut q = 3;
+                while q * q <= n {
+                    if n % q == 0 {
+                        self.factors.push(q);
+                        while n % q == 0 {
+                            n /= q;
+                        }
+                    }
+                    q += 2;
+                }
+                if n > 1 {
+                    self.factors.push(n);
+                }
+            }
+            if self.is_primitive() {
+                return Some((self.poly, self.degree));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+    use {SeedableRng, ChaChaRng};
+    use quasi::QuasiRandom;
+    use super::{Sobol, PrimitivePolynomials};
+
+    const MAX_DIMENSIONS: usize = Sobol::MAX_DIMENSIONS;
+
+    #[test]
+    fn test_primitive_polynomials() {
+        let polys = PrimitivePolynomials::new().collect::<Vec<_>>();
+        assert_eq!(polys.len(), MAX_DIMENSIONS - 1);
+        assert_eq!(&polys[..8], &[(0b11, 1), (0b111, 2), (0b1011, 3),
+                                  (0b1101, 3), (0b10011, 4), (0b11001, 4),
+                                  (0b100101, 5), (0b101001, 5)]);
+        // They are the polynomials of Joe and Kuo, which are given by the
+        // coefficients strictly between the first and last.
+        let joe_kuo = |degree: usize, a: u64| ((1 << degree) | (a << 1) | 1, degree);
+        assert_eq!(&polys[8..10], &[joe_kuo(5, 7), joe_kuo(5, 11)]);
+        assert_eq!(polys[MAX_DIMENSIONS - 2], joe_kuo(18, 131059));
+        // The numbers of primitive polynomials of degree 1 to 18
+        let counts = [1, 1, 2, 2, 6, 6, 18, 16, 48, 60, 176, 144, 630, 756,
+                      1800, 2048, 7710, 7776];
+        for (degree, &count) in counts.iter().enumerate() {
+            assert_eq!(polys.iter().filter(|p| p.1 == degree + 1).count(),
+                       count);
+        }
+    }
+
+    // Check that each of the first 2^m points lies in a different interval
+    // `[j / 2^m, (j + 1) / 2^m)` of each dimension, and that dimensions 0 and
+    // 1 form a (0, m, 2)-net.
+    fn check_stratification(sobol: &Sobol, dims: &[usize], m: u32) {
+        for &dim in dims.iter() {
+            let mut seen = vec![false; 1 << m];
+            for i in 0..(1 << m) {
+                let j = (sobol.sample_u32(i, dim) >> (32 - m)) as usize;
+                assert!(!seen[j], "dimension {}, point {}", dim, i);
+                seen[j] = true;
+            }
+        }
+        for a in 0..(m + 1) {
+            let mut seen = vec![false; 1 << m];
+            for i in 0..(1 << m) {
+                let x = if a == 0 { 0 } else { sobol.sample_u32(i, 0) >> (32 - a) };
+                let y = if a == m { 0 } else { sobol.sample_u32(i, 1) >> (32 - (m - a)) };
+                let j = ((x << (m - a)) | y) as usize;
+                assert!(!seen[j], "net property, a = {}, point {}", a, i);
+                seen[j] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn test_sobol() {
+        let sobol = Sobol::new(MAX_DIMENSIONS);
+        assert_eq!(sobol.dimensions(), MAX_DIMENSIONS);
+        for i in 0..1000u32 {
+            let reversed = (0..32).fold(0, |r, k| r | ((i >> k) & 1) << (31 - k));
+            assert_eq!(sobol.sample_u32(i, 0), reversed);
+        }
+        // Dimension 1 uses the polynomial x + 1, with m_1 = 1
+        let expected = [0.0, 0.5, 0.75, 0.25, 0.625, 0.125, 0.375, 0.875];
+        for (i, &x) in expected.iter().enumerate() {
+            assert_eq!(sobol.sample(i as u32, 1), x);
+        }
+        // Some direction numbers m_k of Joe and Kuo
+        let m = |dim: usize, k: usize| sobol.directions[dim * 32 + k - 1] >> (32 - k);
+        assert_eq!((1..3).map(|k| m(2, k)).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!((1..6).map(|k| m(9, k)).collect::<Vec<_>>(), [1, 1, 7, 11, 19]);
+        assert_eq!((1..19).map(|k| m(MAX_DIMENSIONS - 1, k)).collect::<Vec<_>>(),
+                   [1, 1, 7, 11, 15, 7, 37, 239, 337, 245, 1557, 3681, 7357, 9639,
+                    27367, 26869, 114603, 86317]);
+        assert_eq!(m(MAX_DIMENSIONS - 2, 18), 39683);
+        let dims = (0..40).chain(MAX_DIMENSIONS - 10..MAX_DIMENSIONS)
+            .collect::<Vec<_>>();
+        check_stratification(&sobol, &dims, 10);
+
+        let mut point = vec![0.0; MAX_DIMENSIONS];
+        sobol.sample_point(12345, &mut point);
+        assert_eq!(point[100], sobol.sample(12345, 100));
+        assert!(point.iter().all(|&x| x >= 0.0 && x < 1.0));
+    }
+
+    #[test]
+    fn test_sobol_scrambled() {
+        let mut rng = ChaChaRng::from_seed([11; 32]);
+        let sobol = Sobol::new_scrambled(50, &mut rng);
+        let plain = Sobol::new(50);
+        check_stratification(&sobol, &(0..50).collect::<Vec<_>>(), 10);
+        assert!(sobol.sample_u32(0, 0) != 0 || sobol.sample_u32(0, 1) != 0);
+        assert!((0..100).any(|i| sobol.sample_u32(i, 5) != plain.sample_u32(i, 5)));
+
+        // The same seed gives the same scrambling
+        let mut rng = ChaChaRng::from_seed([11; 32]);
+        let again = Sobol::new_scrambled(50, &mut rng);
+        assert_eq!(sobol.sample_u32(77, 49), again.sample_u32(77, 49));
+
+        // Integrate a product over 5 dimensions
+        let n = 1 << 12;
+        let sum: f64 = sobol.points().take(n)
+            .map(|p| p[..5].iter().map(|x| 2.0 * x).product::<f64>())
+            .sum();
+        assert!((sum / n as f64 - 1.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A wrapper around a PRNG that replaces its state after every request, so
+//! past outputs cannot be recovered from the current state.
+
+use {RngCore, CryptoRng, SeedableRng, Error, ErrorKind};
+
+/// A wrapper around a cryptographic PRNG which provides *backtracking
+/// resistance*: if its state is compromised, the outputs produced before
+/// cannot be reconstructed.
+///
+/// After every request (`next_u32`, `next_u64`, `fill_bytes` or
+/// `try_fill_bytes`), the wrapped PRNG is replaced by a new one seeded from its
+/// own next output, and the old state is discarded. This is the "fast key
+/// erasure" construction: recovering the old state would mean inverting the
+/// PRNG, which is infeasible for a CSPRNG such as [`ChaChaRng`]. Generators
+/// that are not cryptographically secure gain nothing from this wrapper.
+///
+/// This complements [`ReseedingRng`], which limits the damage of a compromised
+/// state to the future (until the next reseed); `RatchetingRng` protects the
+/// past. The two can be combined by wrapping one in the other.
+///
+/// Every request costs a reseed of the wrapped PRNG; this is cheap for
+/// `ChaChaRng`, but `Hc128Rng` has a slow initialisation. Request random data
+/// in larger blocks with `fill_bytes` where possible. With the `zeroize`
+/// feature the discarded state of `ChaChaRng` and `Hc128Rng` is overwritten.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, ChaChaRng, NewRng};
+/// use rand::ratchet::RatchetingRng;
+///
+/// let mut rng = RatchetingRng::new(ChaChaRng::new());
+/// let mut key = [0u8; 32];
+/// rng.fill(&mut key);
+/// // The state of `rng` no longer reveals `key`.
+/// ```
+///
+/// [`ChaChaRng`]: ../struct.ChaChaRng.html
+/// [`ReseedingRng`]: ../reseeding/struct.ReseedingRng.html
+#[derive(Debug)]
+pub struct RatchetingRng<R> {
+    rng: R,
+}
+
+impl<R: RngCore + SeedableRng> RatchetingRng<R> {
+    /// Create a new `RatchetingRng` wrapping `rng`.
+    pub fn new(rng: R) -> RatchetingRng<R> {
+        RatchetingRng { rng: rng }
+    }
+
+    /// Replace the wrapped PRNG with one seeded from its own output.
+    ///
+    /// This is done automatically after every request.
+    pub fn try_ratchet(&mut self) -> Result<(), Error> {
+        let rng = R::from_rng(&mut self.rng).map_err(|e|
+            Error::with_cause(ErrorKind::Unexpected,
+                              "RatchetingRng: reseeding the PRNG failed", e))?;
+        self.rng = rng;
+        Ok(())
+    }
+
+    // Like `try_ratchet`, for the methods which cannot report errors.
+    fn ratchet(&mut self) {
+        self.try_ratchet().unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl<R: RngCore + SeedableRng> RngCore for RatchetingRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        let value = self.rng.next_u32();
+        self.ratchet();
+        value
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let value = self.rng.next_u64();
+        self.ratchet();
+        value
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+        self.ratchet();
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)?;
+        self.try_ratchet()
+    }
+}
+
+impl<R: RngCore + SeedableRng> SeedableRng for RatchetingRng<R> {
+    type Seed = R::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        RatchetingRng::new(R::from_seed(seed))
+    }
+
+    fn from_rng<S: RngCore + ?Sized>(rng: &mut S) -> Result<Self, Error> {
+        R::from_rng(rng).map(RatchetingRng::new)
+    }
+}
+
+impl<R: RngCore + SeedableRng + CryptoRng> CryptoRng for RatchetingRng<R> {}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng, CryptoRng, ChaChaRng};
+    use super::RatchetingRng;
+
+    #[test]
+    fn test_ratcheting() {
+        let mut rng = RatchetingRng::<ChaChaRng>::from_seed([3; 32]);
+        let mut expected = ChaChaRng::from_seed([3; 32]);
+
+        assert_eq!(rng.next_u32(), expected.next_u32());
+        expected = ChaChaRng::from_rng(&mut expected).unwrap();
+        assert_eq!(rng.next_u64(), expected.next_u64());
+        expected = ChaChaRng::from_rng(&mut expected).unwrap();
+        let mut buf = [0u8; 40];
+        let mut expected_buf = [0u8; 40];
+        rng.fill_bytes(&mut buf);
+        expected.fill_bytes(&mut expected_buf);
+        assert_eq!(&buf[..], &expected_buf[..]);
+        expected = ChaChaRng::from_rng(&mut expected).unwrap();
+        rng.try_fill_bytes(&mut buf).unwrap();
+        expected.try_fill_bytes(&mut expected_buf).unwrap();
+        assert_eq!(&buf[..], &expected_buf[..]);
+
+        // The output differs from that of the plain generator
+        let mut plain = ChaChaRng::from_seed([3; 32]);
+        let mut rng = RatchetingRng::<ChaChaRng>::from_seed([3; 32]);
+        assert_eq!(rng.next_u32(), plain.next_u32());
+        assert!(rng.next_u32() != plain.next_u32());
+
+        fn is_crypto<R: CryptoRng>(_: &R) {}
+        is_crypto(&rng);
+    }
+}
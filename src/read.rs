@@ -8,9 +8,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! A wrapper around any Read to treat it as an RNG.
+//! A wrapper around any Read to treat it as an RNG, and an adapter to use any
+//! RNG as a Read.
 
-use std::io::Read;
+use std::cmp;
+use std::io::{self, Read, Seek, SeekFrom};
 
 use rand_core::{RngCore, Error, ErrorKind, impls};
 
@@ -18,6 +20,21 @@ use rand_core::{RngCore, Error, ErrorKind, impls};
 /// An RNG that reads random bytes straight from a `Read`. This will
 /// work best with an infinite reader, but this is not required.
 ///
+/// By default every request is read from the reader directly, so generating
+/// each `u32` costs a `read` call (a system call when reading from a file or
+/// device). [`with_capacity`] adds a buffer, from which small requests are
+/// served instead; data is then read from the reader ahead of use.
+///
+/// When the reader reaches end-of-file before a request is filled,
+/// `try_fill_bytes` returns an error of kind `Unavailable` with message
+/// `"ReadRng: end of data"`. Seekable readers can be
+/// wrapped with [`rewinding`] instead, to start again from the beginning of
+/// the data at end-of-file.
+///
+/// Other errors of the reader are returned with kind `NotReady` if the read
+/// would block and `Unavailable` otherwise, and the `std::io::Error` as
+/// cause. Reads are retried on `ErrorKind::Interrupted`.
+///
 /// # Panics
 ///
 /// It will panic if it there is insufficient data to fulfill a request.
@@ -31,16 +48,82 @@ use rand_core::{RngCore, Error, ErrorKind, impls};
 /// let mut rng = read::ReadRng::new(&data[..]);
 /// println!("{:x}", rng.gen::<u32>());
 /// ```
+///
+/// [`with_capacity`]: #method.with_capacity
+/// [`rewinding`]: #method.rewinding
 #[derive(Debug)]
 pub struct ReadRng<R> {
-    reader: R
+    reader: R,
+    buf: Vec<u8>,
+    pos: usize,
+    rewind: Option<fn(&mut R) -> io::Result<u64>>,
 }
 
 impl<R: Read> ReadRng<R> {
     /// Create a new `ReadRng` from a `Read`.
     pub fn new(r: R) -> ReadRng<R> {
+        ReadRng::with_capacity(r, 0)
+    }
+
+    /// Create a new `ReadRng` from a `Read`, reading through a buffer of
+    /// `capacity` bytes.
+    ///
+    /// Requests at least as large as the buffer are read directly.
+    pub fn with_capacity(r: R, capacity: usize) -> ReadRng<R> {
         ReadRng {
-            reader: r
+            reader: r,
+            buf: Vec::with_capacity(capacity),
+            pos: 0,
+            rewind: None,
+        }
+    }
+
+    /// Unwrap the inner reader. Buffered data is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read + Seek> ReadRng<R> {
+    /// Create a new `ReadRng` from a seekable `Read`, which seeks back to the
+    /// start at end-of-file, repeating the data indefinitely.
+    ///
+    /// Requests fail only if the data is empty.
+    pub fn rewinding(r: R, capacity: usize) -> ReadRng<R> {
+        fn rewind<R: Seek>(r: &mut R) -> io::Result<u64> {
+            r.seek(SeekFrom::Start(0))
+        }
+        let mut rng = ReadRng::with_capacity(r, capacity);
+        rng.rewind = Some(rewind::<R>);
+        rng
+    }
+}
+
+// Read some bytes into `dest`, which must not be empty.
+fn read_some<R: Read>(reader: &mut R, rewind: Option<fn(&mut R) -> io::Result<u64>>,
+                      dest: &mut [u8]) -> Result<usize, Error>
+{
+    let mut rewound = false;
+    loop {
+        match reader.read(dest) {
+            Ok(0) => match rewind {
+                Some(rewind) if !rewound => {
+                    rewind(reader).map_err(|err| Error::with_cause(
+                        ErrorKind::Unavailable, "ReadRng: rewinding failed", err))?;
+                    rewound = true;
+                }
+                _ => return Err(Error::new(ErrorKind::Unavailable,
+                                           "ReadRng: end of data")),
+            },
+            Ok(n) => return Ok(n),
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(match err.kind() {
+                io::ErrorKind::WouldBlock => Error::with_cause(
+                    ErrorKind::NotReady,
+                    "reading from random device would block", err),
+                _ => Error::with_cause(ErrorKind::Unavailable,
+                    "error reading random device", err)
+            }),
         }
     }
 }
@@ -59,17 +142,79 @@ impl<R: Read> RngCore for ReadRng<R> {
     }
 
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
-        if dest.len() == 0 { return Ok(()); }
-        // Use `std::io::read_exact`, which retries on `ErrorKind::Interrupted`.
-        self.reader.read_exact(dest).map_err(|err| {
-            match err.kind() {
-                ::std::io::ErrorKind::WouldBlock => Error::with_cause(
-                    ErrorKind::NotReady,
-                    "reading from random device would block", err),
-                _ => Error::with_cause(ErrorKind::Unavailable,
-                    "error reading random device", err)
+        let mut filled = 0;
+        while filled < dest.len() {
+            if self.pos < self.buf.len() {
+                let n = cmp::min(self.buf.len() - self.pos, dest.len() - filled);
+                dest[filled..filled + n]
+                    .copy_from_slice(&self.buf[self.pos..self.pos + n]);
+                self.pos += n;
+                filled += n;
+            } else if dest.len() - filled >= self.buf.capacity() {
+                filled += read_some(&mut self.reader, self.rewind,
+                                    &mut dest[filled..])?;
+            } else {
+                let capacity = self.buf.capacity();
+                self.buf.resize(capacity, 0);
+                self.pos = 0;
+                match read_some(&mut self.reader, self.rewind, &mut self.buf) {
+                    Ok(n) => self.buf.truncate(n),
+                    Err(err) => {
+                        self.buf.clear();
+                        return Err(err);
+                    }
+                }
             }
-        })
+        }
+        Ok(())
+    }
+}
+
+/// An adapter implementing `std::io::Read` for any RNG, to pass random data
+/// to APIs consuming readers.
+///
+/// Reads always fill the whole buffer. The reader never ends, so use
+/// `Read::take` to read a limited amount. Errors of the RNG are returned as
+/// `std::io::Error` of kind `Other`, with the RNG's `Error` as the inner
+/// error.
+///
+/// # Example
+///
+/// Writing 1 KiB of random data, e.g. to create a test fixture:
+///
+/// ```rust
+/// use std::io::{self, Read};
+/// use rand::read::RngReadAdapter;
+///
+/// let mut data = Vec::new();
+/// let mut reader = RngReadAdapter::new(rand::thread_rng()).take(1024);
+/// io::copy(&mut reader, &mut data).unwrap();
+/// assert_eq!(data.len(), 1024);
+/// ```
+#[derive(Debug)]
+pub struct RngReadAdapter<R> {
+    rng: R
+}
+
+impl<R: RngCore> RngReadAdapter<R> {
+    /// Create a new `RngReadAdapter` from an RNG.
+    pub fn new(rng: R) -> RngReadAdapter<R> {
+        RngReadAdapter {
+            rng: rng
+        }
+    }
+
+    /// Unwrap the inner generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: RngCore> Read for RngReadAdapter<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.rng.try_fill_bytes(buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        Ok(buf.len())
     }
 }
 
@@ -117,4 +262,122 @@ mod test {
         let mut v = [0u8; 3];
         rng.fill_bytes(&mut v);
     }
+
+    #[test]
+    fn test_reader_rng_buffered() {
+        use std::io::{self, Read};
+
+        // Counts the calls to `read`
+        struct Counting<'a>(&'a [u8], usize);
+        impl<'a> Read for Counting<'a> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.1 += 1;
+                self.0.read(buf)
+            }
+        }
+
+        let data: Vec<u8> = (0..100).collect();
+        let mut rng = ReadRng::with_capacity(Counting(&data, 0), 32);
+        assert_eq!(rng.next_u32(), 0x03020100);
+        assert_eq!(rng.next_u32(), 0x07060504);
+        let mut buf = [0u8; 40];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf[0], 8);
+        assert_eq!(buf[39], 47);
+        assert_eq!(rng.reader.1, 2);
+        let mut buf = [0u8; 52];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf[51], 99);
+
+        let err = rng.try_fill_bytes(&mut [0u8; 1]).unwrap_err();
+        assert_eq!(err.kind, ::ErrorKind::Unavailable);
+        assert_eq!(err.msg, "ReadRng: end of data");
+    }
+
+    #[test]
+    fn test_reader_rng_rewinding() {
+        use std::io::Cursor;
+
+        let mut rng = ReadRng::rewinding(Cursor::new(vec![1u8, 2, 3]), 0);
+        let mut buf = [0u8; 7];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf, [1, 2, 3, 1, 2, 3, 1]);
+
+        let mut rng = ReadRng::rewinding(Cursor::new(vec![1u8, 2, 3]), 2);
+        rng.fill_bytes(&mut buf[..1]);
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf, [2, 3, 1, 2, 3, 1, 2]);
+
+        let mut empty = ReadRng::rewinding(Cursor::new(Vec::<u8>::new()), 4);
+        let err = empty.try_fill_bytes(&mut [0u8; 1]).unwrap_err();
+        assert_eq!(err.msg, "ReadRng: end of data");
+    }
+
+    #[test]
+    fn test_rng_read_adapter() {
+        use std::io::{self, Read};
+        use mock::{FaultyRng, StepRng};
+        use super::RngReadAdapter;
+
+        let mut data = Vec::new();
+        let mut reader = RngReadAdapter::new(StepRng::new(1, 1)).take(20);
+        assert_eq!(io::copy(&mut reader, &mut data).unwrap(), 20);
+        assert_eq!(&data[..9], &[1, 0, 0, 0, 0, 0, 0, 0, 2]);
+
+        // Read back through `ReadRng`
+        let mut rng = ReadRng::new(&data[..]);
+        assert_eq!(rng.next_u64(), 1);
+        assert_eq!(rng.next_u64(), 2);
+
+        let faulty = FaultyRng::new(StepRng::new(0, 1),
+                                    |_| Some(::ErrorKind::Unavailable));
+        let err = RngReadAdapter::new(faulty).read(&mut [0u8; 4]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        let inner = err.get_ref().unwrap().downcast_ref::<::Error>().unwrap();
+        assert_eq!(inner.kind, ::ErrorKind::Unavailable);
+    }
+
+    #[test]
+    fn test_reader_rng_error_source() {
+        use std::error::Error;
+        use std::io;
+
+        struct Failing;
+        impl io::Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::from_raw_os_error(5))
+            }
+        }
+
+        let mut rng = ReadRng::new(Failing);
+        let err = rng.try_fill_bytes(&mut [0u8; 4]).unwrap_err();
+        assert_eq!(err.kind, ::ErrorKind::Unavailable);
+        assert_eq!(err.code(), Some(5));
+        assert_eq!(err.domain(), Some("os"));
+        let source = err.source().unwrap();
+        let io_err = source.downcast_ref::<io::Error>().unwrap();
+        assert_eq!(io_err.raw_os_error(), Some(5));
+
+        let err = ::Error::with_code(::ErrorKind::NotReady, "not seeded", 11);
+        assert_eq!(err.code(), Some(11));
+        assert!(err.source().is_none());
+        assert_eq!(err.to_string(), "not seeded (not ready yet); code: 11");
+
+        #[derive(Debug)]
+        struct DeviceError(u8);
+        impl ::std::fmt::Display for DeviceError {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(f, "device {} removed", self.0)
+            }
+        }
+        impl Error for DeviceError {}
+
+        let err = ::Error::custom(::ErrorKind::Unavailable, "no device",
+                                  "hsm", 3, DeviceError(2));
+        assert_eq!((err.domain(), err.code()), (Some("hsm"), Some(3)));
+        assert_eq!(err.downcast_ref::<DeviceError>().unwrap().0, 2);
+        assert!(err.downcast_ref::<io::Error>().is_none());
+        assert_eq!(err.to_string(),
+                   "no device (permanently unavailable); hsm code: 3; cause: device 2 removed");
+    }
 }
@@ -0,0 +1,677 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Recording and replaying the randomness consumed from an RNG.
+//!
+//! [`RecorderRng`] passes all values of an RNG through unchanged, while
+//! recording every call into a [`Sink`]. With the `std` or `alloc` feature a
+//! `Vec<Entry>` can serve as the sink, keeping a complete script of the
+//! randomness that was consumed. [`ReplayRng`] plays such a script back, to
+//! reproduce e.g. a failure in a randomized test or simulation exactly.
+//! [`CountingRng`] only counts the calls and bytes, to measure how much
+//! randomness an algorithm consumes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rand::{Rng, thread_rng};
+//! use rand::record::{RecorderRng, ReplayRng};
+//!
+//! fn simulation<R: Rng>(rng: &mut R) -> u32 {
+//!     rng.gen_range(0..100) + rng.gen_range(0..100)
+//! }
+//!
+//! let mut rng = RecorderRng::new(thread_rng());
+//! let result = simulation(&mut rng);
+//! let (_, script) = rng.into_inner();
+//!
+//! // Later, e.g. after loading the script from a failing test case
+//! let mut replay = ReplayRng::new(script);
+//! assert_eq!(simulation(&mut replay), result);
+//! ```
+//!
+//! [`RecorderRng`]: struct.RecorderRng.html
+//! [`CountingRng`]: struct.CountingRng.html
+//! [`ReplayRng`]: struct.ReplayRng.html
+//! [`Sink`]: trait.Sink.html
+
+use core::{cmp, fmt, mem};
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+use {RngCore, CryptoRng, Error, ErrorKind};
+
+/// A single call to an RNG, as passed to a [`Sink`] by [`RecorderRng`].
+///
+/// [`Sink`]: trait.Sink.html
+/// [`RecorderRng`]: struct.RecorderRng.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event<'a> {
+    /// The result of `next_u32`.
+    U32(u32),
+    /// The result of `next_u64`.
+    U64(u64),
+    /// The bytes written by `fill_bytes`, or by a successful call to
+    /// `try_fill_bytes`.
+    Bytes(&'a [u8]),
+}
+
+/// An owned [`Event`], as stored in a recorded script.
+///
+/// [`Event`]: enum.Event.html
+#[cfg(any(feature="std", feature="alloc"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    /// The result of `next_u32`.
+    U32(u32),
+    /// The result of `next_u64`.
+    U64(u64),
+    /// The bytes written by `fill_bytes` or `try_fill_bytes`.
+    Bytes(Vec<u8>),
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl<'a> From<Event<'a>> for Entry {
+    fn from(event: Event<'a>) -> Entry {
+        match event {
+            Event::U32(x) => Entry::U32(x),
+            Event::U64(x) => Entry::U64(x),
+            Event::Bytes(bytes) => Entry::Bytes(bytes.to_vec()),
+        }
+    }
+}
+
+/// A destination for the events recorded by a [`RecorderRng`].
+///
+/// [`RecorderRng`]: struct.RecorderRng.html
+pub trait Sink {
+    /// Record a single call to the RNG.
+    fn record(&mut self, event: Event);
+}
+
+impl<'a, S: Sink + ?Sized> Sink for &'a mut S {
+    fn record(&mut self, event: Event) {
+        (**self).record(event)
+    }
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl Sink for Vec<Entry> {
+    fn record(&mut self, event: Event) {
+        self.push(event.into());
+    }
+}
+
+/// A wrapper around an RNG that records all values it produces.
+///
+/// Every call to `next_u32`, `next_u64`, `fill_bytes` and successful call to
+/// `try_fill_bytes` is passed on to the wrapped RNG, and its result to the
+/// [`Sink`] before it is returned. Other methods of `RngCore` use their
+/// default implementations, so they are recorded in terms of these four.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, SeedableRng, XorShiftRng};
+/// use rand::record::{RecorderRng, Entry};
+///
+/// let mut rng = RecorderRng::new(XorShiftRng::from_seed([1; 16]));
+/// let x: u32 = rng.gen();
+/// let (_, script) = rng.into_inner();
+/// assert_eq!(script, vec![Entry::U32(x)]);
+/// ```
+///
+/// [`Sink`]: trait.Sink.html
+#[derive(Clone, Debug)]
+pub struct RecorderRng<R, S> {
+    rng: R,
+    sink: S,
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl<R: RngCore> RecorderRng<R, Vec<Entry>> {
+    /// Create a new `RecorderRng` wrapping `rng`, recording into a `Vec`.
+    pub fn new(rng: R) -> RecorderRng<R, Vec<Entry>> {
+        RecorderRng::with_sink(rng, Vec::new())
+    }
+}
+
+impl<R: RngCore, S: Sink> RecorderRng<R, S> {
+    /// Create a new `RecorderRng` wrapping `rng`, recording into `sink`.
+    pub fn with_sink(rng: R, sink: S) -> RecorderRng<R, S> {
+        RecorderRng { rng: rng, sink: sink }
+    }
+
+    /// A reference to the sink.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// A mutable reference to the sink, e.g. to clear a recording.
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Unwrap the inner generator and the sink.
+    pub fn into_inner(self) -> (R, S) {
+        (self.rng, self.sink)
+    }
+}
+
+impl<R: RngCore, S: Sink> RngCore for RecorderRng<R, S> {
+    fn next_u32(&mut self) -> u32 {
+        let x = self.rng.next_u32();
+        self.sink.record(Event::U32(x));
+        x
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let x = self.rng.next_u64();
+        self.sink.record(Event::U64(x));
+        x
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+        self.sink.record(Event::Bytes(dest));
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)?;
+        self.sink.record(Event::Bytes(dest));
+        Ok(())
+    }
+}
+
+impl<R: RngCore + CryptoRng, S: Sink> CryptoRng for RecorderRng<R, S> {}
+
+/// The calls counted by a [`CountingRng`].
+///
+/// `bytes` counts all bytes produced: 4 for `next_u32`, 8 for `next_u64`, and
+/// the length of the destination for `fill_bytes` and successful calls to
+/// `try_fill_bytes`.
+///
+/// If enabled, `sizes` is a histogram of the sizes in bytes of all requests
+/// (including `next_u32` and `next_u64`), in powers of two: `sizes[i]`
+/// counts the requests of `n` bytes where `n` has `i` significant bits, so
+/// `sizes[0]` counts empty requests, `sizes[3]` requests of 4 to 7 bytes and
+/// the last bucket all requests of at least `2^30` bytes.
+///
+/// [`CountingRng`]: struct.CountingRng.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// The number of calls to `next_u32`.
+    pub next_u32: u64,
+    /// The number of calls to `next_u64`.
+    pub next_u64: u64,
+    /// The number of calls to `fill_bytes`.
+    pub fill_bytes: u64,
+    /// The number of calls to `try_fill_bytes`, including failed ones.
+    pub try_fill_bytes: u64,
+    /// The number of calls to `try_fill_bytes` that returned an error.
+    pub errors: u64,
+    /// The number of bytes produced.
+    pub bytes: u64,
+    /// The histogram of request sizes, if enabled.
+    pub sizes: Option<[u64; 32]>,
+}
+
+impl Counts {
+    /// The total number of calls.
+    pub fn calls(&self) -> u64 {
+        self.next_u32 + self.next_u64 + self.fill_bytes + self.try_fill_bytes
+    }
+
+    fn request(&mut self, len: usize) {
+        if let Some(ref mut sizes) = self.sizes {
+            let bits = (0usize.count_zeros() - len.leading_zeros()) as usize;
+            sizes[cmp::min(bits, sizes.len() - 1)] += 1;
+        }
+    }
+}
+
+/// A wrapper around an RNG that counts the calls and bytes produced.
+///
+/// This measures how much randomness an algorithm consumes, e.g. to check
+/// that it needs exactly one value per step. All values of the wrapped RNG
+/// are passed through unchanged. `fill_u32_slice` and `fill_u64_slice` use
+/// their default implementations, so they count as one call to `next_u32` or
+/// `next_u64` per value.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{SeedableRng, XorShiftRng, seq};
+/// use rand::record::CountingRng;
+///
+/// let mut rng = CountingRng::new(XorShiftRng::from_seed([1; 16]));
+/// let mut v = [0; 10];
+/// seq::portable_shuffle(&mut rng, &mut v);
+/// // One value for each of the 9 swaps; rejections are very unlikely
+/// assert_eq!(rng.counts().next_u32, 9);
+/// assert_eq!(rng.counts().bytes, 36);
+/// ```
+///
+/// [`Counts`]: struct.Counts.html
+#[derive(Clone, Debug)]
+pub struct CountingRng<R> {
+    rng: R,
+    counts: Counts,
+}
+
+impl<R: RngCore> CountingRng<R> {
+    /// Create a new `CountingRng` wrapping `rng`.
+    pub fn new(rng: R) -> CountingRng<R> {
+        CountingRng { rng: rng, counts: Counts::default() }
+    }
+
+    /// Create a new `CountingRng` wrapping `rng`, which also keeps a
+    /// histogram of the request sizes.
+    pub fn with_histogram(rng: R) -> CountingRng<R> {
+        let counts = Counts { sizes: Some([0; 32]), ..Counts::default() };
+        CountingRng { rng: rng, counts: counts }
+    }
+
+    /// The counts so far.
+    pub fn counts(&self) -> &Counts {
+        &self.counts
+    }
+
+    /// Reset all counts to zero, and return the previous counts.
+    pub fn reset(&mut self) -> Counts {
+        let empty = Counts {
+            sizes: self.counts.sizes.map(|_| [0; 32]),
+            ..Counts::default()
+        };
+        mem::replace(&mut self.counts, empty)
+    }
+
+    /// Unwrap the inner generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: RngCore> RngCore for CountingRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.counts.next_u32 += 1;
+        self.counts.bytes += 4;
+        self.counts.request(4);
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.counts.next_u64 += 1;
+        self.counts.bytes += 8;
+        self.counts.request(8);
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.counts.fill_bytes += 1;
+        self.counts.bytes += dest.len() as u64;
+        self.counts.request(dest.len());
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.counts.try_fill_bytes += 1;
+        self.counts.request(dest.len());
+        let result = self.rng.try_fill_bytes(dest);
+        match result {
+            Ok(()) => self.counts.bytes += dest.len() as u64,
+            Err(_) => self.counts.errors += 1,
+        }
+        result
+    }
+}
+
+impl<R: RngCore + CryptoRng> CryptoRng for CountingRng<R> {}
+
+/// How a [`ReplayRng`] plays back its script.
+///
+/// [`ReplayRng`]: struct.ReplayRng.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    /// Every call must match the next entry of the script: `next_u32` an
+    /// `Entry::U32`, `next_u64` an `Entry::U64`, and `fill_bytes` an
+    /// `Entry::Bytes` of the same length. This detects any change in how the
+    /// code consumes randomness.
+    Exact,
+    /// The script is treated as one stream of bytes, with the words in
+    /// little-endian order; every call takes as many bytes as it needs. This
+    /// allows replaying when the values are still consumed in the same
+    /// amounts, but e.g. as bytes instead of words.
+    Stream,
+}
+
+/// The error when the script of a [`ReplayRng`] does not match its use.
+///
+/// `try_fill_bytes` returns this as the cause of an `Error` with kind
+/// `ErrorKind::Unavailable`; the other methods panic with its description.
+///
+/// [`ReplayRng`]: struct.ReplayRng.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayError {
+    /// All of the script has been used.
+    Exhausted,
+    /// With `Playback::Exact`, the method called or number of bytes requested
+    /// differs from the next entry.
+    Mismatch,
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+impl ReplayError {
+    fn description(&self) -> &'static str {
+        match *self {
+            ReplayError::Exhausted => "replay script exhausted",
+            ReplayError::Mismatch => "replay script consumed in a different pattern",
+            ReplayError::__Nonexhaustive => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+#[cfg(feature="std")]
+impl ::std::error::Error for ReplayError {
+    fn description(&self) -> &str {
+        self.description()
+    }
+}
+
+impl From<ReplayError> for Error {
+    fn from(err: ReplayError) -> Error {
+        Error::with_cause(ErrorKind::Unavailable, err.description(), err)
+    }
+}
+
+/// An RNG playing back a script recorded by [`RecorderRng`].
+///
+/// The script can be anything implementing `AsRef<[Entry]>`, such as the
+/// `Vec` recorded by `RecorderRng`, or a slice of it. How the entries are
+/// matched to calls is selected with [`Playback`]. When the script is
+/// exhausted or does not match, `try_fill_bytes` returns an error and the
+/// other methods panic; see [`ReplayError`]. The position in the script after
+/// an error is unspecified.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::RngCore;
+/// use rand::record::{ReplayRng, Playback, Entry};
+///
+/// let script = vec![Entry::U32(7), Entry::Bytes(vec![1, 2, 3])];
+/// let mut rng = ReplayRng::new(&script[..]);
+/// assert_eq!(rng.next_u32(), 7);
+/// let mut buf = [0u8; 2];
+/// assert!(rng.try_fill_bytes(&mut buf).is_err()); // 2 bytes instead of 3
+///
+/// let mut rng = ReplayRng::with_playback(&script[..], Playback::Stream);
+/// let mut buf = [0u8; 6];
+/// rng.fill_bytes(&mut buf);
+/// assert_eq!(buf, [7, 0, 0, 0, 1, 2]);
+/// ```
+///
+/// [`RecorderRng`]: struct.RecorderRng.html
+/// [`Playback`]: enum.Playback.html
+/// [`ReplayError`]: enum.ReplayError.html
+#[cfg(any(feature="std", feature="alloc"))]
+#[derive(Clone, Debug)]
+pub struct ReplayRng<T> {
+    script: T,
+    playback: Playback,
+    // Index of the next entry, and with `Playback::Stream` the number of
+    // bytes of it already used.
+    index: usize,
+    offset: usize,
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl<T: AsRef<[Entry]>> ReplayRng<T> {
+    /// Create a `ReplayRng` playing back `script` with `Playback::Exact`.
+    pub fn new(script: T) -> ReplayRng<T> {
+        ReplayRng::with_playback(script, Playback::Exact)
+    }
+
+    /// Create a `ReplayRng` playing back `script` as selected by `playback`.
+    pub fn with_playback(script: T, playback: Playback) -> ReplayRng<T> {
+        ReplayRng { script: script, playback: playback, index: 0, offset: 0 }
+    }
+
+    /// The number of entries of the script not yet (completely) used.
+    pub fn remaining(&self) -> usize {
+        self.script.as_ref().len() - self.index
+    }
+
+    /// Unwrap the script.
+    pub fn into_inner(self) -> T {
+        self.script
+    }
+
+    fn stream_fill(&mut self, dest: &mut [u8]) -> Result<(), ReplayError> {
+        let script = self.script.as_ref();
+        for byte in dest.iter_mut() {
+            loop {
+                let entry = script.get(self.index).ok_or(ReplayError::Exhausted)?;
+                let (x, len) = match *entry {
+                    Entry::U32(x) => (x.checked_shr(8 * self.offset as u32).unwrap_or(0) as u8, 4),
+                    Entry::U64(x) => (x.checked_shr(8 * self.offset as u32).unwrap_or(0) as u8, 8),
+                    Entry::Bytes(ref bytes) =>
+                        (bytes.get(self.offset).cloned().unwrap_or(0), bytes.len()),
+                };
+                if self.offset < len {
+                    *byte = x;
+                    self.offset += 1;
+                    break;
+                }
+                self.index += 1;
+                self.offset = 0;
+            }
+        }
+        Ok(())
+    }
+
+    fn next_entry(&mut self) -> Result<&Entry, ReplayError> {
+        let entry = self.script.as_ref().get(self.index)
+            .ok_or(ReplayError::Exhausted)?;
+        self.index += 1;
+        Ok(entry)
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, ReplayError> {
+        if self.playback == Playback::Stream {
+            let mut buf = [0; 4];
+            self.stream_fill(&mut buf)?;
+            return Ok(u32::from(buf[0]) | u32::from(buf[1]) << 8
+                      | u32::from(buf[2]) << 16 | u32::from(buf[3]) << 24);
+        }
+        match *self.next_entry()? {
+            Entry::U32(x) => Ok(x),
+            _ => Err(ReplayError::Mismatch),
+        }
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, ReplayError> {
+        if self.playback == Playback::Stream {
+            let low = self.try_next_u32()?;
+            let high = self.try_next_u32()?;
+            return Ok(u64::from(high) << 32 | u64::from(low));
+        }
+        match *self.next_entry()? {
+            Entry::U64(x) => Ok(x),
+            _ => Err(ReplayError::Mismatch),
+        }
+    }
+
+    fn try_fill(&mut self, dest: &mut [u8]) -> Result<(), ReplayError> {
+        if self.playback == Playback::Stream {
+            return self.stream_fill(dest);
+        }
+        match *self.next_entry()? {
+            Entry::Bytes(ref bytes) if bytes.len() == dest.len() => {
+                dest.copy_from_slice(bytes);
+                Ok(())
+            }
+            _ => Err(ReplayError::Mismatch),
+        }
+    }
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl<T: AsRef<[Entry]>> RngCore for ReplayRng<T> {
+    fn next_u32(&mut self) -> u32 {
+        self.try_next_u32().unwrap_or_else(|e| panic!("ReplayRng: {}", e))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.try_next_u64().unwrap_or_else(|e| panic!("ReplayRng: {}", e))
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill(dest).unwrap_or_else(|e| panic!("ReplayRng: {}", e))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.try_fill(dest).map_err(Error::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {Rng, RngCore};
+    use mock::StepRng;
+    use super::{RecorderRng, Sink, Event};
+
+    // Counts the recorded bytes, without allocating.
+    struct ByteCount(usize);
+
+    impl Sink for ByteCount {
+        fn record(&mut self, event: Event) {
+            self.0 += match event {
+                Event::U32(_) => 4,
+                Event::U64(_) => 8,
+                Event::Bytes(bytes) => bytes.len(),
+            };
+        }
+    }
+
+    #[test]
+    fn test_recorder_sink() {
+        let mut count = ByteCount(0);
+        {
+            let mut rng = RecorderRng::with_sink(StepRng::new(1, 1), &mut count);
+            assert_eq!(rng.next_u32(), 1);
+            assert_eq!(rng.next_u64(), 2);
+            let mut buf = [0u8; 5];
+            rng.fill_bytes(&mut buf);
+            assert_eq!(buf, [3, 0, 0, 0, 0]);
+            rng.try_fill_bytes(&mut buf[..2]).unwrap();
+            let _: u16 = rng.gen();
+        }
+        assert_eq!(count.0, 4 + 8 + 5 + 2 + 4);
+    }
+
+    #[test]
+    #[cfg(any(feature="std", feature="alloc"))]
+    fn test_recorder_vec() {
+        use super::Entry;
+
+        let mut rng = RecorderRng::new(StepRng::new(1, 1));
+        rng.next_u64();
+        let mut buf = [0u8; 2];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(rng.sink(), &vec![Entry::U64(1), Entry::Bytes(vec![2, 0])]);
+        rng.sink_mut().clear();
+        rng.next_u32();
+        let (mut inner, script) = rng.into_inner();
+        assert_eq!(script, vec![Entry::U32(3)]);
+        assert_eq!(inner.next_u64(), 4);
+    }
+
+    #[test]
+    #[cfg(any(feature="std", feature="alloc"))]
+    fn test_record_replay() {
+        #[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+        use {ErrorKind, SeedableRng, XorShiftRng};
+        use distributions::{Distribution, Range};
+        use super::ReplayRng;
+
+        let mut rng = RecorderRng::new(XorShiftRng::from_seed([3; 16]));
+        let a: Vec<u32> = Range::new(0u32, 10).sample_iter(&mut rng).take(10).collect();
+        let b: [u8; 13] = rng.gen();
+        let (_, script) = rng.into_inner();
+
+        let mut replay = ReplayRng::new(&script);
+        let a2: Vec<u32> = Range::new(0u32, 10).sample_iter(&mut replay).take(10).collect();
+        let b2: [u8; 13] = replay.gen();
+        assert_eq!((a, b), (a2, b2));
+        assert_eq!(replay.remaining(), 0);
+        let err = replay.try_fill_bytes(&mut [0]).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+        assert_eq!(err.msg, "replay script exhausted");
+    }
+
+    #[test]
+    #[cfg(any(feature="std", feature="alloc"))]
+    fn test_replay_stream() {
+        use super::{ReplayRng, Playback, Entry};
+
+        let script = vec![Entry::U32(0x04030201), Entry::Bytes(vec![5, 6]),
+                          Entry::U64(0x0e0d0c0b0a090807)];
+        let mut rng = ReplayRng::with_playback(script, Playback::Stream);
+        assert_eq!(rng.next_u64(), 0x0807060504030201);
+        let mut buf = [0u8; 3];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf, [9, 10, 11]);
+        assert!(rng.try_fill_bytes(&mut [0u8; 4]).is_err());
+    }
+
+    #[test]
+    fn test_counting() {
+        use ErrorKind;
+        use mock::FaultyRng;
+        use super::CountingRng;
+
+        let faulty = FaultyRng::new(StepRng::new(0, 1),
+                                    |call| if call == 3 { Some(ErrorKind::NotReady) } else { None });
+        let mut rng = CountingRng::with_histogram(faulty);
+        rng.next_u32();
+        rng.next_u64();
+        rng.fill_bytes(&mut [0u8; 100]);
+        assert!(rng.try_fill_bytes(&mut [0u8; 2]).is_err());
+        rng.try_fill_bytes(&mut []).unwrap();
+        let counts = rng.reset();
+        assert_eq!((counts.next_u32, counts.next_u64, counts.fill_bytes,
+                    counts.try_fill_bytes, counts.errors), (1, 1, 1, 2, 1));
+        assert_eq!(counts.calls(), 5);
+        assert_eq!(counts.bytes, 4 + 8 + 100);
+        let sizes = counts.sizes.unwrap();
+        assert_eq!(&sizes[..8], &[1, 0, 1, 1, 1, 0, 0, 1]);
+
+        assert_eq!(rng.counts().calls(), 0);
+        assert_eq!(rng.counts().sizes, Some([0; 32]));
+        assert_eq!(CountingRng::new(StepRng::new(0, 1)).counts().sizes, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "consumed in a different pattern")]
+    #[cfg(any(feature="std", feature="alloc"))]
+    fn test_replay_mismatch() {
+        use super::{ReplayRng, Entry};
+
+        let mut rng = ReplayRng::new(vec![Entry::U64(1)]);
+        rng.next_u32();
+    }
+}
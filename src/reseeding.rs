@@ -11,7 +11,7 @@
 //! A wrapper around another PRNG that reseeds it after it
 //! generates a certain number of random bytes.
 
-use {RngCore, SeedableRng, Error, ErrorKind};
+use {RngCore, CryptoRng, SeedableRng, Error, ErrorKind};
 
 /// A wrapper around any PRNG which reseeds the underlying PRNG after it has
 /// generated a certain number of random bytes.
@@ -54,9 +54,18 @@ use {RngCore, SeedableRng, Error, ErrorKind};
 /// `fill_bytes` because they can make use of this error handling strategy.
 /// Use `try_fill_bytes` and possibly `try_reseed` if you want to handle
 /// reseeding errors explicitly.
+///
+/// # Serialization
+///
+/// With the `serde-1` feature, a `ReseedingRng` can be serialized if `R` can.
+/// Only the wrapped PRNG and the reseeding schedule are stored; the reseeder
+/// is an entropy source, not state, and is recreated with `Default` on
+/// deserialization.
 #[derive(Debug)]
+#[cfg_attr(feature="serde-1", derive(Serialize,Deserialize))]
 pub struct ReseedingRng<R, Rsdr> {
     rng: R,
+    #[cfg_attr(feature="serde-1", serde(skip))]
     reseeder: Rsdr,
     threshold: i64,
     bytes_until_reseed: i64,
@@ -167,8 +176,28 @@ impl<R: RngCore + SeedableRng, Rsdr: RngCore> RngCore for ReseedingRng<R, Rsdr>
             res2
         }
     }
+
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        self.rng.fill_u32_slice(dest);
+        self.bytes_until_reseed -= dest.len() as i64 * 4;
+        if self.bytes_until_reseed <= 0 {
+            self.reseed();
+        }
+    }
+
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        self.rng.fill_u64_slice(dest);
+        self.bytes_until_reseed -= dest.len() as i64 * 8;
+        if self.bytes_until_reseed <= 0 {
+            self.reseed();
+        }
+    }
 }
 
+impl<R, Rsdr> CryptoRng for ReseedingRng<R, Rsdr>
+    where R: RngCore + SeedableRng + CryptoRng,
+          Rsdr: RngCore + CryptoRng {}
+
 #[cfg(test)]
 mod test {
     use {Rng, SeedableRng, StdRng};
@@ -191,4 +220,45 @@ mod test {
             assert_eq!(buf, seq);
         }
     }
+
+    #[test]
+    fn test_reseeding_errors() {
+        use ErrorKind;
+        use mock::FaultyRng;
+
+        // Reseeding is retried after fewer bytes for less permanent errors
+        let expected = [(ErrorKind::Transient, 0), (ErrorKind::NotReady, 1),
+                        (ErrorKind::Unavailable, 256)];
+        for &(kind, delay) in expected.iter() {
+            let rng = StdRng::from_seed([0; 32]);
+            let reseeder = FaultyRng::new(StepRng::new(0, 1),
+                                          |call| if call == 0 { Some(kind) } else { None });
+            let mut reseeding = ReseedingRng::new(rng, 256, reseeder);
+            let err = reseeding.try_reseed().unwrap_err();
+            assert_eq!(err.kind, ErrorKind::Transient);
+            assert_eq!(reseeding.bytes_until_reseed, delay);
+            assert!(reseeding.try_reseed().is_ok());
+            assert_eq!(reseeding.bytes_until_reseed, 256);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature="serde-1", feature="std"))]
+    fn test_reseeding_serde() {
+        use bincode;
+        use EntropyRng;
+
+        let mut zero = StepRng::new(0, 0);
+        let inner = StdRng::from_rng(&mut zero).unwrap();
+        let mut rng = ReseedingRng::new(inner, 1 << 20, EntropyRng::new());
+        rng.gen::<u64>();
+
+        let buf = bincode::serialize(&rng).expect("Could not serialize");
+        let mut deserialized: ReseedingRng<StdRng, EntropyRng> =
+            bincode::deserialize(&buf).expect("Could not deserialize");
+        assert_eq!(deserialized.bytes_until_reseed, rng.bytes_until_reseed);
+        for _ in 0..100 {
+            assert_eq!(rng.gen::<u64>(), deserialized.gen::<u64>());
+        }
+    }
 }
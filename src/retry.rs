@@ -0,0 +1,163 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A wrapper around a fallible RNG, which retries failed requests.
+
+use std::cmp;
+use std::thread;
+use std::time::Duration;
+
+use rand_core::{RngCore, CryptoRng, Error, ErrorKind, impls};
+
+/// A wrapper around a fallible RNG, such as `OsRng` or `ReadRng`, which
+/// retries requests that fail with a recoverable error.
+///
+/// Errors of kind `Transient` are retried immediately. For errors of kind
+/// `NotReady` the current thread sleeps before retrying, starting with a
+/// short delay that is doubled on every attempt up to a maximum. Errors of
+/// other kinds, and the last error once the maximum number of attempts is
+/// reached, are returned from `try_fill_bytes`; the other methods panic on
+/// these.
+///
+/// By default a request is attempted at most 10 times, with delays starting
+/// at 1 ms and growing to at most 1 s. This can be configured with
+/// [`set_max_attempts`] and [`set_backoff`].
+///
+/// # Example
+///
+/// ```rust
+/// # use rand::Error;
+/// use rand::{OsRng, SeedableRng, StdRng};
+/// use rand::retry::RetryRng;
+///
+/// # fn try_inner() -> Result<(), Error> {
+/// let mut os_rng = RetryRng::new(OsRng::new()?);
+/// let rng = StdRng::from_rng(&mut os_rng)?;
+/// # let _ = rng;
+/// # Ok(())
+/// # }
+/// # try_inner().unwrap()
+/// ```
+///
+/// [`set_max_attempts`]: #method.set_max_attempts
+/// [`set_backoff`]: #method.set_backoff
+#[derive(Clone, Debug)]
+pub struct RetryRng<R> {
+    rng: R,
+    max_attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+}
+
+impl<R: RngCore> RetryRng<R> {
+    /// Create a new `RetryRng` wrapping `rng`, with the default limits.
+    pub fn new(rng: R) -> RetryRng<R> {
+        RetryRng {
+            rng: rng,
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(1),
+        }
+    }
+
+    /// Configures how many times a request is attempted, including the first
+    /// attempt, before the error is returned. This must be greater than zero.
+    pub fn set_max_attempts(&mut self, attempts: u32) {
+        assert!(attempts > 0);
+        self.max_attempts = attempts;
+    }
+
+    /// Configures the delay before the first retry after a `NotReady` error,
+    /// and the maximum delay it may grow to.
+    pub fn set_backoff(&mut self, initial: Duration, max: Duration) {
+        self.initial_delay = initial;
+        self.max_delay = cmp::max(initial, max);
+    }
+
+    /// Unwrap the inner generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: RngCore> RngCore for RetryRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|err|
+                panic!("RetryRng: failed to get random bytes: {}", err))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        let mut delay = self.initial_delay;
+        let mut attempt = 1;
+        loop {
+            let err = match self.rng.try_fill_bytes(dest) {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            if attempt >= self.max_attempts {
+                return Err(err);
+            }
+            match err.kind {
+                ErrorKind::Transient => {}
+                ErrorKind::NotReady => {
+                    thread::sleep(delay);
+                    delay = cmp::min(delay * 2, self.max_delay);
+                }
+                _ => return Err(err),
+            }
+            attempt += 1;
+        }
+    }
+}
+
+impl<R: RngCore + CryptoRng> CryptoRng for RetryRng<R> {}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use {RngCore, ErrorKind};
+    use mock::{FaultyRng, StepRng};
+    use super::RetryRng;
+
+    #[test]
+    fn test_retry() {
+        let faulty = FaultyRng::new(StepRng::new(0, 1), |call| match call {
+            0 => Some(ErrorKind::Transient),
+            1 => Some(ErrorKind::NotReady),
+            3 => Some(ErrorKind::Unavailable),
+            4 | 5 | 6 => Some(ErrorKind::Transient),
+            _ => None,
+        });
+        let mut rng = RetryRng::new(faulty);
+        rng.set_backoff(Duration::from_millis(0), Duration::from_millis(1));
+        rng.set_max_attempts(3);
+
+        // Succeeds after two retries
+        let mut buf = [0u8; 8];
+        rng.try_fill_bytes(&mut buf).unwrap();
+        // Not retried
+        let err = rng.try_fill_bytes(&mut buf).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+        // Gives up after 3 attempts
+        let err = rng.try_fill_bytes(&mut buf).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Transient);
+        rng.try_fill_bytes(&mut buf).unwrap();
+        assert_eq!(rng.into_inner().calls(), 8);
+    }
+}
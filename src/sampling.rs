@@ -0,0 +1,517 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Stratified sampling designs.
+//!
+//! Stratified samples are random, but spread more evenly than independent
+//! samples: the domain is divided into strata, and each stratum receives its
+//! share of the samples. This reduces the variance of estimates such as
+//! Monte Carlo integrals, and makes sure parameter sweeps cover the whole
+//! range of every parameter.
+//!
+//! [`stratified`] and [`jittered_2d`] sample ranges and rectangles divided
+//! into strata of equal size. [`latin_hypercube`] produces Latin hypercube
+//! designs over the unit hypercube; [`LatinHypercube`] gives more control
+//! over the design.
+//!
+//! [`stratified`]: fn.stratified.html
+//! [`jittered_2d`]: fn.jittered_2d.html
+//! [`latin_hypercube`]: fn.latin_hypercube.html
+//! [`LatinHypercube`]: struct.LatinHypercube.html
+
+use core::ops::Range;
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+use Rng;
+
+/// Sample `n_samples` points from the unit hypercube `[0, 1)^n_dims` with
+/// Latin hypercube sampling.
+///
+/// Every dimension is divided into `n_samples` intervals of equal length,
+/// and each of them contains exactly one point. Within its interval a
+/// coordinate is uniformly distributed, and the assignment of intervals to
+/// points is random, so every point is uniformly distributed on the
+/// hypercube.
+///
+/// This is equivalent to
+/// `LatinHypercube::new(rng, n_samples, n_dims).sample_iter(rng).collect()`.
+///
+/// Panics if `n_samples` or `n_dims` is 0.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::sampling::latin_hypercube;
+///
+/// let points = latin_hypercube(&mut thread_rng(), 10, 3);
+/// assert_eq!(points.len(), 10);
+/// // Exactly one point has its first coordinate in [0, 0.1)
+/// assert_eq!(points.iter().filter(|p| p[0] < 0.1).count(), 1);
+/// ```
+pub fn latin_hypercube<R: Rng + ?Sized>(rng: &mut R, n_samples: usize,
+                                        n_dims: usize) -> Vec<Vec<f64>>
+{
+    LatinHypercube::new(rng, n_samples, n_dims).sample_iter(rng).collect()
+}
+
+/// Like [`latin_hypercube`], but with a design optimised for the maximin
+/// criterion over up to `iterations` steps.
+///
+/// See [`LatinHypercube::optimize_maximin`] for the optimisation, which takes
+/// `O(iterations * n_samples^2)` time.
+///
+/// Panics if `n_samples` or `n_dims` is 0.
+///
+/// [`latin_hypercube`]: fn.latin_hypercube.html
+/// [`LatinHypercube::optimize_maximin`]: struct.LatinHypercube.html#method.optimize_maximin
+pub fn latin_hypercube_maximin<R: Rng + ?Sized>(rng: &mut R, n_samples: usize,
+                                                n_dims: usize, iterations: usize)
+    -> Vec<Vec<f64>>
+{
+    let mut design = LatinHypercube::new(rng, n_samples, n_dims);
+    design.optimize_maximin(rng, iterations);
+    design.sample_iter(rng).collect()
+}
+
+/// A Latin hypercube design: an assignment of one interval ("stratum") per
+/// dimension to each of `n_samples` samples, such that in every dimension
+/// each of the `n_samples` strata is used exactly once.
+///
+/// The points themselves are sampled uniformly within the strata by
+/// [`sample_iter`]; a design can be reused for several sets of points.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::sampling::LatinHypercube;
+///
+/// let mut rng = thread_rng();
+/// let mut design = LatinHypercube::new(&mut rng, 20, 2);
+/// design.optimize_maximin(&mut rng, 1000);
+/// for point in design.sample_iter(&mut rng) {
+///     println!("{:?}", point);
+/// }
+/// ```
+///
+/// [`sample_iter`]: #method.sample_iter
+#[derive(Clone, Debug)]
+pub struct LatinHypercube {
+    n_samples: usize,
+    n_dims: usize,
+    // The strata of sample `i` at `i * n_dims..(i + 1) * n_dims`
+    strata: Vec<usize>,
+}
+
+impl LatinHypercube {
+    /// Create a random design for `n_samples` samples in `n_dims` dimensions.
+    ///
+    /// Panics if `n_samples` or `n_dims` is 0.
+    pub fn new<R: Rng + ?Sized>(rng: &mut R, n_samples: usize, n_dims: usize)
+        -> LatinHypercube
+    {
+        assert!(n_samples > 0 && n_dims > 0,
+                "LatinHypercube::new called with `n_samples == 0` or `n_dims == 0`");
+        let mut strata = vec![0; n_samples * n_dims];
+        let mut permutation = (0..n_samples).collect::<Vec<_>>();
+        for dim in 0..n_dims {
+            rng.shuffle(&mut permutation);
+            for (i, &stratum) in permutation.iter().enumerate() {
+                strata[i * n_dims + dim] = stratum;
+            }
+        }
+        LatinHypercube { n_samples: n_samples, n_dims: n_dims, strata: strata }
+    }
+
+    /// The number of samples.
+    pub fn n_samples(&self) -> usize {
+        self.n_samples
+    }
+
+    /// The number of dimensions.
+    pub fn n_dims(&self) -> usize {
+        self.n_dims
+    }
+
+    /// The strata of `sample`, one per dimension, counted from 0.
+    ///
+    /// Panics if `sample >= self.n_samples()`.
+    pub fn strata(&self, sample: usize) -> &[usize] {
+        &self.strata[sample * self.n_dims..(sample + 1) * self.n_dims]
+    }
+
+    /// Improve the design for the maximin criterion, which maximises the
+    /// smallest distance between any two samples (measured between the
+    /// centres of their strata).
+    ///
+    /// Each of the `iterations` steps swaps the strata of two random samples
+    /// in a random dimension, and keeps the swap if the smallest distance
+    /// does not decrease and is not attained by more pairs than before. A
+    /// step takes `O(n_samples^2)` time.
+    pub fn optimize_maximin<R: Rng + ?Sized>(&mut self, rng: &mut R,
+                                             iterations: usize)
+    {
+        let n = self.n_samples;
+        if n < 3 {
+            // All designs are equivalent.
+            return;
+        }
+        // Squared distances between the samples, in units of strata
+        let mut distances = vec![0u64; n * n];
+        for i in 0..n {
+            self.update_distances(&mut distances, i);
+        }
+        let (mut min, mut count) = min_distance(&distances, n);
+
+        for _ in 0..iterations {
+            let dim = rng.gen_range(0..self.n_dims);
+            let i = rng.gen_range(0..n);
+            let j = (i + rng.gen_range(1..n)) % n;
+            self.strata.swap(i * self.n_dims + dim, j * self.n_dims + dim);
+            self.update_distances(&mut distances, i);
+            self.update_distances(&mut distances, j);
+            let (new_min, new_count) = min_distance(&distances, n);
+            if new_min > min || (new_min == min && new_count <= count) {
+                min = new_min;
+                count = new_count;
+            } else {
+                self.strata.swap(i * self.n_dims + dim, j * self.n_dims + dim);
+                self.update_distances(&mut distances, i);
+                self.update_distances(&mut distances, j);
+            }
+        }
+    }
+
+    // Recompute the distances of sample `i` to all other samples.
+    fn update_distances(&self, distances: &mut [u64], i: usize) {
+        let n = self.n_samples;
+        for j in 0..n {
+            let d = self.strata(i).iter().zip(self.strata(j).iter())
+                .map(|(&a, &b)| {
+                    let diff = (if a > b { a - b } else { b - a }) as u64;
+                    diff * diff
+                })
+                .sum();
+            distances[i * n + j] = d;
+            distances[j * n + i] = d;
+        }
+    }
+
+    /// An iterator sampling one point per sample of the design, uniformly
+    /// within its strata, in the order of the samples.
+    pub fn sample_iter<'a, R: Rng + ?Sized>(&'a self, rng: &'a mut R)
+        -> LatinHypercubeIter<'a, R>
+    {
+        LatinHypercubeIter { design: self, rng: rng, sample: 0 }
+    }
+}
+
+// The smallest distance between two different samples, and the number of
+// pairs at that distance.
+fn min_distance(distances: &[u64], n: usize) -> (u64, usize) {
+    let mut min = ::core::u64::MAX;
+    let mut count = 0;
+    for i in 0..n {
+        for &d in distances[i * n + i + 1..(i + 1) * n].iter() {
+            if d < min {
+                min = d;
+                count = 1;
+            } else if d == min {
+                count += 1;
+            }
+        }
+    }
+    (min, count)
+}
+
+/// An iterator over the points of a [`LatinHypercube`] design.
+///
+/// This struct is created by [`LatinHypercube::sample_iter`].
+///
+/// [`LatinHypercube`]: struct.LatinHypercube.html
+/// [`LatinHypercube::sample_iter`]: struct.LatinHypercube.html#method.sample_iter
+#[derive(Debug)]
+pub struct LatinHypercubeIter<'a, R: 'a + ?Sized> {
+    design: &'a LatinHypercube,
+    rng: &'a mut R,
+    sample: usize,
+}
+
+impl<'a, R: Rng + ?Sized> Iterator for LatinHypercubeIter<'a, R> {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.sample >= self.design.n_samples {
+            return None;
+        }
+        let n = self.design.n_samples as f64;
+        let rng = &mut self.rng;
+        let point = self.design.strata(self.sample).iter().map(|&stratum| {
+            let x = (stratum as f64 + rng.gen::<f64>()) / n;
+            // Rounding may give 1.0 for the last stratum
+            if x < 1.0 { x } else { 1.0 - ::core::f64::EPSILON / 2.0 }
+        }).collect();
+        self.sample += 1;
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.design.n_samples - self.sample;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Sample `strata` values from `range`, one uniformly within each of
+/// `strata` intervals of equal length, in random order.
+///
+/// This is jittered sampling in one dimension. Panics if `strata` is 0 or
+/// `range` is empty.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::sampling::stratified;
+///
+/// let mut values = stratified(&mut thread_rng(), 0.0..10.0, 5)
+///     .collect::<Vec<f64>>();
+/// values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+/// assert!(values[0] < 2.0 && values[1] >= 2.0 && values[4] >= 8.0);
+/// ```
+pub fn stratified<'a, R: Rng + ?Sized>(rng: &'a mut R, range: Range<f64>,
+                                       strata: usize) -> Stratified<'a, R>
+{
+    assert!(strata > 0, "stratified called with `strata == 0`");
+    assert!(range.start < range.end, "stratified called with an empty range");
+    let mut order = (0..strata).collect::<Vec<_>>();
+    rng.shuffle(&mut order);
+    Stratified { rng: rng, range: range, order: order, pos: 0 }
+}
+
+/// Sample points from the rectangle `x_range` × `y_range` divided into a
+/// grid of `x_strata` × `y_strata` cells, one uniformly within each cell, in
+/// random order.
+///
+/// This is the jittered sampling commonly used for pixel and light samples in
+/// rendering. Panics if `x_strata` or `y_strata` is 0 or a range is empty.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::sampling::jittered_2d;
+///
+/// // 16 samples within a pixel
+/// for (x, y) in jittered_2d(&mut thread_rng(), 0.0..1.0, 0.0..1.0, 4, 4) {
+///     assert!(x >= 0.0 && x < 1.0 && y >= 0.0 && y < 1.0);
+/// }
+/// ```
+pub fn jittered_2d<'a, R: Rng + ?Sized>(rng: &'a mut R, x_range: Range<f64>,
+                                        y_range: Range<f64>, x_strata: usize,
+                                        y_strata: usize) -> Jittered2d<'a, R>
+{
+    assert!(x_strata > 0 && y_strata > 0,
+            "jittered_2d called with `x_strata == 0` or `y_strata == 0`");
+    assert!(x_range.start < x_range.end && y_range.start < y_range.end,
+            "jittered_2d called with an empty range");
+    let mut order = (0..x_strata * y_strata).collect::<Vec<_>>();
+    rng.shuffle(&mut order);
+    Jittered2d { rng: rng, x_range: x_range, y_range: y_range,
+                 x_strata: x_strata, y_strata: y_strata, order: order, pos: 0 }
+}
+
+// A uniform sample from stratum `i` of `strata` of `range`.
+fn sample_stratum<R: Rng + ?Sized>(rng: &mut R, range: &Range<f64>, i: usize,
+                                   strata: usize) -> f64
+{
+    let scale = range.end - range.start;
+    let x = range.start + scale * ((i as f64 + rng.gen::<f64>()) / strata as f64);
+    if x < range.end {
+        x
+    } else {
+        // Rounding may give `range.end` for the last stratum
+        range.start + scale * (i as f64 / strata as f64)
+    }
+}
+
+/// An iterator over stratified samples of a range.
+///
+/// This struct is created by [`stratified`].
+///
+/// [`stratified`]: fn.stratified.html
+#[derive(Debug)]
+pub struct Stratified<'a, R: 'a + ?Sized> {
+    rng: &'a mut R,
+    range: Range<f64>,
+    order: Vec<usize>,
+    pos: usize,
+}
+
+impl<'a, R: Rng + ?Sized> Iterator for Stratified<'a, R> {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        let stratum = match self.order.get(self.pos) {
+            Some(&stratum) => stratum,
+            None => return None,
+        };
+        self.pos += 1;
+        Some(sample_stratum(self.rng, &self.range, stratum, self.order.len()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.order.len() - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+/// An iterator over jittered samples of a rectangle.
+///
+/// This struct is created by [`jittered_2d`].
+///
+/// [`jittered_2d`]: fn.jittered_2d.html
+#[derive(Debug)]
+pub struct Jittered2d<'a, R: 'a + ?Sized> {
+    rng: &'a mut R,
+    x_range: Range<f64>,
+    y_range: Range<f64>,
+    x_strata: usize,
+    y_strata: usize,
+    order: Vec<usize>,
+    pos: usize,
+}
+
+impl<'a, R: Rng + ?Sized> Iterator for Jittered2d<'a, R> {
+    type Item = (f64, f64);
+
+    fn next(&mut self) -> Option<(f64, f64)> {
+        let cell = match self.order.get(self.pos) {
+            Some(&cell) => cell,
+            None => return None,
+        };
+        self.pos += 1;
+        let x = sample_stratum(self.rng, &self.x_range, cell % self.x_strata,
+                               self.x_strata);
+        let y = sample_stratum(self.rng, &self.y_range, cell / self.x_strata,
+                               self.y_strata);
+        Some((x, y))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.order.len() - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+    use {SeedableRng, ChaChaRng};
+    use super::{LatinHypercube, latin_hypercube, latin_hypercube_maximin,
+                min_distance, stratified, jittered_2d};
+
+    #[test]
+    fn test_stratified() {
+        let mut rng = ChaChaRng::from_seed([15; 32]);
+        let mut iter = stratified(&mut rng, -1.0..3.0, 8);
+        assert_eq!(iter.size_hint(), (8, Some(8)));
+        let values = iter.by_ref().collect::<Vec<_>>();
+        assert_eq!(iter.next(), None);
+        let mut strata = values.iter()
+            .map(|&x| {
+                assert!(x >= -1.0 && x < 3.0);
+                ((x + 1.0) * 2.0) as usize
+            })
+            .collect::<Vec<_>>();
+        assert!(strata != (0..8).collect::<Vec<_>>());
+        strata.sort();
+        assert_eq!(strata, (0..8).collect::<Vec<_>>());
+
+        for x in stratified(&mut rng, 5.0..5.0000001, 1000) {
+            assert!(x >= 5.0 && x < 5.0000001);
+        }
+    }
+
+    #[test]
+    fn test_jittered_2d() {
+        let mut rng = ChaChaRng::from_seed([16; 32]);
+        let points = jittered_2d(&mut rng, 0.0..3.0, 10.0..12.0, 3, 4)
+            .collect::<Vec<_>>();
+        assert_eq!(points.len(), 12);
+        let mut cells = points.iter()
+            .map(|&(x, y)| {
+                assert!(x >= 0.0 && x < 3.0 && y >= 10.0 && y < 12.0);
+                x as usize + 3 * ((y - 10.0) * 2.0) as usize
+            })
+            .collect::<Vec<_>>();
+        cells.sort();
+        assert_eq!(cells, (0..12).collect::<Vec<_>>());
+    }
+
+    fn check_latin(points: &[Vec<f64>], n_dims: usize) {
+        let n = points.len();
+        for dim in 0..n_dims {
+            let mut seen = vec![false; n];
+            for point in points.iter() {
+                assert_eq!(point.len(), n_dims);
+                assert!(point[dim] >= 0.0 && point[dim] < 1.0);
+                let stratum = (point[dim] * n as f64) as usize;
+                assert!(!seen[stratum]);
+                seen[stratum] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn test_latin_hypercube() {
+        let mut rng = ChaChaRng::from_seed([13; 32]);
+        for &(n, d) in [(1, 1), (1, 5), (7, 1), (10, 3), (100, 8)].iter() {
+            check_latin(&latin_hypercube(&mut rng, n, d), d);
+            check_latin(&latin_hypercube_maximin(&mut rng, n, d, 100), d);
+        }
+
+        let design = LatinHypercube::new(&mut rng, 50, 4);
+        let mut iter = design.sample_iter(&mut rng);
+        assert_eq!(iter.size_hint(), (50, Some(50)));
+        let first = iter.next().unwrap();
+        for (x, &stratum) in first.iter().zip(design.strata(0).iter()) {
+            assert_eq!((x * 50.0) as usize, stratum);
+        }
+        assert_eq!(iter.count(), 49);
+    }
+
+    #[test]
+    fn test_maximin() {
+        let mut rng = ChaChaRng::from_seed([14; 32]);
+        let mut design = LatinHypercube::new(&mut rng, 30, 3);
+        let distances = |design: &LatinHypercube| {
+            let mut distances = vec![0; 30 * 30];
+            for i in 0..30 {
+                design.update_distances(&mut distances, i);
+            }
+            min_distance(&distances, 30)
+        };
+        let before = distances(&design);
+        design.optimize_maximin(&mut rng, 2000);
+        let after = distances(&design);
+        assert!(after.0 > before.0 || (after.0 == before.0 && after.1 <= before.1));
+        assert!(after.0 > 1);
+
+        // Still a Latin hypercube
+        for dim in 0..3 {
+            let mut strata = (0..30).map(|i| design.strata(i)[dim])
+                .collect::<Vec<_>>();
+            strata.sort();
+            assert_eq!(strata, (0..30).collect::<Vec<_>>());
+        }
+    }
+}
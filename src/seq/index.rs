@@ -0,0 +1,251 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sampling of distinct indices.
+//!
+//! [`sample`] picks `amount` distinct indices from `0..length`, returning
+//! them as an [`IndexVec`]. This is the building block for sampling elements
+//! (e.g. rows) from large collections.
+//!
+//! [`sample`]: fn.sample.html
+//! [`IndexVec`]: enum.IndexVec.html
+
+use core::slice;
+
+#[cfg(feature="std")] use std::collections::HashSet;
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::collections::BTreeSet;
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+use Rng;
+
+/// A vector of indices.
+///
+/// Indices are stored as `u32` when they all fit, halving the memory needed
+/// compared to `usize` on 64-bit platforms.
+#[derive(Clone, Debug)]
+pub enum IndexVec {
+    #[doc(hidden)] U32(Vec<u32>),
+    #[doc(hidden)] USize(Vec<usize>),
+}
+
+impl IndexVec {
+    /// Returns the number of indices.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match *self {
+            IndexVec::U32(ref v) => v.len(),
+            IndexVec::USize(ref v) => v.len(),
+        }
+    }
+
+    /// Returns `true` if there are no indices.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the index at position `i`.
+    ///
+    /// Panics if `i >= self.len()`.
+    #[inline]
+    pub fn index(&self, i: usize) -> usize {
+        match *self {
+            IndexVec::U32(ref v) => v[i] as usize,
+            IndexVec::USize(ref v) => v[i],
+        }
+    }
+
+    /// Converts into a `Vec<usize>`.
+    pub fn into_vec(self) -> Vec<usize> {
+        match self {
+            IndexVec::U32(v) => v.into_iter().map(|i| i as usize).collect(),
+            IndexVec::USize(v) => v,
+        }
+    }
+
+    /// Iterate over the indices as `usize` values.
+    #[inline]
+    pub fn iter<'a>(&'a self) -> IndexVecIter<'a> {
+        match *self {
+            IndexVec::U32(ref v) => IndexVecIter::U32(v.iter()),
+            IndexVec::USize(ref v) => IndexVecIter::USize(v.iter()),
+        }
+    }
+}
+
+impl From<Vec<u32>> for IndexVec {
+    #[inline]
+    fn from(v: Vec<u32>) -> Self {
+        IndexVec::U32(v)
+    }
+}
+
+impl From<Vec<usize>> for IndexVec {
+    #[inline]
+    fn from(v: Vec<usize>) -> Self {
+        IndexVec::USize(v)
+    }
+}
+
+/// An iterator over the indices of an `IndexVec`.
+#[derive(Debug)]
+pub enum IndexVecIter<'a> {
+    #[doc(hidden)] U32(slice::Iter<'a, u32>),
+    #[doc(hidden)] USize(slice::Iter<'a, usize>),
+}
+
+impl<'a> Iterator for IndexVecIter<'a> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        match *self {
+            IndexVecIter::U32(ref mut iter) => iter.next().map(|&i| i as usize),
+            IndexVecIter::USize(ref mut iter) => iter.next().cloned(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self {
+            IndexVecIter::U32(ref iter) => iter.size_hint(),
+            IndexVecIter::USize(ref iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for IndexVecIter<'a> {}
+
+/// Randomly sample exactly `amount` distinct indices from `0..length`.
+///
+/// The indices are in random order. This uses Floyd's combination algorithm,
+/// which needs `O(amount)` memory and never materializes `0..length`; it is
+/// therefore suitable for sampling from very large lengths.
+///
+/// Panics if `amount > length`.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::thread_rng;
+/// use rand::seq::index;
+///
+/// let mut rng = thread_rng();
+/// let rows = index::sample(&mut rng, 1_000_000_000, 5);
+/// for row in rows.iter() {
+///     assert!(row < 1_000_000_000);
+/// }
+/// ```
+pub fn sample<R>(rng: &mut R, length: usize, amount: usize) -> IndexVec
+    where R: Rng + ?Sized,
+{
+    if amount > length {
+        panic!("`amount` must be less than or equal to `length`");
+    }
+    if length <= ::core::u32::MAX as usize {
+        IndexVec::U32(sample_floyd(rng, length as u32, amount as u32))
+    } else {
+        IndexVec::USize(sample_floyd(rng, length, amount))
+    }
+}
+
+// Below this amount, checking membership by linear search is faster than
+// using a set.
+const FLOYD_LINEAR_MAX: usize = 40;
+
+trait Index: Copy + Ord + ::core::hash::Hash + ::distributions::range::SampleRange {
+    fn as_usize(self) -> usize;
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn sub(self, other: Self) -> Self;
+    fn add(self, other: Self) -> Self;
+}
+
+macro_rules! impl_index {
+    ($ty:ty) => {
+        impl Index for $ty {
+            #[inline] fn as_usize(self) -> usize { self as usize }
+            #[inline] fn zero() -> Self { 0 }
+            #[inline] fn one() -> Self { 1 }
+            #[inline] fn sub(self, other: Self) -> Self { self - other }
+            #[inline] fn add(self, other: Self) -> Self { self + other }
+        }
+    }
+}
+
+impl_index!(u32);
+impl_index!(usize);
+
+fn sample_floyd<R, X>(rng: &mut R, length: X, amount: X) -> Vec<X>
+    where R: Rng + ?Sized, X: Index,
+{
+    let mut indices = Vec::with_capacity(amount.as_usize());
+    let mut j = length.sub(amount);
+    if amount.as_usize() <= FLOYD_LINEAR_MAX {
+        // Picking `t` from `0..=j` and replacing an earlier pick of `t` by
+        // `j`, then pushing `t`, yields a random subset in random order.
+        while j < length {
+            let t = rng.gen_range(X::zero()..=j);
+            if let Some(pos) = indices.iter().position(|&x| x == t) {
+                indices[pos] = j;
+            }
+            indices.push(t);
+            j = j.add(X::one());
+        }
+    } else {
+        #[cfg(feature="std")] let mut chosen = HashSet::with_capacity(amount.as_usize());
+        #[cfg(not(feature="std"))] let mut chosen = BTreeSet::new();
+        while j < length {
+            let t = rng.gen_range(X::zero()..=j);
+            let x = if chosen.insert(t) { t } else { chosen.insert(j); j };
+            indices.push(x);
+            j = j.add(X::one());
+        }
+        // The set-based variant does not produce a random order.
+        rng.shuffle(&mut indices);
+    }
+    indices
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sample_boundaries() {
+        let mut r = ::test::rng(412);
+        assert_eq!(sample(&mut r, 0, 0).len(), 0);
+        assert_eq!(sample(&mut r, 1, 0).len(), 0);
+        assert_eq!(sample(&mut r, 1, 1).into_vec(), vec![0]);
+    }
+
+    #[test]
+    fn test_sample_distinct() {
+        let mut r = ::test::rng(413);
+        for &(length, amount) in [(10, 10), (100, 30), (1000, 41), (1000, 900),
+                                  (1 << 31, 100)].iter() {
+            let indices = sample(&mut r, length, amount);
+            assert_eq!(indices.len(), amount);
+            assert_eq!(indices.iter().len(), amount);
+            let mut v = indices.into_vec();
+            assert!(v.iter().all(|&i| i < length));
+            v.sort();
+            v.dedup();
+            assert_eq!(v.len(), amount);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_too_many() {
+        let mut r = ::test::rng(414);
+        sample(&mut r, 3, 4);
+    }
+}
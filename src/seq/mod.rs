@@ -0,0 +1,1829 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Functions for randomly accessing and sampling sequences.
+
+use core::fmt;
+
+use super::Rng;
+
+#[cfg(any(feature="std", feature="alloc"))] pub mod index;
+
+// Most functions need either std or alloc to be available.
+// BTreeMap is not as fast in tests, but better than nothing.
+#[cfg(feature="std")] use std::collections::{HashMap, HashSet};
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::collections::BTreeMap;
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::collections::BTreeSet;
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+/// Randomly sample `amount` elements from a finite iterator.
+///
+/// The following can be returned:
+///
+/// - `Ok`: `Vec` of `amount` non-repeating randomly sampled elements. The order is not random.
+/// - `Err`: `Vec` of all the elements from `iterable` in sequential order. This happens when the
+///   length of `iterable` was less than `amount`. This is considered an error since exactly
+///   `amount` elements is typically expected.
+///
+/// This implementation uses `O(len(iterable))` time and `O(amount)` memory.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let sample = seq::sample_iter(&mut rng, 1..100, 5).unwrap();
+/// println!("{:?}", sample);
+/// ```
+#[cfg(any(feature="std", feature="alloc"))]
+pub fn sample_iter<T, I, R>(rng: &mut R, iterable: I, amount: usize) -> Result<Vec<T>, Vec<T>>
+    where I: IntoIterator<Item=T>,
+          R: Rng + ?Sized,
+{
+    let mut iter = iterable.into_iter();
+    let mut reservoir = Vec::with_capacity(amount);
+    reservoir.extend(iter.by_ref().take(amount));
+
+    // Continue unless the iterator was exhausted
+    //
+    // note: this prevents iterators that "restart" from causing problems.
+    // If the iterator stops once, then so do we.
+    if reservoir.len() == amount {
+        for (i, elem) in iter.enumerate() {
+            let k = rng.gen_range(0..i + 1 + amount);
+            if let Some(spot) = reservoir.get_mut(k) {
+                *spot = elem;
+            }
+        }
+        Ok(reservoir)
+    } else {
+        // Don't hang onto extra memory. There is a corner case where
+        // `amount` was much less than `len(iterable)`.
+        reservoir.shrink_to_fit();
+        Err(reservoir)
+    }
+}
+
+/// Randomly choose one element from an iterator.
+///
+/// Returns `None` if the iterator is empty.
+///
+/// The iterator's `size_hint` is used to avoid generating a random number per
+/// element: if it is exact (as for an `ExactSizeIterator`), a single index is
+/// sampled and the element fetched with `Iterator::nth`. If only a lower bound
+/// is known, that many elements are handled at once, falling back to
+/// reservoir sampling where no bound is available. The hints must be correct
+/// for the choice to be uniform.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let x = seq::choose_iter(&mut rng, 0..1_000_000).unwrap();
+/// assert!(x < 1_000_000);
+/// let y = seq::choose_iter(&mut rng, (0..100).filter(|x| x % 7 == 0)).unwrap();
+/// assert_eq!(y % 7, 0);
+/// ```
+pub fn choose_iter<T, I, R>(rng: &mut R, iterable: I) -> Option<T>
+    where I: IntoIterator<Item=T>,
+          R: Rng + ?Sized,
+{
+    let mut iter = iterable.into_iter();
+    let (mut lower, mut upper) = iter.size_hint();
+    if upper == Some(lower) {
+        return if lower == 0 { None } else { iter.nth(rng.gen_range(0..lower)) };
+    }
+
+    let mut consumed = 0;
+    let mut result = None;
+    loop {
+        if lower > 1 {
+            // Treat the next `lower` elements as a block: each of them
+            // replaces `result` with probability `1 / (consumed + lower)`.
+            let index = rng.gen_range(0..consumed + lower);
+            let skip = if index < lower {
+                result = iter.nth(index);
+                lower - (index + 1)
+            } else {
+                lower
+            };
+            if upper == Some(lower) {
+                return result;
+            }
+            consumed += lower;
+            if skip > 0 {
+                iter.nth(skip - 1);
+            }
+        } else {
+            match iter.next() {
+                Some(elem) => {
+                    consumed += 1;
+                    if rng.gen_range(0..consumed) == 0 {
+                        result = Some(elem);
+                    }
+                }
+                None => return result,
+            }
+        }
+        let hint = iter.size_hint();
+        lower = hint.0;
+        upper = hint.1;
+    }
+}
+
+/// Randomly sample elements from an iterator into a caller-provided buffer,
+/// without allocating.
+///
+/// Fills `buf` with distinct elements of `iterable` (distinct by position),
+/// chosen uniformly at random, and returns the number of elements written.
+/// This is `buf.len()` unless the iterator yields fewer elements, in which
+/// case all of its elements are written in order and their count returned.
+/// The order of the chosen elements is not random.
+///
+/// To sample from a slice, pass an iterator over it such as `slice.iter()`
+/// or `slice.iter().cloned()`.
+///
+/// This uses reservoir sampling, in `O(len(iterable))` time, and is available
+/// without `std` or `alloc`.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let values = [5, 6, 1, 3, 4, 6, 7];
+/// let mut buf = [0; 3];
+/// let n = seq::choose_multiple_fill(&mut rng, values.iter().cloned(), &mut buf);
+/// assert_eq!(n, 3);
+/// ```
+pub fn choose_multiple_fill<T, I, R>(rng: &mut R, iterable: I, buf: &mut [T])
+    -> usize
+    where I: IntoIterator<Item=T>,
+          R: Rng + ?Sized,
+{
+    let amount = buf.len();
+    let mut iter = iterable.into_iter();
+    let mut len = 0;
+    while len < amount {
+        match iter.next() {
+            Some(elem) => {
+                buf[len] = elem;
+                len += 1;
+            }
+            None => return len,
+        }
+    }
+
+    for (i, elem) in iter.enumerate() {
+        let k = rng.gen_range(0..i + 1 + amount);
+        if let Some(spot) = buf.get_mut(k) {
+            *spot = elem;
+        }
+    }
+    len
+}
+
+/// Randomly sample up to `amount` elements from an iterator of unknown length,
+/// in a single pass.
+///
+/// This uses reservoir sampling with Li's Algorithm L, which skips over runs
+/// of elements that will not be selected: only `O(amount * (1 +
+/// log(len / amount)))` random numbers are needed, rather than one per
+/// element as with [`sample_iter`]. The iterator is still consumed
+/// completely, using `Iterator::nth` to skip elements.
+///
+/// If the iterator yields fewer than `amount` elements, all of them are
+/// returned. The order of the result is not random.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let sample = seq::choose_multiple(&mut rng, 0..1_000_000, 5);
+/// assert_eq!(sample.len(), 5);
+/// ```
+///
+/// [`sample_iter`]: fn.sample_iter.html
+#[cfg(feature="std")]
+pub fn choose_multiple<T, I, R>(rng: &mut R, iterable: I, amount: usize) -> Vec<T>
+    where I: IntoIterator<Item=T>,
+          R: Rng + ?Sized,
+{
+    let mut iter = iterable.into_iter();
+    let mut reservoir = Vec::with_capacity(amount);
+    reservoir.extend(iter.by_ref().take(amount));
+    if reservoir.len() < amount || amount == 0 {
+        return reservoir;
+    }
+
+    let k = amount as f64;
+    // `w` follows the distribution of the largest of `amount` uniform keys,
+    // as in the classic algorithm assigning a random key to every element.
+    let mut w = (rng.gen::<f64>().ln() / k).exp();
+    loop {
+        // Number of elements to skip before the next replacement; `rng.gen`
+        // yields values in (0, 1), so the logarithms are finite and negative.
+        let skip = (rng.gen::<f64>().ln() / (1.0 - w).ln()).floor();
+        let skip = if skip >= 0.0 && skip < usize::max_value() as f64 {
+            skip as usize
+        } else {
+            usize::max_value()
+        };
+        match iter.nth(skip) {
+            Some(elem) => {
+                reservoir[rng.gen_range(0..amount)] = elem;
+                w *= (rng.gen::<f64>().ln() / k).exp();
+            }
+            None => break,
+        }
+    }
+    reservoir
+}
+
+/// Randomly sample up to `amount` items from an iterator of `(item, weight)`
+/// pairs, without replacement, with probabilities proportional to the
+/// weights, in a single pass.
+///
+/// This is the streaming counterpart of [`choose_multiple_weighted`], using
+/// the algorithm A-ExpJ by Efraimidis and Spirakis: conceptually, each item
+/// receives the random key `u^(1/weight)` and the items with the largest keys
+/// are kept, but runs of items that cannot enter the sample are skipped
+/// without generating random numbers for them. Only `amount` items are held
+/// in memory at any time.
+///
+/// Items with a weight of zero are never chosen. If fewer than `amount` items
+/// have a positive weight, all of those are returned. The order of the result
+/// is not random.
+///
+/// Panics if any weight is negative or NaN.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let pairs = (1..1000).map(|i| (i, i as f64));
+/// let sample = seq::choose_multiple_weighted_iter(&mut rng, pairs, 10);
+/// assert_eq!(sample.len(), 10);
+/// ```
+///
+/// [`choose_multiple_weighted`]: fn.choose_multiple_weighted.html
+#[cfg(feature="std")]
+pub fn choose_multiple_weighted_iter<T, I, R>(rng: &mut R, iterable: I,
+                                              amount: usize) -> Vec<T>
+    where I: IntoIterator<Item=(T, f64)>,
+          R: Rng + ?Sized,
+{
+    use std::collections::BinaryHeap;
+
+    fn check_weight(w: f64) {
+        assert!(w >= 0.0, "choose_multiple_weighted_iter: invalid weight {}", w);
+    }
+
+    if amount == 0 {
+        return Vec::new();
+    }
+    // All keys are kept as `ln(key)`, i.e. in (-inf, 0].
+    let mut heap = BinaryHeap::with_capacity(amount);
+    let mut iter = iterable.into_iter();
+    while heap.len() < amount {
+        match iter.next() {
+            Some((item, w)) => {
+                check_weight(w);
+                if w > 0.0 {
+                    let u: f64 = rng.gen();
+                    heap.push(WeightedKey { key: u.ln() / w, item: item });
+                }
+            }
+            None => break,
+        }
+    }
+
+    if heap.len() == amount {
+        // `ln` of the smallest key in the reservoir
+        let mut threshold = heap.peek().unwrap().key;
+        let mut jump = rng.gen::<f64>().ln() / threshold;
+        for (item, w) in iter {
+            check_weight(w);
+            jump -= w;
+            if jump > 0.0 {
+                continue;
+            }
+            // This item replaces the smallest key; its key is drawn from
+            // (threshold^w, 1), i.e. conditional on exceeding the threshold.
+            let t = (threshold * w).exp();
+            let r = t + rng.gen::<f64>() * (1.0 - t);
+            heap.pop();
+            heap.push(WeightedKey { key: r.ln() / w, item: item });
+            threshold = heap.peek().unwrap().key;
+            jump = rng.gen::<f64>().ln() / threshold;
+        }
+    }
+
+    heap.into_vec().into_iter().map(|entry| entry.item).collect()
+}
+
+// Entry of the reservoir of `choose_multiple_weighted_iter`, ordered so that
+// `BinaryHeap` keeps the smallest key on top.
+#[cfg(feature="std")]
+struct WeightedKey<T> {
+    key: f64,
+    item: T,
+}
+
+#[cfg(feature="std")]
+impl<T> PartialEq for WeightedKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+#[cfg(feature="std")]
+impl<T> Eq for WeightedKey<T> {}
+
+#[cfg(feature="std")]
+impl<T> PartialOrd for WeightedKey<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature="std")]
+impl<T> Ord for WeightedKey<T> {
+    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+        // Reversed, and keys are never NaN
+        other.key.partial_cmp(&self.key).unwrap()
+    }
+}
+
+/// Randomly sample exactly `amount` values from `slice`.
+///
+/// The values are non-repeating and in random order.
+///
+/// This implementation uses `O(amount)` time and memory.
+///
+/// Panics if `amount > slice.len()`
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let values = vec![5, 6, 1, 3, 4, 6, 7];
+/// println!("{:?}", seq::sample_slice(&mut rng, &values, 3));
+/// ```
+#[cfg(any(feature="std", feature="alloc"))]
+pub fn sample_slice<R, T>(rng: &mut R, slice: &[T], amount: usize) -> Vec<T>
+    where R: Rng + ?Sized,
+          T: Clone
+{
+    let indices = sample_indices(rng, slice.len(), amount);
+
+    let mut out = Vec::with_capacity(amount);
+    out.extend(indices.iter().map(|i| slice[*i].clone()));
+    out
+}
+
+/// Randomly sample exactly `amount` references from `slice`.
+///
+/// The references are non-repeating and in random order.
+///
+/// This implementation uses `O(amount)` time and memory.
+///
+/// Panics if `amount > slice.len()`
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let values = vec![5, 6, 1, 3, 4, 6, 7];
+/// println!("{:?}", seq::sample_slice_ref(&mut rng, &values, 3));
+/// ```
+#[cfg(any(feature="std", feature="alloc"))]
+pub fn sample_slice_ref<'a, R, T>(rng: &mut R, slice: &'a [T], amount: usize) -> Vec<&'a T>
+    where R: Rng + ?Sized
+{
+    let indices = sample_indices(rng, slice.len(), amount);
+
+    let mut out = Vec::with_capacity(amount);
+    out.extend(indices.iter().map(|i| &slice[*i]));
+    out
+}
+
+/// Randomly sample `amount` distinct references from `slice`, with the
+/// probability of each element being chosen proportional to its weight.
+///
+/// This performs weighted sampling without replacement: at each step, an
+/// element is picked from those not yet chosen with probability proportional
+/// to its weight. Elements with a weight of zero are never chosen, so fewer
+/// than `amount` references are returned if fewer than `amount` elements have
+/// a positive weight.
+///
+/// The references are returned in the order they were picked.
+///
+/// This implementation uses the algorithm A-Res by Efraimidis and Spirakis,
+/// in `O(len * log(len))` time and `O(len)` memory.
+///
+/// Panics if any weight is negative or NaN.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let items = [('a', 1.0), ('b', 5.0), ('c', 0.5), ('d', 0.0)];
+/// let chosen = seq::choose_multiple_weighted(&mut rng, &items, 2, |item| item.1);
+/// assert_eq!(chosen.len(), 2);
+/// println!("{:?}", chosen);
+/// ```
+#[cfg(feature="std")]
+pub fn choose_multiple_weighted<'a, R, T, F>(rng: &mut R, slice: &'a [T],
+                                             amount: usize, weight: F)
+    -> Vec<&'a T>
+    where R: Rng + ?Sized,
+          F: Fn(&T) -> f64
+{
+    // Each element gets the key `u^(1/w)` for uniform `u`; the elements with
+    // the largest keys form the sample. We use `ln(u) / w` instead, which
+    // orders the same way but is numerically more robust.
+    let mut keyed: Vec<(f64, &'a T)> = Vec::with_capacity(slice.len());
+    for item in slice {
+        let w = weight(item);
+        assert!(w >= 0.0, "choose_multiple_weighted: invalid weight {}", w);
+        if w > 0.0 {
+            let u: f64 = rng.gen();
+            keyed.push((u.ln() / w, item));
+        }
+    }
+    keyed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+    keyed.truncate(amount);
+    keyed.into_iter().map(|(_, item)| item).collect()
+}
+
+/// An error returned by [`choose_weighted`] and [`choose_weighted_mut`].
+///
+/// [`choose_weighted`]: fn.choose_weighted.html
+/// [`choose_weighted_mut`]: fn.choose_weighted_mut.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightedError {
+    /// The slice is empty.
+    NoItem,
+    /// A weight is negative or NaN, or the total weight is not finite.
+    InvalidWeight,
+    /// All weights are zero.
+    AllWeightsZero,
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+impl WeightedError {
+    fn description(&self) -> &'static str {
+        match *self {
+            WeightedError::NoItem => "no items to choose from",
+            WeightedError::InvalidWeight => "a weight is invalid",
+            WeightedError::AllWeightsZero => "all weights are zero",
+            WeightedError::__Nonexhaustive => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Display for WeightedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+#[cfg(feature="std")]
+impl ::std::error::Error for WeightedError {
+    fn description(&self) -> &str {
+        self.description()
+    }
+}
+
+/// Randomly choose one element of `slice`, with the probability of each
+/// element being chosen proportional to its weight.
+///
+/// `weight` is called twice for every element, and must return the same
+/// value both times. Elements with a weight of zero are never chosen.
+///
+/// This uses `O(len)` time and does not allocate.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let choices = [('a', 2.0), ('b', 1.0), ('c', 1.0)];
+/// // 50% chance to print 'a', 25% chance to print 'b', 25% chance to print 'c'
+/// println!("{:?}", seq::choose_weighted(&mut rng, &choices, |item| item.1).unwrap().0);
+/// ```
+pub fn choose_weighted<'a, R, T, F>(rng: &mut R, slice: &'a [T], weight: F)
+    -> Result<&'a T, WeightedError>
+    where R: Rng + ?Sized,
+          F: Fn(&T) -> f64
+{
+    choose_weighted_by(rng, slice.iter(), |item| weight(item)).map(|(_, item)| item)
+}
+
+/// Randomly choose one element of `slice`, with the probability of each
+/// element being chosen proportional to its weight, and return a mutable
+/// reference to it.
+///
+/// See [`choose_weighted`] for details.
+///
+/// [`choose_weighted`]: fn.choose_weighted.html
+pub fn choose_weighted_mut<'a, R, T, F>(rng: &mut R, slice: &'a mut [T],
+                                        weight: F)
+    -> Result<&'a mut T, WeightedError>
+    where R: Rng + ?Sized,
+          F: Fn(&T) -> f64
+{
+    match choose_weighted_by(rng, slice.iter(), |item| weight(item)) {
+        Ok((i, _)) => Ok(&mut slice[i]),
+        Err(e) => Err(e),
+    }
+}
+
+// Choose an item of `iter` (which is iterated twice, or three times if
+// rounding makes the first choice fail) and return it with its position.
+fn choose_weighted_by<R, I, F>(rng: &mut R, mut iter: I, weight: F)
+    -> Result<(usize, I::Item), WeightedError>
+    where R: Rng + ?Sized,
+          I: Iterator + Clone,
+          F: Fn(&I::Item) -> f64
+{
+    let mut total = 0.0;
+    let mut last = None;
+    for (i, item) in iter.clone().enumerate() {
+        let w = weight(&item);
+        if w.is_nan() || w < 0.0 {
+            return Err(WeightedError::InvalidWeight);
+        }
+        if w > 0.0 {
+            last = Some(i);
+        }
+        total += w;
+    }
+    let last = match last {
+        Some(last) => last,
+        None if iter.clone().next().is_none() =>
+            return Err(WeightedError::NoItem),
+        None => return Err(WeightedError::AllWeightsZero),
+    };
+    if !total.is_finite() {
+        return Err(WeightedError::InvalidWeight);
+    }
+
+    let mut target = rng.gen::<f64>() * total;
+    for (i, item) in iter.clone().enumerate() {
+        let w = weight(&item);
+        if w > 0.0 && target < w {
+            return Ok((i, item));
+        }
+        target -= w;
+    }
+    // Due to rounding, `target` may exceed the accumulated weights.
+    Ok((last, iter.nth(last).unwrap()))
+}
+
+/// Randomly choose one entry of a map, or `None` if the map is empty.
+///
+/// This works with any collection whose iterator by reference yields
+/// key-value pairs and has an exact length, such as `HashMap` and
+/// `BTreeMap`. A single index is sampled and the entry at that position
+/// fetched, so every entry is equally likely regardless of how the map
+/// orders or hashes its keys. Since the standard maps do not provide random
+/// access, this takes `O(len)` time: to choose several entries, use
+/// [`choose_entries`], which needs only a single pass over the map.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use rand::{thread_rng, seq};
+///
+/// let mut ages = HashMap::new();
+/// ages.insert("Alice", 31);
+/// ages.insert("Bob", 27);
+/// let (name, age) = seq::choose_entry(&mut thread_rng(), &ages).unwrap();
+/// println!("{} is {}", name, age);
+/// ```
+///
+/// [`choose_entries`]: fn.choose_entries.html
+pub fn choose_entry<'a, R, M, K, V>(rng: &mut R, map: &'a M) -> Option<(&'a K, &'a V)>
+    where R: Rng + ?Sized,
+          &'a M: IntoIterator<Item=(&'a K, &'a V)>,
+          <&'a M as IntoIterator>::IntoIter: ExactSizeIterator,
+          K: 'a,
+          V: 'a,
+{
+    let mut iter = map.into_iter();
+    let len = iter.len();
+    if len == 0 {
+        None
+    } else {
+        iter.nth(rng.gen_range(0..len))
+    }
+}
+
+/// Randomly choose one key of a map, or `None` if the map is empty.
+///
+/// See [`choose_entry`] for details.
+///
+/// [`choose_entry`]: fn.choose_entry.html
+pub fn choose_key<'a, R, M, K, V>(rng: &mut R, map: &'a M) -> Option<&'a K>
+    where R: Rng + ?Sized,
+          &'a M: IntoIterator<Item=(&'a K, &'a V)>,
+          <&'a M as IntoIterator>::IntoIter: ExactSizeIterator,
+          K: 'a,
+          V: 'a,
+{
+    choose_entry(rng, map).map(|(k, _)| k)
+}
+
+/// Randomly choose one value of a map, or `None` if the map is empty.
+///
+/// See [`choose_entry`] for details.
+///
+/// [`choose_entry`]: fn.choose_entry.html
+pub fn choose_value<'a, R, M, K, V>(rng: &mut R, map: &'a M) -> Option<&'a V>
+    where R: Rng + ?Sized,
+          &'a M: IntoIterator<Item=(&'a K, &'a V)>,
+          <&'a M as IntoIterator>::IntoIter: ExactSizeIterator,
+          K: 'a,
+          V: 'a,
+{
+    choose_entry(rng, map).map(|(_, v)| v)
+}
+
+/// Randomly choose `amount` distinct entries of a map, in a single pass.
+///
+/// As with [`choose_entry`], every entry is equally likely to be chosen;
+/// this costs `O(len)` time once instead of for every entry chosen. If the
+/// map has fewer than `amount` entries, all of them are returned. The chosen
+/// entries are returned in the order of the map, not in random order.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use rand::{thread_rng, seq};
+///
+/// let squares: HashMap<u32, u32> = (0..100).map(|i| (i, i * i)).collect();
+/// let picks = seq::choose_entries(&mut thread_rng(), &squares, 3);
+/// assert_eq!(picks.len(), 3);
+/// ```
+///
+/// [`choose_entry`]: fn.choose_entry.html
+#[cfg(any(feature="std", feature="alloc"))]
+pub fn choose_entries<'a, R, M, K, V>(rng: &mut R, map: &'a M, amount: usize)
+    -> Vec<(&'a K, &'a V)>
+    where R: Rng + ?Sized,
+          &'a M: IntoIterator<Item=(&'a K, &'a V)>,
+          <&'a M as IntoIterator>::IntoIter: ExactSizeIterator,
+          K: 'a,
+          V: 'a,
+{
+    let mut iter = map.into_iter();
+    let len = iter.len();
+    let mut indices = sample_indices(rng, len, ::core::cmp::min(amount, len));
+    indices.sort_unstable();
+    let mut entries = Vec::with_capacity(indices.len());
+    let mut next = 0;
+    for i in indices {
+        entries.push(iter.nth(i - next).unwrap());
+        next = i + 1;
+    }
+    entries
+}
+
+/// Randomly choose one entry of a map, with the probability of each entry
+/// being chosen proportional to its value.
+///
+/// The values are the weights, so they must be numbers convertible to
+/// `f64`; the same conditions as for [`choose_weighted`] apply. Like
+/// [`choose_entry`], this works with `HashMap`, `BTreeMap` and similar maps,
+/// and takes `O(len)` time (iterating over the map twice).
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+/// use rand::{thread_rng, seq};
+///
+/// let mut stock = BTreeMap::new();
+/// stock.insert("apple", 3u32);
+/// stock.insert("pear", 1);
+/// stock.insert("plum", 0);
+/// // 75% chance of an apple, 25% of a pear, never a plum
+/// let (fruit, _) = seq::choose_entry_weighted(&mut thread_rng(), &stock).unwrap();
+/// assert!(*fruit != "plum");
+/// ```
+///
+/// [`choose_weighted`]: fn.choose_weighted.html
+/// [`choose_entry`]: fn.choose_entry.html
+pub fn choose_entry_weighted<'a, R, M, K, V>(rng: &mut R, map: &'a M)
+    -> Result<(&'a K, &'a V), WeightedError>
+    where R: Rng + ?Sized,
+          &'a M: IntoIterator<Item=(&'a K, &'a V)>,
+          <&'a M as IntoIterator>::IntoIter: Clone,
+          K: 'a,
+          V: Copy + Into<f64> + 'a,
+{
+    choose_weighted_by(rng, map.into_iter(), |&(_, &v)| v.into())
+        .map(|(_, entry)| entry)
+}
+
+/// Create an iterator over a random subset of `slice`, where each element is
+/// included independently with probability `p`.
+///
+/// The elements are yielded in the order they appear in `slice`. Instead of
+/// flipping a coin for every element, the gaps between included elements are
+/// sampled from the geometric distribution, so only one random number is
+/// needed per included element. This makes small `p` much cheaper.
+///
+/// Panics if `p < 0` or `p > 1`.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let nodes: Vec<u32> = (0..10_000).collect();
+/// let neighbours: Vec<&u32> = seq::subset(&mut rng, &nodes, 0.001).collect();
+/// println!("{:?}", neighbours);
+/// ```
+#[cfg(feature="std")]
+pub fn subset<'a, R, T>(rng: R, slice: &'a [T], p: f64) -> Subset<'a, R, T>
+    where R: Rng
+{
+    assert!(p >= 0.0 && p <= 1.0, "subset called with `p` not in [0, 1]");
+    Subset {
+        rng: rng,
+        slice: if p == 0.0 { &slice[..0] } else { slice },
+        // `1.0 - p` would round to 1 for tiny `p`
+        ln_q: (-p).ln_1p(),
+    }
+}
+
+/// An iterator over a random subset of a slice.
+///
+/// This `struct` is created by [`subset`]. See its documentation for more.
+///
+/// [`subset`]: fn.subset.html
+#[cfg(feature="std")]
+#[derive(Debug)]
+pub struct Subset<'a, R, T: 'a> {
+    rng: R,
+    // The elements not yet considered.
+    slice: &'a [T],
+    // `ln(1 - p)`; negative infinity when `p == 1`.
+    ln_q: f64,
+}
+
+#[cfg(feature="std")]
+impl<'a, R: Rng, T> Iterator for Subset<'a, R, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.slice.is_empty() {
+            return None;
+        }
+        let skip = if self.ln_q == ::core::f64::NEG_INFINITY {
+            0
+        } else {
+            // `u` is in the open range (0, 1), so the logarithm is finite.
+            let u: f64 = self.rng.gen();
+            let skip = (u.ln() / self.ln_q).floor();
+            // Also catches an infinite skip, which would saturate in the
+            // cast below.
+            if !(skip < self.slice.len() as f64) {
+                self.slice = &self.slice[..0];
+                return None;
+            }
+            skip as usize
+        };
+        let item = &self.slice[skip];
+        self.slice = &self.slice[skip + 1..];
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.slice.len()))
+    }
+}
+
+/// Create an iterator over a uniformly random `amount`-combination of the
+/// elements of `slice`.
+///
+/// Every subset of `amount` elements is equally likely. The elements are
+/// yielded in the order they appear in `slice`, which distinguishes this from
+/// [`sample_slice_ref`]. No memory is allocated; this uses selection sampling
+/// (Knuth's Algorithm S), which needs at most one random number per element
+/// of `slice`.
+///
+/// Panics if `amount > slice.len()`.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let letters = ['a', 'b', 'c', 'd', 'e'];
+/// let combination: String = seq::combination(&mut rng, &letters, 3).collect();
+/// assert_eq!(combination.len(), 3);
+/// ```
+///
+/// [`sample_slice_ref`]: fn.sample_slice_ref.html
+pub fn combination<'a, R, T>(rng: R, slice: &'a [T], amount: usize)
+    -> Combination<'a, R, T>
+    where R: Rng
+{
+    assert!(amount <= slice.len(),
+            "combination called with `amount` > `slice.len()`");
+    Combination { rng: rng, slice: slice, amount: amount }
+}
+
+/// An iterator over a random combination of the elements of a slice.
+///
+/// This `struct` is created by [`combination`]. See its documentation for
+/// more.
+///
+/// [`combination`]: fn.combination.html
+#[derive(Debug)]
+pub struct Combination<'a, R, T: 'a> {
+    rng: R,
+    // The elements not yet considered.
+    slice: &'a [T],
+    // The number of elements still to be yielded.
+    amount: usize,
+}
+
+impl<'a, R: Rng, T> Iterator for Combination<'a, R, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        while self.amount > 0 {
+            // Select the next element with probability `amount / remaining`.
+            let remaining = self.slice.len();
+            let (item, rest) = self.slice.split_first().unwrap();
+            self.slice = rest;
+            if remaining == self.amount
+                || self.rng.gen_range(0..remaining) < self.amount
+            {
+                self.amount -= 1;
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.amount, Some(self.amount))
+    }
+}
+
+impl<'a, R: Rng, T> ExactSizeIterator for Combination<'a, R, T> {}
+
+/// Randomly sample exactly `amount` indices from `0..length`.
+///
+/// The values are non-repeating and in random order.
+///
+/// This implementation uses `O(amount)` time and memory.
+///
+/// This method is used internally by the slice sampling methods, but it can sometimes be useful to
+/// have the indices themselves so this is provided as an alternative.
+///
+/// Panics if `amount > length`
+#[cfg(any(feature="std", feature="alloc"))]
+pub fn sample_indices<R>(rng: &mut R, length: usize, amount: usize) -> Vec<usize>
+    where R: Rng + ?Sized,
+{
+    if amount > length {
+        panic!("`amount` must be less than or equal to `slice.len()`");
+    }
+
+    // We are going to have to allocate at least `amount` for the output no matter what. However,
+    // if we use the `cached` version we will have to allocate `amount` as a HashMap as well since
+    // it inserts an element for every loop.
+    //
+    // Therefore, if `amount >= length / 2` then inplace will be both faster and use less memory.
+    // In fact, benchmarks show the inplace version is faster for length up to about 20 times
+    // faster than amount.
+    //
+    // TODO: there is probably even more fine-tuning that can be done here since
+    // `HashMap::with_capacity(amount)` probably allocates more than `amount` in practice,
+    // and a trade off could probably be made between memory/cpu, since hashmap operations
+    // are slower than array index swapping.
+    if amount >= length / 20 {
+        sample_indices_inplace(rng, length, amount)
+    } else {
+        sample_indices_cache(rng, length, amount)
+    }
+}
+
+/// Sample an amount of indices using an inplace partial fisher yates method.
+///
+/// This allocates the entire `length` of indices and randomizes only the first `amount`.
+/// It then truncates to `amount` and returns.
+///
+/// This is better than using a HashMap "cache" when `amount >= length / 2` since it does not
+/// require allocating an extra cache and is much faster.
+#[cfg(any(feature="std", feature="alloc"))]
+fn sample_indices_inplace<R>(rng: &mut R, length: usize, amount: usize) -> Vec<usize>
+    where R: Rng + ?Sized,
+{
+    debug_assert!(amount <= length);
+    let mut indices: Vec<usize> = Vec::with_capacity(length);
+    indices.extend(0..length);
+    for i in 0..amount {
+        let j: usize = rng.gen_range(i..length);
+        let tmp = indices[i];
+        indices[i] = indices[j];
+        indices[j] = tmp;
+    }
+    indices.truncate(amount);
+    debug_assert_eq!(indices.len(), amount);
+    indices
+}
+
+
+/// This method performs a partial fisher-yates on a range of indices using a HashMap
+/// as a cache to record potential collisions.
+///
+/// The cache avoids allocating the entire `length` of values. This is especially useful when
+/// `amount <<< length`, i.e. select 3 non-repeating from 1_000_000
+#[cfg(any(feature="std", feature="alloc"))]
+fn sample_indices_cache<R>(
+    rng: &mut R,
+    length: usize,
+    amount: usize,
+) -> Vec<usize>
+    where R: Rng + ?Sized,
+{
+    debug_assert!(amount <= length);
+    #[cfg(feature="std")] let mut cache = HashMap::with_capacity(amount);
+    #[cfg(not(feature="std"))] let mut cache = BTreeMap::new();
+    let mut out = Vec::with_capacity(amount);
+    for i in 0..amount {
+        let j: usize = rng.gen_range(i..length);
+
+        // equiv: let tmp = slice[i];
+        let tmp = match cache.get(&i) {
+            Some(e) => *e,
+            None => i,
+        };
+
+        // equiv: slice[i] = slice[j];
+        let x = match cache.get(&j) {
+            Some(x) => *x,
+            None => j,
+        };
+
+        // equiv: slice[j] = tmp;
+        cache.insert(j, tmp);
+
+        // note that in the inplace version, slice[i] is automatically "returned" value
+        out.push(x);
+    }
+    debug_assert_eq!(out.len(), amount);
+    out
+}
+
+/// Shuffle a mutable slice in place, producing the same permutation for the
+/// same RNG output on every platform and in every future release.
+///
+/// [`Rng::shuffle`] is free to change its algorithm between releases, and its
+/// result depends on the width of `usize`. `portable_shuffle` instead follows
+/// a fixed, documented algorithm, so it is suitable for procedural generation
+/// and reproducible experiments, where a seeded RNG must always produce the
+/// same result.
+///
+/// # Algorithm (version 1)
+///
+/// For `i` from `len - 1` down to `1`, the element at `i` is swapped with the
+/// element at `j`, where `j` is sampled uniformly from `0..=i` as follows,
+/// with `n = i + 1`:
+///
+/// - If `n <= 2^32`, take `x = rng.next_u32()` and compute the 64-bit
+///   product `m = x * n`. If the low 32 bits of `m` are less than
+///   `(2^32 - n) % n`, reject `x` and draw again; otherwise `j` is the high
+///   32 bits of `m`.
+/// - Otherwise, do the same with `x = rng.next_u64()`, a 128-bit product and
+///   threshold `(2^64 - n) % n`.
+///
+/// Any change to this algorithm will be made under a new function name.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{SeedableRng, XorShiftRng, seq};
+///
+/// let mut rng = XorShiftRng::from_seed([1; 16]);
+/// let mut y = [1, 2, 3, 4, 5];
+/// seq::portable_shuffle(&mut rng, &mut y);
+/// println!("{:?}", y);
+/// ```
+///
+/// [`Rng::shuffle`]: ../trait.Rng.html#method.shuffle
+pub fn portable_shuffle<R, T>(rng: &mut R, values: &mut [T])
+    where R: Rng + ?Sized
+{
+    let mut i = values.len();
+    while i >= 2 {
+        i -= 1;
+        let n = i as u64 + 1;
+        let j = if n <= 1 << 32 {
+            portable_bounded_u32(rng, n)
+        } else {
+            portable_bounded_u64(rng, n)
+        };
+        values.swap(i, j as usize);
+    }
+}
+
+// Sample from `0..n` with `n <= 2^32`, as documented for `portable_shuffle`.
+fn portable_bounded_u32<R: Rng + ?Sized>(rng: &mut R, n: u64) -> u64 {
+    let threshold = ((1 << 32) - n) % n;
+    loop {
+        let m = rng.next_u32() as u64 * n;
+        if m & 0xffff_ffff >= threshold {
+            return m >> 32;
+        }
+    }
+}
+
+// Sample from `0..n` with `n > 2^32`, as documented for `portable_shuffle`.
+fn portable_bounded_u64<R: Rng + ?Sized>(rng: &mut R, n: u64) -> u64 {
+    let threshold = n.wrapping_neg() % n;
+    loop {
+        let m = rng.next_u64() as u128 * n as u128;
+        if m as u64 >= threshold {
+            return (m >> 64) as u64;
+        }
+    }
+}
+
+/// Implementation of `Rng::shuffle`.
+///
+/// This is a Fisher–Yates shuffle, but while the bounds are small several
+/// indices are sampled from a single `u64`, following Brackett-Rozinsky and
+/// Lemire, "Batched Ranged Random Integer Generation" (2024): multiplying the
+/// random word `r` by a bound `n` gives an index in `0..n` in the high half
+/// of the product and the remaining randomness in the low half, which is then
+/// multiplied by the next bound. Slices longer than `2^24` start with
+/// single swaps; after that, between 2 and 6 indices are taken from each
+/// `u64`, keeping the product of the bounds at most `2^48` so that a batch
+/// is rejected with probability below `2^-16`.
+pub(crate) fn batched_shuffle<R, T>(rng: &mut R, values: &mut [T])
+    where R: Rng + ?Sized
+{
+    let mut i = values.len();
+    // Elements with index >= i have been locked in place.
+    while i > 1 << 24 {
+        values.swap(i - 1, rng.gen_range(0..i));
+        i -= 1;
+    }
+    // Each branch passes a constant batch size, so the loops in
+    // `shuffle_batch` can be unrolled.
+    while i > 1 << 16 { shuffle_batch(rng, values, i, 2); i -= 2; }
+    while i > 1 << 12 { shuffle_batch(rng, values, i, 3); i -= 3; }
+    while i > 1 << 9 { shuffle_batch(rng, values, i, 4); i -= 4; }
+    while i > 1 << 8 { shuffle_batch(rng, values, i, 5); i -= 5; }
+    while i > 6 { shuffle_batch(rng, values, i, 6); i -= 6; }
+    if i >= 2 {
+        shuffle_batch(rng, values, i, i - 1);
+    }
+}
+
+// Swap the elements at `i - 1` down to `i - k` with random elements before
+// them, sampling `k` indices from one `u64`.
+#[inline(always)]
+fn shuffle_batch<R, T>(rng: &mut R, values: &mut [T], i: usize, k: usize)
+    where R: Rng + ?Sized
+{
+    let mut indices = [0usize; 6];
+    let mut product = 1u64;
+    for j in 0..k {
+        product *= (i - j) as u64;
+    }
+    loop {
+        let mut r = rng.next_u64();
+        for (j, index) in indices[..k].iter_mut().enumerate() {
+            let m = r as u128 * (i - j) as u128;
+            *index = (m >> 64) as usize;
+            r = m as u64;
+        }
+        // The result is biased only if the remaining randomness falls below
+        // `2^64 % product`, which is rare; avoid the division otherwise.
+        if r >= product || r >= product.wrapping_neg() % product {
+            break;
+        }
+    }
+    for (j, &index) in indices[..k].iter().enumerate() {
+        values.swap(i - 1 - j, index);
+    }
+}
+
+/// Create an iterator over the values `0..length` in uniformly random order.
+///
+/// The permutation is generated lazily, by an incremental Fisher-Yates
+/// shuffle which only remembers the positions that have been swapped. Taking
+/// `k` values therefore costs `O(k)` time and at most `O(min(k, length - k))`
+/// memory, so it is feasible to iterate over a random order of very large
+/// ranges without materializing them.
+///
+/// The iterator takes ownership of `rng`; to keep using an RNG afterwards,
+/// pass `&mut rng`.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// for i in seq::permutation(&mut rng, 1_000_000_000).take(5) {
+///     println!("{}", i);
+/// }
+/// ```
+#[cfg(any(feature="std", feature="alloc"))]
+pub fn permutation<R: Rng>(rng: R, length: usize) -> Permutation<R> {
+    Permutation {
+        rng: rng,
+        pos: 0,
+        length: length,
+        #[cfg(feature="std")] swapped: HashMap::new(),
+        #[cfg(not(feature="std"))] swapped: BTreeMap::new(),
+    }
+}
+
+/// An iterator over the values `0..length` in random order.
+///
+/// This `struct` is created by [`permutation`]. See its documentation for
+/// more.
+///
+/// [`permutation`]: fn.permutation.html
+#[cfg(any(feature="std", feature="alloc"))]
+#[derive(Debug)]
+pub struct Permutation<R> {
+    rng: R,
+    pos: usize,
+    length: usize,
+    // Values of the positions `pos..length` which differ from their index.
+    #[cfg(feature="std")] swapped: HashMap<usize, usize>,
+    #[cfg(not(feature="std"))] swapped: BTreeMap<usize, usize>,
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl<R: Rng> Iterator for Permutation<R> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.pos == self.length {
+            return None;
+        }
+        let i = self.pos;
+        let j = self.rng.gen_range(i..self.length);
+        self.pos += 1;
+
+        // equiv: tmp = slice[i]; slice.swap(i, j); return slice[i];
+        // Position `i` is never visited again, so it can be forgotten.
+        let tmp = self.swapped.remove(&i).unwrap_or(i);
+        if j == i {
+            return Some(tmp);
+        }
+        let x = self.swapped.insert(j, tmp).unwrap_or(j);
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.length - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(any(feature="std", feature="alloc"))]
+impl<R: Rng> ExactSizeIterator for Permutation<R> {}
+
+/// Algorithms for sampling indices without replacement, for use with
+/// [`sample_without_replacement_using`].
+///
+/// [`sample_without_replacement_using`]: fn.sample_without_replacement_using.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexSampling {
+    /// Choose an algorithm based on `amount` and `length`, as
+    /// [`sample_without_replacement`] does.
+    ///
+    /// [`sample_without_replacement`]: fn.sample_without_replacement.html
+    Auto,
+    /// Floyd's combination algorithm: `O(amount^2)` time and `O(amount)`
+    /// memory. Best when `amount` is small.
+    Floyd,
+    /// Partial Fisher-Yates shuffle of all indices: `O(length)` time and
+    /// memory. Best when `amount` is a large fraction of `length`.
+    FisherYates,
+    /// Rejection sampling, remembering chosen indices in a set: `O(amount)`
+    /// expected time and memory when `amount` is a small fraction of `length`,
+    /// but degrading as `amount` approaches `length`.
+    Rejection,
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+/// Randomly sample exactly `amount` distinct indices from `0..length`.
+///
+/// The values are non-repeating and in random order.
+///
+/// Unlike [`sample_indices`], this picks between several algorithms based on
+/// the ratio of `amount` to `length`, so that performance stays good for any
+/// `amount` up to `length`. The chosen algorithm is an implementation detail
+/// and may change, which changes the values produced; use
+/// [`sample_without_replacement_using`] to pick one explicitly.
+///
+/// Panics if `amount > length`.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let indices = seq::sample_without_replacement(&mut rng, 1000, 990);
+/// assert_eq!(indices.len(), 990);
+/// ```
+///
+/// [`sample_indices`]: fn.sample_indices.html
+/// [`sample_without_replacement_using`]: fn.sample_without_replacement_using.html
+#[cfg(any(feature="std", feature="alloc"))]
+pub fn sample_without_replacement<R>(rng: &mut R, length: usize, amount: usize)
+    -> Vec<usize>
+    where R: Rng + ?Sized,
+{
+    sample_without_replacement_using(rng, length, amount, IndexSampling::Auto)
+}
+
+/// Randomly sample exactly `amount` distinct indices from `0..length`, using
+/// the given algorithm.
+///
+/// The values are non-repeating and in random order.
+///
+/// Panics if `amount > length`.
+#[cfg(any(feature="std", feature="alloc"))]
+pub fn sample_without_replacement_using<R>(rng: &mut R, length: usize,
+                                           amount: usize,
+                                           algorithm: IndexSampling)
+    -> Vec<usize>
+    where R: Rng + ?Sized,
+{
+    if amount > length {
+        panic!("`amount` must be less than or equal to `length`");
+    }
+    let algorithm = match algorithm {
+        IndexSampling::Auto | IndexSampling::__Nonexhaustive => {
+            // Floyd's algorithm is quadratic in `amount`, but has very low
+            // overhead, so it wins for small amounts. Otherwise rejection
+            // sampling is best while few samples get rejected; once `amount`
+            // is a sizeable fraction of `length`, shuffling all indices is
+            // cheaper.
+            if amount <= 16 {
+                IndexSampling::Floyd
+            } else if amount <= length / 4 {
+                IndexSampling::Rejection
+            } else {
+                IndexSampling::FisherYates
+            }
+        }
+        a => a,
+    };
+    match algorithm {
+        IndexSampling::Floyd => sample_indices_floyd(rng, length, amount),
+        IndexSampling::Rejection => sample_indices_rejection(rng, length, amount),
+        _ => sample_indices_inplace(rng, length, amount),
+    }
+}
+
+/// Sample indices using Floyd's combination algorithm.
+///
+/// Picking `t` from `0..=j` and replacing an earlier pick of `t` by `j`
+/// selects each subset with equal probability; pushing `t` at the end (rather
+/// than `j`) makes the order of the output random as well.
+#[cfg(any(feature="std", feature="alloc"))]
+fn sample_indices_floyd<R>(rng: &mut R, length: usize, amount: usize) -> Vec<usize>
+    where R: Rng + ?Sized,
+{
+    debug_assert!(amount <= length);
+    let mut indices = Vec::with_capacity(amount);
+    for j in length - amount .. length {
+        let t = rng.gen_range(0..=j);
+        if let Some(pos) = indices.iter().position(|&x| x == t) {
+            indices[pos] = j;
+        }
+        indices.push(t);
+    }
+    debug_assert_eq!(indices.len(), amount);
+    indices
+}
+
+/// Sample indices by rejection, remembering already chosen indices.
+#[cfg(any(feature="std", feature="alloc"))]
+fn sample_indices_rejection<R>(rng: &mut R, length: usize, amount: usize) -> Vec<usize>
+    where R: Rng + ?Sized,
+{
+    debug_assert!(amount <= length);
+    #[cfg(feature="std")] let mut chosen = HashSet::with_capacity(amount);
+    #[cfg(not(feature="std"))] let mut chosen = BTreeSet::new();
+    let mut indices = Vec::with_capacity(amount);
+    while indices.len() < amount {
+        let i = rng.gen_range(0..length);
+        if chosen.insert(i) {
+            indices.push(i);
+        }
+    }
+    indices
+}
+
+#[cfg(all(test, any(feature="std", feature="alloc")))]
+mod test {
+    use super::*;
+    use {XorShiftRng, Rng, SeedableRng};
+    #[cfg(not(feature="std"))]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_sample_iter() {
+        let min_val = 1;
+        let max_val = 100;
+
+        let mut r = ::test::rng(401);
+        let vals = (min_val..max_val).collect::<Vec<i32>>();
+        let small_sample = sample_iter(&mut r, vals.iter(), 5).unwrap();
+        let large_sample = sample_iter(&mut r, vals.iter(), vals.len() + 5).unwrap_err();
+
+        assert_eq!(small_sample.len(), 5);
+        assert_eq!(large_sample.len(), vals.len());
+        // no randomization happens when amount >= len
+        assert_eq!(large_sample, vals.iter().collect::<Vec<_>>());
+
+        assert!(small_sample.iter().all(|e| {
+            **e >= min_val && **e <= max_val
+        }));
+    }
+    #[test]
+    #[cfg(feature="std")]
+    fn test_choose_multiple_weighted() {
+        let mut r = ::test::rng(405);
+        let items = [0usize, 1, 2, 3, 4];
+        let weights = [0.0, 1.0, 2.0, 3.0, 100.0];
+
+        let chosen = choose_multiple_weighted(&mut r, &items, 3, |&i| weights[i]);
+        assert_eq!(chosen.len(), 3);
+        assert!(!chosen.contains(&&0));
+        let mut sorted = chosen.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 3);
+
+        // Only four elements have positive weight
+        let chosen = choose_multiple_weighted(&mut r, &items, 5, |&i| weights[i]);
+        assert_eq!(chosen.len(), 4);
+
+        // The heavy element is almost always picked first
+        let mut first_heavy = 0;
+        for _ in 0..100 {
+            let chosen = choose_multiple_weighted(&mut r, &items, 1, |&i| weights[i]);
+            if *chosen[0] == 4 { first_heavy += 1; }
+        }
+        assert!(first_heavy > 80);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature="std")]
+    fn test_choose_multiple_weighted_negative() {
+        let mut r = ::test::rng(406);
+        choose_multiple_weighted(&mut r, &[1, 2], 1, |&x| if x == 1 { -1.0 } else { 1.0 });
+    }
+
+    #[test]
+    fn test_choose_weighted() {
+        let mut r = ::test::rng(415);
+        let mut items = [('a', 0.0), ('b', 1.0), ('c', 3.0), ('d', 0.0)];
+        let mut counts = [0; 4];
+        for _ in 0..1000 {
+            let &(c, _) = choose_weighted(&mut r, &items, |item| item.1).unwrap();
+            counts[(c as u8 - b'a') as usize] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        assert_eq!(counts[3], 0);
+        assert!(counts[1] > 180 && counts[1] < 320);
+
+        choose_weighted_mut(&mut r, &mut items, |item| item.1).unwrap().0 = 'z';
+        assert!(items.iter().any(|item| item.0 == 'z'));
+
+        let empty: [f64; 0] = [];
+        assert_eq!(choose_weighted(&mut r, &empty, |&w| w).unwrap_err(),
+                   WeightedError::NoItem);
+        assert_eq!(choose_weighted(&mut r, &[0.0, 0.0], |&w| w).unwrap_err(),
+                   WeightedError::AllWeightsZero);
+        assert_eq!(choose_weighted(&mut r, &[1.0, -1.0], |&w| w).unwrap_err(),
+                   WeightedError::InvalidWeight);
+        assert_eq!(choose_weighted(&mut r, &[1.0, ::core::f64::NAN], |&w| w).unwrap_err(),
+                   WeightedError::InvalidWeight);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_choose_map() {
+        use std::collections::{HashMap, BTreeMap};
+        let mut r = ::test::rng(423);
+
+        let hash: HashMap<u32, u32> = (0..10).map(|i| (i, i % 3)).collect();
+        let tree: BTreeMap<u32, u32> = hash.iter().map(|(&k, &v)| (k, v)).collect();
+        let mut counts = [0; 10];
+        for _ in 0..1000 {
+            let (&k, &v) = choose_entry(&mut r, &hash).unwrap();
+            assert_eq!(v, k % 3);
+            counts[k as usize] += 1;
+            counts[*choose_key(&mut r, &tree).unwrap() as usize] += 1;
+            assert!(*choose_value(&mut r, &tree).unwrap() < 3);
+        }
+        assert!(counts.iter().all(|&c| c > 130 && c < 270));
+
+        let mut counts = [0; 10];
+        for _ in 0..500 {
+            let entries = choose_entries(&mut r, &hash, 4);
+            assert_eq!(entries.len(), 4);
+            for (i, &(&k, &v)) in entries.iter().enumerate() {
+                assert_eq!(v, k % 3);
+                assert!(entries[..i].iter().all(|&(&k2, _)| k2 != k));
+                counts[k as usize] += 1;
+            }
+        }
+        assert!(counts.iter().all(|&c| c > 130 && c < 270), "{:?}", counts);
+        assert_eq!(choose_entries(&mut r, &tree, 20).len(), 10);
+        assert!(choose_entries(&mut r, &tree, 0).is_empty());
+
+        // Weighted by value: keys with `k % 3 == 0` are never chosen, and
+        // `k % 3 == 2` twice as often as `k % 3 == 1`.
+        let mut counts = [0; 3];
+        for _ in 0..900 {
+            let (&k, _) = choose_entry_weighted(&mut r, &hash).unwrap();
+            counts[(k % 3) as usize] += 1;
+            let (&k, _) = choose_entry_weighted(&mut r, &tree).unwrap();
+            counts[(k % 3) as usize] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        assert!(counts[1] > 500 && counts[1] < 700);
+
+        let empty: BTreeMap<u32, f64> = BTreeMap::new();
+        assert_eq!(choose_entry(&mut r, &empty), None);
+        assert_eq!(choose_entry_weighted(&mut r, &empty).unwrap_err(),
+                   WeightedError::NoItem);
+        let zeros: HashMap<u32, f32> = (0..3).map(|i| (i, 0.0)).collect();
+        assert_eq!(choose_entry_weighted(&mut r, &zeros).unwrap_err(),
+                   WeightedError::AllWeightsZero);
+    }
+
+    #[test]
+    fn test_permutation() {
+        let mut r = ::test::rng(416);
+        assert_eq!(permutation(&mut r, 0).next(), None);
+        assert_eq!(permutation(&mut r, 1).collect::<Vec<_>>(), vec![0]);
+
+        let perm = permutation(&mut r, 100);
+        assert_eq!(perm.len(), 100);
+        let mut v = perm.collect::<Vec<_>>();
+        assert!(v != (0..100).collect::<Vec<_>>());
+        v.sort();
+        assert_eq!(v, (0..100).collect::<Vec<_>>());
+
+        // A permutation too large to ever be stored
+        #[cfg(target_pointer_width = "64")]
+        {
+            let mut perm = permutation(&mut r, 1 << 40);
+            for _ in 0..100 {
+                assert!(perm.next().unwrap() < 1 << 40);
+            }
+            assert_eq!(perm.len(), (1 << 40) - 100);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_subset() {
+        let mut r = ::test::rng(417);
+        let v: Vec<u32> = (0..1000).collect();
+        assert_eq!(subset(&mut r, &v, 0.0).count(), 0);
+        assert_eq!(subset(&mut r, &v, 1.0).cloned().collect::<Vec<_>>(), v);
+
+        let mut total = 0;
+        for _ in 0..10 {
+            let s: Vec<&u32> = subset(&mut r, &v, 0.1).collect();
+            assert!(s.windows(2).all(|w| w[0] < w[1]));
+            total += s.len();
+        }
+        assert!(total > 850 && total < 1150);
+
+        // Tiny probabilities
+        for &p in &[1e-17, 1e-300, 5e-324] {
+            assert_eq!(subset(&mut r, &v, p).count(), 0);
+        }
+    }
+
+    #[test]
+    fn test_combination() {
+        let mut r = ::test::rng(418);
+        let v: Vec<u32> = (0..10).collect();
+        assert_eq!(combination(&mut r, &v, 0).next(), None);
+        assert_eq!(combination(&mut r, &v, 10).cloned().collect::<Vec<_>>(), v);
+
+        let mut counts = [0; 10];
+        for _ in 0..1000 {
+            let c: Vec<&u32> = combination(&mut r, &v, 3).collect();
+            assert_eq!(c.len(), 3);
+            assert!(c.windows(2).all(|w| w[0] < w[1]));
+            for &&x in c.iter() {
+                counts[x as usize] += 1;
+            }
+        }
+        // Each element is included with probability 0.3
+        assert!(counts.iter().all(|&n| n > 220 && n < 380));
+    }
+
+    #[test]
+    fn test_portable_shuffle_golden() {
+        // These values must never change; see the docs of `portable_shuffle`.
+        let seed = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+        let mut r = XorShiftRng::from_seed(seed);
+        let mut v: Vec<u32> = (0..20).collect();
+        portable_shuffle(&mut r, &mut v);
+        assert_eq!(v, vec![13, 8, 3, 5, 2, 12, 18, 10, 6, 9,
+                           17, 14, 11, 1, 15, 16, 19, 7, 4, 0]);
+        portable_shuffle(&mut r, &mut v[..0]);
+        portable_shuffle(&mut r, &mut v[..1]);
+        let mut v: Vec<u32> = (0..20).collect();
+        portable_shuffle(&mut r, &mut v);
+        assert_eq!(v, vec![3, 16, 6, 14, 13, 5, 7, 1, 17, 18,
+                           19, 10, 0, 15, 4, 11, 2, 8, 9, 12]);
+
+        // The path for slices longer than 2^32
+        let mut r = XorShiftRng::from_seed(seed);
+        let n = (1 << 40) + 7;
+        assert_eq!(portable_bounded_u64(&mut r, n), 257816374285);
+        assert_eq!(portable_bounded_u64(&mut r, n), 520555880);
+        assert_eq!(portable_bounded_u64(&mut r, n), 37941965324);
+    }
+
+    #[test]
+    fn test_batched_shuffle() {
+        let mut r = ::test::rng(408);
+        // Each of the 24 permutations of 4 elements should be about equally
+        // likely; all 3 swaps come from a single batch.
+        let mut counts = [0; 256];
+        for _ in 0..24000 {
+            let mut v = [0, 1, 2, 3];
+            batched_shuffle(&mut r, &mut v);
+            counts[v.iter().fold(0, |acc, &x| acc * 4 + x)] += 1;
+        }
+        let seen: Vec<_> = counts.iter().filter(|&&c| c > 0).collect();
+        assert_eq!(seen.len(), 24);
+        assert!(seen.iter().all(|&&c| c > 850 && c < 1150));
+
+        // Crossing from single swaps to batches
+        let mut v: Vec<u32> = (0..100_000).collect();
+        batched_shuffle(&mut r, &mut v);
+        assert!(v.iter().enumerate().filter(|&(i, &x)| i as u32 == x).count() < 20);
+        v.sort();
+        assert!(v.iter().enumerate().all(|(i, &x)| i as u32 == x));
+    }
+
+    #[test]
+    fn test_sample_without_replacement() {
+        let mut r = ::test::rng(407);
+        let algorithms = [IndexSampling::Auto, IndexSampling::Floyd,
+                          IndexSampling::FisherYates, IndexSampling::Rejection];
+        for &algorithm in algorithms.iter() {
+            for &(length, amount) in [(0, 0), (1, 1), (10, 0), (10, 3), (10, 10),
+                                      (1000, 17), (1000, 300), (1000, 1000)].iter() {
+                let mut v = sample_without_replacement_using(&mut r, length, amount,
+                                                             algorithm);
+                assert_eq!(v.len(), amount);
+                v.sort();
+                v.dedup();
+                assert_eq!(v.len(), amount);
+                assert!(v.iter().all(|&i| i < length));
+            }
+        }
+        assert_eq!(sample_without_replacement(&mut r, 50, 45).len(), 45);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_without_replacement_too_many() {
+        let mut r = ::test::rng(408);
+        sample_without_replacement(&mut r, 5, 6);
+    }
+
+    #[test]
+    fn test_choose_iter() {
+        // An iterator which hides (part of) its size hint
+        struct Hinted<I> { iter: I, exact: bool }
+        impl<I: Iterator> Iterator for Hinted<I> {
+            type Item = I::Item;
+            fn next(&mut self) -> Option<I::Item> { self.iter.next() }
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let (lower, _) = self.iter.size_hint();
+                if self.exact { (lower, None) } else { (lower / 2, None) }
+            }
+        }
+
+        let mut r = ::test::rng(419);
+        assert_eq!(choose_iter(&mut r, 0..0), None);
+        assert_eq!(choose_iter(&mut r, (0..10).filter(|_| false)), None);
+        assert_eq!(choose_iter(&mut r, Hinted { iter: 5..6, exact: false }), Some(5));
+
+        for &exact in [true, false].iter() {
+            let mut counts = [0; 10];
+            for _ in 0..1000 {
+                counts[choose_iter(&mut r, 0..10).unwrap()] += 1;
+                counts[choose_iter(&mut r, (0..10).filter(|_| true)).unwrap()] += 1;
+                let iter = Hinted { iter: 0..10, exact: exact };
+                counts[choose_iter(&mut r, iter).unwrap()] += 1;
+            }
+            assert!(counts.iter().all(|&n| n > 240 && n < 360), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_choose_multiple() {
+        let mut r = ::test::rng(409);
+        assert_eq!(choose_multiple(&mut r, 0..3, 5), vec![0, 1, 2]);
+        assert_eq!(choose_multiple(&mut r, 0..3, 0), Vec::<i32>::new());
+
+        let mut sample = choose_multiple(&mut r, 0..100_000, 10);
+        assert_eq!(sample.len(), 10);
+        sample.sort();
+        sample.dedup();
+        assert_eq!(sample.len(), 10);
+
+        // Elements at the end of the stream can be chosen
+        let mut late = 0;
+        for _ in 0..100 {
+            let sample = choose_multiple(&mut r, 0..100, 10);
+            late += sample.iter().filter(|&&x| x >= 90).count();
+        }
+        assert!(late > 50 && late < 150);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_choose_multiple_weighted_iter() {
+        let mut r = ::test::rng(410);
+        let pairs = vec![(0, 0.0), (1, 1.0), (2, 1.0), (3, 0.0)];
+        let mut sample = choose_multiple_weighted_iter(&mut r, pairs.clone(), 3);
+        sample.sort();
+        assert_eq!(sample, vec![1, 2]);
+        assert!(choose_multiple_weighted_iter(&mut r, pairs, 0).is_empty());
+
+        // A heavy item late in the stream is almost always chosen
+        let mut heavy = 0;
+        for _ in 0..100 {
+            let pairs = (0..1000).map(|i| (i, if i == 999 { 1000.0 } else { 1.0 }));
+            let sample = choose_multiple_weighted_iter(&mut r, pairs, 5);
+            assert_eq!(sample.len(), 5);
+            if sample.contains(&999) { heavy += 1; }
+        }
+        assert!(heavy > 90);
+    }
+
+    #[test]
+    fn test_choose_multiple_fill() {
+        let mut r = ::test::rng(411);
+        let values = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+        let mut buf = [0; 4];
+        assert_eq!(choose_multiple_fill(&mut r, values.iter().cloned(), &mut buf), 4);
+        let mut sorted = buf;
+        sorted.sort();
+        assert!(sorted.windows(2).all(|w| w[0] < w[1]));
+        assert!(buf.iter().all(|&x| x >= 1 && x <= 10));
+
+        let mut buf = [0; 12];
+        assert_eq!(choose_multiple_fill(&mut r, values.iter().cloned(), &mut buf), 10);
+        assert_eq!(&buf[..10], &values[..]);
+
+        let mut refs = [&0; 2];
+        assert_eq!(choose_multiple_fill(&mut r, values.iter(), &mut refs), 2);
+
+        let mut empty: [i32; 0] = [];
+        assert_eq!(choose_multiple_fill(&mut r, values.iter().cloned(), &mut empty), 0);
+    }
+
+    #[test]
+    fn test_sample_slice_boundaries() {
+        let empty: &[u8] = &[];
+
+        let mut r = ::test::rng(402);
+
+        // sample 0 items
+        assert_eq!(&sample_slice(&mut r, empty, 0)[..], [0u8; 0]);
+        assert_eq!(&sample_slice(&mut r, &[42, 2, 42], 0)[..], [0u8; 0]);
+
+        // sample 1 item
+        assert_eq!(&sample_slice(&mut r, &[42], 1)[..], [42]);
+        let v = sample_slice(&mut r, &[1, 42], 1)[0];
+        assert!(v == 1 || v == 42);
+
+        // sample "all" the items
+        let v = sample_slice(&mut r, &[42, 133], 2);
+        assert!(&v[..] == [42, 133] || v[..] == [133, 42]);
+
+        assert_eq!(&sample_indices_inplace(&mut r, 0, 0)[..], [0usize; 0]);
+        assert_eq!(&sample_indices_inplace(&mut r, 1, 0)[..], [0usize; 0]);
+        assert_eq!(&sample_indices_inplace(&mut r, 1, 1)[..], [0]);
+
+        assert_eq!(&sample_indices_cache(&mut r, 0, 0)[..], [0usize; 0]);
+        assert_eq!(&sample_indices_cache(&mut r, 1, 0)[..], [0usize; 0]);
+        assert_eq!(&sample_indices_cache(&mut r, 1, 1)[..], [0]);
+
+        // Make sure lucky 777's aren't lucky
+        let slice = &[42, 777];
+        let mut num_42 = 0;
+        let total = 1000;
+        for _ in 0..total {
+            let v = sample_slice(&mut r, slice, 1);
+            assert_eq!(v.len(), 1);
+            let v = v[0];
+            assert!(v == 42 || v == 777);
+            if v == 42 {
+                num_42 += 1;
+            }
+        }
+        let ratio_42 = num_42 as f64 / 1000 as f64;
+        assert!(0.4 <= ratio_42 || ratio_42 <= 0.6, "{}", ratio_42);
+    }
+
+    #[test]
+    fn test_sample_slice() {
+        let xor_rng = XorShiftRng::from_seed;
+
+        let max_range = 100;
+        let mut r = ::test::rng(403);
+
+        for length in 1usize..max_range {
+            let amount = r.gen_range(0..length);
+            let mut seed = [0u8; 16];
+            r.fill(&mut seed);
+
+            // assert that the two index methods give exactly the same result
+            let inplace = sample_indices_inplace(
+                &mut xor_rng(seed), length, amount);
+            let cache = sample_indices_cache(
+                &mut xor_rng(seed), length, amount);
+            assert_eq!(inplace, cache);
+
+            // assert the basics work
+            let regular = sample_indices(
+                &mut xor_rng(seed), length, amount);
+            assert_eq!(regular.len(), amount);
+            assert!(regular.iter().all(|e| *e < length));
+            assert_eq!(regular, inplace);
+
+            // also test that sampling the slice works
+            let vec: Vec<usize> = (0..length).collect();
+            {
+                let result = sample_slice(&mut xor_rng(seed), &vec, amount);
+                assert_eq!(result, regular);
+            }
+
+            {
+                let result = sample_slice_ref(&mut xor_rng(seed), &vec, amount);
+                let expected = regular.iter().map(|v| v).collect::<Vec<_>>();
+                assert_eq!(result, expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A wrapper around a 64-bit PRNG that serves `next_u32` from both halves of
+//! each generated `u64`.
+
+use {RngCore, CryptoRng, Error};
+
+/// A wrapper around an RNG producing 64-bit words, which splits every
+/// `next_u64` output into two `u32` values.
+///
+/// Many generators, such as `Xoroshiro128PlusRng`, natively produce a `u64`
+/// per step and implement `next_u32` by discarding half of it. Workloads
+/// dominated by `u32` values, e.g. `gen::<u32>()` or small bounded integers,
+/// then waste half of every generated word. `SplitRng` returns the upper half
+/// first, like the `next_u32` of most 64-bit generators, and keeps the lower
+/// half for the next call to `next_u32`: generating two `u32` values costs
+/// only one step of the wrapped generator.
+///
+/// `next_u64` and `fill_bytes` are passed on to the wrapped generator
+/// unchanged, and do not disturb a cached half.
+///
+/// For generators producing 32 bits at a time, or which already keep the
+/// unused half themselves like `Isaac64Rng`, this only adds overhead. Note
+/// also that the lower half is used too, so every other `u32` contains the
+/// lowest bits of the generator: with `Xoroshiro128PlusRng`, whose lowest two
+/// bits fail linearity tests, those values are of lower quality.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, SeedableRng, Xoroshiro128PlusRng};
+/// use rand::split::SplitRng;
+///
+/// let mut rng = SplitRng::new(Xoroshiro128PlusRng::from_seed([1; 16]));
+/// let x: u32 = rng.gen(); // high half of the first `u64`
+/// let y: u32 = rng.gen(); // low half
+/// println!("{} {}", x, y);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SplitRng<R> {
+    rng: R,
+    half: Option<u32>,
+}
+
+impl<R: RngCore> SplitRng<R> {
+    /// Create a new `SplitRng` wrapping `rng`.
+    pub fn new(rng: R) -> SplitRng<R> {
+        SplitRng { rng: rng, half: None }
+    }
+
+    /// Unwrap the inner generator. A cached half is lost.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: RngCore> RngCore for SplitRng<R> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        match self.half.take() {
+            Some(x) => x,
+            None => {
+                let x = self.rng.next_u64();
+                self.half = Some(x as u32);
+                (x >> 32) as u32
+            }
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        self.rng.fill_u64_slice(dest)
+    }
+}
+
+impl<R: RngCore + CryptoRng> CryptoRng for SplitRng<R> {}
+
+#[cfg(test)]
+mod test {
+    use RngCore;
+    use mock::StepRng;
+    use super::SplitRng;
+
+    #[test]
+    fn test_split() {
+        let mut rng = SplitRng::new(StepRng::new(0x1111_2222_3333_4444, 1));
+        assert_eq!(rng.next_u32(), 0x1111_2222);
+        // Does not disturb the cached half
+        assert_eq!(rng.next_u64(), 0x1111_2222_3333_4445);
+        assert_eq!(rng.next_u32(), 0x3333_4444);
+        assert_eq!(rng.next_u32(), 0x1111_2222);
+
+        let mut buf = [0u32; 3];
+        rng.fill_u32_slice(&mut buf);
+        assert_eq!(buf, [0x3333_4446, 0x1111_2222, 0x3333_4447]);
+        assert_eq!(rng.into_inner().next_u64(), 0x1111_2222_3333_4448);
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Independent, reproducible random streams indexed by number.
+
+use core::fmt;
+use {RngCore, SeedableRng, ChaChaRng, Error};
+#[cfg(feature="zeroize")] use zeroize::zeroize;
+
+/// A source of independent random number streams, one per `u64` index,
+/// derived from a master seed.
+///
+/// [`rng_for`] returns the same generator for the same seed and index,
+/// regardless of which other streams were used before or concurrently. This
+/// makes simulations reproducible when work items are processed in parallel
+/// or in a different order: give each work item the stream of its index.
+///
+/// The streams are those of `ChaChaRng` keyed with the master seed. ChaCha is
+/// a counter-based generator with a 128-bit block counter; stream `i` uses
+/// the high 64 bits of the counter as `i` and starts the low half at 0, so
+/// each stream has `2^64` blocks (`2^70` bytes) of output before it would run
+/// into the next one. The streams are thus not merely unlikely to overlap,
+/// they are disjoint parts of a single ChaCha stream.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::Rng;
+/// use rand::stream::StreamFactory;
+///
+/// let factory = StreamFactory::new([42; 32]);
+/// let results = (0..8u64).map(|task| {
+///     let mut rng = factory.rng_for(task);
+///     rng.gen::<f64>()
+/// }).collect::<Vec<_>>();
+///
+/// // Any stream can be recreated independently
+/// assert_eq!(factory.rng_for(5).gen::<f64>(), results[5]);
+/// ```
+///
+/// [`rng_for`]: #method.rng_for
+#[derive(Clone)]
+pub struct StreamFactory {
+    seed: [u8; 32],
+}
+
+// Custom Debug implementation that does not expose the master seed.
+impl fmt::Debug for StreamFactory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "StreamFactory {{}}")
+    }
+}
+
+impl StreamFactory {
+    /// Create a `StreamFactory` from a master seed.
+    pub fn new(seed: [u8; 32]) -> StreamFactory {
+        StreamFactory { seed: seed }
+    }
+
+    /// Create a `StreamFactory` with a master seed taken from `rng`.
+    pub fn from_rng<R: RngCore + ?Sized>(rng: &mut R) -> Result<StreamFactory, Error> {
+        let mut seed = [0u8; 32];
+        rng.try_fill_bytes(&mut seed)?;
+        let factory = StreamFactory::new(seed);
+        #[cfg(feature="zeroize")] zeroize(&mut seed);
+        Ok(factory)
+    }
+
+    /// The generator of stream `index`.
+    pub fn rng_for(&self, index: u64) -> ChaChaRng {
+        let mut rng = ChaChaRng::from_seed(self.seed);
+        rng.set_counter(0, index);
+        rng
+    }
+}
+
+#[cfg(feature="zeroize")]
+impl Drop for StreamFactory {
+    fn drop(&mut self) {
+        zeroize(&mut self.seed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng, ChaChaRng};
+    use mock::StepRng;
+    use super::StreamFactory;
+
+    #[test]
+    fn test_stream_factory() {
+        let factory = StreamFactory::new([7; 32]);
+        let mut first = [0u32; 20];
+        for (i, x) in first.iter_mut().enumerate().rev() {
+            *x = factory.rng_for(i as u64).next_u32();
+        }
+        for (i, &x) in first.iter().enumerate() {
+            assert_eq!(factory.rng_for(i as u64).next_u32(), x);
+        }
+        for i in 1..20 {
+            assert!(first[..i].iter().all(|&x| x != first[i]));
+        }
+
+        // Stream 1 starts after 2^64 blocks of stream 0
+        let mut expected = ChaChaRng::from_seed([7; 32]);
+        expected.set_counter(::core::u64::MAX, 0);
+        for _ in 0..16 {
+            expected.next_u32();
+        }
+        let mut stream = factory.rng_for(1);
+        for _ in 0..40 {
+            assert_eq!(stream.next_u32(), expected.next_u32());
+        }
+
+        let other = StreamFactory::new([8; 32]);
+        assert!(other.rng_for(0).next_u64() != factory.rng_for(0).next_u64());
+
+        let mut rng = StepRng::new(0, 0);
+        let factory = StreamFactory::from_rng(&mut rng).unwrap();
+        assert_eq!(factory.rng_for(3).next_u64(),
+                   StreamFactory::new([0; 32]).rng_for(3).next_u64());
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_stream_factory_debug() {
+        let factory = StreamFactory::new([0; 32]);
+        assert_eq!(format!("{:?}", factory), "StreamFactory {}");
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Statistical tests for validating distributions.
+//!
+//! These functions draw a number of samples from a distribution and test
+//! whether they fit the expected distribution: [`chi_squared`] for discrete
+//! distributions, or continuous ones divided into bins, and
+//! [`kolmogorov_smirnov`] for continuous distributions with a known
+//! cumulative distribution function. Each returns a [`TestResult`] with the
+//! p-value of the test.
+//!
+//! A test of a correct distribution still fails with a probability equal to
+//! the significance level. Unit tests should therefore use a seeded RNG, so
+//! that they are deterministic, and a small significance level like 0.001.
+//!
+//! This module requires the `testing` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rand::{SeedableRng, XorShiftRng};
+//! use rand::distributions::Exp;
+//! use rand::testing::kolmogorov_smirnov;
+//!
+//! let mut rng = XorShiftRng::from_seed([7; 16]);
+//! let result = kolmogorov_smirnov(&mut rng, &Exp::new(2.0), 1000,
+//!                                 |x| 1.0 - (-2.0 * x).exp());
+//! assert!(result.passes(0.001));
+//! ```
+//!
+//! [`chi_squared`]: fn.chi_squared.html
+//! [`kolmogorov_smirnov`]: fn.kolmogorov_smirnov.html
+//! [`TestResult`]: struct.TestResult.html
+
+use std::f64;
+
+use Rng;
+use distributions::Distribution;
+
+/// The outcome of a statistical test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    statistic: f64,
+    p_value: f64,
+}
+
+impl TestResult {
+    /// The test statistic: the chi-squared value, or the largest difference
+    /// `D` between the empirical and expected distribution functions.
+    pub fn statistic(&self) -> f64 {
+        self.statistic
+    }
+
+    /// The probability of a result at least this extreme, if the samples do
+    /// have the expected distribution.
+    pub fn p_value(&self) -> f64 {
+        self.p_value
+    }
+
+    /// Whether the samples fit the expected distribution at the given
+    /// significance level, i.e. `p_value() >= significance`.
+    pub fn passes(&self, significance: f64) -> bool {
+        self.p_value >= significance
+    }
+}
+
+/// Pearson's chi-squared goodness-of-fit test.
+///
+/// Draws `samples` values from `distr`, and sorts them into categories with
+/// `category`, which must return an index into `probabilities`. The counts
+/// are compared with the expected probabilities of the categories, which
+/// should sum to 1. For the test to be accurate, `samples` times every
+/// probability should be at least 5.
+///
+/// # Panics
+///
+/// Panics if there are fewer than 2 categories, or `category` returns an
+/// index out of range.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{SeedableRng, XorShiftRng};
+/// use rand::distributions::Range;
+/// use rand::testing::chi_squared;
+///
+/// let mut rng = XorShiftRng::from_seed([7; 16]);
+/// let result = chi_squared(&mut rng, &Range::new(0, 6), 6000, &[1.0 / 6.0; 6],
+///                          |&x| x as usize);
+/// assert!(result.passes(0.001));
+/// ```
+pub fn chi_squared<T, D, R, F>(rng: &mut R, distr: &D, samples: usize,
+                               probabilities: &[f64], mut category: F)
+    -> TestResult
+    where D: Distribution<T>, R: Rng + ?Sized, F: FnMut(&T) -> usize
+{
+    assert!(probabilities.len() >= 2, "chi_squared called with fewer than 2 categories");
+    let mut counts = vec![0usize; probabilities.len()];
+    for _ in 0..samples {
+        let i = category(&distr.sample(rng));
+        assert!(i < counts.len(), "chi_squared: category out of range");
+        counts[i] += 1;
+    }
+
+    let n = samples as f64;
+    let statistic = counts.iter().zip(probabilities.iter())
+        .map(|(&count, &p)| {
+            let expected = n * p;
+            let diff = count as f64 - expected;
+            diff * diff / expected
+        })
+        .sum::<f64>();
+    let df = (probabilities.len() - 1) as f64;
+    TestResult {
+        statistic: statistic,
+        p_value: gamma_q(df / 2.0, statistic / 2.0),
+    }
+}
+
+/// The one-sample Kolmogorov–Smirnov test.
+///
+/// Draws `samples` values from `distr`, and compares their empirical
+/// distribution function with the cumulative distribution function `cdf` of
+/// the expected distribution, which must be continuous. The p-value uses the
+/// asymptotic distribution of the statistic, which is accurate for `samples`
+/// of about 100 or more.
+///
+/// # Panics
+///
+/// Panics if `samples` is 0 or a sample is NaN.
+pub fn kolmogorov_smirnov<D, R, C>(rng: &mut R, distr: &D, samples: usize,
+                                   mut cdf: C) -> TestResult
+    where D: Distribution<f64>, R: Rng + ?Sized, C: FnMut(f64) -> f64
+{
+    assert!(samples > 0, "kolmogorov_smirnov called with no samples");
+    let mut values: Vec<f64> = (0..samples).map(|_| distr.sample(rng)).collect();
+    values.sort_by(|a, b| a.partial_cmp(b).expect("kolmogorov_smirnov: NaN sample"));
+
+    let n = samples as f64;
+    let mut statistic = 0.0f64;
+    for (i, &x) in values.iter().enumerate() {
+        let f = cdf(x);
+        statistic = statistic.max((i + 1) as f64 / n - f).max(f - i as f64 / n);
+    }
+    // Stephens' correction for finite sample sizes
+    let sqrt_n = n.sqrt();
+    let lambda = (sqrt_n + 0.12 + 0.11 / sqrt_n) * statistic;
+    TestResult {
+        statistic: statistic,
+        p_value: kolmogorov_q(lambda),
+    }
+}
+
+// The survival function of the Kolmogorov distribution, `P(K > lambda)`.
+fn kolmogorov_q(lambda: f64) -> f64 {
+    if lambda < 0.2 {
+        return 1.0;
+    }
+    if lambda < 1.18 {
+        // The series for P(K <= lambda), which converges quickly here
+        let y = -f64::consts::PI * f64::consts::PI / (8.0 * lambda * lambda);
+        let sum: f64 = (0..8).map(|j| ((2 * j + 1) as f64).powi(2) * y)
+            .map(f64::exp).sum();
+        return 1.0 - (2.0 * f64::consts::PI).sqrt() / lambda * sum;
+    }
+    let mut sum = 0.0;
+    let mut sign = 1.0;
+    for j in 1..101 {
+        let term = (-2.0 * (j * j) as f64 * lambda * lambda).exp();
+        sum += sign * term;
+        if term < 1e-16 {
+            break;
+        }
+        sign = -sign;
+    }
+    (2.0 * sum).max(0.0).min(1.0)
+}
+
+// The regularized upper incomplete gamma function `Q(a, x)`, as in
+// Numerical Recipes: a series for `x < a + 1`, otherwise a continued
+// fraction.
+fn gamma_q(a: f64, x: f64) -> f64 {
+    const EPS: f64 = 1e-15;
+    const TINY: f64 = 1e-300;
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let log_prefactor = -x + a * x.ln() - ln_gamma(a);
+    if x < a + 1.0 {
+        let mut ap = a;
+        let mut del = 1.0 / a;
+        let mut sum = del;
+        for _ in 0..1000 {
+            ap += 1.0;
+            del *= x / ap;
+            sum += del;
+            if del.abs() < sum.abs() * EPS {
+                break;
+            }
+        }
+        (1.0 - sum * log_prefactor.exp()).max(0.0)
+    } else {
+        // Modified Lentz's method
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < TINY { d = TINY; }
+            c = b + an / c;
+            if c.abs() < TINY { c = TINY; }
+            d = 1.0 / d;
+            let del = d * c;
+            h *= del;
+            if (del - 1.0).abs() < EPS {
+                break;
+            }
+        }
+        log_prefactor.exp() * h
+    }
+}
+
+// The natural logarithm of the gamma function for `x > 0`, using the
+// Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146, -86.50532032941677, 24.01409824083091,
+        -1.231739572450155, 0.1208650973866179e-2, -0.5395239384953e-5];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000000000190015;
+    for (i, &c) in COEFFICIENTS.iter().enumerate() {
+        series += c / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{gamma_q, kolmogorov_q, ln_gamma, chi_squared, kolmogorov_smirnov};
+    use distributions::Range;
+
+    #[test]
+    fn test_special_functions() {
+        // ln(4!) and ln(sqrt(pi))
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-10);
+        assert!((ln_gamma(0.5) - 0.5723649429247001).abs() < 1e-10);
+        // The chi-squared critical values for 1 and 10 degrees of freedom at
+        // p = 0.05, and a p-value for 100 degrees of freedom.
+        assert!((gamma_q(0.5, 3.841459 / 2.0) - 0.05).abs() < 1e-6);
+        assert!((gamma_q(5.0, 18.307038 / 2.0) - 0.05).abs() < 1e-6);
+        assert!((gamma_q(50.0, 124.342 / 2.0) - 0.05).abs() < 1e-5);
+        // The Kolmogorov distribution at its 0.05 and 0.5 quantiles
+        assert!((kolmogorov_q(1.3581) - 0.05).abs() < 1e-4);
+        assert!((kolmogorov_q(0.8276) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_detects_bias() {
+        let mut rng = ::test::rng(501);
+        // A die that never rolls 5
+        let result = chi_squared(&mut rng, &Range::new(0, 5), 600,
+                                 &[1.0 / 6.0; 6], |&x| x as usize);
+        assert!(!result.passes(0.001));
+        // Uniform values tested against a triangular distribution
+        let result = kolmogorov_smirnov(&mut rng, &Range::new(0.0, 1.0), 1000,
+                                        |x| x * x);
+        assert!(!result.passes(0.001));
+    }
+}
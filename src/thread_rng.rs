@@ -11,10 +11,14 @@
 //! Thread-local random number generator
 
 use std::cell::RefCell;
+use std::fmt;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::thread;
 
-use {RngCore, CryptoRng, StdRng, SeedableRng, EntropyRng, Error};
+use {RngCore, CryptoRng, SeedableRng, EntropyRng, Error};
 use {Distribution, Rng, Uniform};
+use {Hc128Rng, ChaChaRng};
 use reseeding::ReseedingRng;
 
 // Number of generated bytes after which to reseed `TreadRng`.
@@ -25,22 +29,277 @@ use reseeding::ReseedingRng;
 // that basically never happens.
 const THREAD_RNG_RESEED_THRESHOLD: u64 = 32*1024*1024; // 32 MiB
 
+/// The algorithm used by the generator behind [`thread_rng`].
+///
+/// See [`configure_thread_rng`].
+///
+/// [`thread_rng`]: fn.thread_rng.html
+/// [`configure_thread_rng`]: fn.configure_thread_rng.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThreadRngAlgorithm {
+    /// [HC-128], the default and currently also the algorithm of `StdRng`.
+    ///
+    /// [HC-128]: struct.Hc128Rng.html
+    Hc128,
+    /// [ChaCha] with 20 rounds.
+    ///
+    /// [ChaCha]: struct.ChaChaRng.html
+    ChaCha20,
+    /// [ChaCha] with 12 rounds.
+    ///
+    /// [ChaCha]: struct.ChaChaRng.html
+    ChaCha12,
+    /// [ChaCha] with 8 rounds, the minimum number of rounds considered secure.
+    ///
+    /// [ChaCha]: struct.ChaChaRng.html
+    ChaCha8,
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+/// Process-wide settings for [`thread_rng`].
+///
+/// The default is equivalent to the behaviour of `thread_rng` without any
+/// configuration: [HC-128], reseeded after generating 32 MiB of data.
+///
+/// [`thread_rng`]: fn.thread_rng.html
+/// [HC-128]: struct.Hc128Rng.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThreadRngConfig {
+    /// The number of generated bytes after which the generator is reseeded
+    /// from [`EntropyRng`]. Must not be larger than `i64::MAX`.
+    ///
+    /// [`EntropyRng`]: struct.EntropyRng.html
+    pub reseed_threshold: u64,
+    /// The algorithm of the generator.
+    pub algorithm: ThreadRngAlgorithm,
+}
+
+impl Default for ThreadRngConfig {
+    fn default() -> ThreadRngConfig {
+        ThreadRngConfig {
+            reseed_threshold: THREAD_RNG_RESEED_THRESHOLD,
+            algorithm: ThreadRngAlgorithm::Hc128,
+        }
+    }
+}
+
+// State of `THREAD_RNG_CONFIG`: it can be written while `OPEN`, and becomes
+// read-only once it is `FROZEN` by the first use of `thread_rng`.
+const CONFIG_OPEN: usize = 0;
+const CONFIG_WRITING: usize = 1;
+const CONFIG_FROZEN: usize = 2;
+
+static THREAD_RNG_CONFIG_STATE: AtomicUsize = ATOMIC_USIZE_INIT;
+// Only ever copied in or out as a whole, never borrowed: it is written while
+// holding `CONFIG_WRITING`, and read once `CONFIG_FROZEN`.
+static mut THREAD_RNG_CONFIG: ThreadRngConfig = ThreadRngConfig {
+    reseed_threshold: THREAD_RNG_RESEED_THRESHOLD,
+    algorithm: ThreadRngAlgorithm::Hc128,
+};
+
+/// Configure the generator used by [`thread_rng`] for the whole process.
+///
+/// This must be called before `thread_rng` is used for the first time, on any
+/// thread; all threads use the same configuration. Once `thread_rng` has been
+/// used the configuration is fixed and this function returns
+/// `ThreadRngConfigError::AlreadyInUse`. If `config.reseed_threshold` is
+/// larger than `i64::MAX`, it returns `ThreadRngConfigError::InvalidThreshold`
+/// and leaves the configuration unchanged.
+///
+/// A larger reseeding threshold and a faster algorithm may suit throughput
+/// sensitive applications like fuzzers, while a small threshold gives some
+/// extra protection to applications with high security requirements.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, ThreadRngAlgorithm, ThreadRngConfig, configure_thread_rng};
+///
+/// // Configuration should happen early, e.g. at the start of `main`.
+/// let config = ThreadRngConfig {
+///     reseed_threshold: 1024 * 1024 * 1024,
+///     algorithm: ThreadRngAlgorithm::ChaCha8,
+/// };
+/// if let Err(err) = configure_thread_rng(config) {
+///     println!("thread_rng was already in use: {}", err);
+/// }
+/// let x: u32 = rand::thread_rng().gen();
+/// ```
+///
+/// [`thread_rng`]: fn.thread_rng.html
+pub fn configure_thread_rng(config: ThreadRngConfig)
+    -> Result<(), ThreadRngConfigError>
+{
+    if config.reseed_threshold > ::core::i64::MAX as u64 {
+        return Err(ThreadRngConfigError::InvalidThreshold);
+    }
+    loop {
+        match THREAD_RNG_CONFIG_STATE.compare_exchange(
+                CONFIG_OPEN, CONFIG_WRITING,
+                Ordering::Acquire, Ordering::Relaxed)
+        {
+            Ok(_) => break,
+            Err(CONFIG_FROZEN) => return Err(ThreadRngConfigError::AlreadyInUse),
+            Err(_) => thread::yield_now(),
+        }
+    }
+    unsafe { THREAD_RNG_CONFIG = config; }
+    THREAD_RNG_CONFIG_STATE.store(CONFIG_OPEN, Ordering::Release);
+    Ok(())
+}
+
+/// An error returned by [`configure_thread_rng`].
+///
+/// [`configure_thread_rng`]: fn.configure_thread_rng.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadRngConfigError {
+    /// `thread_rng` has already been used, so the configuration is fixed.
+    AlreadyInUse,
+    /// The reseeding threshold is larger than `i64::MAX`.
+    InvalidThreshold,
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+impl ThreadRngConfigError {
+    fn description(&self) -> &'static str {
+        match *self {
+            ThreadRngConfigError::AlreadyInUse =>
+                "thread_rng is already in use and cannot be configured",
+            ThreadRngConfigError::InvalidThreshold =>
+                "reseed_threshold must not be larger than i64::MAX",
+            ThreadRngConfigError::__Nonexhaustive => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Display for ThreadRngConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+impl ::std::error::Error for ThreadRngConfigError {
+    fn description(&self) -> &str {
+        self.description()
+    }
+}
+
+// Freeze the configuration and return it.
+fn thread_rng_config() -> ThreadRngConfig {
+    loop {
+        match THREAD_RNG_CONFIG_STATE.compare_exchange(
+                CONFIG_OPEN, CONFIG_FROZEN,
+                Ordering::Acquire, Ordering::Acquire)
+        {
+            Ok(_) | Err(CONFIG_FROZEN) => break,
+            Err(_) => thread::yield_now(),
+        }
+    }
+    // Once frozen the configuration is never written again.
+    unsafe { THREAD_RNG_CONFIG }
+}
+
+// The generator behind `ThreadRng`, using the configured algorithm.
+//
+// `ReseedingRng` constructs a new generator with `from_rng` when reseeding,
+// so the algorithm is looked up in the (frozen) configuration on every seeding.
+#[derive(Debug)]
+enum ThreadRngCore {
+    Hc128(Hc128Rng),
+    ChaCha(ChaChaRng),
+}
+
+impl ThreadRngCore {
+    fn with_algorithm(algorithm: ThreadRngAlgorithm, seed: [u8; 32]) -> Self {
+        let rounds = match algorithm {
+            ThreadRngAlgorithm::ChaCha20 => 20,
+            ThreadRngAlgorithm::ChaCha12 => 12,
+            ThreadRngAlgorithm::ChaCha8 => 8,
+            _ => return ThreadRngCore::Hc128(Hc128Rng::from_seed(seed)),
+        };
+        let mut rng = ChaChaRng::from_seed(seed);
+        rng.set_rounds(rounds);
+        ThreadRngCore::ChaCha(rng)
+    }
+}
+
+impl RngCore for ThreadRngCore {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        match *self {
+            ThreadRngCore::Hc128(ref mut rng) => rng.next_u32(),
+            ThreadRngCore::ChaCha(ref mut rng) => rng.next_u32(),
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        match *self {
+            ThreadRngCore::Hc128(ref mut rng) => rng.next_u64(),
+            ThreadRngCore::ChaCha(ref mut rng) => rng.next_u64(),
+        }
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match *self {
+            ThreadRngCore::Hc128(ref mut rng) => rng.fill_bytes(dest),
+            ThreadRngCore::ChaCha(ref mut rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        match *self {
+            ThreadRngCore::Hc128(ref mut rng) => rng.try_fill_bytes(dest),
+            ThreadRngCore::ChaCha(ref mut rng) => rng.try_fill_bytes(dest),
+        }
+    }
+
+    #[inline]
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        match *self {
+            ThreadRngCore::Hc128(ref mut rng) => rng.fill_u32_slice(dest),
+            ThreadRngCore::ChaCha(ref mut rng) => rng.fill_u32_slice(dest),
+        }
+    }
+
+    #[inline]
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        match *self {
+            ThreadRngCore::Hc128(ref mut rng) => rng.fill_u64_slice(dest),
+            ThreadRngCore::ChaCha(ref mut rng) => rng.fill_u64_slice(dest),
+        }
+    }
+}
+
+impl SeedableRng for ThreadRngCore {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        ThreadRngCore::with_algorithm(thread_rng_config().algorithm, seed)
+    }
+}
+
 /// The type returned by [`thread_rng`], essentially just a reference to the
 /// PRNG in thread-local memory.
 /// 
 /// [`thread_rng`]: fn.thread_rng.html
 #[derive(Clone, Debug)]
 pub struct ThreadRng {
-    rng: Rc<RefCell<ReseedingRng<StdRng, EntropyRng>>>,
+    rng: Rc<RefCell<ReseedingRng<ThreadRngCore, EntropyRng>>>,
 }
 
 thread_local!(
-    static THREAD_RNG_KEY: Rc<RefCell<ReseedingRng<StdRng, EntropyRng>>> = {
+    static THREAD_RNG_KEY: Rc<RefCell<ReseedingRng<ThreadRngCore, EntropyRng>>> = {
+        let config = thread_rng_config();
         let mut entropy_source = EntropyRng::new();
-        let r = StdRng::from_rng(&mut entropy_source).unwrap_or_else(|err|
+        let r = ThreadRngCore::from_rng(&mut entropy_source).unwrap_or_else(|err|
                 panic!("could not initialize thread_rng: {}", err));
         let rng = ReseedingRng::new(r,
-                                    THREAD_RNG_RESEED_THRESHOLD,
+                                    config.reseed_threshold,
                                     entropy_source);
         Rc::new(RefCell::new(rng))
     }
@@ -67,10 +326,14 @@ thread_local!(
 /// usage for better performance. This makes it similar to ISAAC, the algorithm
 /// used in `ThreadRng` before rand 0.5.
 ///
+/// The algorithm and reseeding threshold can be changed for the whole process
+/// with [`configure_thread_rng`], before `thread_rng` is first used.
+///
 /// [`ReseedingRng`]: reseeding/struct.ReseedingRng.html
 /// [`StdRng`]: struct.StdRng.html
 /// [`EntropyRng`]: struct.EntropyRng.html
 /// [HC-128]: struct.Hc128Rng.html
+/// [`configure_thread_rng`]: fn.configure_thread_rng.html
 pub fn thread_rng() -> ThreadRng {
     ThreadRng { rng: THREAD_RNG_KEY.with(|t| t.clone()) }
 }
@@ -95,10 +358,114 @@ impl RngCore for ThreadRng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         self.rng.borrow_mut().try_fill_bytes(dest)
     }
+
+    #[inline]
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        self.rng.borrow_mut().fill_u32_slice(dest)
+    }
+
+    #[inline]
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        self.rng.borrow_mut().fill_u64_slice(dest)
+    }
 }
 
 impl CryptoRng for ThreadRng {}
 
+impl ThreadRng {
+    /// Create an independent [`TaskRng`], seeded from this generator.
+    ///
+    /// This is cheap compared to seeding from [`EntropyRng`], and the result
+    /// may be moved to other threads, e.g. held across `.await` points by
+    /// tasks which migrate between worker threads.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::thread;
+    /// use rand::Rng;
+    ///
+    /// let mut rng = rand::thread_rng().fork_for_task();
+    /// thread::spawn(move || {
+    ///     println!("{}", rng.gen::<u32>());
+    /// }).join().unwrap();
+    /// ```
+    ///
+    /// [`TaskRng`]: struct.TaskRng.html
+    /// [`EntropyRng`]: struct.EntropyRng.html
+    pub fn fork_for_task(&mut self) -> TaskRng {
+        let r = ThreadRngCore::from_rng(self).unwrap_or_else(|err|
+                panic!("could not initialize TaskRng: {}", err));
+        TaskRng {
+            rng: ReseedingRng::new(r,
+                                   thread_rng_config().reseed_threshold,
+                                   EntropyRng::new()),
+        }
+    }
+}
+
+/// An owned generator with the same properties as [`ThreadRng`], which
+/// unlike `ThreadRng` is `Send`.
+///
+/// `TaskRng` uses the algorithm and reseeding threshold of `thread_rng` (see
+/// [`configure_thread_rng`]), and is periodically reseeded from
+/// [`EntropyRng`]. It is initially seeded from `thread_rng`, which is much
+/// cheaper than seeding from the operating system.
+///
+/// Each `TaskRng` has its own state. It is created with [`TaskRng::new`] or
+/// [`ThreadRng::fork_for_task`].
+///
+/// [`ThreadRng`]: struct.ThreadRng.html
+/// [`configure_thread_rng`]: fn.configure_thread_rng.html
+/// [`EntropyRng`]: struct.EntropyRng.html
+/// [`TaskRng::new`]: #method.new
+/// [`ThreadRng::fork_for_task`]: struct.ThreadRng.html#method.fork_for_task
+#[derive(Debug)]
+pub struct TaskRng {
+    rng: ReseedingRng<ThreadRngCore, EntropyRng>,
+}
+
+impl TaskRng {
+    /// Create a new `TaskRng`, seeded from `thread_rng`.
+    pub fn new() -> TaskRng {
+        thread_rng().fork_for_task()
+    }
+}
+
+impl RngCore for TaskRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+
+    #[inline]
+    fn fill_u32_slice(&mut self, dest: &mut [u32]) {
+        self.rng.fill_u32_slice(dest)
+    }
+
+    #[inline]
+    fn fill_u64_slice(&mut self, dest: &mut [u64]) {
+        self.rng.fill_u64_slice(dest)
+    }
+}
+
+impl CryptoRng for TaskRng {}
+
 /// DEPRECATED: use `thread_rng().gen()` instead.
 ///
 /// Generates a random value using the thread-local random number generator.
@@ -152,7 +519,8 @@ pub fn random<T>() -> T where Uniform: Distribution<T> {
 
 #[cfg(test)]
 mod test {
-    use Rng;
+    use {Rng, RngCore, SeedableRng, Hc128Rng, ChaChaRng};
+    use super::{ThreadRngCore, ThreadRngAlgorithm, ThreadRngConfig};
 
     #[test]
     #[cfg(feature="std")]
@@ -164,7 +532,66 @@ mod test {
         r.shuffle(&mut v);
         let b: &[_] = &[1, 1, 1];
         assert_eq!(v, b);
-        assert_eq!(r.gen_range(0, 1), 0);
+        assert_eq!(r.gen_range(0..1), 0);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+    fn test_configure_after_use() {
+        use super::{configure_thread_rng, ThreadRngConfigError};
+        ::thread_rng().gen::<u32>();
+        assert_eq!(configure_thread_rng(ThreadRngConfig::default()).unwrap_err(),
+                   ThreadRngConfigError::AlreadyInUse);
+    }
+
+    #[test]
+    fn test_configure_too_large_threshold() {
+        use super::{configure_thread_rng, ThreadRngConfigError};
+        let config = ThreadRngConfig {
+            reseed_threshold: ::core::u64::MAX,
+            .. ThreadRngConfig::default()
+        };
+        assert_eq!(configure_thread_rng(config).unwrap_err(),
+                   ThreadRngConfigError::InvalidThreshold);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+    fn test_task_rng() {
+        use std::thread;
+        use super::TaskRng;
+
+        fn is_send<T: Send>(_: &T) {}
+
+        let mut a = ::thread_rng().fork_for_task();
+        let mut b = TaskRng::new();
+        is_send(&a);
+        assert!(a.next_u64() != b.next_u64());
+
+        let x = thread::spawn(move || {
+            a.gen_range(0..10)
+        }).join().unwrap();
+        assert!(x < 10);
+    }
+
+    #[test]
+    fn test_thread_rng_algorithms() {
+        let seed = [7u8; 32];
+
+        let mut rng = ThreadRngCore::with_algorithm(ThreadRngAlgorithm::Hc128, seed);
+        let mut hc = Hc128Rng::from_seed(seed);
+        assert_eq!(rng.next_u64(), hc.next_u64());
+
+        for &(algorithm, rounds) in [(ThreadRngAlgorithm::ChaCha20, 20),
+                                     (ThreadRngAlgorithm::ChaCha12, 12),
+                                     (ThreadRngAlgorithm::ChaCha8, 8)].iter() {
+            let mut rng = ThreadRngCore::with_algorithm(algorithm, seed);
+            let mut chacha = ChaChaRng::from_seed(seed);
+            chacha.set_rounds(rounds);
+            assert_eq!(rng.next_u64(), chacha.next_u64());
+        }
     }
 
     #[test]
@@ -0,0 +1,132 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A combiner XOR-ing the outputs of two generators.
+
+use {RngCore, CryptoRng, Error};
+
+/// An RNG combining two independent generators by XOR-ing their outputs.
+///
+/// If one of the generators is unpredictable and the two are independent,
+/// the combined output is at least as unpredictable: a flaw or backdoor in
+/// one source does not compromise the result. This is a common hedge when
+/// combining e.g. a hardware generator with a seeded CSPRNG.
+///
+/// The generators must really be independent; in particular they must not
+/// share state or be seeded from each other. XOR-ing a generator with a copy
+/// of itself gives only zeros.
+///
+/// `XorRng` implements `CryptoRng` when both generators do. Every request is
+/// served by both generators, so the combination is slower than either.
+///
+/// # Example
+///
+/// ```rust
+/// use rand::{Rng, OsRng, ChaChaRng, NewRng};
+/// use rand::xor::XorRng;
+///
+/// let mut rng = XorRng::new(OsRng::new().unwrap(), ChaChaRng::new());
+/// let key: [u8; 32] = rng.gen();
+/// # let _ = key;
+/// ```
+#[derive(Clone, Debug)]
+pub struct XorRng<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: RngCore, B: RngCore> XorRng<A, B> {
+    /// Create a new `XorRng` combining `a` and `b`.
+    pub fn new(a: A, b: B) -> XorRng<A, B> {
+        XorRng { a: a, b: b }
+    }
+
+    /// Unwrap the inner generators.
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A: RngCore, B: RngCore> RngCore for XorRng<A, B> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.a.next_u32() ^ self.b.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.a.next_u64() ^ self.b.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.a.fill_bytes(dest);
+        let mut buf = [0u8; 64];
+        for chunk in dest.chunks_mut(64) {
+            let buf = &mut buf[..chunk.len()];
+            self.b.fill_bytes(buf);
+            for (x, y) in chunk.iter_mut().zip(buf.iter()) {
+                *x ^= *y;
+            }
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.a.try_fill_bytes(dest)?;
+        let mut buf = [0u8; 64];
+        for chunk in dest.chunks_mut(64) {
+            let buf = &mut buf[..chunk.len()];
+            self.b.try_fill_bytes(buf)?;
+            for (x, y) in chunk.iter_mut().zip(buf.iter()) {
+                *x ^= *y;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<A: RngCore + CryptoRng, B: RngCore + CryptoRng> CryptoRng for XorRng<A, B> {}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, ErrorKind};
+    use mock::{StepRng, FaultyRng};
+    use super::XorRng;
+
+    #[test]
+    fn test_xor_rng() {
+        let mut rng = XorRng::new(StepRng::new(0b0110, 1), StepRng::new(0b1010, 0));
+        assert_eq!(rng.next_u32(), 0b1100);
+        assert_eq!(rng.next_u64(), 0b0111 ^ 0b1010);
+
+        let mut a = StepRng::new(0x0123_4567_89ab_cdef, 0x1111);
+        let mut b = StepRng::new(!0, 3);
+        let mut rng = XorRng::new(a.clone(), b.clone());
+        let mut buf = [0u8; 150];
+        rng.fill_bytes(&mut buf);
+        let mut expected = [0u8; 150];
+        let mut other = [0u8; 150];
+        a.fill_bytes(&mut expected);
+        for chunk in other.chunks_mut(64) {
+            b.fill_bytes(chunk);
+        }
+        for (x, y) in expected.iter_mut().zip(other.iter()) {
+            *x ^= *y;
+        }
+        assert_eq!(&buf[..], &expected[..]);
+
+        let faulty = FaultyRng::new(StepRng::new(0, 0), |call| {
+            if call >= 3 { Some(ErrorKind::Unavailable) } else { None }
+        });
+        let mut rng = XorRng::new(StepRng::new(0, 0), faulty);
+        assert!(rng.try_fill_bytes(&mut buf).is_ok());
+        assert_eq!(rng.try_fill_bytes(&mut buf).unwrap_err().kind,
+                   ErrorKind::Unavailable);
+    }
+}
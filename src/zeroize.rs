@@ -0,0 +1,72 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Wiping of secret state, for the `zeroize` feature.
+
+/// Overwrite `dest` with zeros (the `Default` of integer types), in a way the
+/// compiler will not optimise out even if `dest` is never read again.
+///
+/// Without the `zeroize` feature this does nothing.
+#[inline]
+pub fn zeroize<T: Copy + Default>(dest: &mut [T]) {
+    #[cfg(feature="zeroize")] {
+        use core::ptr;
+        use core::sync::atomic::{compiler_fence, Ordering};
+
+        for x in dest.iter_mut() {
+            unsafe { ptr::write_volatile(x, T::default()) }
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+    #[cfg(not(feature="zeroize"))] {
+        let _ = dest;
+    }
+}
+
+#[cfg(all(test, feature="zeroize"))]
+mod test {
+    use core::mem::ManuallyDrop;
+    use core::ptr;
+    use {Rng, SeedableRng, StateSerializable, ChaChaRng, Hc128Rng};
+    use super::zeroize;
+
+    #[test]
+    fn test_zeroize() {
+        let mut buf = [0x55u32; 7];
+        zeroize(&mut buf);
+        assert_eq!(buf, [0; 7]);
+    }
+
+    fn state_after_drop<R: StateSerializable>(rng: R) -> Vec<u8> {
+        let mut rng = ManuallyDrop::new(rng);
+        let mut state = vec![0u8; R::STATE_LEN];
+        unsafe {
+            ptr::drop_in_place(&mut *rng);
+            // The fields are plain integers, which are still valid to read.
+            rng.to_state_bytes(&mut state);
+        }
+        state
+    }
+
+    #[test]
+    fn test_zeroize_on_drop() {
+        let mut chacha = ChaChaRng::from_seed([7; 32]);
+        chacha.gen::<u32>();
+        let state = state_after_drop(chacha);
+        // Everything but the index and the number of rounds is wiped.
+        assert!(state[1..129].iter().all(|&b| b == 0));
+
+        let mut hc = Hc128Rng::from_seed([7; 32]);
+        hc.gen::<u32>();
+        let state = state_after_drop(hc);
+        assert!(state[1..4097].iter().all(|&b| b == 0));
+        assert!(state[4101..4165].iter().all(|&b| b == 0));
+    }
+}
@@ -0,0 +1,90 @@
+#!/usr/bin/env python
+#
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# https://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+# This creates `src/quasi/sobol_table.bin`, the initial direction numbers
+# used by `rand::quasi::Sobol`, from the file `new-joe-kuo-6.21201` of
+# Stephen Joe and Frances Kuo, available at
+# https://web.maths.unsw.edu.au/~fkuo/sobol/. They are changed rarely, so
+# the generated file should be checked in to git.
+#
+# The direction numbers are distributed under the following license:
+#
+#   Copyright (c) 2008, Frances Y. Kuo and Stephen Joe
+#   All rights reserved.
+#
+#   Redistribution and use in source and binary forms, with or without
+#   modification, are permitted provided that the following conditions are
+#   met:
+#
+#     * Redistributions of source code must retain the above copyright
+#       notice, this list of conditions and the following disclaimer.
+#
+#     * Redistributions in binary form must reproduce the above copyright
+#       notice, this list of conditions and the following disclaimer in the
+#       documentation and/or other materials provided with the distribution.
+#
+#     * Neither the names of the copyright holders nor the names of the
+#       University of New South Wales and the University of Waikato and its
+#       contributors may be used to endorse or promote products derived from
+#       this software without specific prior written permission.
+#
+#   THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS ``AS IS'' AND ANY
+#   EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+#   WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+#   DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS BE LIABLE FOR ANY
+#   DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+#   DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+#   OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+#   HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT,
+#   STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN
+#   ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+#   POSSIBILITY OF SUCH DAMAGE.
+#
+# The primitive polynomials of the file are those of degree up to 18 in
+# order of degree and then of their coefficients, which `Sobol` generates
+# itself, so only the direction numbers are stored. The numbers
+# m_1, ..., m_s of a dimension with a polynomial of degree s are odd and
+# m_k < 2^k, so m_1 = 1 is left out and m_k is stored as m_k >> 1 in k - 1
+# bits. These are written as one stream of bits, least significant bit
+# first, in order of dimension and then of k.
+#
+# Run it as `python sobol_table.py new-joe-kuo-6.21201`. It is designed to
+# be compatible with Python 2 and 3.
+
+import sys
+from os import path
+
+def main(source):
+    lines = open(source).read().splitlines()[1:]
+    data = bytearray()
+    bits = 0
+    prev = (0, 0)
+    for line in lines:
+        fields = [int(f) for f in line.split()]
+        s, a, m = fields[1], fields[2], fields[3:]
+        assert len(m) == s and (s, a) > prev
+        prev = (s, a)
+        for k, mk in enumerate(m):
+            assert mk % 2 == 1 and mk < 2 << k
+            for i in range(k):
+                if bits % 8 == 0:
+                    data.append(0)
+                data[-1] |= ((mk >> (i + 1)) & 1) << (bits % 8)
+                bits += 1
+
+    out = path.join(path.dirname(path.abspath(__file__)),
+                    '..', 'src', 'quasi', 'sobol_table.bin')
+    with open(out, 'wb') as f:
+        f.write(data)
+
+if __name__ == '__main__':
+    main(sys.argv[1])
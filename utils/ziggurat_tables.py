@@ -22,6 +22,9 @@
 # variants). Note that the adZigR table is unnecessary because of
 # algebra.
 #
+# The X and F tables are also output rounded to `f32`, with the suffix
+# `_F32`, for the single-precision samplers.
+#
 # It is designed to be compatible with Python 2 and 3.
 
 from math import exp, sqrt, log, floor
@@ -89,19 +92,19 @@ def render_static(name, type, value):
     # no space or
     return 'pub static %s: %s =%s;\n' % (name, type, value)
 
-# static `name`: [`type`, .. `len(values)`] =
+# static `name`: [`type`; `len(values)`] =
 #     [values[0], ..., values[3],
 #      values[4], ..., values[7],
 #      ... ];
-def render_table(name, values):
+def render_table(name, values, type='f64', fmt='%.18f'):
     rows = []
     # 4 values on each row
     for i in range(0, len(values), 4):
         row = values[i:i+4]
-        rows.append(', '.join('%.18f' % f for f in row))
+        rows.append(', '.join(fmt % f for f in row))
 
     rendered = '\n    [%s]' % ',\n     '.join(rows)
-    return render_static(name, '[f64, .. %d]' % len(values), rendered)
+    return render_static(name, '[%s; %d]' % (type, len(values)), rendered)
 
 
 with open('ziggurat_tables.rs', 'w') as f:
@@ -118,10 +121,14 @@ with open('ziggurat_tables.rs', 'w') as f:
 // Tables for distributions which are sampled using the ziggurat
 // algorithm. Autogenerated by `ziggurat_tables.py`.
 
-pub type ZigTable = &\'static [f64, .. %d];
-'''  % (TABLE_LEN + 1))
+''')
     for name, tables, r in [('NORM', NORM, NORM_R),
                             ('EXP', EXP, EXP_R)]:
         f.write(render_static('ZIG_%s_R' % name, 'f64', ' %.18f' % r))
         for (tabname, table) in zip(TABLE_NAMES, tables):
             f.write(render_table('ZIG_%s_%s' % (name, tabname), table))
+    # 9 significant digits are enough to round-trip an `f32`.
+    for name, tables in [('NORM', NORM), ('EXP', EXP)]:
+        for (tabname, table) in zip(TABLE_NAMES, tables):
+            f.write(render_table('ZIG_%s_%s_F32' % (name, tabname), table,
+                                 'f32', '%.9e'))